@@ -30,7 +30,7 @@
 //! See the commented example for how to use the builder pattern.
 
 use rs_trainz::hal::MockMotor;
-use rs_trainz::{Config, ThrottleController};
+use rs_trainz::{Config, HeartbeatLease, ThrottleController};
 
 #[cfg(any(feature = "web", feature = "mqtt"))]
 use std::sync::Arc;
@@ -72,7 +72,18 @@ fn main() {
 
         // Create mock motor and controller
         let motor = MockMotor::new();
-        let controller = ThrottleController::new(motor);
+        let mut controller = ThrottleController::new(motor)
+            .with_heartbeat(HeartbeatLease::from_config(&config.throttle))
+            .with_presets(config.throttle.presets.clone())
+            .with_service_brake(config.throttle.service_brake.clone())
+            .with_loco(config.throttle.loco_profile())
+            .with_driving_mode(config.throttle.driving_mode)
+            .with_force_release_source(config.throttle.force_release_source)
+            .with_priorities(config.throttle.priorities)
+            .with_rate_limits(config.throttle.rate_limits);
+        if config.throttle.max_lock_ms > 0 {
+            controller = controller.with_max_lock_duration(config.throttle.max_lock_ms as u64);
+        }
 
         #[cfg(all(feature = "web", feature = "mqtt"))]
         {
@@ -111,7 +122,12 @@ async fn run_web_only(controller: ThrottleController<MockMotor>, config: &Config
     println!();
 
     // Create shared state
-    let state = Arc::new(SharedThrottleState::new(controller));
+    let state = Arc::new(
+        SharedThrottleState::new(controller)
+            .with_lease(rs_trainz::ThrottleLease::from_config(&config.throttle))
+            .with_lockout(rs_trainz::SourceLockout::from_config(&config.throttle))
+            .with_speed_coalescing(config.throttle.speed_coalesce_ms as u64),
+    );
 
     // Spawn controller update task
     spawn_update_loop(Arc::clone(&state));
@@ -145,7 +161,12 @@ async fn run_mqtt_only(controller: ThrottleController<MockMotor>, config: &Confi
     println!();
 
     // Create shared state
-    let state = Arc::new(SharedThrottleState::new(controller));
+    let state = Arc::new(
+        SharedThrottleState::new(controller)
+            .with_lease(rs_trainz::ThrottleLease::from_config(&config.throttle))
+            .with_lockout(rs_trainz::SourceLockout::from_config(&config.throttle))
+            .with_speed_coalescing(config.throttle.speed_coalesce_ms as u64),
+    );
 
     // Spawn controller update task
     spawn_update_loop(Arc::clone(&state));
@@ -185,7 +206,12 @@ async fn run_web_and_mqtt(controller: ThrottleController<MockMotor>, config: &Co
     // =========================================================================
     // SINGLE shared state for both web and MQTT
     // =========================================================================
-    let shared_state = Arc::new(SharedThrottleState::new(controller));
+    let shared_state = Arc::new(
+        SharedThrottleState::new(controller)
+            .with_lease(rs_trainz::ThrottleLease::from_config(&config.throttle))
+            .with_lockout(rs_trainz::SourceLockout::from_config(&config.throttle))
+            .with_speed_coalescing(config.throttle.speed_coalesce_ms as u64),
+    );
 
     // =========================================================================
     // SINGLE update loop for both services
@@ -219,6 +245,7 @@ async fn run_web_and_mqtt(controller: ThrottleController<MockMotor>, config: &Co
 /// Spawn the single controller update loop.
 ///
 /// This task runs every 20ms and:
+/// - Applies speed commands held back by coalescing
 /// - Progresses any active speed transitions
 /// - Updates the motor with the current speed
 #[cfg(any(feature = "web", feature = "mqtt"))]
@@ -227,10 +254,7 @@ fn spawn_update_loop(state: Arc<SharedThrottleState<MockMotor>>) {
         let mut interval = tokio::time::interval(Duration::from_millis(20));
         loop {
             interval.tick().await;
-            let now_ms = state.now_ms();
-            state.with_controller(|controller| {
-                let _ = controller.update(now_ms);
-            });
+            let _ = state.update();
         }
     });
 }
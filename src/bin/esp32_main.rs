@@ -39,10 +39,12 @@ use esp_idf_hal::adc::oneshot::AdcDriver;
 use esp_idf_hal::peripherals::Peripherals;
 use esp_idf_hal::prelude::*;
 use rs_trainz::hal::esp32::{Esp32Clock, Esp32Encoder, Esp32Fault, Esp32Motor};
-use rs_trainz::traits::{Clock, EncoderInput, FaultDetector};
+use rs_trainz::traits::{Clock, EncoderInput, FaultDetector, MotorController};
 use rs_trainz::{
-    CommandSource, Config, Direction, ThrottleCommand, ThrottleCommandDyn, ThrottleController,
+    ClientId, CommandOutcome, CommandSource, Config, Direction, HeartbeatLease, Speed,
+    ThrottleCommand, ThrottleCommandDyn, ThrottleController, ThrottleLease, TransitionResult,
 };
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -85,6 +87,9 @@ fn main() -> anyhow::Result<()> {
 
     let peripherals = Peripherals::take()?;
 
+    // Throttle lease, shared by HTTP and MQTT commands
+    let lease = Arc::new(Mutex::new(ThrottleLease::from_config(&config.throttle)));
+
     // =========================================================================
     // Initialize Motor (BTS7960 on GPIO2/3)
     // =========================================================================
@@ -184,9 +189,13 @@ fn main() -> anyhow::Result<()> {
     #[cfg(feature = "esp32-http")]
     let http_state = {
         use rs_trainz::hal::esp32::{Esp32HttpServer, Esp32SharedState};
-        use std::sync::{Arc, Mutex};
 
-        let shared = Arc::new(Mutex::new(Esp32SharedState::default()));
+        let shared = Arc::new(Mutex::new(Esp32SharedState {
+            presets: config.throttle.presets.clone(),
+            lease: Arc::clone(&lease),
+            priorities: config.throttle.priorities,
+            ..Esp32SharedState::default()
+        }));
         let _server = Esp32HttpServer::new(&config.web, shared.clone())?;
         println!("[OK] HTTP server started on port {}", config.web.port);
         Some(shared)
@@ -223,11 +232,22 @@ fn main() -> anyhow::Result<()> {
     // Initialize Clock and Controller
     // =========================================================================
     let clock = Esp32Clock::new();
-    let mut controller = ThrottleController::new(motor);
+    let mut controller = ThrottleController::new(motor)
+        .with_heartbeat(HeartbeatLease::from_config(&config.throttle))
+        .with_presets(config.throttle.presets.clone())
+        .with_service_brake(config.throttle.service_brake.clone())
+        .with_loco(config.throttle.loco_profile())
+        .with_driving_mode(config.throttle.driving_mode)
+        .with_force_release_source(config.throttle.force_release_source)
+        .with_priorities(config.throttle.priorities)
+        .with_rate_limits(config.throttle.rate_limits);
+    if config.throttle.max_lock_ms > 0 {
+        controller = controller.with_max_lock_duration(config.throttle.max_lock_ms as u64);
+    }
 
     println!();
     println!("Controls:");
-    println!("  Rotate encoder: Adjust speed");
+    println!("  Rotate encoder: Adjust speed (throttle notch in cab mode)");
     println!("  Press button:   Toggle direction");
     #[cfg(feature = "esp32-http")]
     if let Some(ref _state) = http_state {
@@ -259,7 +279,16 @@ fn main() -> anyhow::Result<()> {
             guard.now_ms = now;
 
             if let Some(cmd) = guard.pending_command.take() {
-                let _ = controller.apply_command(cmd, CommandSource::WebLocal, now);
+                let client = guard.pending_client.take();
+                let mut lease = lease.lock().unwrap();
+                apply_remote(
+                    &mut controller,
+                    &mut lease,
+                    cmd,
+                    CommandSource::WebLocal,
+                    client,
+                    now,
+                );
             }
         }
 
@@ -269,7 +298,15 @@ fn main() -> anyhow::Result<()> {
         #[cfg(feature = "esp32-mqtt")]
         if let Some(ref mut client) = mqtt {
             while let Some(cmd) = client.recv_command() {
-                let _ = controller.apply_command(cmd, CommandSource::Mqtt, now);
+                let mut lease = lease.lock().unwrap();
+                apply_remote(
+                    &mut controller,
+                    &mut lease,
+                    cmd,
+                    CommandSource::Mqtt,
+                    None,
+                    now,
+                );
             }
         }
 
@@ -277,12 +314,17 @@ fn main() -> anyhow::Result<()> {
         // Speed control via encoder rotation
         // ---------------------------------------------------------------------
         let delta = encoder.read_delta();
-        if delta != 0 {
+        if let (true, Some(cab)) = (delta != 0, controller.cab_status()) {
+            let notch = cab.notch.step(delta);
+            let cmd = ThrottleCommandDyn::SetNotch(notch);
+            let _ = controller.apply_command(cmd, CommandSource::Physical, now);
+            println!("Notch: {}", notch);
+        } else if delta != 0 {
             let current = controller.current_speed();
-            let new_speed = (current + delta as f32 * SPEED_STEP).clamp(0.0, 1.0);
+            let new_speed = Speed::clamped(current.get() + delta as f32 * SPEED_STEP);
             let cmd = ThrottleCommand::speed_immediate(new_speed);
             let _ = controller.apply_command(cmd.into(), CommandSource::Physical, now);
-            println!("Speed: {:.0}%", new_speed * 100.0);
+            println!("Speed: {:.0}%", new_speed.get() * 100.0);
         }
 
         // ---------------------------------------------------------------------
@@ -313,7 +355,8 @@ fn main() -> anyhow::Result<()> {
         let _ = controller.update(now);
 
         // Get current state for display/network
-        let state = controller.state(now);
+        let mut state = controller.state(now);
+        state.lease = lease.lock().unwrap().status(now);
 
         // ---------------------------------------------------------------------
         // Update HTTP shared state
@@ -351,3 +394,25 @@ fn main() -> anyhow::Result<()> {
         thread::sleep(Duration::from_millis(LOOP_INTERVAL_MS));
     }
 }
+
+/// Apply a remote command if the throttle lease allows it.
+///
+/// The lease is only renewed once the controller has accepted the command,
+/// as on the desktop server.
+fn apply_remote<M: MotorController>(
+    controller: &mut ThrottleController<M>,
+    lease: &mut ThrottleLease,
+    cmd: ThrottleCommandDyn,
+    source: CommandSource,
+    client: Option<ClientId>,
+    now: u64,
+) {
+    if let Err(reason) = lease.check(&cmd, source, client, now) {
+        println!("Refused {} command: {}", source.as_str(), reason.as_str());
+        return;
+    }
+    match controller.apply_command(cmd, source, now) {
+        Ok(CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })) | Err(_) => {}
+        Ok(_) => lease.renew(source, client, now),
+    }
+}
@@ -0,0 +1,635 @@
+//! Cab simulation: throttle notches, an independent brake and train physics.
+//!
+//! In [`DrivingMode::Cab`] the throttle no longer sets a speed target. The
+//! driver selects a [`Notch`] (0–8) that sets tractive effort and a
+//! [`BrakeSetting`] that charges or releases the brake; speed then follows
+//! from effort, drag, braking and the mass of the loco's [`LocoProfile`].
+//!
+//! # Physics Model
+//!
+//! Each tick the net acceleration is
+//!
+//! ```text
+//! (tractive_effort * notch / 8 - rolling_resistance - drag * v² - brake_force * brake) / mass
+//! ```
+//!
+//! where resistance and braking only oppose motion, so a stopped train
+//! stays put until the throttle overcomes the brake.
+//!
+//! # Example
+//!
+//! ```
+//! use rs_trainz::cab::{BrakeSetting, CabSimulation, LocoProfile, Notch};
+//! use rs_trainz::hal::MockClock;
+//! use rs_trainz::traits::Clock;
+//! use rs_trainz::Speed;
+//!
+//! let mut clock = MockClock::new();
+//! let mut cab = CabSimulation::new(LocoProfile::default(), Speed::ZERO);
+//! cab.set_notch(Notch::MAX);
+//!
+//! for _ in 0..100 {
+//!     cab.update(clock.now_ms(), Speed::FULL);
+//!     clock.advance(20);
+//! }
+//! assert!(cab.speed() > Speed::ZERO);
+//!
+//! cab.set_notch(Notch::IDLE);
+//! cab.set_brake(BrakeSetting::Emergency);
+//! assert_eq!(cab.brake_level(), 1.0);
+//! ```
+
+use core::fmt;
+
+use crate::speed::Speed;
+
+/// Highest throttle notch
+pub const MAX_NOTCH: u8 = 8;
+
+/// Longest physics step; longer gaps between updates are subdivided
+const MAX_STEP_MS: u64 = 50;
+
+// ============================================================================
+// Driver Controls
+// ============================================================================
+
+/// How the throttle turns driver input into train speed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DrivingMode {
+    /// Commands set a target speed directly.
+    #[default]
+    Direct,
+    /// Commands set a throttle notch and brake; speed is simulated.
+    Cab,
+}
+
+impl DrivingMode {
+    /// Returns the mode as a lowercase string.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            DrivingMode::Direct => "direct",
+            DrivingMode::Cab => "cab",
+        }
+    }
+}
+
+/// Throttle notch position (0 = idle, [`MAX_NOTCH`] = full power).
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::cab::Notch;
+///
+/// assert_eq!(Notch::new(4).unwrap().power(), 0.5);
+/// assert!(Notch::new(9).is_err());
+/// assert_eq!(Notch::MAX.step(3), Notch::MAX);
+/// assert_eq!(Notch::IDLE.step(-1), Notch::IDLE);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub struct Notch(u8);
+
+impl Notch {
+    /// Idle (no power)
+    pub const IDLE: Notch = Notch(0);
+    /// Full power
+    pub const MAX: Notch = Notch(MAX_NOTCH);
+
+    /// Create a notch, rejecting positions above [`MAX_NOTCH`]
+    pub const fn new(notch: u8) -> Result<Self, NotchError> {
+        if notch > MAX_NOTCH {
+            return Err(NotchError);
+        }
+        Ok(Self(notch))
+    }
+
+    /// Create a notch, clamping to the 0..=[`MAX_NOTCH`] range
+    pub fn clamped(notch: i32) -> Self {
+        Self(notch.clamp(0, MAX_NOTCH as i32) as u8)
+    }
+
+    /// The notch position
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Move by `steps` notches, stopping at idle and full power
+    pub fn step(self, steps: i32) -> Self {
+        Self::clamped(self.0 as i32 + steps)
+    }
+
+    /// Fraction of full power (0.0 to 1.0)
+    pub fn power(self) -> f32 {
+        self.0 as f32 / MAX_NOTCH as f32
+    }
+}
+
+impl TryFrom<u8> for Notch {
+    type Error = NotchError;
+
+    fn try_from(notch: u8) -> Result<Self, Self::Error> {
+        Self::new(notch)
+    }
+}
+
+impl From<Notch> for u8 {
+    fn from(notch: Notch) -> Self {
+        notch.0
+    }
+}
+
+impl fmt::Display for Notch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A notch position above [`MAX_NOTCH`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotchError;
+
+impl fmt::Display for NotchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "notch must be between 0 and {}", MAX_NOTCH)
+    }
+}
+
+/// Position of the brake handle.
+///
+/// Like an air brake, the handle doesn't select a braking force directly:
+/// `Apply` builds brake pressure, `Release` bleeds it off and `Lap` holds
+/// it where it is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BrakeSetting {
+    /// Brake pressure bleeds off.
+    #[default]
+    Release,
+    /// Brake pressure is held.
+    Lap,
+    /// Brake pressure builds up.
+    Apply,
+    /// Full brake at once, and traction power is cut.
+    Emergency,
+}
+
+impl BrakeSetting {
+    /// Returns the setting as a lowercase string.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            BrakeSetting::Release => "release",
+            BrakeSetting::Lap => "lap",
+            BrakeSetting::Apply => "apply",
+            BrakeSetting::Emergency => "emergency",
+        }
+    }
+
+    /// Parse a brake setting from text (case-insensitive).
+    pub fn from_text(text: &str) -> Option<Self> {
+        let text = text.trim();
+        [Self::Release, Self::Lap, Self::Apply, Self::Emergency]
+            .into_iter()
+            .find(|setting| setting.as_str().eq_ignore_ascii_case(text))
+    }
+}
+
+// ============================================================================
+// Loco Profile
+// ============================================================================
+
+/// Physical characteristics of a loco (and its train) for cab simulation.
+///
+/// Forces are expressed as accelerations of a train with a `mass` of 1.0,
+/// in speed units (full scale = 1.0) per second.
+///
+/// With the defaults, full power settles at full speed and idle coasts
+/// down slowly.
+///
+/// Every value must be positive and finite: the builders and
+/// deserializing reject anything else with [`LocoProfileError`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LocoProfile {
+    /// Train mass relative to a typical loco; heavier trains respond slower
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub mass: f32,
+    /// Acceleration at full power
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub tractive_effort: f32,
+    /// Constant deceleration while moving
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub rolling_resistance: f32,
+    /// Deceleration per unit of speed squared
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub drag: f32,
+    /// Deceleration at full brake
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub brake_force: f32,
+    /// Brake gained per second while applying (full brake = 1.0)
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub brake_apply_rate: f32,
+    /// Brake lost per second while releasing
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_positive"))]
+    pub brake_release_rate: f32,
+}
+
+impl Default for LocoProfile {
+    fn default() -> Self {
+        Self {
+            mass: 1.0,
+            tractive_effort: 0.25,
+            rolling_resistance: 0.01,
+            drag: 0.24,
+            brake_force: 0.5,
+            brake_apply_rate: 0.5,
+            brake_release_rate: 0.25,
+        }
+    }
+}
+
+impl LocoProfile {
+    /// Light, punchy switcher for yard work
+    pub fn switcher() -> Self {
+        Self {
+            mass: 0.6,
+            tractive_effort: 0.2,
+            drag: 0.3,
+            ..Self::default()
+        }
+    }
+
+    /// Heavy freight that takes a long time to get going and to stop
+    pub fn freight() -> Self {
+        Self {
+            mass: 4.0,
+            ..Self::default()
+        }
+    }
+
+    /// Passenger train with strong brakes
+    pub fn passenger() -> Self {
+        Self {
+            mass: 1.5,
+            brake_force: 0.8,
+            brake_apply_rate: 0.8,
+            ..Self::default()
+        }
+    }
+
+    /// Set the relative mass
+    pub fn with_mass(mut self, mass: f32) -> Result<Self, LocoProfileError> {
+        self.mass = positive(mass)?;
+        Ok(self)
+    }
+
+    /// Set the acceleration at full power
+    pub fn with_tractive_effort(mut self, tractive_effort: f32) -> Result<Self, LocoProfileError> {
+        self.tractive_effort = positive(tractive_effort)?;
+        Ok(self)
+    }
+
+    /// Set the rolling resistance and drag coefficient
+    pub fn with_resistance(
+        mut self,
+        rolling_resistance: f32,
+        drag: f32,
+    ) -> Result<Self, LocoProfileError> {
+        self.rolling_resistance = positive(rolling_resistance)?;
+        self.drag = positive(drag)?;
+        Ok(self)
+    }
+
+    /// Set the full-brake deceleration
+    pub fn with_brake_force(mut self, brake_force: f32) -> Result<Self, LocoProfileError> {
+        self.brake_force = positive(brake_force)?;
+        Ok(self)
+    }
+}
+
+/// Accept `value` if it is positive and finite
+fn positive(value: f32) -> Result<f32, LocoProfileError> {
+    if value > 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(LocoProfileError)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_positive<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<f32, D::Error> {
+    let value = <f32 as serde::Deserialize>::deserialize(deserializer)?;
+    positive(value).map_err(serde::de::Error::custom)
+}
+
+/// A loco profile value that isn't positive and finite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocoProfileError;
+
+impl fmt::Display for LocoProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "loco profile values must be positive and finite")
+    }
+}
+
+// ============================================================================
+// Simulation
+// ============================================================================
+
+/// Snapshot of the cab controls for UI/API.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CabStatus {
+    /// Selected throttle notch
+    pub notch: Notch,
+    /// Brake handle position
+    pub brake: BrakeSetting,
+    /// Brake application (0.0 = released, 1.0 = full)
+    pub brake_level: f32,
+}
+
+/// Simulated train driven by a throttle notch and brake.
+///
+/// Call [`update`](Self::update) every tick; the first call only records
+/// the time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CabSimulation {
+    profile: LocoProfile,
+    notch: Notch,
+    brake: BrakeSetting,
+    brake_level: f32,
+    speed: f32,
+    last_ms: Option<u64>,
+}
+
+impl CabSimulation {
+    /// Create a simulation already moving at `speed`, with idle throttle
+    /// and released brake
+    pub fn new(profile: LocoProfile, speed: Speed) -> Self {
+        Self {
+            profile,
+            notch: Notch::IDLE,
+            brake: BrakeSetting::Release,
+            brake_level: 0.0,
+            speed: speed.get(),
+            last_ms: None,
+        }
+    }
+
+    /// Select a throttle notch
+    pub fn set_notch(&mut self, notch: Notch) {
+        self.notch = notch;
+    }
+
+    /// Move the brake handle
+    ///
+    /// [`BrakeSetting::Emergency`] applies full brake immediately.
+    pub fn set_brake(&mut self, brake: BrakeSetting) {
+        self.brake = brake;
+        if brake == BrakeSetting::Emergency {
+            self.brake_level = 1.0;
+        }
+    }
+
+    /// Replace the loco profile, keeping the current speed and controls
+    pub fn set_profile(&mut self, profile: LocoProfile) {
+        self.profile = profile;
+    }
+
+    /// Stop dead: idle throttle, emergency brake and zero speed
+    pub fn emergency_stop(&mut self) {
+        self.notch = Notch::IDLE;
+        self.set_brake(BrakeSetting::Emergency);
+        self.speed = 0.0;
+    }
+
+    /// Advance the simulation to `now_ms`, never exceeding `limit`
+    pub fn update(&mut self, now_ms: u64, limit: Speed) -> Speed {
+        let mut elapsed = self.last_ms.map_or(0, |last| now_ms.saturating_sub(last));
+        self.last_ms = Some(now_ms);
+
+        while elapsed > 0 {
+            let step = elapsed.min(MAX_STEP_MS);
+            self.step(step as f32 / 1000.0);
+            elapsed -= step;
+        }
+        self.speed = self.speed.min(limit.get());
+        self.speed()
+    }
+
+    fn step(&mut self, dt: f32) {
+        let p = &self.profile;
+        self.brake_level = match self.brake {
+            BrakeSetting::Release => (self.brake_level - p.brake_release_rate * dt).max(0.0),
+            BrakeSetting::Lap => self.brake_level,
+            BrakeSetting::Apply => (self.brake_level + p.brake_apply_rate * dt).min(1.0),
+            BrakeSetting::Emergency => 1.0,
+        };
+
+        let power = match self.brake {
+            BrakeSetting::Emergency => 0.0,
+            _ => self.notch.power(),
+        };
+        let tractive = p.tractive_effort * power;
+        let resistance = p.rolling_resistance
+            + p.drag * self.speed * self.speed
+            + p.brake_force * self.brake_level;
+
+        // Resistance only opposes motion: it can stop the train, not reverse it
+        let accel = if self.speed > 0.0 || tractive > resistance {
+            (tractive - resistance) / p.mass
+        } else {
+            0.0
+        };
+        self.speed = (self.speed + accel * dt).clamp(0.0, 1.0);
+    }
+
+    /// Current simulated speed
+    pub fn speed(&self) -> Speed {
+        Speed::clamped(self.speed)
+    }
+
+    /// Selected throttle notch
+    pub fn notch(&self) -> Notch {
+        self.notch
+    }
+
+    /// Brake handle position
+    pub fn brake(&self) -> BrakeSetting {
+        self.brake
+    }
+
+    /// Brake application (0.0 = released, 1.0 = full)
+    pub fn brake_level(&self) -> f32 {
+        self.brake_level
+    }
+
+    /// The loco profile being simulated
+    pub fn profile(&self) -> &LocoProfile {
+        &self.profile
+    }
+
+    /// Snapshot of the controls for UI/API
+    pub fn status(&self) -> CabStatus {
+        CabStatus {
+            notch: self.notch,
+            brake: self.brake,
+            brake_level: self.brake_level,
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run the simulation for `ms` in 20ms ticks, returning the final time
+    fn run(cab: &mut CabSimulation, from_ms: u64, ms: u64) -> u64 {
+        let mut now = from_ms;
+        cab.update(now, Speed::FULL);
+        while now < from_ms + ms {
+            now += 20;
+            cab.update(now, Speed::FULL);
+        }
+        now
+    }
+
+    // === Notch Tests ===
+
+    #[test]
+    fn notch_validates() {
+        assert_eq!(Notch::new(8), Ok(Notch::MAX));
+        assert_eq!(Notch::new(9), Err(NotchError));
+        assert_eq!(Notch::clamped(-2), Notch::IDLE);
+        assert_eq!(Notch::clamped(20), Notch::MAX);
+        assert_eq!(Notch::new(3).unwrap().step(2).get(), 5);
+    }
+
+    #[test]
+    fn loco_profile_rejects_invalid_values() {
+        let heavy = LocoProfile::freight().with_mass(8.0).unwrap();
+        assert_eq!(heavy.mass, 8.0);
+        assert_eq!(LocoProfile::default().with_mass(0.0), Err(LocoProfileError));
+        assert_eq!(
+            LocoProfile::default().with_tractive_effort(f32::NAN),
+            Err(LocoProfileError)
+        );
+        assert_eq!(
+            LocoProfile::default().with_resistance(0.01, -0.2),
+            Err(LocoProfileError)
+        );
+        assert_eq!(
+            LocoProfile::default().with_brake_force(f32::INFINITY),
+            Err(LocoProfileError)
+        );
+    }
+
+    #[test]
+    fn brake_setting_from_text() {
+        assert_eq!(BrakeSetting::from_text("LAP"), Some(BrakeSetting::Lap));
+        assert_eq!(
+            BrakeSetting::from_text(" emergency\n"),
+            Some(BrakeSetting::Emergency)
+        );
+        assert_eq!(BrakeSetting::from_text("hold"), None);
+    }
+
+    // === Simulation Tests ===
+
+    #[test]
+    fn idle_train_stays_put() {
+        let mut cab = CabSimulation::new(LocoProfile::default(), Speed::ZERO);
+        run(&mut cab, 0, 5000);
+        assert_eq!(cab.speed(), Speed::ZERO);
+    }
+
+    #[test]
+    fn higher_notch_settles_faster() {
+        let mut low = CabSimulation::new(LocoProfile::default(), Speed::ZERO);
+        let mut high = low.clone();
+        low.set_notch(Notch::new(4).unwrap());
+        high.set_notch(Notch::MAX);
+
+        run(&mut low, 0, 60_000);
+        run(&mut high, 0, 60_000);
+        assert!(high.speed() > low.speed());
+        // Full power balances drag at full speed
+        assert!(high.speed().get() > 0.95);
+        // Half power: (0.125 - 0.01) / 0.24 = v², v ≈ 0.69
+        assert!((low.speed().get() - 0.69).abs() < 0.02);
+    }
+
+    #[test]
+    fn heavier_train_accelerates_slower() {
+        let mut light = CabSimulation::new(LocoProfile::default(), Speed::ZERO);
+        let mut heavy = CabSimulation::new(LocoProfile::freight(), Speed::ZERO);
+        light.set_notch(Notch::MAX);
+        heavy.set_notch(Notch::MAX);
+
+        run(&mut light, 0, 2000);
+        run(&mut heavy, 0, 2000);
+        assert!(heavy.speed() < light.speed());
+        assert!(heavy.speed() > Speed::ZERO);
+    }
+
+    #[test]
+    fn brake_builds_and_holds() {
+        let mut cab = CabSimulation::new(LocoProfile::default(), Speed::new(0.5).unwrap());
+        cab.set_brake(BrakeSetting::Apply);
+        let now = run(&mut cab, 0, 1000);
+        assert!((cab.brake_level() - 0.5).abs() < 0.01);
+
+        cab.set_brake(BrakeSetting::Lap);
+        let now = run(&mut cab, now, 500);
+        assert!((cab.brake_level() - 0.5).abs() < 0.01);
+
+        cab.set_brake(BrakeSetting::Release);
+        run(&mut cab, now, 1000);
+        assert!((cab.brake_level() - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn brake_stops_train_without_reversing() {
+        let mut cab = CabSimulation::new(LocoProfile::default(), Speed::new(0.3).unwrap());
+        cab.set_brake(BrakeSetting::Apply);
+        run(&mut cab, 0, 10_000);
+        assert_eq!(cab.speed(), Speed::ZERO);
+
+        // Power against a full brake doesn't move the train
+        cab.set_notch(Notch::new(6).unwrap());
+        run(&mut cab, 10_000, 2000);
+        assert_eq!(cab.speed(), Speed::ZERO);
+    }
+
+    #[test]
+    fn emergency_cuts_power() {
+        let mut cab = CabSimulation::new(LocoProfile::default(), Speed::new(0.5).unwrap());
+        cab.set_notch(Notch::MAX);
+        cab.set_brake(BrakeSetting::Emergency);
+        assert_eq!(cab.brake_level(), 1.0);
+
+        run(&mut cab, 0, 3000);
+        assert_eq!(cab.speed(), Speed::ZERO);
+        assert_eq!(cab.notch(), Notch::MAX);
+    }
+
+    #[test]
+    fn update_respects_limit_and_subdivides_gaps() {
+        let mut cab = CabSimulation::new(LocoProfile::default(), Speed::ZERO);
+        cab.set_notch(Notch::MAX);
+        cab.update(0, Speed::FULL);
+        // One long gap is simulated in small steps
+        let speed = cab.update(60_000, Speed::new(0.4).unwrap());
+        assert_eq!(speed, 0.4);
+    }
+}
@@ -12,6 +12,11 @@
 //! 3. E-stop commands from any source are automatically promoted to [`CommandSource::Emergency`]
 //! 4. Commands can be wrapped as [`PrioritizedCommand`] for queue-based processing
 //!
+//! A command may also carry a [`ClientId`] naming the browser session, MQTT
+//! client or API token that sent it, so two clients of the same source can
+//! be told apart, and a [`CorrelationId`] chosen by the client to match
+//! acknowledgements to the commands it sent.
+//!
 //! # Typed vs Dynamic Commands
 //!
 //! The module provides two command representations:
@@ -22,10 +27,10 @@
 //! Use typed commands when you know the strategy at compile time:
 //!
 //! ```rust
-//! use rs_trainz::{ThrottleCommand, traits::EaseInOut};
+//! use rs_trainz::{Speed, ThrottleCommand, traits::EaseInOut};
 //!
 //! let cmd = ThrottleCommand::SetSpeed {
-//!     target: 0.8,
+//!     target: Speed::new(0.8).unwrap(),
 //!     strategy: EaseInOut::departure(2000),
 //! };
 //! ```
@@ -33,10 +38,10 @@
 //! Convert to dynamic for queuing with mixed strategy types:
 //!
 //! ```rust
-//! use rs_trainz::{ThrottleCommand, ThrottleCommandDyn, traits::Linear};
+//! use rs_trainz::{Speed, ThrottleCommand, ThrottleCommandDyn, traits::Linear};
 //!
 //! let typed = ThrottleCommand::SetSpeed {
-//!     target: 0.5,
+//!     target: Speed::new(0.5).unwrap(),
 //!     strategy: Linear::new(1000),
 //! };
 //! let dynamic: ThrottleCommandDyn = typed.into();
@@ -51,10 +56,13 @@
 //!
 //! [`ExecutionStrategy`]: crate::traits::ExecutionStrategy
 
-extern crate alloc;
-
+use crate::cab::{BrakeSetting, DrivingMode, Notch};
+use crate::speed::{Speed, Velocity};
 use crate::strategy_dyn::AnyStrategy;
+#[cfg(not(feature = "alloc"))]
+use crate::traits::StrategySpec;
 use crate::traits::{Direction, ExecutionStrategy, Immediate};
+use core::fmt;
 
 // ============================================================================
 // Command Source Priority
@@ -62,6 +70,9 @@ use crate::traits::{Direction, ExecutionStrategy, Immediate};
 
 /// Source of a command, ordered by priority (lower = lower priority).
 ///
+/// The declaration order is the default ranking; see [`SourcePriorities`]
+/// to re-rank sources at runtime.
+///
 /// Priority ordering determines which commands can interrupt or override others:
 /// - Lower priority sources cannot interrupt higher priority sources during lockout
 /// - E-stop commands from any source are automatically promoted to [`Emergency`](Self::Emergency)
@@ -125,6 +136,125 @@ pub enum CommandSource {
     Emergency = 5,
 }
 
+impl CommandSource {
+    /// Returns true for remote sources that are subject to a heartbeat lease.
+    ///
+    /// See [`HeartbeatLease`](crate::priority::HeartbeatLease).
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::Mqtt | Self::WebApi | Self::WebLocal)
+    }
+
+    /// Returns the source as a snake_case string, as used in JSON.
+    ///
+    /// ```
+    /// use rs_trainz::CommandSource;
+    ///
+    /// assert_eq!(CommandSource::WebLocal.as_str(), "web_local");
+    /// ```
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mqtt => "mqtt",
+            Self::WebApi => "web_api",
+            Self::WebLocal => "web_local",
+            Self::Physical => "physical",
+            Self::Fault => "fault",
+            Self::Emergency => "emergency",
+        }
+    }
+}
+
+/// Runtime priority ranking of command sources.
+///
+/// Ranks [`Mqtt`](CommandSource::Mqtt), [`WebApi`](CommandSource::WebApi),
+/// [`WebLocal`](CommandSource::WebLocal) and [`Physical`](CommandSource::Physical)
+/// by a configurable number; higher ranks win and equal ranks are equal
+/// priority. [`Fault`](CommandSource::Fault) and
+/// [`Emergency`](CommandSource::Emergency) are pinned above every other source.
+/// The default follows the declaration order of [`CommandSource`].
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::{CommandSource, SourcePriorities};
+///
+/// // Exhibition layout: automation outranks the public web UI
+/// let exhibition = SourcePriorities::default()
+///     .with_rank(CommandSource::Mqtt, 2)
+///     .with_rank(CommandSource::WebLocal, 0);
+/// assert!(exhibition.outranks(CommandSource::Mqtt, CommandSource::WebLocal));
+///
+/// // Faults always win
+/// assert!(exhibition.outranks(CommandSource::Fault, CommandSource::Physical));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SourcePriorities {
+    /// Rank of [`CommandSource::Mqtt`].
+    pub mqtt: u8,
+    /// Rank of [`CommandSource::WebApi`].
+    pub web_api: u8,
+    /// Rank of [`CommandSource::WebLocal`].
+    pub web_local: u8,
+    /// Rank of [`CommandSource::Physical`].
+    pub physical: u8,
+}
+
+impl Default for SourcePriorities {
+    fn default() -> Self {
+        Self {
+            mqtt: CommandSource::Mqtt as u8,
+            web_api: CommandSource::WebApi as u8,
+            web_local: CommandSource::WebLocal as u8,
+            physical: CommandSource::Physical as u8,
+        }
+    }
+}
+
+impl SourcePriorities {
+    /// Set the rank of `source`
+    ///
+    /// [`Fault`](CommandSource::Fault) and [`Emergency`](CommandSource::Emergency)
+    /// are pinned; setting their rank has no effect.
+    pub fn with_rank(mut self, source: CommandSource, rank: u8) -> Self {
+        match source {
+            CommandSource::Mqtt => self.mqtt = rank,
+            CommandSource::WebApi => self.web_api = rank,
+            CommandSource::WebLocal => self.web_local = rank,
+            CommandSource::Physical => self.physical = rank,
+            CommandSource::Fault | CommandSource::Emergency => {}
+        }
+        self
+    }
+
+    /// Effective rank of `source`; higher ranks take precedence
+    pub const fn rank(&self, source: CommandSource) -> u16 {
+        match source {
+            CommandSource::Mqtt => self.mqtt as u16,
+            CommandSource::WebApi => self.web_api as u16,
+            CommandSource::WebLocal => self.web_local as u16,
+            CommandSource::Physical => self.physical as u16,
+            CommandSource::Fault => u8::MAX as u16 + 1,
+            CommandSource::Emergency => u8::MAX as u16 + 2,
+        }
+    }
+
+    /// Compare two sources by rank
+    pub fn compare(&self, a: CommandSource, b: CommandSource) -> core::cmp::Ordering {
+        self.rank(a).cmp(&self.rank(b))
+    }
+
+    /// Returns true if `a` has strictly higher priority than `b`
+    pub fn outranks(&self, a: CommandSource, b: CommandSource) -> bool {
+        self.rank(a) > self.rank(b)
+    }
+
+    /// Returns true if `a` has strictly lower priority than `b`
+    pub fn is_below(&self, a: CommandSource, b: CommandSource) -> bool {
+        self.rank(a) < self.rank(b)
+    }
+}
+
 /// Type of command, used for secondary priority ordering.
 ///
 /// When two commands have the same [`CommandSource`], the command type
@@ -132,22 +262,245 @@ pub enum CommandSource {
 ///
 /// # Priority Order (lowest to highest)
 ///
-/// 1. [`SetMaxSpeed`](Self::SetMaxSpeed) - Configuration commands
-/// 2. [`SetDirection`](Self::SetDirection) - Direction changes
-/// 3. [`SetSpeed`](Self::SetSpeed) - Speed control
-/// 4. [`EmergencyStop`](Self::EmergencyStop) - Always highest priority
+/// 1. [`Heartbeat`](Self::Heartbeat) - Keepalive for remote sources
+/// 2. [`SetMaxSpeed`](Self::SetMaxSpeed) - Configuration commands
+/// 3. [`SetDirection`](Self::SetDirection) - Direction changes
+/// 4. [`SetSpeed`](Self::SetSpeed) - Speed control
+/// 5. [`Brake`](Self::Brake) - Service brake applications
+/// 6. [`EmergencyStop`](Self::EmergencyStop) - Always highest priority
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CommandType {
-    /// Set maximum speed limit (configuration).
-    SetMaxSpeed = 0,
+    /// Keepalive that renews a remote source's heartbeat lease.
+    Heartbeat = 0,
+    /// Configuration: maximum speed limit or driving mode.
+    SetMaxSpeed = 1,
     /// Set direction of travel (forward/reverse/stopped).
-    SetDirection = 1,
-    /// Set speed with optional transition strategy.
-    SetSpeed = 2,
+    SetDirection = 2,
+    /// Speed control: speed, velocity, throttle notch or brake handle.
+    SetSpeed = 3,
+    /// Service brake application or release.
+    Brake = 4,
     /// Emergency stop - immediately halts the motor.
-    EmergencyStop = 3,
+    EmergencyStop = 5,
+}
+
+// ============================================================================
+// Client Identity
+// ============================================================================
+
+/// Longest [`ClientId`] in bytes
+pub const MAX_CLIENT_ID: usize = 32;
+
+/// Identity of one client within a [`CommandSource`] category.
+///
+/// A browser session id, MQTT client id or API token name: 1 to
+/// [`MAX_CLIENT_ID`] ASCII letters, digits or `-_.:@`, so it is safe to put
+/// in JSON and MQTT topics unescaped.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::ClientId;
+///
+/// let phone = ClientId::new("alice-phone").unwrap();
+/// assert_eq!(phone.as_str(), "alice-phone");
+///
+/// assert!(ClientId::new("").is_err());
+/// assert!(ClientId::new("no spaces").is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "heapless::String<MAX_CLIENT_ID>",
+        into = "heapless::String<MAX_CLIENT_ID>"
+    )
+)]
+pub struct ClientId {
+    bytes: [u8; MAX_CLIENT_ID],
+    len: u8,
+}
+
+impl ClientId {
+    /// Create a client id, rejecting empty, overlong or unsafe names
+    pub fn new(id: &str) -> Result<Self, ClientIdError> {
+        if !is_safe_id(id, MAX_CLIENT_ID) {
+            return Err(ClientIdError);
+        }
+        let mut bytes = [0; MAX_CLIENT_ID];
+        bytes[..id.len()].copy_from_slice(id.as_bytes());
+        Ok(Self {
+            bytes,
+            len: id.len() as u8,
+        })
+    }
+
+    /// The id as a string
+    pub fn as_str(&self) -> &str {
+        // Only ever built from validated ASCII
+        core::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for ClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ClientId({:?})", self.as_str())
+    }
+}
+
+impl fmt::Display for ClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<&str> for ClientId {
+    type Error = ClientIdError;
+
+    fn try_from(id: &str) -> Result<Self, Self::Error> {
+        Self::new(id)
+    }
+}
+
+impl TryFrom<heapless::String<MAX_CLIENT_ID>> for ClientId {
+    type Error = ClientIdError;
+
+    fn try_from(id: heapless::String<MAX_CLIENT_ID>) -> Result<Self, Self::Error> {
+        Self::new(&id)
+    }
+}
+
+impl From<ClientId> for heapless::String<MAX_CLIENT_ID> {
+    fn from(id: ClientId) -> Self {
+        let mut s = heapless::String::new();
+        let _ = s.push_str(id.as_str());
+        s
+    }
+}
+
+/// A client id that is empty, too long or has unsupported characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientIdError;
+
+impl fmt::Display for ClientIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "client id must be 1 to {} letters, digits or -_.:@",
+            MAX_CLIENT_ID
+        )
+    }
+}
+
+/// 1 to `max` ASCII letters, digits or `-_.:@`
+fn is_safe_id(id: &str, max: usize) -> bool {
+    let valid = |b: u8| b.is_ascii_alphanumeric() || b"-_.:@".contains(&b);
+    !id.is_empty() && id.len() <= max && id.bytes().all(valid)
+}
+
+/// Longest [`CorrelationId`] in bytes, enough for a UUID
+pub const MAX_CORRELATION_ID: usize = 64;
+
+/// Client-chosen tag echoed back in a command's acknowledgements.
+///
+/// Same character set as [`ClientId`], up to [`MAX_CORRELATION_ID`] bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::CorrelationId;
+///
+/// let tag = CorrelationId::new("6f1c2a9e-0d4b-4d8e-9a51-2f3c7b8e1d20").unwrap();
+/// assert_eq!(tag.as_str().len(), 36);
+///
+/// assert!(CorrelationId::new("").is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "heapless::String<MAX_CORRELATION_ID>",
+        into = "heapless::String<MAX_CORRELATION_ID>"
+    )
+)]
+pub struct CorrelationId {
+    bytes: [u8; MAX_CORRELATION_ID],
+    len: u8,
+}
+
+impl CorrelationId {
+    /// Create a correlation id, rejecting empty, overlong or unsafe tags
+    pub fn new(id: &str) -> Result<Self, CorrelationIdError> {
+        if !is_safe_id(id, MAX_CORRELATION_ID) {
+            return Err(CorrelationIdError);
+        }
+        let mut bytes = [0; MAX_CORRELATION_ID];
+        bytes[..id.len()].copy_from_slice(id.as_bytes());
+        Ok(Self {
+            bytes,
+            len: id.len() as u8,
+        })
+    }
+
+    /// The id as a string
+    pub fn as_str(&self) -> &str {
+        // Only ever built from validated ASCII
+        core::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CorrelationId({:?})", self.as_str())
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<&str> for CorrelationId {
+    type Error = CorrelationIdError;
+
+    fn try_from(id: &str) -> Result<Self, Self::Error> {
+        Self::new(id)
+    }
+}
+
+impl TryFrom<heapless::String<MAX_CORRELATION_ID>> for CorrelationId {
+    type Error = CorrelationIdError;
+
+    fn try_from(id: heapless::String<MAX_CORRELATION_ID>) -> Result<Self, Self::Error> {
+        Self::new(&id)
+    }
+}
+
+impl From<CorrelationId> for heapless::String<MAX_CORRELATION_ID> {
+    fn from(id: CorrelationId) -> Self {
+        let mut s = heapless::String::new();
+        let _ = s.push_str(id.as_str());
+        s
+    }
+}
+
+/// A correlation id that is empty, too long or has unsupported characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CorrelationIdError;
+
+impl fmt::Display for CorrelationIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "correlation id must be 1 to {} letters, digits or -_.:@",
+            MAX_CORRELATION_ID
+        )
+    }
 }
 
 // ============================================================================
@@ -168,10 +521,10 @@ pub enum CommandType {
 /// Use `.into()` to convert to [`ThrottleCommandDyn`] for storage in mixed-strategy queues:
 ///
 /// ```rust
-/// use rs_trainz::{ThrottleCommand, ThrottleCommandDyn, traits::Linear};
+/// use rs_trainz::{Speed, ThrottleCommand, ThrottleCommandDyn, traits::Linear};
 ///
 /// let typed = ThrottleCommand::SetSpeed {
-///     target: 0.5,
+///     target: Speed::new(0.5).unwrap(),
 ///     strategy: Linear::new(1000),
 /// };
 /// let dynamic: ThrottleCommandDyn = typed.into();
@@ -184,12 +537,23 @@ pub enum ThrottleCommand<S: ExecutionStrategy = Immediate> {
     ///
     /// The `strategy` determines how the speed change is applied over time.
     SetSpeed {
-        /// Target speed value (0.0 to 1.0, clamped to max_speed).
-        target: f32,
+        /// Target speed (limited to max_speed).
+        target: Speed,
         /// Strategy for transitioning to the target speed.
         strategy: S,
     },
 
+    /// Set a signed velocity with a transition strategy.
+    ///
+    /// Reversing decelerates to zero, flips direction, then accelerates,
+    /// running `strategy` for each leg.
+    SetVelocity {
+        /// Target velocity (magnitude limited to max_speed).
+        target: Velocity,
+        /// Strategy for each leg of the transition.
+        strategy: S,
+    },
+
     /// Set the direction of travel.
     ///
     /// Direction changes are applied immediately without transitions.
@@ -208,32 +572,148 @@ pub enum ThrottleCommand<S: ExecutionStrategy = Immediate> {
     ///
     /// Speed commands will be clamped to this value. Does not affect
     /// currently running transitions.
-    SetMaxSpeed(f32),
+    SetMaxSpeed(Speed),
+
+    /// Keepalive from a remote source.
+    ///
+    /// Renews the sender's [`HeartbeatLease`](crate::priority::HeartbeatLease)
+    /// without changing any throttle state.
+    Heartbeat,
+
+    /// Select a throttle notch (cab driving mode only).
+    ///
+    /// The notch sets tractive effort; speed follows from the simulation.
+    SetNotch(Notch),
+
+    /// Move the brake handle (cab driving mode only).
+    SetBrake(BrakeSetting),
+
+    /// Switch between direct speed control and cab simulation.
+    SetDrivingMode(DrivingMode),
+
+    /// Service brake - decelerate to a stop without cutting the direction.
+    ///
+    /// Unlike [`EmergencyStop`](Self::EmergencyStop) this follows a
+    /// deceleration profile, which may carry a lock. With `hold`, the
+    /// train is held at rest until [`ReleaseBrake`](Self::ReleaseBrake).
+    Brake {
+        /// Deceleration profile (`None` = the controller's configured profile).
+        strategy: Option<S>,
+        /// Keep the brake applied once stopped.
+        hold: bool,
+    },
+
+    /// Release a held service brake.
+    ReleaseBrake,
+
+    /// Remove one command from the transition queue by id.
+    ///
+    /// Only the same or a higher priority source than the one that queued
+    /// the command may cancel it.
+    CancelQueued(u32),
+
+    /// Remove every queued command the sender is allowed to cancel.
+    ClearQueue,
+
+    /// Freeze the active speed transition at its current value.
+    ///
+    /// Locked transitions can only be paused by the lock owner or a higher
+    /// priority source; hard locks only by the owner or the force release
+    /// source.
+    Pause,
+
+    /// Continue a paused speed transition where it left off.
+    ///
+    /// Only the source that paused it, or a higher priority one, may resume.
+    Resume,
+
+    /// Drop the lock on the active speed transition, letting it be interrupted.
+    ///
+    /// Only sources at or above the controller's force release source
+    /// (physical controls by default) may send this.
+    ForceRelease,
 }
 
 impl ThrottleCommand<Immediate> {
     /// Create an immediate speed change
-    pub fn speed_immediate(target: f32) -> Self {
+    pub fn speed_immediate(target: Speed) -> Self {
         Self::SetSpeed {
             target,
             strategy: Immediate,
         }
     }
 
+    /// Create an immediate velocity change
+    pub fn velocity_immediate(target: Velocity) -> Self {
+        Self::SetVelocity {
+            target,
+            strategy: Immediate,
+        }
+    }
+
     /// Create an emergency stop command
     pub fn estop() -> Self {
         Self::EmergencyStop
     }
+
+    /// Create a service brake command using the configured profile
+    pub fn brake(hold: bool) -> Self {
+        Self::Brake {
+            strategy: None,
+            hold,
+        }
+    }
 }
 
 impl<S: ExecutionStrategy> ThrottleCommand<S> {
     /// Get the command type for priority ordering
     pub fn command_type(&self) -> CommandType {
         match self {
-            Self::SetSpeed { .. } => CommandType::SetSpeed,
+            Self::SetSpeed { .. }
+            | Self::SetVelocity { .. }
+            | Self::SetNotch(_)
+            | Self::SetBrake(_)
+            | Self::CancelQueued(_)
+            | Self::ClearQueue
+            | Self::Pause
+            | Self::Resume
+            | Self::ForceRelease => CommandType::SetSpeed,
             Self::SetDirection(_) => CommandType::SetDirection,
+            Self::Brake { .. } | Self::ReleaseBrake => CommandType::Brake,
             Self::EmergencyStop => CommandType::EmergencyStop,
-            Self::SetMaxSpeed(_) => CommandType::SetMaxSpeed,
+            Self::SetMaxSpeed(_) | Self::SetDrivingMode(_) => CommandType::SetMaxSpeed,
+            Self::Heartbeat => CommandType::Heartbeat,
+        }
+    }
+
+    /// Convert to a [`ThrottleCommandDyn`], erasing the strategy with `erase`
+    fn map_strategy(self, erase: impl FnOnce(S) -> AnyStrategy) -> ThrottleCommandDyn {
+        match self {
+            Self::SetSpeed { target, strategy } => ThrottleCommandDyn::SetSpeed {
+                target,
+                strategy: erase(strategy),
+            },
+            Self::SetVelocity { target, strategy } => ThrottleCommandDyn::SetVelocity {
+                target,
+                strategy: erase(strategy),
+            },
+            Self::SetDirection(d) => ThrottleCommandDyn::SetDirection(d),
+            Self::EmergencyStop => ThrottleCommandDyn::EmergencyStop,
+            Self::SetMaxSpeed(s) => ThrottleCommandDyn::SetMaxSpeed(s),
+            Self::Heartbeat => ThrottleCommandDyn::Heartbeat,
+            Self::SetNotch(n) => ThrottleCommandDyn::SetNotch(n),
+            Self::SetBrake(b) => ThrottleCommandDyn::SetBrake(b),
+            Self::SetDrivingMode(m) => ThrottleCommandDyn::SetDrivingMode(m),
+            Self::Brake { strategy, hold } => ThrottleCommandDyn::Brake {
+                strategy: strategy.map(erase),
+                hold,
+            },
+            Self::ReleaseBrake => ThrottleCommandDyn::ReleaseBrake,
+            Self::CancelQueued(id) => ThrottleCommandDyn::CancelQueued(id),
+            Self::ClearQueue => ThrottleCommandDyn::ClearQueue,
+            Self::Pause => ThrottleCommandDyn::Pause,
+            Self::Resume => ThrottleCommandDyn::Resume,
+            Self::ForceRelease => ThrottleCommandDyn::ForceRelease,
         }
     }
 }
@@ -260,10 +740,10 @@ impl<S: ExecutionStrategy> ThrottleCommand<S> {
 /// Convert from typed commands:
 ///
 /// ```rust
-/// use rs_trainz::{ThrottleCommand, ThrottleCommandDyn, traits::EaseInOut};
+/// use rs_trainz::{Speed, ThrottleCommand, ThrottleCommandDyn, traits::EaseInOut};
 ///
 /// let typed = ThrottleCommand::SetSpeed {
-///     target: 0.8,
+///     target: Speed::new(0.8).unwrap(),
 ///     strategy: EaseInOut::departure(2000),
 /// };
 /// let dynamic: ThrottleCommandDyn = typed.into();
@@ -272,10 +752,10 @@ impl<S: ExecutionStrategy> ThrottleCommand<S> {
 /// Or construct directly:
 ///
 /// ```rust
-/// use rs_trainz::{ThrottleCommandDyn, AnyStrategy, traits::Immediate};
+/// use rs_trainz::{ThrottleCommandDyn, AnyStrategy, Speed, traits::Immediate};
 ///
 /// let cmd = ThrottleCommandDyn::SetSpeed {
-///     target: 0.5,
+///     target: Speed::new(0.5).unwrap(),
 ///     strategy: AnyStrategy::new(Immediate),
 /// };
 /// ```
@@ -283,12 +763,20 @@ impl<S: ExecutionStrategy> ThrottleCommand<S> {
 pub enum ThrottleCommandDyn {
     /// Set the target speed with a type-erased transition strategy.
     SetSpeed {
-        /// Target speed value (0.0 to 1.0, clamped to max_speed).
-        target: f32,
+        /// Target speed (limited to max_speed).
+        target: Speed,
         /// Type-erased strategy for transitioning to the target speed.
         strategy: AnyStrategy,
     },
 
+    /// Set a signed velocity with a type-erased transition strategy.
+    SetVelocity {
+        /// Target velocity (magnitude limited to max_speed).
+        target: Velocity,
+        /// Type-erased strategy for each leg of the transition.
+        strategy: AnyStrategy,
+    },
+
     /// Set the direction of travel.
     SetDirection(Direction),
 
@@ -298,17 +786,65 @@ pub enum ThrottleCommandDyn {
     EmergencyStop,
 
     /// Set the maximum allowed speed.
-    SetMaxSpeed(f32),
+    SetMaxSpeed(Speed),
+
+    /// Keepalive that renews the sender's heartbeat lease.
+    Heartbeat,
+
+    /// Select a throttle notch (cab driving mode only).
+    SetNotch(Notch),
+
+    /// Move the brake handle (cab driving mode only).
+    SetBrake(BrakeSetting),
+
+    /// Switch between direct speed control and cab simulation.
+    SetDrivingMode(DrivingMode),
+
+    /// Service brake with a type-erased deceleration profile.
+    Brake {
+        /// Deceleration profile (`None` = the controller's configured profile).
+        strategy: Option<AnyStrategy>,
+        /// Keep the brake applied once stopped.
+        hold: bool,
+    },
+
+    /// Release a held service brake.
+    ReleaseBrake,
+
+    /// Remove one command from the transition queue by id.
+    CancelQueued(u32),
+
+    /// Remove every queued command the sender is allowed to cancel.
+    ClearQueue,
+
+    /// Freeze the active speed transition at its current value.
+    Pause,
+
+    /// Continue a paused speed transition where it left off.
+    Resume,
+
+    /// Drop the lock on the active speed transition.
+    ForceRelease,
 }
 
 impl ThrottleCommandDyn {
     /// Returns the command type for priority ordering.
     pub fn command_type(&self) -> CommandType {
         match self {
-            Self::SetSpeed { .. } => CommandType::SetSpeed,
+            Self::SetSpeed { .. }
+            | Self::SetVelocity { .. }
+            | Self::SetNotch(_)
+            | Self::SetBrake(_)
+            | Self::CancelQueued(_)
+            | Self::ClearQueue
+            | Self::Pause
+            | Self::Resume
+            | Self::ForceRelease => CommandType::SetSpeed,
             Self::SetDirection(_) => CommandType::SetDirection,
+            Self::Brake { .. } | Self::ReleaseBrake => CommandType::Brake,
             Self::EmergencyStop => CommandType::EmergencyStop,
-            Self::SetMaxSpeed(_) => CommandType::SetMaxSpeed,
+            Self::SetMaxSpeed(_) | Self::SetDrivingMode(_) => CommandType::SetMaxSpeed,
+            Self::Heartbeat => CommandType::Heartbeat,
         }
     }
 
@@ -316,19 +852,48 @@ impl ThrottleCommandDyn {
     pub fn is_estop(&self) -> bool {
         matches!(self, Self::EmergencyStop)
     }
+
+    /// Returns the command's snake_case name, as used in command envelopes.
+    ///
+    /// ```
+    /// use rs_trainz::ThrottleCommandDyn;
+    ///
+    /// assert_eq!(ThrottleCommandDyn::ForceRelease.name(), "force_release");
+    /// ```
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::SetSpeed { .. } => "set_speed",
+            Self::SetVelocity { .. } => "set_velocity",
+            Self::SetDirection(_) => "set_direction",
+            Self::EmergencyStop => "emergency_stop",
+            Self::SetMaxSpeed(_) => "set_max_speed",
+            Self::Heartbeat => "heartbeat",
+            Self::SetNotch(_) => "set_notch",
+            Self::SetBrake(_) => "set_brake",
+            Self::SetDrivingMode(_) => "set_driving_mode",
+            Self::Brake { .. } => "brake",
+            Self::ReleaseBrake => "release_brake",
+            Self::CancelQueued(_) => "cancel_queued",
+            Self::ClearQueue => "clear_queue",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::ForceRelease => "force_release",
+        }
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl<S: ExecutionStrategy + Send + Sync + 'static> From<ThrottleCommand<S>> for ThrottleCommandDyn {
     fn from(cmd: ThrottleCommand<S>) -> Self {
-        match cmd {
-            ThrottleCommand::SetSpeed { target, strategy } => ThrottleCommandDyn::SetSpeed {
-                target,
-                strategy: AnyStrategy::new(strategy),
-            },
-            ThrottleCommand::SetDirection(d) => ThrottleCommandDyn::SetDirection(d),
-            ThrottleCommand::EmergencyStop => ThrottleCommandDyn::EmergencyStop,
-            ThrottleCommand::SetMaxSpeed(s) => ThrottleCommandDyn::SetMaxSpeed(s),
-        }
+        cmd.map_strategy(AnyStrategy::new)
+    }
+}
+
+/// Without `alloc`, only built-in strategies can be erased.
+#[cfg(not(feature = "alloc"))]
+impl<S: ExecutionStrategy + Into<StrategySpec>> From<ThrottleCommand<S>> for ThrottleCommandDyn {
+    fn from(cmd: ThrottleCommand<S>) -> Self {
+        cmd.map_strategy(|s| AnyStrategy::from(s.into()))
     }
 }
 
@@ -355,7 +920,7 @@ impl<S: ExecutionStrategy + Send + Sync + 'static> From<ThrottleCommand<S>> for
 ///     CommandSource::Mqtt,
 ///     0,
 /// );
-/// assert_eq!(estop.priority().0, CommandSource::Emergency);
+/// assert_eq!(estop.effective_source(), CommandSource::Emergency);
 /// ```
 ///
 /// # Ordering
@@ -363,23 +928,35 @@ impl<S: ExecutionStrategy + Send + Sync + 'static> From<ThrottleCommand<S>> for
 /// Commands implement `Ord` for use with [`BinaryHeap`](std::collections::BinaryHeap):
 ///
 /// ```rust
-/// use rs_trainz::{PrioritizedCommand, ThrottleCommandDyn, CommandSource, AnyStrategy};
+/// use rs_trainz::{PrioritizedCommand, ThrottleCommandDyn, CommandSource, AnyStrategy, Speed};
 /// use rs_trainz::traits::Immediate;
 ///
+/// let half = Speed::new(0.5).unwrap();
 /// let mqtt = PrioritizedCommand::new(
-///     ThrottleCommandDyn::SetSpeed { target: 0.5, strategy: AnyStrategy::new(Immediate) },
+///     ThrottleCommandDyn::SetSpeed { target: half, strategy: AnyStrategy::new(Immediate) },
 ///     CommandSource::Mqtt,
 ///     0,
 /// );
 /// let physical = PrioritizedCommand::new(
-///     ThrottleCommandDyn::SetSpeed { target: 0.5, strategy: AnyStrategy::new(Immediate) },
+///     ThrottleCommandDyn::SetSpeed { target: half, strategy: AnyStrategy::new(Immediate) },
 ///     CommandSource::Physical,
 ///     0,
 /// );
 ///
 /// // Physical has higher priority
 /// assert!(physical > mqtt);
+///
+/// // Equal priority: the earlier sequence number goes first
+/// let first = mqtt.clone().with_sequence(1);
+/// let second = mqtt.with_sequence(2);
+/// assert!(first > second);
 /// ```
+///
+/// Sources are ranked by [`SourcePriorities::default`] unless the command
+/// is given another table with [`with_priorities`](Self::with_priorities).
+/// [`CommandQueue`](crate::CommandQueue) stamps each pushed command with
+/// the next sequence number, so equal-priority commands pop in arrival
+/// order; `timestamp_ms` plays no part in ordering.
 #[derive(Clone, Debug)]
 pub struct PrioritizedCommand {
     /// The actual command to execute.
@@ -388,6 +965,10 @@ pub struct PrioritizedCommand {
     pub source: CommandSource,
     /// Timestamp when the command was issued (milliseconds since start).
     pub timestamp_ms: u64,
+    /// Client within the source that issued this command, if known.
+    pub client: Option<ClientId>,
+    priorities: SourcePriorities,
+    sequence: u64,
 }
 
 impl PrioritizedCommand {
@@ -397,21 +978,49 @@ impl PrioritizedCommand {
             command,
             source,
             timestamp_ms,
+            client: None,
+            priorities: SourcePriorities::default(),
+            sequence: 0,
         }
     }
 
-    /// Get effective priority (source, command_type)
-    /// E-stop from any source gets promoted to Emergency level
-    pub fn priority(&self) -> (CommandSource, CommandType) {
-        let cmd_type = self.command.command_type();
+    /// Attribute the command to `client`
+    pub fn with_client(mut self, client: ClientId) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the arrival sequence number used to break priority ties
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Arrival sequence number (lower arrived earlier)
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Rank the source with `priorities` instead of the default table
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.priorities = priorities;
+        self
+    }
 
-        let effective_source = if self.command.is_estop() {
+    /// Source the command is ranked as
+    /// E-stop from any source gets promoted to Emergency level
+    pub fn effective_source(&self) -> CommandSource {
+        if self.command.is_estop() {
             CommandSource::Emergency
         } else {
             self.source
-        };
+        }
+    }
 
-        (effective_source, cmd_type)
+    /// Get effective priority (source rank, command_type)
+    pub fn priority(&self) -> (u16, CommandType) {
+        let rank = self.priorities.rank(self.effective_source());
+        (rank, self.command.command_type())
     }
 }
 
@@ -419,13 +1028,16 @@ impl Eq for PrioritizedCommand {}
 
 impl PartialEq for PrioritizedCommand {
     fn eq(&self, other: &Self) -> bool {
-        self.priority() == other.priority() && self.timestamp_ms == other.timestamp_ms
+        self.cmp(other) == core::cmp::Ordering::Equal
     }
 }
 
 impl Ord for PrioritizedCommand {
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        self.priority().cmp(&other.priority())
+        // Earlier arrivals rank higher within the same priority
+        self.priority()
+            .cmp(&other.priority())
+            .then_with(|| other.sequence.cmp(&self.sequence))
     }
 }
 
@@ -471,7 +1083,8 @@ pub enum CommandOutcome {
 /// fn handle_result(result: TransitionResult) {
 ///     match result {
 ///         TransitionResult::Started => println!("Transition started"),
-///         TransitionResult::Queued => println!("Command queued for later"),
+///         TransitionResult::Queued { id } => println!("Command {} queued for later", id),
+///         TransitionResult::Deferred => println!("Held back for a newer command"),
 ///         TransitionResult::Rejected { reason } => {
 ///             println!("Rejected: {:?}", reason);
 ///         }
@@ -492,7 +1105,16 @@ pub enum TransitionResult {
     ///
     /// This happens when a locked transition is in progress and its
     /// [`InterruptBehavior`](crate::traits::InterruptBehavior) is `Queue`.
-    Queued,
+    Queued {
+        /// Id of the queue entry, for cancelling it.
+        id: u32,
+    },
+
+    /// Command was held back to coalesce a burst of speed commands.
+    ///
+    /// It is applied shortly, unless a newer speed command from the same
+    /// source replaces it first. See `services::SharedThrottleState::submit`.
+    Deferred,
 
     /// Command was rejected.
     ///
@@ -507,7 +1129,7 @@ pub enum TransitionResult {
     /// The previous transition was cancelled and the new one started.
     Interrupted {
         /// The target speed of the interrupted transition.
-        previous_target: f32,
+        previous_target: Speed,
     },
 }
 
@@ -539,8 +1161,89 @@ pub enum RejectReason {
     /// Command queue is full.
     ///
     /// The command processor's queue is at capacity and the new command
-    /// doesn't have high enough priority to displace existing commands.
+    /// doesn't have high enough priority to displace existing commands,
+    /// or the transition queue behind a locked transition is full.
     QueueFull,
+
+    /// No queued transition has the given id.
+    ///
+    /// Returned for [`ThrottleCommand::CancelQueued`] when the command
+    /// already started, was cancelled, or never existed.
+    NotQueued,
+
+    /// No speed transition is in progress.
+    ///
+    /// Returned for [`ThrottleCommand::Pause`] when there is nothing to pause.
+    NotTransitioning,
+
+    /// The speed transition isn't paused.
+    ///
+    /// Returned for [`ThrottleCommand::Resume`] when nothing is paused.
+    NotPaused,
+
+    /// The speed transition isn't locked.
+    ///
+    /// Returned for [`ThrottleCommand::ForceRelease`] when there is no lock
+    /// to release.
+    NotLocked,
+
+    /// Named strategy preset doesn't exist.
+    ///
+    /// The command's [`StrategySpec::Preset`] isn't in the controller's
+    /// preset registry.
+    ///
+    /// [`StrategySpec::Preset`]: crate::traits::StrategySpec::Preset
+    UnknownPreset,
+
+    /// Command doesn't apply in the current driving mode.
+    ///
+    /// Speed and velocity targets are refused in [`DrivingMode::Cab`];
+    /// notch and brake commands are refused in [`DrivingMode::Direct`].
+    WrongDrivingMode,
+
+    /// The service brake is holding the train.
+    ///
+    /// Commands that would move the train are refused until the brake is
+    /// released with [`ThrottleCommand::ReleaseBrake`].
+    BrakeHeld,
+
+    /// The command's source exceeded its rate limit.
+    ///
+    /// See [`RateLimits`](crate::priority::RateLimits).
+    RateLimited,
+
+    /// Another client holds the throttle lease.
+    ///
+    /// Returned when acquiring a lease that is already held, or releasing
+    /// one held by someone else.
+    LeaseHeld,
+
+    /// The command's client doesn't hold the throttle lease.
+    ///
+    /// While a lease is held, only its holder may drive from a remote
+    /// source. E-stops are always accepted.
+    NotLeaseHolder,
+}
+
+impl RejectReason {
+    /// Returns the reason as a snake_case string, as used in JSON.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::TransitionLocked => "transition_locked",
+            Self::LowerPriority => "lower_priority",
+            Self::QueueFull => "queue_full",
+            Self::NotQueued => "not_queued",
+            Self::NotTransitioning => "not_transitioning",
+            Self::NotPaused => "not_paused",
+            Self::NotLocked => "not_locked",
+            Self::UnknownPreset => "unknown_preset",
+            Self::WrongDrivingMode => "wrong_driving_mode",
+            Self::BrakeHeld => "brake_held",
+            Self::RateLimited => "rate_limited",
+            Self::LeaseHeld => "lease_held",
+            Self::NotLeaseHolder => "not_lease_holder",
+        }
+    }
 }
 
 /// Type alias for priority tuple (source, command_type).
@@ -581,20 +1284,89 @@ mod tests {
         assert_eq!(s, "Physical");
     }
 
+    #[test]
+    fn command_source_is_remote() {
+        assert!(CommandSource::Mqtt.is_remote());
+        assert!(CommandSource::WebApi.is_remote());
+        assert!(CommandSource::WebLocal.is_remote());
+        assert!(!CommandSource::Physical.is_remote());
+        assert!(!CommandSource::Fault.is_remote());
+        assert!(!CommandSource::Emergency.is_remote());
+    }
+
+    #[test]
+    fn command_source_as_str() {
+        assert_eq!(CommandSource::Mqtt.as_str(), "mqtt");
+        assert_eq!(CommandSource::WebApi.as_str(), "web_api");
+        assert_eq!(CommandSource::Emergency.as_str(), "emergency");
+    }
+
+    // === SourcePriorities Tests ===
+    #[test]
+    fn source_priorities_default_follows_declaration_order() {
+        let priorities = SourcePriorities::default();
+        let sources = [
+            CommandSource::Mqtt,
+            CommandSource::WebApi,
+            CommandSource::WebLocal,
+            CommandSource::Physical,
+            CommandSource::Fault,
+            CommandSource::Emergency,
+        ];
+        for pair in sources.windows(2) {
+            assert!(priorities.outranks(pair[1], pair[0]));
+            assert_eq!(priorities.compare(pair[0], pair[1]), pair[0].cmp(&pair[1]));
+        }
+    }
+
+    #[test]
+    fn source_priorities_rerank() {
+        let priorities = SourcePriorities::default()
+            .with_rank(CommandSource::Mqtt, 2)
+            .with_rank(CommandSource::WebLocal, 0);
+
+        assert!(priorities.outranks(CommandSource::Mqtt, CommandSource::WebLocal));
+        assert!(priorities.is_below(CommandSource::WebLocal, CommandSource::WebApi));
+        assert_eq!(
+            priorities.compare(CommandSource::Mqtt, CommandSource::WebLocal),
+            core::cmp::Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn source_priorities_pin_fault_and_emergency() {
+        let priorities = SourcePriorities::default()
+            .with_rank(CommandSource::Physical, u8::MAX)
+            .with_rank(CommandSource::Fault, 0)
+            .with_rank(CommandSource::Emergency, 0);
+
+        assert!(priorities.outranks(CommandSource::Fault, CommandSource::Physical));
+        assert!(priorities.outranks(CommandSource::Emergency, CommandSource::Fault));
+    }
+
+    #[test]
+    fn source_priorities_equal_ranks_tie() {
+        let priorities = SourcePriorities::default().with_rank(CommandSource::Mqtt, 3);
+        assert!(!priorities.outranks(CommandSource::Mqtt, CommandSource::Physical));
+        assert!(!priorities.is_below(CommandSource::Mqtt, CommandSource::Physical));
+    }
+
     // === CommandType Tests ===
     #[test]
     fn command_type_ordering() {
+        assert!(CommandType::Heartbeat < CommandType::SetMaxSpeed);
         assert!(CommandType::SetMaxSpeed < CommandType::SetDirection);
         assert!(CommandType::SetDirection < CommandType::SetSpeed);
-        assert!(CommandType::SetSpeed < CommandType::EmergencyStop);
+        assert!(CommandType::SetSpeed < CommandType::Brake);
+        assert!(CommandType::Brake < CommandType::EmergencyStop);
     }
 
     // === ThrottleCommand Tests ===
     #[test]
     fn throttle_command_speed_immediate() {
-        let cmd = ThrottleCommand::speed_immediate(0.5);
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
         assert!(
-            matches!(cmd, ThrottleCommand::SetSpeed { target, .. } if (target - 0.5).abs() < 0.001)
+            matches!(cmd, ThrottleCommand::SetSpeed { target, .. } if (target.get() - 0.5).abs() < 0.001)
         );
         assert_eq!(cmd.command_type(), CommandType::SetSpeed);
     }
@@ -606,6 +1378,34 @@ mod tests {
         assert_eq!(cmd.command_type(), CommandType::EmergencyStop);
     }
 
+    #[test]
+    fn throttle_command_brake() {
+        let cmd = ThrottleCommand::brake(true);
+        assert!(matches!(
+            cmd,
+            ThrottleCommand::Brake {
+                strategy: None,
+                hold: true
+            }
+        ));
+        assert_eq!(cmd.command_type(), CommandType::Brake);
+
+        let cmd: ThrottleCommand = ThrottleCommand::ReleaseBrake;
+        assert_eq!(cmd.command_type(), CommandType::Brake);
+
+        let dyn_cmd: ThrottleCommandDyn = ThrottleCommand::Brake {
+            strategy: Some(Linear::locked(1500)),
+            hold: false,
+        }
+        .into();
+        let ThrottleCommandDyn::Brake { strategy, hold } = &dyn_cmd else {
+            panic!("expected brake");
+        };
+        assert_eq!(strategy.as_ref().and_then(|s| s.duration_ms()), Some(1500));
+        assert!(!hold);
+        assert_eq!(dyn_cmd.command_type(), CommandType::Brake);
+    }
+
     #[test]
     fn throttle_command_set_direction() {
         let cmd: ThrottleCommand = ThrottleCommand::SetDirection(Direction::Forward);
@@ -614,14 +1414,14 @@ mod tests {
 
     #[test]
     fn throttle_command_set_max_speed() {
-        let cmd: ThrottleCommand = ThrottleCommand::SetMaxSpeed(0.8);
+        let cmd: ThrottleCommand = ThrottleCommand::SetMaxSpeed(Speed::literal(0.8));
         assert_eq!(cmd.command_type(), CommandType::SetMaxSpeed);
     }
 
     #[test]
     fn throttle_command_with_strategy() {
         let cmd = ThrottleCommand::SetSpeed {
-            target: 0.75,
+            target: Speed::literal(0.75),
             strategy: Linear::new(1000),
         };
         assert_eq!(cmd.command_type(), CommandType::SetSpeed);
@@ -630,11 +1430,11 @@ mod tests {
     // === ThrottleCommandDyn Tests ===
     #[test]
     fn throttle_command_dyn_from_immediate() {
-        let cmd = ThrottleCommand::speed_immediate(0.5);
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
         let dyn_cmd: ThrottleCommandDyn = cmd.into();
 
         assert!(
-            matches!(dyn_cmd, ThrottleCommandDyn::SetSpeed { target, .. } if (target - 0.5).abs() < 0.001)
+            matches!(dyn_cmd, ThrottleCommandDyn::SetSpeed { target, .. } if (target.get() - 0.5).abs() < 0.001)
         );
         assert_eq!(dyn_cmd.command_type(), CommandType::SetSpeed);
     }
@@ -642,13 +1442,13 @@ mod tests {
     #[test]
     fn throttle_command_dyn_from_linear() {
         let cmd = ThrottleCommand::SetSpeed {
-            target: 0.75,
+            target: Speed::literal(0.75),
             strategy: Linear::new(1000),
         };
         let dyn_cmd: ThrottleCommandDyn = cmd.into();
 
         assert!(
-            matches!(dyn_cmd, ThrottleCommandDyn::SetSpeed { target, .. } if (target - 0.75).abs() < 0.001)
+            matches!(dyn_cmd, ThrottleCommandDyn::SetSpeed { target, .. } if (target.get() - 0.75).abs() < 0.001)
         );
     }
 
@@ -672,19 +1472,66 @@ mod tests {
         assert!(dyn_cmd.is_estop());
     }
 
+    #[test]
+    fn throttle_command_dyn_from_velocity() {
+        let cmd = ThrottleCommand::SetVelocity {
+            target: Velocity::new(-0.4).unwrap(),
+            strategy: Linear::new(1000),
+        };
+        assert_eq!(cmd.command_type(), CommandType::SetSpeed);
+        let dyn_cmd: ThrottleCommandDyn = cmd.into();
+
+        assert!(matches!(
+            dyn_cmd,
+            ThrottleCommandDyn::SetVelocity { target, strategy }
+                if target == -0.4 && strategy.duration_ms() == Some(1000)
+        ));
+    }
+
     #[test]
     fn throttle_command_dyn_from_max_speed() {
-        let cmd: ThrottleCommand = ThrottleCommand::SetMaxSpeed(0.6);
+        let cmd: ThrottleCommand = ThrottleCommand::SetMaxSpeed(Speed::literal(0.6));
         let dyn_cmd: ThrottleCommandDyn = cmd.into();
 
-        assert!(matches!(dyn_cmd, ThrottleCommandDyn::SetMaxSpeed(s) if (s - 0.6).abs() < 0.001));
+        assert!(
+            matches!(dyn_cmd, ThrottleCommandDyn::SetMaxSpeed(s) if (s.get() - 0.6).abs() < 0.001)
+        );
+    }
+
+    #[test]
+    fn throttle_command_dyn_from_queue_commands() {
+        let cmd: ThrottleCommand = ThrottleCommand::CancelQueued(3);
+        assert_eq!(cmd.command_type(), CommandType::SetSpeed);
+        let dyn_cmd: ThrottleCommandDyn = cmd.into();
+        assert!(matches!(dyn_cmd, ThrottleCommandDyn::CancelQueued(3)));
+        assert_eq!(dyn_cmd.command_type(), CommandType::SetSpeed);
+
+        let dyn_cmd: ThrottleCommandDyn = ThrottleCommand::<Immediate>::ClearQueue.into();
+        assert!(matches!(dyn_cmd, ThrottleCommandDyn::ClearQueue));
+    }
+
+    #[test]
+    fn throttle_command_dyn_from_pause_resume() {
+        let dyn_cmd: ThrottleCommandDyn = ThrottleCommand::<Immediate>::Pause.into();
+        assert!(matches!(dyn_cmd, ThrottleCommandDyn::Pause));
+        assert_eq!(dyn_cmd.command_type(), CommandType::SetSpeed);
+
+        let dyn_cmd: ThrottleCommandDyn = ThrottleCommand::<Immediate>::Resume.into();
+        assert!(matches!(dyn_cmd, ThrottleCommandDyn::Resume));
+    }
+
+    #[test]
+    fn throttle_command_dyn_from_force_release() {
+        let dyn_cmd: ThrottleCommandDyn = ThrottleCommand::<Immediate>::ForceRelease.into();
+        assert!(matches!(dyn_cmd, ThrottleCommandDyn::ForceRelease));
+        assert_eq!(dyn_cmd.command_type(), CommandType::SetSpeed);
     }
 
     #[test]
     fn throttle_command_dyn_is_estop() {
         let estop = ThrottleCommandDyn::EmergencyStop;
         let speed = ThrottleCommandDyn::SetSpeed {
-            target: 0.5,
+            target: Speed::literal(0.5),
             strategy: AnyStrategy::new(Immediate),
         };
 
@@ -705,13 +1552,17 @@ mod tests {
     #[test]
     fn prioritized_command_priority_normal() {
         let cmd = ThrottleCommandDyn::SetSpeed {
-            target: 0.5,
+            target: Speed::literal(0.5),
             strategy: AnyStrategy::new(Immediate),
         };
         let pc = PrioritizedCommand::new(cmd, CommandSource::WebApi, 0);
 
-        let (source, cmd_type) = pc.priority();
-        assert_eq!(source, CommandSource::WebApi);
+        let (rank, cmd_type) = pc.priority();
+        assert_eq!(pc.effective_source(), CommandSource::WebApi);
+        assert_eq!(
+            rank,
+            SourcePriorities::default().rank(CommandSource::WebApi)
+        );
         assert_eq!(cmd_type, CommandType::SetSpeed);
     }
 
@@ -720,9 +1571,9 @@ mod tests {
         let cmd = ThrottleCommandDyn::EmergencyStop;
         let pc = PrioritizedCommand::new(cmd, CommandSource::Mqtt, 0);
 
-        let (source, cmd_type) = pc.priority();
+        let (_, cmd_type) = pc.priority();
         // E-stop from Mqtt should be promoted to Emergency
-        assert_eq!(source, CommandSource::Emergency);
+        assert_eq!(pc.effective_source(), CommandSource::Emergency);
         assert_eq!(cmd_type, CommandType::EmergencyStop);
     }
 
@@ -730,7 +1581,7 @@ mod tests {
     fn prioritized_command_ordering_by_source() {
         let mqtt_cmd = PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
+                target: Speed::literal(0.5),
                 strategy: AnyStrategy::new(Immediate),
             },
             CommandSource::Mqtt,
@@ -738,7 +1589,7 @@ mod tests {
         );
         let physical_cmd = PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
+                target: Speed::literal(0.5),
                 strategy: AnyStrategy::new(Immediate),
             },
             CommandSource::Physical,
@@ -750,11 +1601,36 @@ mod tests {
         assert!(mqtt_cmd < physical_cmd);
     }
 
+    #[test]
+    fn prioritized_command_ordering_follows_priorities() {
+        let priorities = SourcePriorities::default().with_rank(CommandSource::Mqtt, 4);
+        let mqtt_cmd = PrioritizedCommand::new(
+            ThrottleCommandDyn::SetSpeed {
+                target: Speed::literal(0.5),
+                strategy: AnyStrategy::new(Immediate),
+            },
+            CommandSource::Mqtt,
+            0,
+        )
+        .with_priorities(priorities);
+        let physical_cmd = PrioritizedCommand::new(
+            ThrottleCommandDyn::SetSpeed {
+                target: Speed::literal(0.5),
+                strategy: AnyStrategy::new(Immediate),
+            },
+            CommandSource::Physical,
+            0,
+        )
+        .with_priorities(priorities);
+
+        assert!(mqtt_cmd > physical_cmd);
+    }
+
     #[test]
     fn prioritized_command_ordering_by_type() {
         let speed_cmd = PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
+                target: Speed::literal(0.5),
                 strategy: AnyStrategy::new(Immediate),
             },
             CommandSource::Physical,
@@ -774,7 +1650,7 @@ mod tests {
     fn prioritized_command_equality() {
         let cmd1 = PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
+                target: Speed::literal(0.5),
                 strategy: AnyStrategy::new(Immediate),
             },
             CommandSource::Physical,
@@ -782,37 +1658,44 @@ mod tests {
         );
         let cmd2 = PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.8, // Different target
+                target: Speed::literal(0.8), // Different target
                 strategy: AnyStrategy::new(Immediate),
             },
             CommandSource::Physical, // Same source
-            100,                     // Same timestamp
+            200,                     // Different timestamp
         );
 
-        // Same priority and timestamp means equal
+        // Same priority and sequence means equal
         assert_eq!(cmd1, cmd2);
     }
 
     #[test]
-    fn prioritized_command_inequality_different_timestamp() {
-        let cmd1 = PrioritizedCommand::new(
+    fn prioritized_command_inequality_different_sequence() {
+        let cmd = PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
+                target: Speed::literal(0.5),
                 strategy: AnyStrategy::new(Immediate),
             },
             CommandSource::Physical,
             100,
         );
-        let cmd2 = PrioritizedCommand::new(
-            ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
-                strategy: AnyStrategy::new(Immediate),
-            },
-            CommandSource::Physical,
-            200, // Different timestamp
-        );
+        let first = cmd.clone().with_sequence(1);
+        let second = cmd.with_sequence(2);
 
-        assert_ne!(cmd1, cmd2);
+        assert_ne!(first, second);
+        assert!(first > second);
+        assert_eq!(second.sequence(), 2);
+    }
+
+    #[test]
+    fn prioritized_command_priority_beats_sequence() {
+        let mqtt = PrioritizedCommand::new(ThrottleCommandDyn::ClearQueue, CommandSource::Mqtt, 0)
+            .with_sequence(1);
+        let physical =
+            PrioritizedCommand::new(ThrottleCommandDyn::ClearQueue, CommandSource::Physical, 0)
+                .with_sequence(2);
+
+        assert!(physical > mqtt);
     }
 
     // === RejectReason Tests ===
@@ -835,16 +1718,16 @@ mod tests {
     #[test]
     fn transition_result_variants() {
         let started = TransitionResult::Started;
-        let queued = TransitionResult::Queued;
+        let queued = TransitionResult::Queued { id: 1 };
         let rejected = TransitionResult::Rejected {
             reason: RejectReason::TransitionLocked,
         };
         let interrupted = TransitionResult::Interrupted {
-            previous_target: 0.5,
+            previous_target: Speed::literal(0.5),
         };
 
         assert!(matches!(started, TransitionResult::Started));
-        assert!(matches!(queued, TransitionResult::Queued));
+        assert!(matches!(queued, TransitionResult::Queued { id: 1 }));
         assert!(matches!(
             rejected,
             TransitionResult::Rejected {
@@ -853,7 +1736,7 @@ mod tests {
         ));
         assert!(matches!(
             interrupted,
-            TransitionResult::Interrupted { previous_target } if (previous_target - 0.5).abs() < 0.001
+            TransitionResult::Interrupted { previous_target } if (previous_target.get() - 0.5).abs() < 0.001
         ));
     }
 
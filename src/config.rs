@@ -17,6 +17,12 @@
 //!     .with_web(WebConfig::default().with_port(3000));
 //! ```
 
+use crate::cab::{DrivingMode, LocoProfile};
+use crate::commands::{CommandSource, SourcePriorities};
+use crate::priority::{RateLimit, RateLimits, SafeStop, DEFAULT_LEASE_MS};
+use crate::traits::{
+    preset_name, EaseInOut, Linear, Momentum, PresetName, PresetNameError, StrategySpec,
+};
 use heapless::String as HString;
 
 /// Maximum length for short config strings (hostnames, client IDs)
@@ -293,6 +299,49 @@ pub struct ThrottleConfig {
     pub update_interval_ms: u32,
     /// Physical control lockout duration in milliseconds
     pub lockout_ms: u32,
+    /// Remote source heartbeat lease in milliseconds (0 = disabled)
+    pub heartbeat_timeout_ms: u32,
+    /// Action taken when a remote source's heartbeat lease lapses
+    pub safe_stop: SafeStop,
+    /// Deceleration profile for service brake applications
+    #[cfg_attr(feature = "serde", serde(default = "default_service_brake"))]
+    pub service_brake: StrategySpec,
+    /// Named strategies selectable from HTTP/MQTT payloads
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub presets: StrategyPresets,
+    /// Driving mode the throttle starts in
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub driving_mode: DrivingMode,
+    /// Roster entry driven by this throttle in cab mode (`None` = default profile)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub loco: Option<ShortString>,
+    /// Locomotive profiles selectable by name
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub roster: Roster,
+    /// Longest a transition lock holds in milliseconds (0 = no limit)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_lock_ms: u32,
+    /// Lowest source allowed to force-release a transition lock
+    #[cfg_attr(feature = "serde", serde(default = "default_force_release_source"))]
+    pub force_release_source: CommandSource,
+    /// Priority rank of each command source
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priorities: SourcePriorities,
+    /// Per-source command rate limits (unset = unlimited)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rate_limits: RateLimits,
+    /// Throttle lease duration in milliseconds without commands from its holder
+    #[cfg_attr(feature = "serde", serde(default = "default_lease_ms"))]
+    pub lease_ms: u32,
+    /// Whether remote clients must hold the throttle lease to drive
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lease_required: bool,
+    /// Whether a source may steal the lease from a holder of equal priority
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lease_steal_same_rank: bool,
+    /// Window in milliseconds for coalescing bursts of speed commands (0 = off)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub speed_coalesce_ms: u32,
 }
 
 impl Default for ThrottleConfig {
@@ -303,6 +352,21 @@ impl Default for ThrottleConfig {
             default_smooth: true,
             update_interval_ms: 20,
             lockout_ms: 2000,
+            heartbeat_timeout_ms: 0,
+            safe_stop: SafeStop::default(),
+            service_brake: default_service_brake(),
+            presets: StrategyPresets::default(),
+            driving_mode: DrivingMode::default(),
+            loco: None,
+            roster: Roster::default(),
+            max_lock_ms: 0,
+            force_release_source: default_force_release_source(),
+            priorities: SourcePriorities::default(),
+            rate_limits: RateLimits::default(),
+            lease_ms: default_lease_ms(),
+            lease_required: false,
+            lease_steal_same_rank: false,
+            speed_coalesce_ms: 0,
         }
     }
 }
@@ -337,6 +401,323 @@ impl ThrottleConfig {
         self.lockout_ms = ms;
         self
     }
+
+    /// Set the remote source heartbeat lease (0 = disabled)
+    pub fn with_heartbeat_timeout_ms(mut self, ms: u32) -> Self {
+        self.heartbeat_timeout_ms = ms;
+        self
+    }
+
+    /// Set the action taken when a heartbeat lease lapses
+    pub fn with_safe_stop(mut self, safe_stop: SafeStop) -> Self {
+        self.safe_stop = safe_stop;
+        self
+    }
+
+    /// Set the service brake deceleration profile
+    pub fn with_service_brake(mut self, strategy: impl Into<StrategySpec>) -> Self {
+        self.service_brake = strategy.into();
+        self
+    }
+
+    /// Define (or redefine) a named strategy preset
+    ///
+    /// Fails if `name` is longer than
+    /// [`MAX_PRESET_NAME`](crate::traits::MAX_PRESET_NAME) bytes.
+    pub fn with_preset(
+        mut self,
+        name: &str,
+        strategy: impl Into<StrategySpec>,
+    ) -> Result<Self, PresetNameError> {
+        self.presets = self.presets.with_preset(name, strategy)?;
+        Ok(self)
+    }
+
+    /// Set the driving mode the throttle starts in
+    pub fn with_driving_mode(mut self, mode: DrivingMode) -> Self {
+        self.driving_mode = mode;
+        self
+    }
+
+    /// Select the roster entry driven in cab mode
+    pub fn with_loco(mut self, name: &str) -> Self {
+        self.loco = Some(short_string(name));
+        self
+    }
+
+    /// Add (or redefine) a roster entry
+    pub fn with_roster_entry(mut self, name: &str, profile: LocoProfile) -> Self {
+        self.roster = self.roster.with_loco(name, profile);
+        self
+    }
+
+    /// Set the longest a transition lock holds (0 = no limit)
+    pub fn with_max_lock_ms(mut self, ms: u32) -> Self {
+        self.max_lock_ms = ms;
+        self
+    }
+
+    /// Set the lowest source allowed to force-release a transition lock
+    pub fn with_force_release_source(mut self, source: CommandSource) -> Self {
+        self.force_release_source = source;
+        self
+    }
+
+    /// Set the priority rank of one command source
+    pub fn with_source_rank(mut self, source: CommandSource, rank: u8) -> Self {
+        self.priorities = self.priorities.with_rank(source, rank);
+        self
+    }
+
+    /// Rate-limit commands from one source
+    pub fn with_rate_limit(mut self, source: CommandSource, limit: RateLimit) -> Self {
+        self.rate_limits = self.rate_limits.with_limit(source, limit);
+        self
+    }
+
+    /// Set the throttle lease duration
+    pub fn with_lease_ms(mut self, ms: u32) -> Self {
+        self.lease_ms = ms;
+        self
+    }
+
+    /// Set whether remote clients must hold the throttle lease to drive
+    pub fn with_lease_required(mut self, required: bool) -> Self {
+        self.lease_required = required;
+        self
+    }
+
+    /// Set whether a source may steal the lease from an equal-priority holder
+    pub fn with_lease_steal_same_rank(mut self, allow: bool) -> Self {
+        self.lease_steal_same_rank = allow;
+        self
+    }
+
+    /// Set the speed command coalescing window (0 = off)
+    pub fn with_speed_coalesce_ms(mut self, ms: u32) -> Self {
+        self.speed_coalesce_ms = ms;
+        self
+    }
+
+    /// Profile of the selected loco, falling back to the default profile
+    /// when none is selected or the name isn't in the roster.
+    pub fn loco_profile(&self) -> LocoProfile {
+        self.loco
+            .as_ref()
+            .and_then(|name| self.roster.get(name))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Default force release source: physical controls and above
+fn default_force_release_source() -> CommandSource {
+    CommandSource::Physical
+}
+
+/// Default throttle lease duration
+fn default_lease_ms() -> u32 {
+    DEFAULT_LEASE_MS as u32
+}
+
+/// Default service brake: a 2 second source-locked linear stop
+pub(crate) fn default_service_brake() -> StrategySpec {
+    StrategySpec::Linear(Linear::source_locked(2000))
+}
+
+// ============================================================================
+// Strategy Presets
+// ============================================================================
+
+/// Maximum number of strategy presets
+pub const MAX_PRESETS: usize = 8;
+
+/// A named strategy, selectable by name from remote payloads
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrategyPreset {
+    /// Preset name (e.g. "departure")
+    pub name: PresetName,
+    /// Strategy the name stands for
+    pub strategy: StrategySpec,
+}
+
+/// Registry of named strategy presets.
+///
+/// The default registry holds the built-in presets `departure`, `arrival`,
+/// `yard` and `express`; user config can redefine them or add more, up to
+/// [`MAX_PRESETS`].
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::config::StrategyPresets;
+/// use rs_trainz::traits::{Linear, StrategySpec};
+///
+/// let presets = StrategyPresets::default().with_preset("shunt", Linear::new(4000))?;
+/// assert!(presets.get("departure").is_some());
+/// assert_eq!(
+///     presets.resolve(&StrategySpec::preset("shunt")?),
+///     Some(StrategySpec::Linear(Linear::new(4000)))
+/// );
+/// # Ok::<(), rs_trainz::traits::PresetNameError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct StrategyPresets {
+    presets: heapless::Vec<StrategyPreset, MAX_PRESETS>,
+}
+
+impl Default for StrategyPresets {
+    fn default() -> Self {
+        Self::empty()
+            .with_preset("departure", EaseInOut::departure(3000))
+            .and_then(|p| p.with_preset("arrival", EaseInOut::arrival(3000)))
+            .and_then(|p| p.with_preset("yard", Linear::source_locked(2000)))
+            .and_then(|p| p.with_preset("express", Momentum::responsive()))
+            .expect("built-in preset names fit in MAX_PRESET_NAME")
+    }
+}
+
+impl StrategyPresets {
+    /// Create a registry with no presets
+    pub fn empty() -> Self {
+        Self {
+            presets: heapless::Vec::new(),
+        }
+    }
+
+    /// Define a preset, replacing any existing one with the same name.
+    ///
+    /// New names beyond [`MAX_PRESETS`] are ignored. Fails if `name` is
+    /// longer than [`MAX_PRESET_NAME`](crate::traits::MAX_PRESET_NAME) bytes.
+    pub fn with_preset(
+        mut self,
+        name: &str,
+        strategy: impl Into<StrategySpec>,
+    ) -> Result<Self, PresetNameError> {
+        let strategy = strategy.into();
+        let name = preset_name(name)?;
+        if let Some(existing) = self.presets.iter_mut().find(|p| p.name == name) {
+            existing.strategy = strategy;
+        } else {
+            let _ = self.presets.push(StrategyPreset { name, strategy });
+        }
+        Ok(self)
+    }
+
+    /// Look up a preset by name
+    pub fn get(&self, name: &str) -> Option<&StrategySpec> {
+        self.presets
+            .iter()
+            .find(|p| p.name.as_str() == name)
+            .map(|p| &p.strategy)
+    }
+
+    /// Resolve a spec that may name a preset.
+    ///
+    /// Concrete specs are returned unchanged. Returns `None` if the named
+    /// preset doesn't exist or itself refers to another preset.
+    pub fn resolve(&self, spec: &StrategySpec) -> Option<StrategySpec> {
+        match spec.preset_name() {
+            None => Some(spec.clone()),
+            Some(name) => self
+                .get(name)
+                .filter(|s| s.preset_name().is_none())
+                .cloned(),
+        }
+    }
+
+    /// Iterate over all presets
+    pub fn iter(&self) -> impl Iterator<Item = &StrategyPreset> {
+        self.presets.iter()
+    }
+}
+
+// ============================================================================
+// Loco Roster
+// ============================================================================
+
+/// Maximum number of roster entries
+pub const MAX_ROSTER: usize = 8;
+
+/// A named locomotive profile
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosterEntry {
+    /// Loco name (e.g. "freight")
+    pub name: ShortString,
+    /// Physics profile used in cab mode
+    pub profile: LocoProfile,
+}
+
+/// Roster of locomotive profiles used by the cab simulation.
+///
+/// The default roster holds `switcher`, `freight` and `passenger`; user
+/// config can redefine them or add more, up to [`MAX_ROSTER`].
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::config::Roster;
+/// use rs_trainz::LocoProfile;
+///
+/// let heavy = LocoProfile::freight().with_mass(8.0)?;
+/// let roster = Roster::default().with_loco("heavy", heavy);
+/// assert!(roster.get("switcher").is_some());
+/// assert_eq!(roster.get("heavy").unwrap().mass, 8.0);
+/// # Ok::<(), rs_trainz::LocoProfileError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Roster {
+    locos: heapless::Vec<RosterEntry, MAX_ROSTER>,
+}
+
+impl Default for Roster {
+    fn default() -> Self {
+        Self::empty()
+            .with_loco("switcher", LocoProfile::switcher())
+            .with_loco("freight", LocoProfile::freight())
+            .with_loco("passenger", LocoProfile::passenger())
+    }
+}
+
+impl Roster {
+    /// Create a roster with no entries
+    pub fn empty() -> Self {
+        Self {
+            locos: heapless::Vec::new(),
+        }
+    }
+
+    /// Add a loco, replacing any existing one with the same name.
+    ///
+    /// New names beyond [`MAX_ROSTER`] are ignored.
+    pub fn with_loco(mut self, name: &str, profile: LocoProfile) -> Self {
+        let name = short_string(name);
+        if let Some(existing) = self.locos.iter_mut().find(|e| e.name == name) {
+            existing.profile = profile;
+        } else {
+            let _ = self.locos.push(RosterEntry { name, profile });
+        }
+        self
+    }
+
+    /// Look up a loco profile by name
+    pub fn get(&self, name: &str) -> Option<&LocoProfile> {
+        self.locos
+            .iter()
+            .find(|e| e.name.as_str() == name)
+            .map(|e| &e.profile)
+    }
+
+    /// Iterate over all roster entries
+    pub fn iter(&self) -> impl Iterator<Item = &RosterEntry> {
+        self.locos.iter()
+    }
 }
 
 // ============================================================================
@@ -510,6 +891,105 @@ mod tests {
         assert_eq!(config.max_speed, 0.0);
     }
 
+    #[test]
+    fn throttle_service_brake_profile() {
+        let config = ThrottleConfig::default();
+        assert_eq!(config.service_brake, default_service_brake());
+
+        let config = config.with_service_brake(Linear::locked(3000));
+        assert_eq!(
+            config.service_brake,
+            StrategySpec::Linear(Linear::locked(3000))
+        );
+    }
+
+    // =========================================================================
+    // StrategyPresets Tests
+    // =========================================================================
+
+    #[test]
+    fn strategy_presets_builtins() {
+        let presets = StrategyPresets::default();
+        assert_eq!(
+            presets.get("departure"),
+            Some(&StrategySpec::EaseInOut(EaseInOut::departure(3000)))
+        );
+        assert!(presets.get("arrival").is_some());
+        assert!(presets.get("yard").is_some());
+        assert!(presets.get("express").is_some());
+        assert!(presets.get("missing").is_none());
+    }
+
+    #[test]
+    fn strategy_presets_override_and_add() {
+        let config = ThrottleConfig::default()
+            .with_preset("departure", Linear::new(5000))
+            .unwrap()
+            .with_preset("shunt", Linear::new(4000))
+            .unwrap();
+
+        assert_eq!(
+            config.presets.get("departure"),
+            Some(&StrategySpec::Linear(Linear::new(5000)))
+        );
+        assert_eq!(config.presets.iter().count(), 5);
+
+        let long = ThrottleConfig::default().with_preset("a-very-long-preset-name", Linear::new(1));
+        assert_eq!(long.err(), Some(PresetNameError));
+    }
+
+    #[test]
+    fn strategy_presets_capacity() {
+        let mut presets = StrategyPresets::empty();
+        for i in 0..(MAX_PRESETS + 2) {
+            let name = alloc::format!("p{}", i);
+            presets = presets.with_preset(&name, Linear::new(100)).unwrap();
+        }
+        assert_eq!(presets.iter().count(), MAX_PRESETS);
+    }
+
+    #[test]
+    fn strategy_presets_resolve() {
+        let presets = StrategyPresets::empty()
+            .with_preset("slow", Linear::new(4000))
+            .unwrap()
+            .with_preset("alias", StrategySpec::preset("slow").unwrap())
+            .unwrap();
+
+        assert_eq!(
+            presets.resolve(&StrategySpec::preset("slow").unwrap()),
+            Some(StrategySpec::Linear(Linear::new(4000)))
+        );
+        assert_eq!(
+            presets.resolve(&StrategySpec::Immediate),
+            Some(StrategySpec::Immediate)
+        );
+        assert_eq!(
+            presets.resolve(&StrategySpec::preset("nope").unwrap()),
+            None
+        );
+        assert_eq!(
+            presets.resolve(&StrategySpec::preset("alias").unwrap()),
+            None
+        );
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    #[test]
+    fn strategy_presets_serde() {
+        let presets = StrategyPresets::empty()
+            .with_preset("yard", Linear::source_locked(2000))
+            .unwrap();
+        let mut buf = [0u8; 256];
+        let len = serde_json_core::to_slice(&presets, &mut buf).unwrap();
+        assert!(buf[..len].starts_with(br#"[{"name":"yard","strategy":{"linear""#));
+        let (back, _): (StrategyPresets, _) = serde_json_core::from_slice(&buf[..len]).unwrap();
+        assert_eq!(back, presets);
+
+        let long = br#"[{"name":"a-very-long-preset-name","strategy":"immediate"}]"#;
+        assert!(serde_json_core::from_slice::<StrategyPresets>(long).is_err());
+    }
+
     // =========================================================================
     // WifiConfig Tests
     // =========================================================================
@@ -620,6 +1100,13 @@ mod tests {
         assert!(throttle.default_smooth);
         assert_eq!(throttle.update_interval_ms, 20);
         assert_eq!(throttle.lockout_ms, 2000);
+        assert_eq!(throttle.heartbeat_timeout_ms, 0);
+        assert_eq!(throttle.safe_stop, SafeStop::Ramp { duration_ms: 1000 });
+        assert_eq!(throttle.max_lock_ms, 0);
+        assert_eq!(throttle.force_release_source, CommandSource::Physical);
+        assert_eq!(throttle.priorities, SourcePriorities::default());
+        assert_eq!(throttle.rate_limits, RateLimits::default());
+        assert_eq!(throttle.speed_coalesce_ms, 0);
     }
 
     #[test]
@@ -628,12 +1115,68 @@ mod tests {
             .with_default_transition_ms(1000)
             .with_default_smooth(false)
             .with_update_interval_ms(50)
-            .with_lockout_ms(5000);
+            .with_lockout_ms(5000)
+            .with_heartbeat_timeout_ms(3000)
+            .with_safe_stop(SafeStop::EmergencyStop)
+            .with_max_lock_ms(10_000)
+            .with_force_release_source(CommandSource::WebLocal)
+            .with_speed_coalesce_ms(50);
 
         assert_eq!(throttle.default_transition_ms, 1000);
         assert!(!throttle.default_smooth);
         assert_eq!(throttle.update_interval_ms, 50);
         assert_eq!(throttle.lockout_ms, 5000);
+        assert_eq!(throttle.heartbeat_timeout_ms, 3000);
+        assert_eq!(throttle.safe_stop, SafeStop::EmergencyStop);
+        assert_eq!(throttle.max_lock_ms, 10_000);
+        assert_eq!(throttle.force_release_source, CommandSource::WebLocal);
+        assert_eq!(throttle.speed_coalesce_ms, 50);
+    }
+
+    #[test]
+    fn throttle_config_source_ranks() {
+        let throttle = ThrottleConfig::default()
+            .with_source_rank(CommandSource::Mqtt, 2)
+            .with_source_rank(CommandSource::WebLocal, 0);
+
+        assert!(throttle
+            .priorities
+            .outranks(CommandSource::Mqtt, CommandSource::WebLocal));
+        assert_eq!(throttle.priorities.rank(CommandSource::WebApi), 1);
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    #[test]
+    fn source_priorities_load_partial_table() {
+        let (priorities, _): (SourcePriorities, _) =
+            serde_json_core::from_slice(br#"{"mqtt":2,"web_local":0}"#).unwrap();
+        assert_eq!(
+            priorities,
+            SourcePriorities::default()
+                .with_rank(CommandSource::Mqtt, 2)
+                .with_rank(CommandSource::WebLocal, 0)
+        );
+    }
+
+    #[test]
+    fn throttle_config_rate_limits() {
+        let throttle =
+            ThrottleConfig::default().with_rate_limit(CommandSource::Mqtt, RateLimit::new(5, 10));
+
+        assert_eq!(
+            throttle.rate_limits.get(CommandSource::Mqtt),
+            Some(RateLimit::new(5, 10))
+        );
+        assert_eq!(throttle.rate_limits.get(CommandSource::WebApi), None);
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    #[test]
+    fn rate_limits_load_from_json() {
+        let (limits, _): (RateLimits, _) =
+            serde_json_core::from_slice(br#"{"mqtt":{"per_second":5,"burst":10}}"#).unwrap();
+        assert_eq!(limits.get(CommandSource::Mqtt), Some(RateLimit::new(5, 10)));
+        assert_eq!(limits.get(CommandSource::Physical), None);
     }
 
     // =========================================================================
@@ -696,4 +1239,67 @@ mod tests {
         assert!(s.len() <= MAX_SHORT_STRING);
         assert!(core::str::from_utf8(s.as_bytes()).is_ok());
     }
+
+    // =========================================================================
+    // Roster Tests
+    // =========================================================================
+
+    #[test]
+    fn roster_builtins() {
+        let roster = Roster::default();
+        assert_eq!(roster.get("switcher"), Some(&LocoProfile::switcher()));
+        assert_eq!(roster.get("freight"), Some(&LocoProfile::freight()));
+        assert_eq!(roster.get("passenger"), Some(&LocoProfile::passenger()));
+        assert!(roster.get("missing").is_none());
+    }
+
+    #[test]
+    fn roster_capacity() {
+        let mut roster = Roster::empty();
+        for i in 0..MAX_ROSTER + 2 {
+            let name = alloc::format!("loco{}", i);
+            roster = roster.with_loco(&name, LocoProfile::default());
+        }
+        assert_eq!(roster.iter().count(), MAX_ROSTER);
+    }
+
+    #[test]
+    fn throttle_loco_profile_resolution() {
+        let config = ThrottleConfig::default();
+        assert_eq!(config.driving_mode, DrivingMode::Direct);
+        assert_eq!(config.loco_profile(), LocoProfile::default());
+
+        let config = ThrottleConfig::default()
+            .with_driving_mode(DrivingMode::Cab)
+            .with_loco("freight");
+        assert_eq!(config.loco_profile(), LocoProfile::freight());
+
+        let heavy = LocoProfile::freight().with_mass(8.0).unwrap();
+        let config = config.with_roster_entry("heavy", heavy).with_loco("heavy");
+        assert_eq!(config.loco_profile(), heavy);
+
+        let config = config.with_loco("unknown");
+        assert_eq!(config.loco_profile(), LocoProfile::default());
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    #[test]
+    fn roster_serde() {
+        let roster = Roster::empty().with_loco("yard", LocoProfile::switcher());
+        let mut buf = [0u8; 512];
+        let len = serde_json_core::to_slice(&roster, &mut buf).unwrap();
+        let (back, _): (Roster, _) = serde_json_core::from_slice(&buf[..len]).unwrap();
+        assert_eq!(back, roster);
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    #[test]
+    fn roster_serde_rejects_invalid_profiles() {
+        let parse =
+            |json: &str| serde_json_core::from_str::<Roster>(json).map(|(roster, _)| roster);
+        let roster = parse(r#"[{"name":"heavy","profile":{"mass":8.0}}]"#).unwrap();
+        assert_eq!(roster.get("heavy").unwrap().mass, 8.0);
+        assert!(parse(r#"[{"name":"heavy","profile":{"mass":0.0}}]"#).is_err());
+        assert!(parse(r#"[{"name":"slippery","profile":{"drag":-0.1}}]"#).is_err());
+    }
 }
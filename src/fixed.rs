@@ -0,0 +1,275 @@
+//! Fixed-point arithmetic for microcontrollers without an FPU.
+//!
+//! The ESP32-C3 has no floating-point unit, so every `f32` operation is a
+//! software routine. [`Q16`] is a signed Q16.16 fixed-point number that the
+//! built-in strategies, the transition manager and motor duty computation can
+//! use instead, with nothing but integer adds, multiplies and shifts.
+//!
+//! Enable the `fixed-point` cargo feature to run the per-tick path of
+//! [`TransitionManager`] and [`ThrottleController`] in fixed point. The
+//! public API still speaks `f32`; values are converted only at the edges
+//! (incoming commands and state snapshots).
+//!
+//! # Accuracy
+//!
+//! One Q16 step is 1/65536 (about 0.0015%). The built-in strategies match
+//! their `f32` implementation within [`Q16::TOLERANCE`] (0.1% of full speed),
+//! which is less than one step of a 10-bit PWM.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rs_trainz::fixed::Q16;
+//!
+//! let half = Q16::from_ratio(1, 2);
+//! assert_eq!(half, Q16::from_f32(0.5));
+//! assert_eq!(half.duty(1023), 511);
+//! assert_eq!((half * half).to_f32(), 0.25);
+//! ```
+//!
+//! [`TransitionManager`]: crate::transition::TransitionManager
+//! [`ThrottleController`]: crate::throttle::ThrottleController
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Signed Q16.16 fixed-point number.
+///
+/// Covers roughly -32768.0 to 32768.0 with a resolution of 1/65536.
+/// Arithmetic saturates instead of wrapping.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Q16(i32);
+
+impl Q16 {
+    /// Number of fractional bits
+    pub const FRAC_BITS: u32 = 16;
+    /// 0.0
+    pub const ZERO: Self = Self(0);
+    /// 1.0
+    pub const ONE: Self = Self(1 << Self::FRAC_BITS);
+    /// Largest representable value
+    pub const MAX: Self = Self(i32::MAX);
+    /// Smallest representable value
+    pub const MIN: Self = Self(i32::MIN);
+    /// Documented worst-case difference from the `f32` strategies
+    pub const TOLERANCE: f32 = 0.001;
+
+    /// Create from the raw Q16.16 representation
+    pub const fn from_raw(raw: i32) -> Self {
+        Self(raw)
+    }
+
+    /// The raw Q16.16 representation
+    pub const fn raw(self) -> i32 {
+        self.0
+    }
+
+    /// Convert from `f32`, rounding to the nearest step and saturating
+    /// out-of-range values (NaN becomes zero)
+    pub fn from_f32(value: f32) -> Self {
+        let scaled = value * Self::ONE.0 as f32;
+        let half = if scaled < 0.0 { -0.5 } else { 0.5 };
+        Self((scaled + half) as i32)
+    }
+
+    /// Convert to `f32`
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    /// `num / den` as a fixed-point value (`ONE` if `den` is zero)
+    pub fn from_ratio(num: u64, den: u64) -> Self {
+        if den == 0 {
+            return Self::ONE;
+        }
+        if num > u64::MAX >> Self::FRAC_BITS {
+            return Self::MAX;
+        }
+        let raw = (num << Self::FRAC_BITS) / den;
+        Self(raw.min(i32::MAX as u64) as i32)
+    }
+
+    /// Absolute value (saturating)
+    pub fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    /// Restrict to the range `min..=max`
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Self(self.0.clamp(min.0, max.0))
+    }
+
+    /// Linear interpolation from `from` to `to` at `t` (0.0 to 1.0)
+    pub fn lerp(from: Self, to: Self, t: Self) -> Self {
+        from + (to - from) * t
+    }
+
+    /// Smoothstep curve `t² × (3 - 2t)`
+    pub fn smoothstep(t: Self) -> Self {
+        let three = Self(3 << Self::FRAC_BITS);
+        t * t * (three - t - t)
+    }
+
+    /// Square root (zero for negative values)
+    pub fn sqrt(self) -> Self {
+        let wide = (self.0.max(0) as u64) << Self::FRAC_BITS;
+        Self(wide.isqrt() as i32)
+    }
+
+    /// PWM duty for this speed, clamped to 0.0 to 1.0 and truncated
+    /// like `(speed * max_duty as f32) as u32`
+    pub fn duty(self, max_duty: u32) -> u32 {
+        let speed = self.clamp(Self::ZERO, Self::ONE).0 as u64;
+        ((speed * max_duty as u64) >> Self::FRAC_BITS) as u32
+    }
+}
+
+impl Add for Q16 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Q16 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul for Q16 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let wide = (self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS;
+        Self(wide.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl Div for Q16 {
+    type Output = Self;
+
+    /// Saturates to `MAX`/`MIN` when dividing by zero
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return match self.0 {
+                0 => Self::ZERO,
+                n if n > 0 => Self::MAX,
+                _ => Self::MIN,
+            };
+        }
+        let wide = ((self.0 as i64) << Self::FRAC_BITS) / rhs.0 as i64;
+        Self(wide.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl Neg for Q16 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl From<Q16> for f32 {
+    fn from(value: Q16) -> Self {
+        value.to_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // === Conversion Tests ===
+    #[test]
+    fn q16_round_trips_f32() {
+        for v in [0.0, 0.25, 0.5, 1.0, -0.75, 100.5] {
+            assert_eq!(Q16::from_f32(v).to_f32(), v);
+        }
+        assert!((Q16::from_f32(0.3).to_f32() - 0.3).abs() < 1.0 / 65536.0);
+    }
+
+    #[test]
+    fn q16_from_f32_saturates() {
+        assert_eq!(Q16::from_f32(f32::NAN), Q16::ZERO);
+        assert_eq!(Q16::from_f32(1e9), Q16::MAX);
+        assert_eq!(Q16::from_f32(-1e9), Q16::MIN);
+    }
+
+    #[test]
+    fn q16_from_ratio() {
+        assert_eq!(Q16::from_ratio(1, 4), Q16::from_f32(0.25));
+        assert_eq!(Q16::from_ratio(3, 0), Q16::ONE);
+        assert_eq!(Q16::from_ratio(2500, 1000), Q16::from_f32(2.5));
+        assert_eq!(Q16::from_ratio(u64::MAX, 1), Q16::MAX);
+    }
+
+    // === Arithmetic Tests ===
+    #[test]
+    fn q16_arithmetic() {
+        let a = Q16::from_f32(0.75);
+        let b = Q16::from_f32(0.5);
+        assert_eq!((a + b).to_f32(), 1.25);
+        assert_eq!((a - b).to_f32(), 0.25);
+        assert_eq!((a * b).to_f32(), 0.375);
+        assert_eq!((a / b).to_f32(), 1.5);
+        assert_eq!((-a).to_f32(), -0.75);
+        assert_eq!((b - a).abs(), Q16::from_f32(0.25));
+    }
+
+    #[test]
+    fn q16_arithmetic_saturates() {
+        assert_eq!(Q16::MAX + Q16::ONE, Q16::MAX);
+        assert_eq!(Q16::MIN - Q16::ONE, Q16::MIN);
+        assert_eq!(Q16::MAX * Q16::from_f32(2.0), Q16::MAX);
+        assert_eq!(-Q16::MIN, Q16::MAX);
+        assert_eq!(Q16::ONE / Q16::ZERO, Q16::MAX);
+        assert_eq!(-Q16::ONE / Q16::ZERO, Q16::MIN);
+        assert_eq!(Q16::ZERO / Q16::ZERO, Q16::ZERO);
+        assert_eq!(Q16::MAX / Q16::from_f32(0.5), Q16::MAX);
+    }
+
+    #[test]
+    fn q16_sqrt() {
+        assert_eq!(Q16::from_f32(0.25).sqrt(), Q16::from_f32(0.5));
+        assert_eq!(Q16::from_f32(4.0).sqrt(), Q16::from_f32(2.0));
+        assert!((Q16::from_f32(0.1).sqrt().to_f32() - 0.316_227_8).abs() < 1.0 / 65536.0);
+        assert_eq!(Q16::from_f32(-1.0).sqrt(), Q16::ZERO);
+    }
+
+    #[test]
+    fn q16_lerp_and_smoothstep() {
+        let from = Q16::from_f32(0.2);
+        let to = Q16::from_f32(0.6);
+        let mid = Q16::lerp(from, to, Q16::from_ratio(1, 2));
+        assert!((mid.to_f32() - 0.4).abs() < Q16::TOLERANCE);
+
+        assert_eq!(Q16::smoothstep(Q16::ZERO), Q16::ZERO);
+        assert_eq!(Q16::smoothstep(Q16::ONE), Q16::ONE);
+        assert_eq!(
+            Q16::smoothstep(Q16::from_ratio(1, 2)),
+            Q16::from_ratio(1, 2)
+        );
+    }
+
+    // === Duty Tests ===
+    #[test]
+    fn q16_duty_matches_float() {
+        for i in 0..=1000u32 {
+            let speed = i as f32 / 1000.0;
+            let float_duty = (speed * 1023.0) as u32;
+            let fixed_duty = Q16::from_f32(speed).duty(1023);
+            assert!(float_duty.abs_diff(fixed_duty) <= 1, "speed {}", speed);
+        }
+    }
+
+    #[test]
+    fn q16_duty_clamps() {
+        assert_eq!(Q16::from_f32(1.5).duty(1023), 1023);
+        assert_eq!(Q16::from_f32(-0.5).duty(1023), 0);
+        assert_eq!(Q16::ONE.duty(255), 255);
+    }
+}
@@ -92,7 +92,7 @@ impl ThrottleDisplay for Esp32Display<'_> {
         let fill_style = PrimitiveStyle::with_fill(BinaryColor::On);
 
         // Speed bar at top (120 pixels wide max, leaving 4px margin each side)
-        let bar_width = (state.speed * 120.0) as u32;
+        let bar_width = (state.speed.get() * 120.0) as u32;
         if bar_width > 0 {
             Rectangle::new(Point::new(4, 2), Size::new(bar_width, 8))
                 .into_styled(fill_style)
@@ -101,7 +101,7 @@ impl ThrottleDisplay for Esp32Display<'_> {
 
         // Speed percentage text
         // Format speed without heap allocation
-        let speed_pct = (state.speed * 100.0) as u32;
+        let speed_pct = (state.speed.get() * 100.0) as u32;
         let mut speed_buf = [0u8; 16];
         let speed_text = format_speed(&mut speed_buf, speed_pct);
         Text::new(speed_text, Point::new(4, 26), text_style).draw(&mut self.display)?;
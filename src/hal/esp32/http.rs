@@ -8,10 +8,34 @@
 //!
 //! - `GET /api/state` - Get current throttle state (JSON)
 //! - `POST /api/speed` - Set speed `{"speed": 0.5}`
+//! - `POST /api/velocity` - Set signed velocity `{"velocity": -0.5}`
 //! - `POST /api/direction` - Set direction `{"direction": "forward"|"reverse"}`
 //! - `POST /api/estop` - Emergency stop
+//! - `POST /api/heartbeat` - Renew the heartbeat lease
+//! - `POST /api/service-brake` - Brake to a stop `{"hold": true}` (body optional)
+//! - `POST /api/service-brake/release` - Release a held service brake
+//! - `POST /api/notch` - Select a throttle notch `{"notch": 0..8}` (cab mode)
+//! - `POST /api/brake` - Move the brake handle `{"brake": "release"|"lap"|"apply"|"emergency"}`
+//! - `POST /api/mode` - Switch driving mode `{"mode": "direct"|"cab"}`
+//! - `GET /api/queue` - List queued speed commands
+//! - `POST /api/queue/cancel` - Cancel a queued command `{"id": 3}`
+//! - `POST /api/queue/clear` - Cancel all queued commands
+//! - `POST /api/preview` - Sample a strategy's speed curve `{"from": 0.0, "to": 0.8, "strategy": {...}}`
+//! - `POST /api/pause` - Freeze the active speed transition
+//! - `POST /api/resume` - Continue a paused speed transition
+//! - `POST /api/lock/release` - Force release the transition lock
+//! - `GET /api/lease` - Current throttle lease holder
+//! - `POST /api/lease/acquire` - Acquire the throttle lease `{"client": "cab-3"}` (body optional)
+//! - `POST /api/lease/release` - Release the throttle lease (same body)
+//! - `POST /api/lease/steal` - Take the throttle lease from its holder (same body)
+//! - `GET /api/metrics` - Counts of rate-limited, coalesced and queue-full drops
+//! - `POST /api/command` - Apply a versioned command envelope
 //! - `GET /` - Web UI (serves embedded HTML)
 //!
+//! Requests may name their client in an `X-Client-Id` header, as on the
+//! desktop server. While a client holds the throttle lease, the main loop
+//! drops commands from anyone else.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -25,17 +49,25 @@
 //! ```
 
 use crate::config::WebConfig;
-use crate::messages::{parse_direction_request, parse_speed_request};
-use crate::traits::{EaseInOut, Linear};
-use crate::{ThrottleCommand, ThrottleCommandDyn, ThrottleState};
+use crate::messages::{
+    parse_brake_request, parse_cancel_queued_request, parse_command, parse_direction_request,
+    parse_driving_mode_request, parse_lease_payload, parse_notch_request, parse_preview_request,
+    parse_service_brake_request, parse_speed_request, parse_velocity_request, CommandMessage,
+    LeaseAction, MessageError,
+};
+use crate::{
+    preview, AnyStrategy, ClientId, CommandSource, SourcePriorities, StrategyPresets,
+    ThrottleCommandDyn, ThrottleLease, ThrottleState,
+};
 use esp_idf_hal::io::Write;
 use esp_idf_svc::http::server::{Configuration, EspHttpServer};
+use esp_idf_svc::http::Headers;
 use esp_idf_svc::io::EspIOError;
 use std::sync::{Arc, Mutex};
 
 // Import shared helpers from http_handler (when available)
 #[cfg(any(feature = "web", feature = "mqtt"))]
-use crate::services::http_handler::state_to_json;
+use crate::services::http_handler::{lease_to_json, metrics_to_json, queue_to_json, state_to_json};
 
 // Fallback state_to_json for when services module isn't available.
 // Direction::as_str() is always available from the core crate.
@@ -43,13 +75,112 @@ use crate::services::http_handler::state_to_json;
 fn state_to_json(state: &ThrottleState) -> String {
     let target = state.target_speed.unwrap_or(state.speed);
     let is_transitioning = state.transition_progress.is_some();
+    let is_paused = state.transition_progress.as_ref().is_some_and(|p| p.paused);
+    let service_brake = match state.service_brake {
+        Some(brake) if brake.hold => "hold",
+        Some(_) => "applied",
+        None => "off",
+    };
+    let cab = match &state.cab {
+        Some(cab) => format!(
+            r#","mode":"cab","notch":{},"brake":"{}","brake_level":{:.2}"#,
+            cab.notch,
+            cab.brake.as_str(),
+            cab.brake_level
+        ),
+        None => String::from(r#","mode":"direct""#),
+    };
     format!(
-        r#"{{"speed":{:.2},"target_speed":{:.2},"direction":"{}","max_speed":{:.2},"is_transitioning":{}}}"#,
+        r#"{{"speed":{:.2},"target_speed":{:.2},"direction":"{}","velocity":{:.2},"max_speed":{:.2},"is_transitioning":{},"is_paused":{},"service_brake":"{}","queue":{},"lock":{},"lease":{}{}}}"#,
         state.speed,
         target,
         state.direction.as_str(),
+        state.velocity,
         state.max_speed,
-        is_transitioning
+        is_transitioning,
+        is_paused,
+        service_brake,
+        queue_to_json(&state.queue),
+        lock_to_json(state.lock_status.as_ref()),
+        lease_to_json(state.lease.as_ref()),
+        cab
+    )
+}
+
+// Fallback lock_to_json for when services module isn't available.
+#[cfg(not(any(feature = "web", feature = "mqtt")))]
+fn lock_to_json(lock: Option<&crate::LockStatus>) -> String {
+    let Some(lock) = lock else {
+        return String::from("null");
+    };
+    let remaining_ms = match lock.remaining_ms {
+        Some(ms) => format!("{}", ms),
+        None => String::from("null"),
+    };
+    let released = match lock.released {
+        Some(crate::LockRelease::Forced { by }) => {
+            format!(r#"{{"reason":"forced","by":"{}"}}"#, by.as_str())
+        }
+        Some(release) => format!(r#"{{"reason":"{}"}}"#, release.as_str()),
+        None => String::from("null"),
+    };
+    format!(
+        r#"{{"level":"{}","owner":"{}","remaining_ms":{},"released":{}}}"#,
+        lock.lock.as_str(),
+        lock.source.as_str(),
+        remaining_ms,
+        released
+    )
+}
+
+// Fallback lease_to_json for when services module isn't available.
+#[cfg(not(any(feature = "web", feature = "mqtt")))]
+fn lease_to_json(lease: Option<&crate::LeaseStatus>) -> String {
+    let Some(lease) = lease else {
+        return String::from("null");
+    };
+    let client = match lease.client {
+        Some(client) => format!(r#","client":"{}""#, client),
+        None => String::new(),
+    };
+    format!(
+        r#"{{"owner":"{}"{},"remaining_ms":{}}}"#,
+        lease.source.as_str(),
+        client,
+        lease.remaining_ms
+    )
+}
+
+// Fallback queue_to_json for when services module isn't available.
+#[cfg(not(any(feature = "web", feature = "mqtt")))]
+fn queue_to_json(queue: &[crate::QueuedCommand]) -> String {
+    let entries: Vec<String> = queue
+        .iter()
+        .map(|q| {
+            format!(
+                r#"{{"id":{},"target":{:.2},"source":"{}"}}"#,
+                q.id,
+                q.target,
+                q.source.as_str()
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+// Fallback metrics_to_json for when services module isn't available.
+#[cfg(not(any(feature = "web", feature = "mqtt")))]
+fn metrics_to_json(metrics: &crate::CommandMetrics) -> String {
+    let limited = &metrics.rate_limited;
+    format!(
+        r#"{{"rate_limited":{{"mqtt":{},"web_api":{},"web_local":{},"physical":{},"total":{}}},"coalesced":{},"queue_full":{}}}"#,
+        limited.mqtt,
+        limited.web_api,
+        limited.web_local,
+        limited.physical,
+        limited.total(),
+        metrics.coalesced,
+        metrics.queue_full
     )
 }
 
@@ -66,7 +197,8 @@ pub struct Esp32HttpServer {
 /// This struct uses a command queue pattern suitable for ESP32's
 /// callback-based HTTP server. The main loop should:
 /// 1. Update `state` and `now_ms` regularly
-/// 2. Check and consume `pending_command` when present
+/// 2. Check and consume `pending_command` when present, applying it only
+///    if `lease` allows `pending_client` to drive
 ///
 /// Note: This is different from `services::SharedThrottleState` which
 /// wraps a full `ThrottleController`. This ESP32 variant is designed
@@ -76,8 +208,16 @@ pub struct Esp32SharedState {
     pub state: ThrottleState,
     /// Pending command from HTTP (consumed by main loop)
     pub pending_command: Option<ThrottleCommandDyn>,
+    /// Client that sent `pending_command`, from its `X-Client-Id` header
+    pub pending_client: Option<ClientId>,
     /// Current timestamp in milliseconds
     pub now_ms: u64,
+    /// Presets for resolving `/api/preview` strategies
+    pub presets: StrategyPresets,
+    /// Throttle lease, shared with the main loop and MQTT
+    pub lease: Arc<Mutex<ThrottleLease>>,
+    /// Source ranking for lease steals
+    pub priorities: SourcePriorities,
 }
 
 impl Default for Esp32SharedState {
@@ -85,11 +225,31 @@ impl Default for Esp32SharedState {
         Self {
             state: ThrottleState::default(),
             pending_command: None,
+            pending_client: None,
             now_ms: 0,
+            presets: StrategyPresets::default(),
+            lease: Arc::new(Mutex::new(ThrottleLease::default())),
+            priorities: SourcePriorities::default(),
         }
     }
 }
 
+impl Esp32SharedState {
+    /// Queue a command from `client` for the main loop.
+    pub fn queue_command(&mut self, cmd: ThrottleCommandDyn, client: Option<ClientId>) {
+        self.pending_command = Some(cmd);
+        self.pending_client = client;
+    }
+}
+
+/// Header naming the client that sent a request
+const CLIENT_ID_HEADER: &str = "X-Client-Id";
+
+/// Client named by a request's `X-Client-Id` header, if valid.
+fn request_client(req: &impl Headers) -> Option<ClientId> {
+    ClientId::new(req.header(CLIENT_ID_HEADER)?.trim()).ok()
+}
+
 /// Type alias for backward compatibility.
 #[deprecated(since = "0.2.0", note = "Use Esp32SharedState instead")]
 pub type SharedThrottleState = Esp32SharedState;
@@ -100,7 +260,8 @@ impl Esp32HttpServer {
     /// The server shares state via the provided `Arc<Mutex<Esp32SharedState>>`.
     /// The main loop should:
     /// 1. Update `state` and `now_ms` regularly
-    /// 2. Check and consume `pending_command` when present
+    /// 2. Check and consume `pending_command` when present, applying it only
+    ///    if `lease` allows `pending_client` to drive
     ///
     /// # Errors
     ///
@@ -119,8 +280,25 @@ impl Esp32HttpServer {
         // Clone Arc for each handler
         let state_for_get = shared_state.clone();
         let state_for_speed = shared_state.clone();
+        let state_for_velocity = shared_state.clone();
         let state_for_dir = shared_state.clone();
         let state_for_estop = shared_state.clone();
+        let state_for_heartbeat = shared_state.clone();
+        let state_for_command = shared_state.clone();
+        let state_for_service_brake = shared_state.clone();
+        let state_for_release = shared_state.clone();
+        let state_for_notch = shared_state.clone();
+        let state_for_brake = shared_state.clone();
+        let state_for_mode = shared_state.clone();
+        let state_for_queue = shared_state.clone();
+        let state_for_cancel_queued = shared_state.clone();
+        let state_for_clear_queue = shared_state.clone();
+        let state_for_preview = shared_state.clone();
+        let state_for_pause = shared_state.clone();
+        let state_for_resume = shared_state.clone();
+        let state_for_lock_release = shared_state.clone();
+        let state_for_metrics = shared_state.clone();
+        let state_for_lease = shared_state.clone();
 
         // GET /api/state - Return current throttle state
         server.fn_handler("/api/state", esp_idf_svc::http::Method::Get, move |req| {
@@ -136,41 +314,62 @@ impl Esp32HttpServer {
             "/api/speed",
             esp_idf_svc::http::Method::Post,
             move |mut req| {
+                let client = request_client(&req);
                 let mut buf = [0u8; 128];
                 let len = req.read(&mut buf).unwrap_or(0);
 
-                if let Some(speed_req) = parse_speed_request(&buf[..len]) {
-                    if (0.0..=1.0).contains(&speed_req.speed) {
-                        let cmd = if speed_req.duration_ms > 0 {
-                            if speed_req.smooth {
-                                ThrottleCommand::SetSpeed {
-                                    target: speed_req.speed,
-                                    strategy: EaseInOut::new(speed_req.duration_ms),
-                                }
-                                .into()
-                            } else {
-                                ThrottleCommand::SetSpeed {
-                                    target: speed_req.speed,
-                                    strategy: Linear::new(speed_req.duration_ms),
-                                }
-                                .into()
-                            }
-                        } else {
-                            ThrottleCommand::speed_immediate(speed_req.speed).into()
-                        };
+                match parse_speed_request(&buf[..len]) {
+                    Ok(speed_req) => {
+                        let cmd = CommandMessage::from(speed_req).into();
                         let mut state = state_for_speed.lock().unwrap();
-                        state.pending_command = Some(cmd);
+                        state.queue_command(cmd, client);
                         let mut resp = req.into_ok_response()?;
                         resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
-                    } else {
+                    }
+                    Err(MessageError::InvalidSpeed(e)) => {
+                        let body = format!(r#"{{"error":"{}"}}"#, e);
                         let mut resp =
                             req.into_response(400, None, &[("Content-Type", "application/json")])?;
-                        resp.write_all(b"{\"error\":\"speed must be between 0.0 and 1.0\"}")?;
+                        resp.write_all(body.as_bytes())?;
+                    }
+                    Err(_) => {
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(b"{\"error\":\"invalid speed\"}")?;
+                    }
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/velocity - Set signed velocity, reversing through zero
+        server.fn_handler(
+            "/api/velocity",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 128];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                match parse_velocity_request(&buf[..len]) {
+                    Ok(velocity_req) => {
+                        let cmd = CommandMessage::from(velocity_req).into();
+                        let mut state = state_for_velocity.lock().unwrap();
+                        state.queue_command(cmd, client);
+                        let mut resp = req.into_ok_response()?;
+                        resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                    }
+                    Err(MessageError::InvalidSpeed(e)) => {
+                        let body = format!(r#"{{"error":"{}"}}"#, e);
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(body.as_bytes())?;
+                    }
+                    Err(_) => {
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(b"{\"error\":\"invalid velocity\"}")?;
                     }
-                } else {
-                    let mut resp =
-                        req.into_response(400, None, &[("Content-Type", "application/json")])?;
-                    resp.write_all(b"{\"error\":\"invalid speed\"}")?;
                 }
                 Ok::<_, EspIOError>(())
             },
@@ -181,12 +380,14 @@ impl Esp32HttpServer {
             "/api/direction",
             esp_idf_svc::http::Method::Post,
             move |mut req| {
+                let client = request_client(&req);
                 let mut buf = [0u8; 128];
                 let len = req.read(&mut buf).unwrap_or(0);
 
-                if let Some(dir_req) = parse_direction_request(&buf[..len]) {
+                if let Ok(dir_req) = parse_direction_request(&buf[..len]) {
                     let mut state = state_for_dir.lock().unwrap();
-                    state.pending_command = Some(ThrottleCommandDyn::SetDirection(dir_req.direction));
+                    let cmd = ThrottleCommandDyn::SetDirection(dir_req.direction);
+                    state.queue_command(cmd, client);
                     let mut resp = req.into_ok_response()?;
                     resp.write_all(b"{\"ok\":true,\"result\":\"direction_set\"}")?;
                 } else {
@@ -200,13 +401,365 @@ impl Esp32HttpServer {
 
         // POST /api/estop - Emergency stop
         server.fn_handler("/api/estop", esp_idf_svc::http::Method::Post, move |req| {
+            let client = request_client(&req);
             let mut state = state_for_estop.lock().unwrap();
-            state.pending_command = Some(ThrottleCommandDyn::EmergencyStop);
+            state.queue_command(ThrottleCommandDyn::EmergencyStop, client);
             let mut resp = req.into_ok_response()?;
             resp.write_all(b"{\"ok\":true,\"result\":\"emergency_stop\"}")?;
             Ok::<_, EspIOError>(())
         })?;
 
+        // POST /api/heartbeat - Renew heartbeat lease
+        server.fn_handler(
+            "/api/heartbeat",
+            esp_idf_svc::http::Method::Post,
+            move |req| {
+                let client = request_client(&req);
+                let mut state = state_for_heartbeat.lock().unwrap();
+                // Any pending command renews the lease anyway; never displace it
+                if state.pending_command.is_none() {
+                    state.queue_command(ThrottleCommandDyn::Heartbeat, client);
+                }
+                let mut resp = req.into_ok_response()?;
+                resp.write_all(b"{\"ok\":true,\"result\":\"heartbeat\"}")?;
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/service-brake - Brake to a stop, optionally holding
+        server.fn_handler(
+            "/api/service-brake",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 128];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                if let Ok(brake_req) = parse_service_brake_request(&buf[..len]) {
+                    let mut state = state_for_service_brake.lock().unwrap();
+                    state.queue_command(CommandMessage::from(brake_req).into(), client);
+                    let mut resp = req.into_ok_response()?;
+                    resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                } else {
+                    let mut resp =
+                        req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                    resp.write_all(b"{\"error\":\"invalid brake request\"}")?;
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/service-brake/release - Release a held service brake
+        server.fn_handler(
+            "/api/service-brake/release",
+            esp_idf_svc::http::Method::Post,
+            move |req| {
+                let client = request_client(&req);
+                let mut state = state_for_release.lock().unwrap();
+                state.queue_command(ThrottleCommandDyn::ReleaseBrake, client);
+                let mut resp = req.into_ok_response()?;
+                resp.write_all(b"{\"ok\":true,\"result\":\"brake_released\"}")?;
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/notch - Select a throttle notch (cab mode)
+        server.fn_handler(
+            "/api/notch",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 64];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                match parse_notch_request(&buf[..len]) {
+                    Ok(notch_req) => {
+                        let mut state = state_for_notch.lock().unwrap();
+                        state.queue_command(ThrottleCommandDyn::SetNotch(notch_req.notch), client);
+                        let mut resp = req.into_ok_response()?;
+                        resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                    }
+                    Err(e) => {
+                        let body = format!(r#"{{"error":"{}"}}"#, e);
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(body.as_bytes())?;
+                    }
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/brake - Move the brake handle (cab mode)
+        server.fn_handler(
+            "/api/brake",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 64];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                if let Ok(brake_req) = parse_brake_request(&buf[..len]) {
+                    let mut state = state_for_brake.lock().unwrap();
+                    state.queue_command(ThrottleCommandDyn::SetBrake(brake_req.brake), client);
+                    let mut resp = req.into_ok_response()?;
+                    resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                } else {
+                    let mut resp =
+                        req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                    resp.write_all(b"{\"error\":\"invalid brake setting\"}")?;
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/mode - Switch driving mode
+        server.fn_handler(
+            "/api/mode",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 64];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                if let Ok(mode_req) = parse_driving_mode_request(&buf[..len]) {
+                    let mut state = state_for_mode.lock().unwrap();
+                    state.queue_command(ThrottleCommandDyn::SetDrivingMode(mode_req.mode), client);
+                    let mut resp = req.into_ok_response()?;
+                    resp.write_all(b"{\"ok\":true,\"result\":\"driving_mode_set\"}")?;
+                } else {
+                    let mut resp =
+                        req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                    resp.write_all(b"{\"error\":\"invalid driving mode\"}")?;
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // GET /api/queue - List queued speed commands
+        server.fn_handler("/api/queue", esp_idf_svc::http::Method::Get, move |req| {
+            let state = state_for_queue.lock().unwrap();
+            let json = format!(r#"{{"queue":{}}}"#, queue_to_json(&state.state.queue));
+            let mut resp = req.into_ok_response()?;
+            resp.write_all(json.as_bytes())?;
+            Ok::<_, EspIOError>(())
+        })?;
+
+        // GET /api/metrics - Counts of dropped commands
+        server.fn_handler("/api/metrics", esp_idf_svc::http::Method::Get, move |req| {
+            let state = state_for_metrics.lock().unwrap();
+            let json = metrics_to_json(&state.state.metrics);
+            let mut resp = req.into_ok_response()?;
+            resp.write_all(json.as_bytes())?;
+            Ok::<_, EspIOError>(())
+        })?;
+
+        // POST /api/queue/cancel - Cancel one queued speed command
+        server.fn_handler(
+            "/api/queue/cancel",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 64];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                if let Ok(cancel_req) = parse_cancel_queued_request(&buf[..len]) {
+                    let mut state = state_for_cancel_queued.lock().unwrap();
+                    state.queue_command(CommandMessage::from(cancel_req).into(), client);
+                    let mut resp = req.into_ok_response()?;
+                    resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                } else {
+                    let mut resp =
+                        req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                    resp.write_all(b"{\"error\":\"invalid queue id\"}")?;
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/queue/clear - Cancel all queued speed commands
+        server.fn_handler(
+            "/api/queue/clear",
+            esp_idf_svc::http::Method::Post,
+            move |req| {
+                let client = request_client(&req);
+                let mut state = state_for_clear_queue.lock().unwrap();
+                state.queue_command(ThrottleCommandDyn::ClearQueue, client);
+                let mut resp = req.into_ok_response()?;
+                resp.write_all(b"{\"ok\":true,\"result\":\"queue_cleared\"}")?;
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/preview - Sample a strategy's speed curve
+        server.fn_handler(
+            "/api/preview",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                // Room for a full keyframe profile
+                let mut buf = [0u8; 1024];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                let resolved = parse_preview_request(&buf[..len])
+                    .ok()
+                    .and_then(|preview_req| {
+                        let state = state_for_preview.lock().unwrap();
+                        let strategy = state.presets.resolve(&preview_req.strategy)?;
+                        Some((preview_req, strategy))
+                    });
+                match resolved {
+                    Some((preview_req, strategy)) => {
+                        let curve = preview(
+                            &AnyStrategy::from(strategy),
+                            preview_req.from,
+                            preview_req.to,
+                            preview_req.resolution_ms,
+                        );
+                        let json = serde_json::to_string(&curve).unwrap_or_default();
+                        let mut resp = req.into_ok_response()?;
+                        resp.write_all(json.as_bytes())?;
+                    }
+                    None => {
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(b"{\"error\":\"invalid preview request\"}")?;
+                    }
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // POST /api/pause - Freeze the active speed transition
+        server.fn_handler("/api/pause", esp_idf_svc::http::Method::Post, move |req| {
+            let client = request_client(&req);
+            let mut state = state_for_pause.lock().unwrap();
+            state.queue_command(ThrottleCommandDyn::Pause, client);
+            let mut resp = req.into_ok_response()?;
+            resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+            Ok::<_, EspIOError>(())
+        })?;
+
+        // POST /api/resume - Continue a paused speed transition
+        server.fn_handler("/api/resume", esp_idf_svc::http::Method::Post, move |req| {
+            let client = request_client(&req);
+            let mut state = state_for_resume.lock().unwrap();
+            state.queue_command(ThrottleCommandDyn::Resume, client);
+            let mut resp = req.into_ok_response()?;
+            resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+            Ok::<_, EspIOError>(())
+        })?;
+
+        // POST /api/lock/release - Force release the transition lock
+        server.fn_handler(
+            "/api/lock/release",
+            esp_idf_svc::http::Method::Post,
+            move |req| {
+                let client = request_client(&req);
+                let mut state = state_for_lock_release.lock().unwrap();
+                state.queue_command(ThrottleCommandDyn::ForceRelease, client);
+                let mut resp = req.into_ok_response()?;
+                resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
+        // GET /api/lease - Current throttle lease holder
+        server.fn_handler("/api/lease", esp_idf_svc::http::Method::Get, move |req| {
+            let state = state_for_lease.lock().unwrap();
+            let lease = state.lease.lock().unwrap().status(state.now_ms);
+            let json = format!(r#"{{"lease":{}}}"#, lease_to_json(lease.as_ref()));
+            let mut resp = req.into_ok_response()?;
+            resp.write_all(json.as_bytes())?;
+            Ok::<_, EspIOError>(())
+        })?;
+
+        // POST /api/lease/acquire, /api/lease/release, /api/lease/steal
+        for (uri, action, result) in [
+            ("/api/lease/acquire", LeaseAction::Acquire, "acquired"),
+            ("/api/lease/release", LeaseAction::Release, "released"),
+            ("/api/lease/steal", LeaseAction::Steal, "stolen"),
+        ] {
+            let state_for_lease_action = shared_state.clone();
+            server.fn_handler(uri, esp_idf_svc::http::Method::Post, move |mut req| {
+                let client = request_client(&req);
+                let mut buf = [0u8; 64];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                match parse_lease_payload(&buf[..len]) {
+                    Ok(body_client) => {
+                        // The header wins over the body, as on the desktop server
+                        let client = client.or(body_client);
+                        let state = state_for_lease_action.lock().unwrap();
+                        let (source, now_ms) = (CommandSource::WebLocal, state.now_ms);
+                        let mut lease = state.lease.lock().unwrap();
+                        let held = match action {
+                            LeaseAction::Acquire => lease.acquire(source, client, now_ms).map(Some),
+                            LeaseAction::Release => {
+                                lease.release(source, client, now_ms).map(|()| None)
+                            }
+                            LeaseAction::Steal => lease
+                                .steal(source, client, &state.priorities, now_ms)
+                                .map(Some),
+                        };
+                        match held {
+                            Ok(held) => {
+                                let body = format!(
+                                    r#"{{"ok":true,"result":"{}","lease":{}}}"#,
+                                    result,
+                                    lease_to_json(held.as_ref())
+                                );
+                                let mut resp = req.into_ok_response()?;
+                                resp.write_all(body.as_bytes())?;
+                            }
+                            Err(reason) => {
+                                let body = format!(r#"{{"error":"{}"}}"#, reason.as_str());
+                                let mut resp = req.into_response(
+                                    409,
+                                    None,
+                                    &[("Content-Type", "application/json")],
+                                )?;
+                                resp.write_all(body.as_bytes())?;
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(b"{\"error\":\"invalid lease request\"}")?;
+                    }
+                }
+                Ok::<_, EspIOError>(())
+            })?;
+        }
+
+        // POST /api/command - Versioned command envelope
+        server.fn_handler(
+            "/api/command",
+            esp_idf_svc::http::Method::Post,
+            move |mut req| {
+                let client = request_client(&req);
+                // Room for a full keyframe profile
+                let mut buf = [0u8; 1024];
+                let len = req.read(&mut buf).unwrap_or(0);
+
+                match parse_command(&buf[..len]) {
+                    Ok(cmd) => {
+                        let mut state = state_for_command.lock().unwrap();
+                        state.queue_command(cmd, client);
+                        let mut resp = req.into_ok_response()?;
+                        resp.write_all(b"{\"ok\":true,\"result\":\"applied\"}")?;
+                    }
+                    Err(e) => {
+                        let body = format!(r#"{{"error":"{}"}}"#, e);
+                        let mut resp =
+                            req.into_response(400, None, &[("Content-Type", "application/json")])?;
+                        resp.write_all(body.as_bytes())?;
+                    }
+                }
+                Ok::<_, EspIOError>(())
+            },
+        )?;
+
         // GET / - Serve web UI (shared with desktop)
         server.fn_handler("/", esp_idf_svc::http::Method::Get, move |req| {
             let html = include_str!("../../../www/index.html");
@@ -9,6 +9,7 @@
 //! - Reverse: L_PWM = 0%, R_PWM = duty%
 //! - Stopped: Both = 0%
 
+use crate::fixed::Q16;
 use crate::traits::{Direction, MotorController};
 use esp_idf_hal::ledc::{config::TimerConfig, LedcDriver, LedcTimerDriver, Resolution};
 use esp_idf_hal::peripheral::Peripheral;
@@ -50,7 +51,7 @@ pub struct Esp32Motor<'d> {
     /// Reverse PWM channel (R_PWM on BTS7960)
     r_pwm: LedcDriver<'d>,
     /// Current speed setting (0.0 to 1.0)
-    current_speed: f32,
+    current_speed: Q16,
     /// Current direction
     current_direction: Direction,
 }
@@ -110,7 +111,7 @@ impl<'d> Esp32Motor<'d> {
         let mut motor = Self {
             l_pwm,
             r_pwm,
-            current_speed: 0.0,
+            current_speed: Q16::ZERO,
             current_direction: Direction::Stopped,
         };
 
@@ -122,7 +123,7 @@ impl<'d> Esp32Motor<'d> {
 
     /// Applies the current speed and direction to the PWM outputs.
     fn apply_pwm(&mut self) -> Result<(), esp_idf_hal::sys::EspError> {
-        let duty = (self.current_speed * Self::MAX_DUTY as f32) as u32;
+        let duty = self.current_speed.duty(Self::MAX_DUTY);
 
         match self.current_direction {
             Direction::Forward => {
@@ -145,7 +146,7 @@ impl<'d> Esp32Motor<'d> {
     /// Returns the current speed setting (0.0 to 1.0).
     #[inline]
     pub fn speed(&self) -> f32 {
-        self.current_speed
+        self.current_speed.to_f32()
     }
 
     /// Returns the current direction setting.
@@ -159,7 +160,11 @@ impl MotorController for Esp32Motor<'_> {
     type Error = esp_idf_hal::sys::EspError;
 
     fn set_speed(&mut self, speed: f32) -> Result<(), Self::Error> {
-        self.current_speed = speed.clamp(0.0, 1.0);
+        self.set_speed_q16(Q16::from_f32(speed))
+    }
+
+    fn set_speed_q16(&mut self, speed: Q16) -> Result<(), Self::Error> {
+        self.current_speed = speed.clamp(Q16::ZERO, Q16::ONE);
         self.apply_pwm()
     }
 
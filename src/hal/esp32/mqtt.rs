@@ -9,8 +9,11 @@
 //! Using default prefix "train":
 //! - `train/state` - Published state (JSON)
 //! - `train/speed/set` - Subscribe for speed commands
+//! - `train/velocity/set` - Subscribe for signed velocity commands
 //! - `train/direction/set` - Subscribe for direction commands
 //! - `train/estop` - Subscribe for emergency stop
+//! - `train/heartbeat` - Subscribe for heartbeat lease renewal
+//! - `train/command` - Subscribe for versioned command envelopes
 //!
 //! # Example
 //!
@@ -30,7 +33,7 @@
 //! ```
 
 use crate::config::MqttConfig;
-use crate::messages::parse_mqtt_command;
+use crate::messages::{parse_mqtt_command, MessageError};
 use crate::traits::{MqttClient, MqttMessage};
 use crate::{ThrottleCommandDyn, ThrottleState};
 use esp_idf_svc::mqtt::client::{
@@ -102,7 +105,25 @@ impl Esp32Mqtt {
 
     /// Subscribe to all control topics.
     fn subscribe_all(&mut self) -> anyhow::Result<()> {
-        let topics = ["speed/set", "direction/set", "estop", "max-speed/set"];
+        let topics = [
+            "speed/set",
+            "velocity/set",
+            "direction/set",
+            "estop",
+            "max-speed/set",
+            "heartbeat",
+            "service-brake",
+            "service-brake/release",
+            "notch/set",
+            "brake/set",
+            "mode/set",
+            "queue/cancel",
+            "queue/clear",
+            "pause",
+            "resume",
+            "lock/release",
+            "command",
+        ];
         for topic_suffix in topics {
             let mut full_topic: heapless::String<128> = heapless::String::new();
             let _ = full_topic.push_str(self.topic_prefix.as_str());
@@ -124,12 +145,29 @@ impl Esp32Mqtt {
 
         let target = state.target_speed.unwrap_or(state.speed);
         let is_transitioning = state.transition_progress.is_some();
+        let service_brake = match state.service_brake {
+            Some(brake) if brake.hold => "hold",
+            Some(_) => "applied",
+            None => "off",
+        };
+        let cab = match &state.cab {
+            Some(cab) => format!(
+                r#","mode":"cab","notch":{},"brake":"{}","brake_level":{:.2}"#,
+                cab.notch,
+                cab.brake.as_str(),
+                cab.brake_level
+            ),
+            None => String::from(r#","mode":"direct""#),
+        };
         let json = format!(
-            r#"{{"speed":{:.2},"target_speed":{:.2},"direction":"{}","is_transitioning":{}}}"#,
+            r#"{{"speed":{:.2},"target_speed":{:.2},"direction":"{}","velocity":{:.2},"is_transitioning":{},"service_brake":"{}"{}}}"#,
             state.speed,
             target,
             state.direction.as_str(),
-            is_transitioning
+            state.velocity,
+            is_transitioning,
+            service_brake,
+            cab
         );
 
         self.client
@@ -254,5 +292,12 @@ fn parse_mqtt_message(
     prefix: &heapless::String<64>,
 ) -> Option<ThrottleCommandDyn> {
     let suffix = topic.strip_prefix(prefix.as_str())?.strip_prefix('/')?;
-    parse_mqtt_command(suffix, data)
+    match parse_mqtt_command(suffix, data) {
+        Ok(cmd) => Some(cmd),
+        Err(MessageError::UnknownTopic) => None,
+        Err(e) => {
+            println!("[MQTT] Rejected {}: {}", topic, e);
+            None
+        }
+    }
 }
@@ -18,7 +18,7 @@
 //! # Example
 //!
 //! ```rust
-//! use rs_trainz::{ThrottleController, ThrottleCommand, CommandSource};
+//! use rs_trainz::{ThrottleController, ThrottleCommand, CommandSource, Speed};
 //! use rs_trainz::hal::MockMotor;
 //! use rs_trainz::traits::MotorController;
 //!
@@ -27,13 +27,13 @@
 //! let mut controller = ThrottleController::new(motor);
 //!
 //! // Apply command
-//! let cmd = ThrottleCommand::speed_immediate(0.5);
+//! let cmd = ThrottleCommand::speed_immediate(Speed::new(0.5).unwrap());
 //! controller.apply_command(cmd.into(), CommandSource::Physical, 0).unwrap();
 //! controller.update(0).unwrap();
 //!
 //! // Verify via state
 //! let state = controller.state(0);
-//! assert!((state.speed - 0.5).abs() < 0.01);
+//! assert!((state.speed.get() - 0.5).abs() < 0.01);
 //! ```
 //!
 //! [`MotorController`]: crate::traits::MotorController
@@ -324,7 +324,6 @@ impl Clock for MockClock {
 // Network Mocks
 // ============================================================================
 
-extern crate alloc;
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -564,7 +563,7 @@ impl HttpServer for MockHttp {
 mod tests {
     use super::*;
     use crate::traits::ThrottleDisplay;
-    use crate::{Direction, ThrottleState};
+    use crate::{Direction, Speed, ThrottleState, Velocity};
 
     // =========================================================================
     // MockMotor Tests
@@ -758,13 +757,21 @@ mod tests {
         display.init().unwrap();
 
         let state = ThrottleState {
-            speed: 0.5,
-            target_speed: Some(0.8),
+            speed: Speed::literal(0.5),
+            target_speed: Some(Speed::literal(0.8)),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: Default::default(),
         };
 
         display.render(&state).unwrap();
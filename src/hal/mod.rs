@@ -5,14 +5,16 @@
 //!
 //! # Available Implementations
 //!
-//! - `mock`: Test implementations for desktop development
+//! - `mock`: Test implementations for desktop development (requires `alloc` feature)
 //! - `esp32`: ESP32-C3 SuperMini with BTS7960 motor driver (requires `esp32` feature)
 
+#[cfg(feature = "alloc")]
 pub mod mock;
 
 #[cfg(feature = "esp32")]
 pub mod esp32;
 
+#[cfg(feature = "alloc")]
 pub use mock::*;
 
 #[cfg(feature = "esp32")]
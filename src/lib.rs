@@ -17,6 +17,7 @@
 //!
 //! - `traits` - Hardware and network abstractions
 //! - `commands` - Command types with priority system
+//! - `speed` - Validated speed values shared by commands, state and messages
 //! - `transition` - Smooth speed transition management
 //! - `throttle` - Main controller that ties everything together
 //! - `hal` - Concrete implementations (mock for testing, esp32 for hardware)
@@ -25,7 +26,7 @@
 //!
 //! ```rust
 //! use rs_trainz::{
-//!     ThrottleController, ThrottleCommand, CommandSource, PrioritizedCommand,
+//!     ThrottleController, ThrottleCommand, CommandSource, PrioritizedCommand, Speed,
 //!     hal::MockMotor,
 //!     traits::{EaseInOut, Immediate},
 //! };
@@ -35,12 +36,12 @@
 //! let mut controller = ThrottleController::new(motor);
 //!
 //! // Apply an immediate speed command
-//! let cmd = ThrottleCommand::speed_immediate(0.5);
+//! let cmd = ThrottleCommand::speed_immediate(Speed::new(0.5).unwrap());
 //! controller.apply_command(cmd.into(), CommandSource::Physical, 0).unwrap();
 //!
 //! // Or use a smooth transition
 //! let cmd = ThrottleCommand::SetSpeed {
-//!     target: 0.8,
+//!     target: Speed::new(0.8).unwrap(),
 //!     strategy: EaseInOut::departure(2000), // 2 second smooth start, locked
 //! };
 //! controller.apply_command(cmd.into(), CommandSource::Mqtt, 0).unwrap();
@@ -52,14 +53,23 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
+/// Cab simulation with throttle notches, brake and train physics.
+pub mod cab;
 /// Command types and priority system for throttle control.
 pub mod commands;
+/// Fixed-point arithmetic for microcontrollers without an FPU.
+pub mod fixed;
 /// Hardware abstraction layer with mock implementations for testing.
 pub mod hal;
 /// Command queue and processor with source-based lockouts.
 pub mod priority;
+/// Composite strategies that chain, delay and hold other strategies.
+pub mod sequence;
+/// Validated throttle speed and signed velocity newtypes.
+pub mod speed;
 /// Type-erased execution strategies for runtime polymorphism.
 pub mod strategy_dyn;
 /// Main throttle controller that coordinates commands, transitions, and hardware.
@@ -83,13 +93,22 @@ pub mod messages;
 pub mod services;
 
 // Re-exports for convenience
+pub use cab::{BrakeSetting, CabStatus, DrivingMode, LocoProfile, LocoProfileError, Notch};
 pub use commands::{
-    CommandOutcome, CommandSource, CommandType, PrioritizedCommand, RejectReason, ThrottleCommand,
+    ClientId, ClientIdError, CommandOutcome, CommandSource, CommandType, CorrelationId,
+    CorrelationIdError, PrioritizedCommand, RejectReason, SourcePriorities, ThrottleCommand,
     ThrottleCommandDyn, TransitionResult,
 };
-pub use priority::{CommandProcessor, CommandQueue, LockoutStatus, SourceLockout};
+pub use fixed::Q16;
+pub use priority::{
+    AuditEntry, AuditLog, CommandMetrics, CommandProcessor, CommandQueue, HeartbeatLease,
+    HeartbeatStatus, LeaseStatus, LockoutStatus, RateLimit, RateLimiter, RateLimits, SafeStop,
+    SourceCounts, SourceLockout, ThrottleLease,
+};
+pub use sequence::{Sequence, SequenceStep};
+pub use speed::{Speed, SpeedError, Velocity};
 pub use strategy_dyn::{AnyStrategy, ExecutionStrategyDyn};
-pub use throttle::{ThrottleController, ThrottleState};
+pub use throttle::{ServiceBrakeStatus, ThrottleController, ThrottleState};
 pub use traits::{
     // Hardware
     Clock,
@@ -97,33 +116,53 @@ pub use traits::{
     Direction,
     // Strategies
     EaseInOut,
+    Easing,
     EncoderInput,
     ExecutionStrategy,
     FaultDetector,
     FaultKind,
-    // Network
-    HttpMethod,
-    HttpRequest,
-    HttpResponse,
-    HttpServer,
     Immediate,
     InterruptBehavior,
+    Keyframe,
+    KeyframeError,
+    Keyframes,
     Linear,
     Momentum,
     MotorController,
-    MqttClient,
-    MqttMessage,
+    SCurve,
+    SegmentProgress,
+    StrategySpec,
     TransitionLock,
 };
-pub use transition::{LockStatus, TransitionManager, TransitionProgress};
+// Network
+#[cfg(feature = "alloc")]
+pub use traits::{HttpMethod, HttpRequest, HttpResponse, HttpServer, MqttClient, MqttMessage};
+pub use transition::{
+    preview, LockRelease, LockStatus, PreviewPoint, QueuedCommand, TransitionEvent,
+    TransitionManager, TransitionPreview, TransitionProgress, DEFAULT_QUEUE_DEPTH,
+    MAX_PREVIEW_POINTS, MAX_QUEUE_DEPTH, MAX_TRANSITION_EVENTS,
+};
 
 // Config re-exports
-pub use config::{Config, DeviceConfig, MqttConfig, ThrottleConfig, WebConfig, WifiConfig};
+pub use config::{
+    Config, DeviceConfig, MqttConfig, StrategyPreset, StrategyPresets, ThrottleConfig, WebConfig,
+    WifiConfig,
+};
 
 // Message re-exports (for HTTP/MQTT APIs)
 #[cfg(feature = "serde")]
-pub use messages::{SetDirectionRequest, SetMaxSpeedRequest, SetSpeedRequest};
+pub use messages::{
+    CancelQueuedRequest, CommandEnvelope, CommandMessage, MessageError, PreviewRequest,
+    ServiceBrakeRequest, SetBrakeRequest, SetDirectionRequest, SetDrivingModeRequest,
+    SetMaxSpeedRequest, SetNotchRequest, SetSpeedRequest, SetVelocityRequest,
+    COMMAND_SCHEMA_VERSION,
+};
 
 // Parsing function re-exports (serde-json-core based)
 #[cfg(feature = "serde-json-core")]
-pub use messages::{parse_direction_request, parse_max_speed_request, parse_speed_request};
+pub use messages::{
+    parse_brake_request, parse_cancel_queued_request, parse_command, parse_direction_request,
+    parse_driving_mode_request, parse_max_speed_request, parse_notch_request,
+    parse_preview_request, parse_service_brake_request, parse_speed_request,
+    parse_velocity_request, write_command,
+};
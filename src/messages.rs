@@ -3,6 +3,14 @@
 //! These types are `no_std` compatible and can be deserialized using either
 //! `serde_json` (desktop) or `serde-json-core` (embedded).
 //!
+//! [`CommandEnvelope`] is the unified, versioned wire format: every transport
+//! parses it with [`parse_command`], and the older per-endpoint request types
+//! convert into a [`CommandMessage`] so they share the same conversion path.
+//!
+//! Speeds are carried as [`Speed`], so a NaN, infinite or out-of-range value
+//! fails to parse and is reported as [`MessageError::InvalidSpeed`] no
+//! matter which transport it arrived on.
+//!
 //! # Example
 //!
 //! ```
@@ -23,6 +31,10 @@
 //! }
 //! ```
 
+use crate::cab::{BrakeSetting, DrivingMode, Notch, NotchError};
+use crate::commands::{ClientId, CorrelationId};
+use crate::speed::{Speed, SpeedError, Velocity};
+use crate::traits::{preset_name, PresetName, PresetNameError, StrategySpec};
 use crate::Direction;
 use serde::{Deserialize, Serialize};
 
@@ -37,6 +49,7 @@ use serde::{Deserialize, Serialize};
 /// - `speed`: Target speed (0.0 to 1.0)
 /// - `duration_ms`: Transition duration in milliseconds (0 = immediate)
 /// - `smooth`: Whether to use ease-in-out smoothing (requires `duration_ms` > 0)
+/// - `preset`: Named strategy preset; overrides `duration_ms` and `smooth`
 ///
 /// # JSON Examples
 ///
@@ -54,43 +67,114 @@ use serde::{Deserialize, Serialize};
 /// ```json
 /// {"speed": 1.0, "duration_ms": 3000, "smooth": true}
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+///
+/// Named preset from the strategy registry:
+/// ```json
+/// {"speed": 0.6, "preset": "departure"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SetSpeedRequest {
     /// Target speed (0.0 to 1.0)
-    pub speed: f32,
+    pub speed: Speed,
     /// Transition duration in milliseconds (0 = immediate)
     #[serde(default)]
     pub duration_ms: u64,
     /// Whether to use ease-in-out smoothing
     #[serde(default)]
     pub smooth: bool,
+    /// Named strategy preset (e.g. "departure")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<PresetName>,
 }
 
 impl SetSpeedRequest {
+    /// Create a new request using a named strategy preset.
+    ///
+    /// Fails if `name` is longer than [`MAX_PRESET_NAME`](crate::traits::MAX_PRESET_NAME) bytes.
+    pub fn preset(speed: Speed, name: &str) -> Result<Self, PresetNameError> {
+        Ok(Self {
+            speed,
+            duration_ms: 0,
+            smooth: false,
+            preset: Some(preset_name(name)?),
+        })
+    }
+
     /// Create a new immediate speed request.
-    pub fn immediate(speed: f32) -> Self {
+    pub fn immediate(speed: Speed) -> Self {
         Self {
             speed,
             duration_ms: 0,
             smooth: false,
+            preset: None,
         }
     }
 
     /// Create a new linear transition request.
-    pub fn linear(speed: f32, duration_ms: u64) -> Self {
+    pub fn linear(speed: Speed, duration_ms: u64) -> Self {
         Self {
             speed,
             duration_ms,
             smooth: false,
+            preset: None,
         }
     }
 
     /// Create a new smooth (ease-in-out) transition request.
-    pub fn smooth(speed: f32, duration_ms: u64) -> Self {
+    pub fn smooth(speed: Speed, duration_ms: u64) -> Self {
         Self {
             speed,
             duration_ms,
             smooth: true,
+            preset: None,
+        }
+    }
+}
+
+/// Request to set a signed velocity (single-axis control).
+///
+/// Takes the same transition fields as [`SetSpeedRequest`]. A change of
+/// sign decelerates to zero, flips direction and accelerates again.
+///
+/// # JSON Examples
+///
+/// ```json
+/// {"velocity": -0.5}
+/// {"velocity": 0.8, "duration_ms": 2000, "smooth": true}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetVelocityRequest {
+    /// Target velocity (-1.0 to 1.0, negative is reverse)
+    pub velocity: Velocity,
+    /// Transition duration in milliseconds (0 = immediate)
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Whether to use ease-in-out smoothing
+    #[serde(default)]
+    pub smooth: bool,
+    /// Named strategy preset (e.g. "departure")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<PresetName>,
+}
+
+impl SetVelocityRequest {
+    /// Create a new immediate velocity request.
+    pub fn immediate(velocity: Velocity) -> Self {
+        Self {
+            velocity,
+            duration_ms: 0,
+            smooth: false,
+            preset: None,
+        }
+    }
+
+    /// Create a new linear transition request.
+    pub fn linear(velocity: Velocity, duration_ms: u64) -> Self {
+        Self {
+            velocity,
+            duration_ms,
+            smooth: false,
+            preset: None,
         }
     }
 }
@@ -127,16 +211,225 @@ impl SetDirectionRequest {
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SetMaxSpeedRequest {
     /// Maximum speed (0.0 to 1.0)
-    pub max_speed: f32,
+    pub max_speed: Speed,
 }
 
 impl SetMaxSpeedRequest {
     /// Create a new max speed request.
-    pub fn new(max_speed: f32) -> Self {
+    pub fn new(max_speed: Speed) -> Self {
         Self { max_speed }
     }
 }
 
+/// Request to apply the service brake.
+///
+/// Without a `preset` or `duration_ms` the controller's configured
+/// deceleration profile is used.
+///
+/// # JSON Examples
+///
+/// ```json
+/// {}
+/// {"hold": true}
+/// {"duration_ms": 1500}
+/// {"preset": "arrival", "hold": true}
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServiceBrakeRequest {
+    /// Keep the brake applied once stopped
+    #[serde(default)]
+    pub hold: bool,
+    /// Stopping time in milliseconds (0 = configured profile)
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Named strategy preset (e.g. "arrival")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<PresetName>,
+}
+
+impl ServiceBrakeRequest {
+    /// Create a new request using the configured deceleration profile.
+    pub fn new(hold: bool) -> Self {
+        Self {
+            hold,
+            ..Self::default()
+        }
+    }
+
+    /// Create a new request that stops linearly over `duration_ms`.
+    pub fn linear(hold: bool, duration_ms: u64) -> Self {
+        Self {
+            hold,
+            duration_ms,
+            preset: None,
+        }
+    }
+}
+
+/// Request to select a throttle notch (cab driving mode).
+///
+/// # JSON Example
+///
+/// ```json
+/// {"notch": 3}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SetNotchRequest {
+    /// Throttle notch (0 to 8)
+    pub notch: Notch,
+}
+
+impl SetNotchRequest {
+    /// Create a new notch request.
+    pub fn new(notch: Notch) -> Self {
+        Self { notch }
+    }
+}
+
+/// Request to cancel a queued speed command.
+///
+/// # JSON Example
+///
+/// ```json
+/// {"id": 3}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CancelQueuedRequest {
+    /// Queue entry id, as returned when the command was queued
+    pub id: u32,
+}
+
+impl CancelQueuedRequest {
+    /// Create a new cancel request.
+    pub fn new(id: u32) -> Self {
+        Self { id }
+    }
+}
+
+/// Request to preview a strategy's speed curve without moving the train.
+///
+/// # JSON Example
+///
+/// ```json
+/// {"from": 0.0, "to": 0.8, "strategy": {"ease_in_out": {"duration_ms": 2000}}, "resolution_ms": 100}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewRequest {
+    /// Starting speed (0.0 to 1.0)
+    pub from: Speed,
+    /// Target speed (0.0 to 1.0)
+    pub to: Speed,
+    /// Strategy to preview (defaults to immediate)
+    #[serde(default)]
+    pub strategy: StrategySpec,
+    /// Spacing between samples in milliseconds (0 = finest that fits)
+    #[serde(default)]
+    pub resolution_ms: u64,
+}
+
+impl PreviewRequest {
+    /// Create a new preview request.
+    pub fn new(from: Speed, to: Speed, strategy: StrategySpec) -> Self {
+        Self {
+            from,
+            to,
+            strategy,
+            resolution_ms: 0,
+        }
+    }
+}
+
+/// Request to move the brake handle (cab driving mode).
+///
+/// # JSON Examples
+///
+/// ```json
+/// {"brake": "apply"}
+/// {"brake": "lap"}
+/// {"brake": "release"}
+/// {"brake": "emergency"}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SetBrakeRequest {
+    /// Brake handle position
+    pub brake: BrakeSetting,
+}
+
+impl SetBrakeRequest {
+    /// Create a new brake request.
+    pub fn new(brake: BrakeSetting) -> Self {
+        Self { brake }
+    }
+}
+
+/// Request to switch driving mode.
+///
+/// # JSON Examples
+///
+/// ```json
+/// {"mode": "cab"}
+/// {"mode": "direct"}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SetDrivingModeRequest {
+    /// Driving mode
+    pub mode: DrivingMode,
+}
+
+impl SetDrivingModeRequest {
+    /// Create a new driving mode request.
+    pub fn new(mode: DrivingMode) -> Self {
+        Self { mode }
+    }
+}
+
+/// A throttle lease operation.
+///
+/// Clients acquire the throttle before driving and release it when done.
+/// A client may steal a lease held by a source that doesn't outrank it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseAction {
+    /// Take the lease if it is free, or renew it if already held
+    Acquire,
+    /// Give up the lease
+    Release,
+    /// Take the lease from its current holder
+    Steal,
+}
+
+impl LeaseAction {
+    /// Returns the action as a snake_case string, as used in JSON.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Acquire => "acquire",
+            Self::Release => "release",
+            Self::Steal => "steal",
+        }
+    }
+}
+
+/// Request to acquire, release or steal the throttle lease.
+///
+/// # JSON Example
+///
+/// ```json
+/// {"client": "cab-3"}
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LeaseRequest {
+    /// Client taking or giving up the lease
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientId>,
+}
+
+impl LeaseRequest {
+    /// Create a new lease request.
+    pub fn new(client: Option<ClientId>) -> Self {
+        Self { client }
+    }
+}
+
 // ============================================================================
 // Parsing Functions (using serde-json-core for no_std compatibility)
 // ============================================================================
@@ -157,8 +450,25 @@ impl SetMaxSpeedRequest {
 /// assert!(req.smooth);
 /// ```
 #[cfg(feature = "serde-json-core")]
-pub fn parse_speed_request(json: &[u8]) -> Option<SetSpeedRequest> {
-    serde_json_core::from_slice(json).ok().map(|(req, _)| req)
+pub fn parse_speed_request(json: &[u8]) -> Result<SetSpeedRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a velocity request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_velocity_request;
+///
+/// let json = br#"{"velocity": -0.5, "duration_ms": 2000}"#;
+/// let req = parse_velocity_request(json).unwrap();
+/// assert_eq!(req.velocity, -0.5);
+/// assert_eq!(req.duration_ms, 2000);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_velocity_request(json: &[u8]) -> Result<SetVelocityRequest, MessageError> {
+    from_json(json)
 }
 
 /// Parse a direction request from JSON bytes.
@@ -174,24 +484,662 @@ pub fn parse_speed_request(json: &[u8]) -> Option<SetSpeedRequest> {
 /// assert_eq!(req.direction, Direction::Forward);
 /// ```
 #[cfg(feature = "serde-json-core")]
-pub fn parse_direction_request(json: &[u8]) -> Option<SetDirectionRequest> {
-    serde_json_core::from_slice(json).ok().map(|(req, _)| req)
+pub fn parse_direction_request(json: &[u8]) -> Result<SetDirectionRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a max speed request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::{parse_max_speed_request, MessageError};
+/// use rs_trainz::SpeedError;
+///
+/// let json = br#"{"max_speed": 0.8}"#;
+/// let req = parse_max_speed_request(json).unwrap();
+/// assert_eq!(req.max_speed, 0.8);
+///
+/// let json = br#"{"max_speed": 1.5}"#;
+/// assert_eq!(
+///     parse_max_speed_request(json),
+///     Err(MessageError::InvalidSpeed(SpeedError::OutOfRange))
+/// );
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_max_speed_request(json: &[u8]) -> Result<SetMaxSpeedRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a service brake request from JSON bytes.
+///
+/// An empty payload is the default request.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_service_brake_request;
+///
+/// let req = parse_service_brake_request(br#"{"hold": true}"#).unwrap();
+/// assert!(req.hold);
+/// assert_eq!(req.duration_ms, 0);
+///
+/// assert!(!parse_service_brake_request(b"").unwrap().hold);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_service_brake_request(json: &[u8]) -> Result<ServiceBrakeRequest, MessageError> {
+    if json.iter().all(u8::is_ascii_whitespace) {
+        return Ok(ServiceBrakeRequest::default());
+    }
+    from_json(json)
+}
+
+/// Parse a notch request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::{parse_notch_request, MessageError};
+///
+/// let req = parse_notch_request(br#"{"notch": 3}"#).unwrap();
+/// assert_eq!(req.notch.get(), 3);
+///
+/// assert_eq!(
+///     parse_notch_request(br#"{"notch": 9}"#),
+///     Err(MessageError::InvalidNotch)
+/// );
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_notch_request(json: &[u8]) -> Result<SetNotchRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a cancel-queued request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_cancel_queued_request;
+///
+/// let req = parse_cancel_queued_request(br#"{"id": 3}"#).unwrap();
+/// assert_eq!(req.id, 3);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_cancel_queued_request(json: &[u8]) -> Result<CancelQueuedRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a lease request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_lease_request;
+///
+/// let req = parse_lease_request(br#"{"client": "cab-3"}"#).unwrap();
+/// assert_eq!(req.client.unwrap().as_str(), "cab-3");
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_lease_request(json: &[u8]) -> Result<LeaseRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a preview request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_preview_request;
+///
+/// let json = br#"{"from": 0.0, "to": 0.8, "strategy": {"linear": {"duration_ms": 1000}}}"#;
+/// let req = parse_preview_request(json).unwrap();
+/// assert_eq!(req.to.get(), 0.8);
+/// assert_eq!(req.resolution_ms, 0);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_preview_request(json: &[u8]) -> Result<PreviewRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a brake request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_brake_request;
+/// use rs_trainz::BrakeSetting;
+///
+/// let req = parse_brake_request(br#"{"brake": "lap"}"#).unwrap();
+/// assert_eq!(req.brake, BrakeSetting::Lap);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_brake_request(json: &[u8]) -> Result<SetBrakeRequest, MessageError> {
+    from_json(json)
+}
+
+/// Parse a driving mode request from JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_driving_mode_request;
+/// use rs_trainz::DrivingMode;
+///
+/// let req = parse_driving_mode_request(br#"{"mode": "cab"}"#).unwrap();
+/// assert_eq!(req.mode, DrivingMode::Cab);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_driving_mode_request(json: &[u8]) -> Result<SetDrivingModeRequest, MessageError> {
+    from_json(json)
+}
+
+/// Deserialize a JSON message with `serde-json-core`.
+#[cfg(feature = "serde-json-core")]
+fn from_json<'a, T: Deserialize<'a>>(json: &'a [u8]) -> Result<T, MessageError> {
+    match serde_json_core::from_slice(json) {
+        Ok((value, _)) => Ok(value),
+        Err(serde_json_core::de::Error::CustomError) => Err(rejection(json)),
+        Err(_) => Err(MessageError::InvalidJson),
+    }
+}
+
+// serde-json-core discards custom error messages, so when a message fails
+// validation its speed, velocity or notch is re-read as a plain number to
+// report why.
+
+/// A request's speed field, unvalidated.
+#[cfg(feature = "serde-json-core")]
+#[derive(Deserialize)]
+struct RawSpeed {
+    #[serde(alias = "max_speed")]
+    speed: f32,
+}
+
+/// A request's velocity field, unvalidated.
+#[cfg(feature = "serde-json-core")]
+#[derive(Deserialize)]
+struct RawVelocity {
+    velocity: f32,
+}
+
+/// A request's notch field, unvalidated.
+#[cfg(feature = "serde-json-core")]
+#[derive(Deserialize)]
+struct RawNotch {
+    notch: u8,
+}
+
+/// A [`CommandEnvelope`] reduced to its validated field, unvalidated.
+#[cfg(feature = "serde-json-core")]
+#[derive(Deserialize)]
+struct RawEnvelope {
+    command: RawCommand,
+}
+
+#[cfg(feature = "serde-json-core")]
+#[derive(Deserialize)]
+enum RawCommand {
+    #[serde(rename = "set_speed")]
+    Speed(RawSpeed),
+    #[serde(rename = "set_max_speed")]
+    MaxSpeed(RawSpeed),
+    #[serde(rename = "set_velocity")]
+    Velocity(RawVelocity),
+    #[serde(rename = "set_notch")]
+    Notch(RawNotch),
+}
+
+#[cfg(feature = "serde-json-core")]
+impl RawCommand {
+    fn validate(self) -> Result<(), MessageError> {
+        match self {
+            Self::Speed(raw) | Self::MaxSpeed(raw) => Speed::new(raw.speed).map(drop)?,
+            Self::Velocity(raw) => Velocity::new(raw.velocity).map(drop)?,
+            Self::Notch(raw) => Notch::new(raw.notch).map(drop)?,
+        }
+        Ok(())
+    }
+}
+
+/// Explain why a message that failed validation was rejected.
+#[cfg(feature = "serde-json-core")]
+fn rejection(json: &[u8]) -> MessageError {
+    let raw = serde_json_core::from_slice::<RawSpeed>(json)
+        .map(|(raw, _)| RawCommand::Speed(raw))
+        .or_else(|_| {
+            serde_json_core::from_slice::<RawVelocity>(json)
+                .map(|(raw, _)| RawCommand::Velocity(raw))
+        })
+        .or_else(|_| {
+            serde_json_core::from_slice::<RawNotch>(json).map(|(raw, _)| RawCommand::Notch(raw))
+        })
+        .or_else(|_| serde_json_core::from_slice::<RawEnvelope>(json).map(|(raw, _)| raw.command));
+    match raw.map(RawCommand::validate) {
+        Ok(Err(e)) => e,
+        _ => MessageError::InvalidJson,
+    }
+}
+
+// ============================================================================
+// Command Messages (versioned, transport independent)
+// ============================================================================
+
+/// Current schema version for [`CommandEnvelope`].
+///
+/// Bumped whenever a change to [`CommandMessage`] would be misread by an
+/// older device. Envelopes with a newer version are rejected.
+pub const COMMAND_SCHEMA_VERSION: u16 = 1;
+
+fn default_schema_version() -> u16 {
+    COMMAND_SCHEMA_VERSION
+}
+
+/// A throttle command as it travels over the wire.
+///
+/// Covers every [`ThrottleCommandDyn`] variant, with the full strategy
+/// (lock, interrupt behavior, momentum parameters) carried as a
+/// [`StrategySpec`]. Externally tagged so both `serde_json` and
+/// `serde-json-core` can read and write it.
+///
+/// # JSON Examples
+///
+/// ```json
+/// {"set_speed": {"speed": 0.5}}
+/// {"set_speed": {"speed": 0.8, "strategy": {"ease_in_out": {"duration_ms": 3000, "lock": "hard"}}}}
+/// {"set_velocity": {"velocity": -0.5, "strategy": {"linear": {"duration_ms": 2000}}}}
+/// {"set_direction": {"direction": "forward"}}
+/// {"set_max_speed": {"max_speed": 0.8}}
+/// {"set_notch": {"notch": 3}}
+/// {"set_brake": {"brake": "apply"}}
+/// {"set_driving_mode": {"mode": "cab"}}
+/// {"brake": {"hold": true}}
+/// {"brake": {"strategy": {"linear": {"duration_ms": 1500, "lock": "hard"}}}}
+/// "release_brake"
+/// {"cancel_queued": {"id": 3}}
+/// "clear_queue"
+/// "pause"
+/// "resume"
+/// "force_release"
+/// "emergency_stop"
+/// "heartbeat"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandMessage {
+    /// Set speed using the given strategy.
+    SetSpeed {
+        /// Target speed (0.0 to 1.0)
+        speed: Speed,
+        /// Transition strategy (defaults to immediate)
+        #[serde(default)]
+        strategy: StrategySpec,
+    },
+    /// Set a signed velocity using the given strategy.
+    SetVelocity {
+        /// Target velocity (-1.0 to 1.0, negative is reverse)
+        velocity: Velocity,
+        /// Transition strategy for each leg (defaults to immediate)
+        #[serde(default)]
+        strategy: StrategySpec,
+    },
+    /// Set direction of travel.
+    SetDirection {
+        /// Target direction
+        direction: Direction,
+    },
+    /// Emergency stop.
+    EmergencyStop,
+    /// Set maximum allowed speed.
+    SetMaxSpeed {
+        /// Maximum speed (0.0 to 1.0)
+        max_speed: Speed,
+    },
+    /// Renew the heartbeat lease.
+    Heartbeat,
+    /// Select a throttle notch (cab driving mode).
+    SetNotch {
+        /// Throttle notch (0 to 8)
+        notch: Notch,
+    },
+    /// Move the brake handle (cab driving mode).
+    SetBrake {
+        /// Brake handle position
+        brake: BrakeSetting,
+    },
+    /// Switch driving mode.
+    SetDrivingMode {
+        /// Driving mode
+        mode: DrivingMode,
+    },
+    /// Apply the service brake.
+    Brake {
+        /// Deceleration profile (defaults to the controller's profile)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        strategy: Option<StrategySpec>,
+        /// Keep the brake applied once stopped
+        #[serde(default)]
+        hold: bool,
+    },
+    /// Release a held service brake.
+    ReleaseBrake,
+    /// Cancel a queued speed command.
+    CancelQueued {
+        /// Queue entry id
+        id: u32,
+    },
+    /// Cancel all queued speed commands the sender may cancel.
+    ClearQueue,
+    /// Freeze the active speed transition.
+    Pause,
+    /// Continue a paused speed transition.
+    Resume,
+    /// Drop the lock on the active speed transition.
+    ForceRelease,
+}
+
+impl From<CommandMessage> for ThrottleCommandDyn {
+    fn from(msg: CommandMessage) -> Self {
+        match msg {
+            CommandMessage::SetSpeed { speed, strategy } => ThrottleCommandDyn::SetSpeed {
+                target: speed,
+                strategy: strategy.into(),
+            },
+            CommandMessage::SetVelocity { velocity, strategy } => ThrottleCommandDyn::SetVelocity {
+                target: velocity,
+                strategy: strategy.into(),
+            },
+            CommandMessage::SetDirection { direction } => {
+                ThrottleCommandDyn::SetDirection(direction)
+            }
+            CommandMessage::EmergencyStop => ThrottleCommandDyn::EmergencyStop,
+            CommandMessage::SetMaxSpeed { max_speed } => ThrottleCommandDyn::SetMaxSpeed(max_speed),
+            CommandMessage::Heartbeat => ThrottleCommandDyn::Heartbeat,
+            CommandMessage::SetNotch { notch } => ThrottleCommandDyn::SetNotch(notch),
+            CommandMessage::SetBrake { brake } => ThrottleCommandDyn::SetBrake(brake),
+            CommandMessage::SetDrivingMode { mode } => ThrottleCommandDyn::SetDrivingMode(mode),
+            CommandMessage::Brake { strategy, hold } => ThrottleCommandDyn::Brake {
+                strategy: strategy.map(Into::into),
+                hold,
+            },
+            CommandMessage::ReleaseBrake => ThrottleCommandDyn::ReleaseBrake,
+            CommandMessage::CancelQueued { id } => ThrottleCommandDyn::CancelQueued(id),
+            CommandMessage::ClearQueue => ThrottleCommandDyn::ClearQueue,
+            CommandMessage::Pause => ThrottleCommandDyn::Pause,
+            CommandMessage::Resume => ThrottleCommandDyn::Resume,
+            CommandMessage::ForceRelease => ThrottleCommandDyn::ForceRelease,
+        }
+    }
+}
+
+/// The strategy described by a request's `preset`, `duration_ms` and `smooth` fields.
+fn request_strategy(preset: Option<PresetName>, duration_ms: u64, smooth: bool) -> StrategySpec {
+    match (preset, duration_ms, smooth) {
+        (Some(name), _, _) => StrategySpec::Preset(name),
+        (None, 0, _) => StrategySpec::Immediate,
+        (None, ms, true) => StrategySpec::EaseInOut(EaseInOut::new(ms)),
+        (None, ms, false) => StrategySpec::Linear(Linear::new(ms)),
+    }
+}
+
+impl From<SetSpeedRequest> for CommandMessage {
+    fn from(req: SetSpeedRequest) -> Self {
+        Self::SetSpeed {
+            speed: req.speed,
+            strategy: request_strategy(req.preset, req.duration_ms, req.smooth),
+        }
+    }
+}
+
+impl From<SetVelocityRequest> for CommandMessage {
+    fn from(req: SetVelocityRequest) -> Self {
+        Self::SetVelocity {
+            velocity: req.velocity,
+            strategy: request_strategy(req.preset, req.duration_ms, req.smooth),
+        }
+    }
+}
+
+impl From<SetDirectionRequest> for CommandMessage {
+    fn from(req: SetDirectionRequest) -> Self {
+        Self::SetDirection {
+            direction: req.direction,
+        }
+    }
+}
+
+impl From<SetMaxSpeedRequest> for CommandMessage {
+    fn from(req: SetMaxSpeedRequest) -> Self {
+        Self::SetMaxSpeed {
+            max_speed: req.max_speed,
+        }
+    }
+}
+
+impl From<ServiceBrakeRequest> for CommandMessage {
+    fn from(req: ServiceBrakeRequest) -> Self {
+        let strategy = match (&req.preset, req.duration_ms) {
+            (None, 0) => None,
+            _ => Some(request_strategy(req.preset, req.duration_ms, false)),
+        };
+        Self::Brake {
+            strategy,
+            hold: req.hold,
+        }
+    }
+}
+
+impl From<SetNotchRequest> for CommandMessage {
+    fn from(req: SetNotchRequest) -> Self {
+        Self::SetNotch { notch: req.notch }
+    }
+}
+
+impl From<CancelQueuedRequest> for CommandMessage {
+    fn from(req: CancelQueuedRequest) -> Self {
+        Self::CancelQueued { id: req.id }
+    }
+}
+
+impl From<SetBrakeRequest> for CommandMessage {
+    fn from(req: SetBrakeRequest) -> Self {
+        Self::SetBrake { brake: req.brake }
+    }
+}
+
+impl From<SetDrivingModeRequest> for CommandMessage {
+    fn from(req: SetDrivingModeRequest) -> Self {
+        Self::SetDrivingMode { mode: req.mode }
+    }
+}
+
+/// Versioned wrapper around a [`CommandMessage`].
+///
+/// This is the single wire format accepted by `POST /api/command` and the
+/// `{prefix}/command` MQTT topic. `version` may be omitted and defaults to
+/// [`COMMAND_SCHEMA_VERSION`]. `client` optionally names the sender within
+/// its source (see [`ClientId`]), and `correlation_id` is echoed back in the
+/// command's acknowledgements (see [`CorrelationId`]).
+///
+/// # JSON Example
+///
+/// ```json
+/// {"version": 1, "command": {"set_speed": {"speed": 0.5, "strategy": {"linear": {"duration_ms": 1000}}}}}
+/// {"version": 1, "client": "alice-phone", "command": "pause"}
+/// {"correlation_id": "req-42", "command": "emergency_stop"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandEnvelope {
+    /// Schema version the sender was written against
+    #[serde(default = "default_schema_version")]
+    pub version: u16,
+    /// Client that sent the command, if it names itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientId>,
+    /// Tag to echo back in the command's acknowledgements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<CorrelationId>,
+    /// The command itself
+    pub command: CommandMessage,
+}
+
+impl CommandEnvelope {
+    /// Wrap a command with the current schema version.
+    pub fn new(command: CommandMessage) -> Self {
+        Self {
+            version: COMMAND_SCHEMA_VERSION,
+            client: None,
+            correlation_id: None,
+            command,
+        }
+    }
+
+    /// Name the client sending the command.
+    pub fn with_client(mut self, client: ClientId) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Tag the command so its acknowledgements can be matched to it.
+    pub fn with_correlation_id(mut self, correlation_id: CorrelationId) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Check the schema version, then convert to a command.
+    pub fn into_command(self) -> Result<ThrottleCommandDyn, MessageError> {
+        if self.version == 0 || self.version > COMMAND_SCHEMA_VERSION {
+            return Err(MessageError::UnsupportedVersion(self.version));
+        }
+        Ok(self.command.into())
+    }
+}
+
+/// A command parsed from the wire, with whatever the sender said about it.
+#[derive(Debug, Clone)]
+pub struct ParsedCommand {
+    /// The command to apply
+    pub command: ThrottleCommandDyn,
+    /// Client that sent the command, if it names itself
+    pub client: Option<ClientId>,
+    /// Tag to echo back in the command's acknowledgements
+    pub correlation_id: Option<CorrelationId>,
+}
+
+impl From<ThrottleCommandDyn> for ParsedCommand {
+    fn from(command: ThrottleCommandDyn) -> Self {
+        Self {
+            command,
+            client: None,
+            correlation_id: None,
+        }
+    }
+}
+
+/// Error from parsing or serializing a command message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageError {
+    /// Payload is not valid JSON for the expected type.
+    InvalidJson,
+    /// Envelope was written against an unknown schema version.
+    UnsupportedVersion(u16),
+    /// A speed or velocity value is not finite or is out of range.
+    InvalidSpeed(SpeedError),
+    /// A throttle notch is above the highest notch.
+    InvalidNotch,
+    /// MQTT topic doesn't name a command.
+    UnknownTopic,
+    /// Output buffer too small for the serialized message.
+    BufferFull,
+}
+
+impl From<SpeedError> for MessageError {
+    fn from(e: SpeedError) -> Self {
+        Self::InvalidSpeed(e)
+    }
+}
+
+impl From<NotchError> for MessageError {
+    fn from(_: NotchError) -> Self {
+        Self::InvalidNotch
+    }
+}
+
+impl core::fmt::Display for MessageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidJson => write!(f, "invalid command message"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported schema version {}", v),
+            Self::InvalidSpeed(e) => write!(f, "{}", e),
+            Self::InvalidNotch => write!(f, "{}", NotchError),
+            Self::UnknownTopic => write!(f, "unknown topic"),
+            Self::BufferFull => write!(f, "buffer too small"),
+        }
+    }
+}
+
+/// Parse a versioned command envelope into a throttle command.
+///
+/// This is the shared parse path for every transport.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_command;
+/// use rs_trainz::{ExecutionStrategy, ThrottleCommandDyn, TransitionLock};
+///
+/// let json = br#"{"version": 1, "command": {"set_speed": {"speed": 0.5,
+///     "strategy": {"linear": {"duration_ms": 1000, "lock": "source"}}}}}"#;
+/// let cmd = parse_command(json).unwrap();
+///
+/// let ThrottleCommandDyn::SetSpeed { target, strategy } = cmd else { panic!() };
+/// assert_eq!(target, 0.5);
+/// assert_eq!(strategy.lock(), TransitionLock::Source);
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_command(json: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    parse_command_as(json).map(|parsed| parsed.command)
+}
+
+/// Parse a versioned command envelope, keeping its client and correlation id.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::parse_command_as;
+/// use rs_trainz::ThrottleCommandDyn;
+///
+/// let json = br#"{"client": "alice-phone", "correlation_id": "req-1", "command": "pause"}"#;
+/// let parsed = parse_command_as(json).unwrap();
+/// assert!(matches!(parsed.command, ThrottleCommandDyn::Pause));
+/// assert_eq!(parsed.client.unwrap().as_str(), "alice-phone");
+/// assert_eq!(parsed.correlation_id.unwrap().as_str(), "req-1");
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_command_as(json: &[u8]) -> Result<ParsedCommand, MessageError> {
+    let envelope = from_json::<CommandEnvelope>(json)?;
+    let client = envelope.client;
+    let correlation_id = envelope.correlation_id;
+    Ok(ParsedCommand {
+        command: envelope.into_command()?,
+        client,
+        correlation_id,
+    })
 }
 
-/// Parse a max speed request from JSON bytes.
+/// Serialize a command envelope into `buf`, returning the number of bytes written.
 ///
 /// # Example
 ///
 /// ```
-/// use rs_trainz::messages::parse_max_speed_request;
+/// use rs_trainz::messages::{write_command, CommandEnvelope, CommandMessage};
 ///
-/// let json = br#"{"max_speed": 0.8}"#;
-/// let req = parse_max_speed_request(json).unwrap();
-/// assert_eq!(req.max_speed, 0.8);
+/// let mut buf = [0u8; 64];
+/// let len = write_command(&CommandEnvelope::new(CommandMessage::EmergencyStop), &mut buf).unwrap();
+/// assert_eq!(&buf[..len], br#"{"version":1,"command":"emergency_stop"}"#);
 /// ```
 #[cfg(feature = "serde-json-core")]
-pub fn parse_max_speed_request(json: &[u8]) -> Option<SetMaxSpeedRequest> {
-    serde_json_core::from_slice(json).ok().map(|(req, _)| req)
+pub fn write_command(envelope: &CommandEnvelope, buf: &mut [u8]) -> Result<usize, MessageError> {
+    serde_json_core::to_slice(envelope, buf).map_err(|_| MessageError::BufferFull)
 }
 
 // ============================================================================
@@ -209,37 +1157,141 @@ use crate::traits::{EaseInOut, Linear};
 /// # Topic Suffixes
 ///
 /// - `"speed/set"` - Set speed (JSON or plain float)
+/// - `"velocity/set"` - Set signed velocity (JSON or plain float)
 /// - `"direction/set"` - Set direction (JSON or plain text)
 /// - `"estop"` - Emergency stop (any payload)
 /// - `"max-speed/set"` - Set max speed (JSON or plain float)
+/// - `"heartbeat"` - Renew the heartbeat lease (any payload)
+/// - `"service-brake"` - Apply the service brake (JSON, `"hold"` or any payload)
+/// - `"service-brake/release"` - Release a held service brake (any payload)
+/// - `"notch/set"` - Select a throttle notch (JSON or plain integer)
+/// - `"brake/set"` - Move the brake handle (JSON or plain text)
+/// - `"mode/set"` - Switch driving mode (JSON or plain text)
+/// - `"queue/cancel"` - Cancel a queued speed command (JSON or plain id)
+/// - `"queue/clear"` - Cancel all queued speed commands (any payload)
+/// - `"pause"` - Freeze the active speed transition (any payload)
+/// - `"resume"` - Continue a paused speed transition (any payload)
+/// - `"lock/release"` - Force release the transition lock (any payload)
+/// - `"command"` - Versioned [`CommandEnvelope`] (see [`parse_command`])
+///
+/// Invalid speeds (NaN, infinity, outside 0.0 to 1.0) are rejected with
+/// [`MessageError::InvalidSpeed`], never clamped.
 ///
 /// # Examples
 ///
 /// ```
-/// use rs_trainz::messages::parse_mqtt_command;
-/// use rs_trainz::ThrottleCommandDyn;
+/// use rs_trainz::messages::{parse_mqtt_command, MessageError};
+/// use rs_trainz::{SpeedError, ThrottleCommandDyn};
 ///
 /// // Speed from plain text
 /// let cmd = parse_mqtt_command("speed/set", b"0.5");
-/// assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { .. })));
+/// assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetSpeed { .. })));
 ///
 /// // Direction from text
 /// let cmd = parse_mqtt_command("direction/set", b"forward");
-/// assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(_))));
+/// assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(_))));
 ///
 /// // Emergency stop
 /// let cmd = parse_mqtt_command("estop", b"");
-/// assert!(matches!(cmd, Some(ThrottleCommandDyn::EmergencyStop)));
+/// assert!(matches!(cmd, Ok(ThrottleCommandDyn::EmergencyStop)));
+///
+/// // NaN never reaches the motor
+/// let cmd = parse_mqtt_command("speed/set", b"NaN");
+/// assert!(matches!(cmd, Err(MessageError::InvalidSpeed(SpeedError::NotFinite))));
 /// ```
 #[cfg(feature = "serde-json-core")]
-pub fn parse_mqtt_command(topic_suffix: &str, payload: &[u8]) -> Option<ThrottleCommandDyn> {
+pub fn parse_mqtt_command(
+    topic_suffix: &str,
+    payload: &[u8],
+) -> Result<ThrottleCommandDyn, MessageError> {
     match topic_suffix {
         "speed/set" => parse_speed_payload(payload),
+        "velocity/set" => parse_velocity_payload(payload),
         "direction/set" => parse_direction_payload(payload),
-        "estop" => Some(ThrottleCommandDyn::EmergencyStop),
+        "estop" => Ok(ThrottleCommandDyn::EmergencyStop),
         "max-speed/set" => parse_max_speed_payload(payload),
-        _ => None,
+        "heartbeat" => Ok(ThrottleCommandDyn::Heartbeat),
+        "service-brake" => parse_service_brake_payload(payload),
+        "service-brake/release" => Ok(ThrottleCommandDyn::ReleaseBrake),
+        "notch/set" => parse_notch_payload(payload),
+        "brake/set" => parse_brake_payload(payload),
+        "mode/set" => parse_driving_mode_payload(payload),
+        "queue/cancel" => parse_cancel_queued_payload(payload),
+        "queue/clear" => Ok(ThrottleCommandDyn::ClearQueue),
+        "pause" => Ok(ThrottleCommandDyn::Pause),
+        "resume" => Ok(ThrottleCommandDyn::Resume),
+        "lock/release" => Ok(ThrottleCommandDyn::ForceRelease),
+        "command" => parse_command(payload),
+        _ => Err(MessageError::UnknownTopic),
+    }
+}
+
+/// Parse an MQTT payload into a throttle command and what the sender said about it.
+///
+/// Like [`parse_mqtt_command`], but a `"command"` envelope may also name
+/// its client and correlation id. Other topics carry neither.
+#[cfg(feature = "serde-json-core")]
+pub fn parse_mqtt_command_as(
+    topic_suffix: &str,
+    payload: &[u8],
+) -> Result<ParsedCommand, MessageError> {
+    match topic_suffix {
+        "command" => parse_command_as(payload),
+        _ => parse_mqtt_command(topic_suffix, payload).map(ParsedCommand::from),
+    }
+}
+
+/// Parse an MQTT lease topic and payload into a lease action and client.
+///
+/// Topics are `"lease/acquire"`, `"lease/release"` and `"lease/steal"`;
+/// anything else is [`MessageError::UnknownTopic`]. See
+/// [`parse_lease_payload`] for the payload.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::messages::{parse_mqtt_lease, LeaseAction};
+///
+/// let (action, client) = parse_mqtt_lease("lease/acquire", b"cab-3").unwrap();
+/// assert_eq!(action, LeaseAction::Acquire);
+/// assert_eq!(client.unwrap().as_str(), "cab-3");
+/// ```
+#[cfg(feature = "serde-json-core")]
+pub fn parse_mqtt_lease(
+    topic_suffix: &str,
+    payload: &[u8],
+) -> Result<(LeaseAction, Option<ClientId>), MessageError> {
+    let action = match topic_suffix {
+        "lease/acquire" => LeaseAction::Acquire,
+        "lease/release" => LeaseAction::Release,
+        "lease/steal" => LeaseAction::Steal,
+        _ => return Err(MessageError::UnknownTopic),
+    };
+    Ok((action, parse_lease_payload(payload)?))
+}
+
+/// Parse a lease payload from JSON, plain text or nothing.
+///
+/// Supports:
+/// - Empty: no client
+/// - Plain text: `"cab-3"`
+/// - JSON: `{"client": "cab-3"}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_lease_payload(payload: &[u8]) -> Result<Option<ClientId>, MessageError> {
+    // Try JSON first
+    match parse_lease_request(payload) {
+        Ok(req) => return Ok(req.client),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
+    }
+
+    let text = text_payload(payload)?.trim();
+    if text.is_empty() {
+        return Ok(None);
     }
+    ClientId::new(text)
+        .map(Some)
+        .map_err(|_| MessageError::InvalidJson)
 }
 
 /// Parse speed payload from JSON or plain float.
@@ -248,33 +1300,35 @@ pub fn parse_mqtt_command(topic_suffix: &str, payload: &[u8]) -> Option<Throttle
 /// - Plain float: `"0.5"`
 /// - JSON: `{"speed": 0.5, "duration_ms": 1000, "smooth": true}`
 #[cfg(feature = "serde-json-core")]
-pub fn parse_speed_payload(payload: &[u8]) -> Option<ThrottleCommandDyn> {
+pub fn parse_speed_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
     // Try JSON first
-    if let Some(req) = parse_speed_request(payload) {
-        let speed = req.speed.clamp(0.0, 1.0);
-        return Some(if req.duration_ms > 0 {
-            if req.smooth {
-                ThrottleCommand::SetSpeed {
-                    target: speed,
-                    strategy: EaseInOut::new(req.duration_ms),
-                }
-                .into()
-            } else {
-                ThrottleCommand::SetSpeed {
-                    target: speed,
-                    strategy: Linear::new(req.duration_ms),
-                }
-                .into()
-            }
-        } else {
-            ThrottleCommand::speed_immediate(speed).into()
-        });
+    match parse_speed_request(payload) {
+        Ok(req) => return Ok(CommandMessage::from(req).into()),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
     }
 
     // Fall back to plain float for backward compatibility
-    let payload_str = core::str::from_utf8(payload).ok()?;
-    let speed: f32 = payload_str.trim().parse().ok()?;
-    Some(ThrottleCommand::speed_immediate(speed.clamp(0.0, 1.0)).into())
+    let speed: Speed = text_payload(payload)?.parse()?;
+    Ok(ThrottleCommand::speed_immediate(speed).into())
+}
+
+/// Parse velocity payload from JSON or plain float.
+///
+/// Supports:
+/// - Plain float: `"-0.5"`
+/// - JSON: `{"velocity": -0.5, "duration_ms": 1000, "smooth": true}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_velocity_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    // Try JSON first
+    match parse_velocity_request(payload) {
+        Ok(req) => return Ok(CommandMessage::from(req).into()),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
+    }
+
+    let velocity: Velocity = text_payload(payload)?.parse()?;
+    Ok(ThrottleCommand::velocity_immediate(velocity).into())
 }
 
 /// Parse direction payload from JSON or plain text.
@@ -283,16 +1337,15 @@ pub fn parse_speed_payload(payload: &[u8]) -> Option<ThrottleCommandDyn> {
 /// - Plain text: `"forward"`, `"fwd"`, `"1"`, `"reverse"`, `"rev"`, `"-1"`, `"stop"`, `"stopped"`
 /// - JSON: `{"direction": "forward"}`
 #[cfg(feature = "serde-json-core")]
-pub fn parse_direction_payload(payload: &[u8]) -> Option<ThrottleCommandDyn> {
+pub fn parse_direction_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
     // Try JSON first
-    if let Some(req) = parse_direction_request(payload) {
-        return Some(ThrottleCommandDyn::SetDirection(req.direction));
+    if let Ok(req) = parse_direction_request(payload) {
+        return Ok(ThrottleCommandDyn::SetDirection(req.direction));
     }
 
     // Fall back to plain text using Direction::from_text()
-    let payload_str = core::str::from_utf8(payload).ok()?;
-    let dir = Direction::from_text(payload_str)?;
-    Some(ThrottleCommandDyn::SetDirection(dir))
+    let dir = Direction::from_text(text_payload(payload)?).ok_or(MessageError::InvalidJson)?;
+    Ok(ThrottleCommandDyn::SetDirection(dir))
 }
 
 /// Parse max speed payload from JSON or plain float.
@@ -301,16 +1354,120 @@ pub fn parse_direction_payload(payload: &[u8]) -> Option<ThrottleCommandDyn> {
 /// - Plain float: `"0.8"`
 /// - JSON: `{"max_speed": 0.8}`
 #[cfg(feature = "serde-json-core")]
-pub fn parse_max_speed_payload(payload: &[u8]) -> Option<ThrottleCommandDyn> {
+pub fn parse_max_speed_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
     // Try JSON first
-    if let Some(req) = parse_max_speed_request(payload) {
-        return Some(ThrottleCommandDyn::SetMaxSpeed(req.max_speed.clamp(0.0, 1.0)));
+    match parse_max_speed_request(payload) {
+        Ok(req) => return Ok(ThrottleCommandDyn::SetMaxSpeed(req.max_speed)),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
     }
 
     // Fall back to plain float
-    let payload_str = core::str::from_utf8(payload).ok()?;
-    let max_speed: f32 = payload_str.trim().parse().ok()?;
-    Some(ThrottleCommandDyn::SetMaxSpeed(max_speed.clamp(0.0, 1.0)))
+    let max_speed: Speed = text_payload(payload)?.parse()?;
+    Ok(ThrottleCommandDyn::SetMaxSpeed(max_speed))
+}
+
+/// Parse service brake payload from JSON or plain text.
+///
+/// Supports:
+/// - Plain text: `"hold"` holds the train once stopped; any other payload
+///   (including empty) brakes without holding
+/// - JSON: `{"hold": true, "duration_ms": 1500}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_service_brake_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    match parse_service_brake_request(payload) {
+        Ok(req) => return Ok(CommandMessage::from(req).into()),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
+    }
+
+    let hold = text_payload(payload)?.trim().eq_ignore_ascii_case("hold");
+    Ok(ThrottleCommandDyn::Brake {
+        strategy: None,
+        hold,
+    })
+}
+
+/// Parse notch payload from JSON or plain integer.
+///
+/// Supports:
+/// - Plain integer: `"3"`
+/// - JSON: `{"notch": 3}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_notch_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    // Try JSON first
+    match parse_notch_request(payload) {
+        Ok(req) => return Ok(ThrottleCommandDyn::SetNotch(req.notch)),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
+    }
+
+    let notch: u8 = text_payload(payload)?
+        .trim()
+        .parse()
+        .map_err(|_| MessageError::InvalidJson)?;
+    Ok(ThrottleCommandDyn::SetNotch(Notch::new(notch)?))
+}
+
+/// Parse cancel-queued payload from JSON or plain integer.
+///
+/// Supports:
+/// - Plain integer: `"3"`
+/// - JSON: `{"id": 3}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_cancel_queued_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    // Try JSON first
+    match parse_cancel_queued_request(payload) {
+        Ok(req) => return Ok(ThrottleCommandDyn::CancelQueued(req.id)),
+        Err(MessageError::InvalidJson) => {}
+        Err(e) => return Err(e),
+    }
+
+    let id: u32 = text_payload(payload)?
+        .trim()
+        .parse()
+        .map_err(|_| MessageError::InvalidJson)?;
+    Ok(ThrottleCommandDyn::CancelQueued(id))
+}
+
+/// Parse brake payload from JSON or plain text.
+///
+/// Supports:
+/// - Plain text: `"release"`, `"lap"`, `"apply"`, `"emergency"`
+/// - JSON: `{"brake": "apply"}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_brake_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    if let Ok(req) = parse_brake_request(payload) {
+        return Ok(ThrottleCommandDyn::SetBrake(req.brake));
+    }
+
+    let brake = BrakeSetting::from_text(text_payload(payload)?).ok_or(MessageError::InvalidJson)?;
+    Ok(ThrottleCommandDyn::SetBrake(brake))
+}
+
+/// Parse driving mode payload from JSON or plain text.
+///
+/// Supports:
+/// - Plain text: `"direct"`, `"cab"`
+/// - JSON: `{"mode": "cab"}`
+#[cfg(feature = "serde-json-core")]
+pub fn parse_driving_mode_payload(payload: &[u8]) -> Result<ThrottleCommandDyn, MessageError> {
+    if let Ok(req) = parse_driving_mode_request(payload) {
+        return Ok(ThrottleCommandDyn::SetDrivingMode(req.mode));
+    }
+
+    let mode = match text_payload(payload)?.trim() {
+        text if text.eq_ignore_ascii_case("direct") => DrivingMode::Direct,
+        text if text.eq_ignore_ascii_case("cab") => DrivingMode::Cab,
+        _ => return Err(MessageError::InvalidJson),
+    };
+    Ok(ThrottleCommandDyn::SetDrivingMode(mode))
+}
+
+/// A plain-text payload as a string.
+#[cfg(feature = "serde-json-core")]
+fn text_payload(payload: &[u8]) -> Result<&str, MessageError> {
+    core::str::from_utf8(payload).map_err(|_| MessageError::InvalidJson)
 }
 
 #[cfg(test)]
@@ -323,7 +1480,7 @@ mod tests {
 
     #[test]
     fn test_set_speed_request_immediate() {
-        let req = SetSpeedRequest::immediate(0.5);
+        let req = SetSpeedRequest::immediate(Speed::literal(0.5));
         assert_eq!(req.speed, 0.5);
         assert_eq!(req.duration_ms, 0);
         assert!(!req.smooth);
@@ -331,15 +1488,28 @@ mod tests {
 
     #[test]
     fn test_set_speed_request_linear() {
-        let req = SetSpeedRequest::linear(0.8, 2000);
+        let req = SetSpeedRequest::linear(Speed::literal(0.8), 2000);
         assert_eq!(req.speed, 0.8);
         assert_eq!(req.duration_ms, 2000);
         assert!(!req.smooth);
     }
 
+    #[test]
+    fn test_set_speed_request_preset_overrides_timing() {
+        let mut req = SetSpeedRequest::preset(Speed::literal(0.5), "arrival").unwrap();
+        req.duration_ms = 1000;
+        assert_eq!(
+            CommandMessage::from(req),
+            CommandMessage::SetSpeed {
+                speed: Speed::literal(0.5),
+                strategy: StrategySpec::preset("arrival").unwrap(),
+            }
+        );
+    }
+
     #[test]
     fn test_set_speed_request_smooth() {
-        let req = SetSpeedRequest::smooth(1.0, 3000);
+        let req = SetSpeedRequest::smooth(Speed::literal(1.0), 3000);
         assert_eq!(req.speed, 1.0);
         assert_eq!(req.duration_ms, 3000);
         assert!(req.smooth);
@@ -369,7 +1539,7 @@ mod tests {
     #[cfg(feature = "std")]
     #[test]
     fn test_set_speed_request_serialize() {
-        let req = SetSpeedRequest::smooth(0.5, 1000);
+        let req = SetSpeedRequest::smooth(Speed::literal(0.5), 1000);
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"speed\":0.5"));
         assert!(json.contains("\"duration_ms\":1000"));
@@ -424,7 +1594,7 @@ mod tests {
 
     #[test]
     fn test_set_max_speed_request_new() {
-        let req = SetMaxSpeedRequest::new(0.8);
+        let req = SetMaxSpeedRequest::new(Speed::literal(0.8));
         assert_eq!(req.max_speed, 0.8);
     }
 
@@ -439,11 +1609,54 @@ mod tests {
     #[cfg(feature = "std")]
     #[test]
     fn test_set_max_speed_request_serialize() {
-        let req = SetMaxSpeedRequest::new(0.9);
+        let req = SetMaxSpeedRequest::new(Speed::literal(0.9));
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"max_speed\":0.9"));
     }
 
+    // =========================================================================
+    // ServiceBrakeRequest tests
+    // =========================================================================
+
+    #[test]
+    fn test_service_brake_request_strategy() {
+        let msg = CommandMessage::from(ServiceBrakeRequest::new(true));
+        assert_eq!(
+            msg,
+            CommandMessage::Brake {
+                strategy: None,
+                hold: true
+            }
+        );
+
+        let msg = CommandMessage::from(ServiceBrakeRequest::linear(false, 1500));
+        assert_eq!(
+            msg,
+            CommandMessage::Brake {
+                strategy: Some(StrategySpec::Linear(Linear::new(1500))),
+                hold: false
+            }
+        );
+    }
+
+    // =========================================================================
+    // Cab request tests
+    // =========================================================================
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_cab_requests_serde() {
+        let req: SetNotchRequest = serde_json::from_str(r#"{"notch": 5}"#).unwrap();
+        assert_eq!(req, SetNotchRequest::new(Notch::new(5).unwrap()));
+        assert!(serde_json::from_str::<SetNotchRequest>(r#"{"notch": 9}"#).is_err());
+
+        let req: SetBrakeRequest = serde_json::from_str(r#"{"brake": "emergency"}"#).unwrap();
+        assert_eq!(req, SetBrakeRequest::new(BrakeSetting::Emergency));
+
+        let req: SetDrivingModeRequest = serde_json::from_str(r#"{"mode": "cab"}"#).unwrap();
+        assert_eq!(req, SetDrivingModeRequest::new(DrivingMode::Cab));
+    }
+
     // =========================================================================
     // MQTT Command Parsing tests
     // =========================================================================
@@ -456,118 +1669,774 @@ mod tests {
         #[test]
         fn test_parse_mqtt_command_speed_plain() {
             let cmd = super::super::parse_mqtt_command("speed/set", b"0.5");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { target, .. }) if (target - 0.5).abs() < 0.001));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetSpeed { target, .. }) if (target.get() - 0.5).abs() < 0.001));
         }
 
         #[test]
         fn test_parse_mqtt_command_speed_json_immediate() {
             let cmd = super::super::parse_mqtt_command("speed/set", br#"{"speed": 0.75}"#);
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { target, .. }) if (target - 0.75).abs() < 0.001));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetSpeed { target, .. }) if (target.get() - 0.75).abs() < 0.001));
         }
 
         #[test]
         fn test_parse_mqtt_command_speed_json_linear() {
             let cmd = super::super::parse_mqtt_command("speed/set", br#"{"speed": 0.5, "duration_ms": 1000}"#);
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { target, .. }) if (target - 0.5).abs() < 0.001));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetSpeed { target, .. }) if (target.get() - 0.5).abs() < 0.001));
         }
 
         #[test]
         fn test_parse_mqtt_command_speed_json_smooth() {
             let cmd = super::super::parse_mqtt_command("speed/set", br#"{"speed": 0.5, "duration_ms": 2000, "smooth": true}"#);
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { target, .. }) if (target - 0.5).abs() < 0.001));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetSpeed { target, .. }) if (target.get() - 0.5).abs() < 0.001));
         }
 
         #[test]
-        fn test_parse_mqtt_command_speed_clamped() {
+        fn test_parse_mqtt_command_speed_rejected() {
             let cmd = super::super::parse_mqtt_command("speed/set", b"1.5");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { target, .. }) if (target - 1.0).abs() < 0.001));
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::OutOfRange)));
 
             let cmd = super::super::parse_mqtt_command("speed/set", b"-0.5");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetSpeed { target, .. }) if target.abs() < 0.001));
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::OutOfRange)));
+
+            let cmd = super::super::parse_mqtt_command("speed/set", b"NaN");
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::NotFinite)));
+
+            let cmd = super::super::parse_mqtt_command("speed/set", b"inf");
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::NotFinite)));
+
+            let cmd = super::super::parse_mqtt_command("speed/set", br#"{"speed": 1.5}"#);
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::OutOfRange)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_velocity() {
+            let cmd = super::super::parse_mqtt_command("velocity/set", b"-0.5");
+            assert!(
+                matches!(cmd, Ok(ThrottleCommandDyn::SetVelocity { target, .. }) if target == -0.5)
+            );
+
+            let cmd = super::super::parse_mqtt_command(
+                "velocity/set",
+                br#"{"velocity": 0.4, "duration_ms": 1000}"#,
+            );
+            let Ok(ThrottleCommandDyn::SetVelocity { target, strategy }) = cmd else {
+                panic!("expected set_velocity");
+            };
+            assert_eq!(target, 0.4);
+            assert_eq!(strategy.duration_ms(), Some(1000));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_velocity_rejected() {
+            let cmd = super::super::parse_mqtt_command("velocity/set", b"-1.5");
+            assert_eq!(
+                cmd.err(),
+                Some(MessageError::InvalidSpeed(SpeedError::VelocityOutOfRange))
+            );
+
+            let cmd = super::super::parse_mqtt_command("velocity/set", br#"{"velocity": 2.0}"#);
+            assert_eq!(
+                cmd.err(),
+                Some(MessageError::InvalidSpeed(SpeedError::VelocityOutOfRange))
+            );
+
+            let cmd = super::super::parse_mqtt_command("velocity/set", b"NaN");
+            assert_eq!(
+                cmd.err(),
+                Some(MessageError::InvalidSpeed(SpeedError::NotFinite))
+            );
         }
 
         #[test]
         fn test_parse_mqtt_command_direction_text() {
             let cmd = super::super::parse_mqtt_command("direction/set", b"forward");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Forward))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Forward))));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"reverse");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Reverse))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Reverse))));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"stopped");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Stopped))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Stopped))));
         }
 
         #[test]
         fn test_parse_mqtt_command_direction_abbreviations() {
             let cmd = super::super::parse_mqtt_command("direction/set", b"fwd");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Forward))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Forward))));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"rev");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Reverse))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Reverse))));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"stop");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Stopped))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Stopped))));
         }
 
         #[test]
         fn test_parse_mqtt_command_direction_numeric() {
             let cmd = super::super::parse_mqtt_command("direction/set", b"1");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Forward))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Forward))));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"-1");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Reverse))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Reverse))));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"0");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Stopped))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Stopped))));
         }
 
         #[test]
         fn test_parse_mqtt_command_direction_json() {
             let cmd = super::super::parse_mqtt_command("direction/set", br#"{"direction": "forward"}"#);
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetDirection(Direction::Forward))));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetDirection(Direction::Forward))));
         }
 
         #[test]
         fn test_parse_mqtt_command_estop() {
             let cmd = super::super::parse_mqtt_command("estop", b"");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::EmergencyStop)));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::EmergencyStop)));
 
             let cmd = super::super::parse_mqtt_command("estop", b"any payload");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::EmergencyStop)));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::EmergencyStop)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_heartbeat() {
+            let cmd = super::super::parse_mqtt_command("heartbeat", b"");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::Heartbeat)));
         }
 
         #[test]
         fn test_parse_mqtt_command_max_speed_plain() {
             let cmd = super::super::parse_mqtt_command("max-speed/set", b"0.8");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetMaxSpeed(max)) if (max - 0.8).abs() < 0.001));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetMaxSpeed(max)) if (max.get() - 0.8).abs() < 0.001));
         }
 
         #[test]
         fn test_parse_mqtt_command_max_speed_json() {
             let cmd = super::super::parse_mqtt_command("max-speed/set", br#"{"max_speed": 0.75}"#);
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetMaxSpeed(max)) if (max - 0.75).abs() < 0.001));
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetMaxSpeed(max)) if (max.get() - 0.75).abs() < 0.001));
         }
 
         #[test]
-        fn test_parse_mqtt_command_max_speed_clamped() {
+        fn test_parse_mqtt_command_max_speed_rejected() {
             let cmd = super::super::parse_mqtt_command("max-speed/set", b"1.5");
-            assert!(matches!(cmd, Some(ThrottleCommandDyn::SetMaxSpeed(max)) if (max - 1.0).abs() < 0.001));
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::OutOfRange)));
+
+            let cmd = super::super::parse_mqtt_command("max-speed/set", b"NaN");
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::NotFinite)));
+
+            let cmd = super::super::parse_mqtt_command("max-speed/set", br#"{"max_speed": -0.1}"#);
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::OutOfRange)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_service_brake() {
+            let cmd = super::super::parse_mqtt_command("service-brake", b"");
+            assert!(matches!(
+                cmd,
+                Ok(ThrottleCommandDyn::Brake {
+                    strategy: None,
+                    hold: false
+                })
+            ));
+
+            let cmd = super::super::parse_mqtt_command("service-brake", b"HOLD");
+            assert!(matches!(
+                cmd,
+                Ok(ThrottleCommandDyn::Brake {
+                    strategy: None,
+                    hold: true
+                })
+            ));
+
+            let cmd = super::super::parse_mqtt_command(
+                "service-brake",
+                br#"{"preset": "arrival", "hold": true}"#,
+            );
+            let Ok(ThrottleCommandDyn::Brake { strategy, hold }) = cmd else {
+                panic!("expected brake");
+            };
+            assert!(hold);
+            assert_eq!(
+                strategy.and_then(|s| s.spec().cloned()),
+                Some(StrategySpec::preset("arrival").unwrap())
+            );
+
+            let cmd = super::super::parse_mqtt_command("service-brake/release", b"");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::ReleaseBrake)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_notch() {
+            let cmd = super::super::parse_mqtt_command("notch/set", b"4");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetNotch(n)) if n.get() == 4));
+
+            let cmd = super::super::parse_mqtt_command("notch/set", br#"{"notch": 8}"#);
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetNotch(n)) if n == Notch::MAX));
+
+            let cmd = super::super::parse_mqtt_command("notch/set", b"9");
+            assert_eq!(cmd.err(), Some(MessageError::InvalidNotch));
+
+            let cmd = super::super::parse_mqtt_command("notch/set", br#"{"notch": 12}"#);
+            assert_eq!(cmd.err(), Some(MessageError::InvalidNotch));
+
+            let cmd = super::super::parse_mqtt_command("notch/set", b"full");
+            assert_eq!(cmd.err(), Some(MessageError::InvalidJson));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_brake() {
+            let cmd = super::super::parse_mqtt_command("brake/set", b"Apply");
+            assert!(matches!(
+                cmd,
+                Ok(ThrottleCommandDyn::SetBrake(BrakeSetting::Apply))
+            ));
+
+            let cmd = super::super::parse_mqtt_command("brake/set", br#"{"brake": "lap"}"#);
+            assert!(matches!(
+                cmd,
+                Ok(ThrottleCommandDyn::SetBrake(BrakeSetting::Lap))
+            ));
+
+            let cmd = super::super::parse_mqtt_command("brake/set", b"handbrake");
+            assert!(cmd.is_err());
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_driving_mode() {
+            let cmd = super::super::parse_mqtt_command("mode/set", b"cab");
+            assert!(matches!(
+                cmd,
+                Ok(ThrottleCommandDyn::SetDrivingMode(DrivingMode::Cab))
+            ));
+
+            let cmd = super::super::parse_mqtt_command("mode/set", br#"{"mode": "direct"}"#);
+            assert!(matches!(
+                cmd,
+                Ok(ThrottleCommandDyn::SetDrivingMode(DrivingMode::Direct))
+            ));
+
+            let cmd = super::super::parse_mqtt_command("mode/set", b"autopilot");
+            assert!(cmd.is_err());
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_queue() {
+            let cmd = super::super::parse_mqtt_command("queue/cancel", b"3");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::CancelQueued(3))));
+
+            let cmd = super::super::parse_mqtt_command("queue/cancel", br#"{"id": 4}"#);
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::CancelQueued(4))));
+
+            let cmd = super::super::parse_mqtt_command("queue/cancel", b"next");
+            assert!(cmd.is_err());
+
+            let cmd = super::super::parse_mqtt_command("queue/clear", b"");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::ClearQueue)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_pause_resume() {
+            let cmd = super::super::parse_mqtt_command("pause", b"");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::Pause)));
+
+            let cmd = super::super::parse_mqtt_command("resume", b"1");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::Resume)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_lock_release() {
+            let cmd = super::super::parse_mqtt_command("lock/release", b"");
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::ForceRelease)));
+        }
+
+        #[test]
+        fn test_parse_mqtt_lease() {
+            let cab = ClientId::new("cab-3").unwrap();
+
+            let lease = super::super::parse_mqtt_lease("lease/acquire", b"cab-3");
+            assert_eq!(lease, Ok((LeaseAction::Acquire, Some(cab))));
+
+            let lease = super::super::parse_mqtt_lease("lease/steal", br#"{"client": "cab-3"}"#);
+            assert_eq!(lease, Ok((LeaseAction::Steal, Some(cab))));
+
+            let lease = super::super::parse_mqtt_lease("lease/release", b"");
+            assert_eq!(lease, Ok((LeaseAction::Release, None)));
+
+            let lease = super::super::parse_mqtt_lease("lease/acquire", b"no spaces");
+            assert_eq!(lease, Err(MessageError::InvalidJson));
+
+            let lease = super::super::parse_mqtt_lease("speed/set", b"0.5");
+            assert_eq!(lease, Err(MessageError::UnknownTopic));
         }
 
         #[test]
         fn test_parse_mqtt_command_unknown_topic() {
             let cmd = super::super::parse_mqtt_command("unknown/topic", b"payload");
-            assert!(cmd.is_none());
+            assert_eq!(cmd.err(), Some(MessageError::UnknownTopic));
         }
 
         #[test]
         fn test_parse_mqtt_command_invalid_payload() {
             let cmd = super::super::parse_mqtt_command("speed/set", b"not a number");
-            assert!(cmd.is_none());
+            assert_eq!(cmd.err(), Some(MessageError::InvalidSpeed(SpeedError::Malformed)));
+
+            let cmd = super::super::parse_mqtt_command("speed/set", &[0xFF, 0xFE]);
+            assert_eq!(cmd.err(), Some(MessageError::InvalidJson));
 
             let cmd = super::super::parse_mqtt_command("direction/set", b"invalid");
-            assert!(cmd.is_none());
+            assert!(cmd.is_err());
+        }
+
+        #[test]
+        fn test_parse_mqtt_command_envelope() {
+            let cmd = super::super::parse_mqtt_command(
+                "command",
+                br#"{"version": 1, "command": {"set_max_speed": {"max_speed": 0.5}}}"#,
+            );
+            assert!(
+                matches!(cmd, Ok(ThrottleCommandDyn::SetMaxSpeed(max)) if (max.get() - 0.5).abs() < 0.001)
+            );
+        }
+    }
+
+    // =========================================================================
+    // CommandMessage tests
+    // =========================================================================
+
+    #[test]
+    fn test_command_message_from_speed_request() {
+        let msg = CommandMessage::from(SetSpeedRequest::smooth(Speed::literal(0.5), 1000));
+        assert_eq!(
+            msg,
+            CommandMessage::SetSpeed {
+                speed: Speed::literal(0.5),
+                strategy: StrategySpec::EaseInOut(EaseInOut::new(1000)),
+            }
+        );
+
+        let msg = CommandMessage::from(SetSpeedRequest::immediate(Speed::literal(0.3)));
+        assert!(matches!(
+            msg,
+            CommandMessage::SetSpeed {
+                strategy: StrategySpec::Immediate,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_command_message_from_velocity_request() {
+        let velocity = Velocity::new(-0.5).unwrap();
+        let msg = CommandMessage::from(SetVelocityRequest::linear(velocity, 1000));
+        assert_eq!(
+            msg,
+            CommandMessage::SetVelocity {
+                velocity,
+                strategy: StrategySpec::Linear(Linear::new(1000)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_message_error_from_speed_error() {
+        let err = MessageError::from(SpeedError::NotFinite);
+        assert_eq!(err, MessageError::InvalidSpeed(SpeedError::NotFinite));
+        assert_eq!(format!("{}", err), "speed must be finite");
+    }
+
+    #[test]
+    fn test_command_envelope_rejects_future_version() {
+        let envelope = CommandEnvelope {
+            version: COMMAND_SCHEMA_VERSION + 1,
+            client: None,
+            correlation_id: None,
+            command: CommandMessage::Heartbeat,
+        };
+        assert!(matches!(
+            envelope.into_command(),
+            Err(MessageError::UnsupportedVersion(v)) if v == COMMAND_SCHEMA_VERSION + 1
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_command_envelope_serde_json_roundtrip() {
+        let envelope = CommandEnvelope::new(CommandMessage::SetSpeed {
+            speed: Speed::literal(0.8),
+            strategy: StrategySpec::Momentum(crate::Momentum::gentle()),
+        });
+        let json = serde_json::to_string(&envelope).unwrap();
+        let back: CommandEnvelope = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, envelope);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_command_envelope_version_defaults() {
+        let json = r#"{"command": "emergency_stop"}"#;
+        let envelope: CommandEnvelope = serde_json::from_str(json).unwrap();
+        assert_eq!(envelope.version, COMMAND_SCHEMA_VERSION);
+        assert_eq!(envelope.command, CommandMessage::EmergencyStop);
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    mod command_parsing {
+        use super::*;
+        use crate::{InterruptBehavior, ThrottleCommandDyn, TransitionLock};
+
+        #[test]
+        fn test_parse_command_full_strategy() {
+            let json = br#"{"version": 1, "command": {"set_speed": {"speed": 0.6,
+                "strategy": {"ease_in_out": {"duration_ms": 2000, "lock": "source", "interrupt": "queue"}}}}}"#;
+            let Ok(ThrottleCommandDyn::SetSpeed { target, strategy }) = parse_command(json) else {
+                panic!("expected set_speed");
+            };
+            assert_eq!(target, 0.6);
+            assert_eq!(strategy.duration_ms(), Some(2000));
+            assert_eq!(strategy.lock(), TransitionLock::Source);
+            assert_eq!(strategy.on_interrupt(), InterruptBehavior::Queue);
+        }
+
+        #[test]
+        fn test_parse_command_preset() {
+            let json =
+                br#"{"command": {"set_speed": {"speed": 0.4, "strategy": {"preset": "yard"}}}}"#;
+            let Ok(ThrottleCommandDyn::SetSpeed { strategy, .. }) = parse_command(json) else {
+                panic!("expected set_speed");
+            };
+            assert_eq!(
+                strategy.spec(),
+                Some(&StrategySpec::preset("yard").unwrap())
+            );
+        }
+
+        #[test]
+        fn test_parse_speed_request_preset() {
+            let req = parse_speed_request(br#"{"speed": 0.6, "preset": "departure"}"#).unwrap();
+            assert_eq!(
+                req,
+                SetSpeedRequest::preset(Speed::literal(0.6), "departure").unwrap()
+            );
+
+            let cmd = parse_speed_payload(br#"{"speed": 0.6, "preset": "departure"}"#);
+            let Ok(ThrottleCommandDyn::SetSpeed { strategy, .. }) = cmd else {
+                panic!("expected set_speed");
+            };
+            assert_eq!(
+                strategy.spec(),
+                Some(&StrategySpec::preset("departure").unwrap())
+            );
+
+            let long = br#"{"speed": 0.6, "preset": "a-very-long-preset-name"}"#;
+            assert!(parse_speed_request(long).is_err());
+            let long = br#"{"command": {"set_speed": {"speed": 0.4, "strategy": {"preset": "a-very-long-preset-name"}}}}"#;
+            assert!(parse_command(long).is_err());
+        }
+
+        #[test]
+        fn test_parse_command_momentum() {
+            let json = br#"{"command": {"set_speed": {"speed": 1.0,
+                "strategy": {"momentum": {"acceleration": 0.5, "max_rate": 0.3}}}}}"#;
+            let Ok(ThrottleCommandDyn::SetSpeed { strategy, .. }) = parse_command(json) else {
+                panic!("expected set_speed");
+            };
+            assert_eq!(strategy.duration_ms(), None);
+        }
+
+        #[test]
+        fn test_parse_command_s_curve() {
+            let json = br#"{"command": {"set_speed": {"speed": 1.0, "strategy": {"s_curve":
+                {"acceleration": 0.5, "deceleration": 0.25, "jerk": 1.0, "lock": "hard"}}}}}"#;
+            let Ok(ThrottleCommandDyn::SetSpeed { strategy, .. }) = parse_command(json) else {
+                panic!("expected set_speed");
+            };
+            assert_eq!(strategy.duration_for(0.0, 1.0), Some(2500));
+            assert_eq!(strategy.lock(), TransitionLock::Hard);
+        }
+
+        #[test]
+        fn test_parse_command_keyframes() {
+            let json = br#"{"command": {"set_speed": {"speed": 0.5, "strategy": {"keyframes":
+                {"frames": [{"time_ms": 2000, "fraction": 0.1},
+                {"time_ms": 4000, "fraction": 1.0, "easing": "cubic"}],
+                "lock": "source", "interrupt": "queue"}}}}}"#;
+            let Ok(ThrottleCommandDyn::SetSpeed { strategy, .. }) = parse_command(json) else {
+                panic!("expected set_speed");
+            };
+            let expected = crate::Keyframes::arrival()
+                .with_keyframe(2000, 0.1, crate::Easing::Linear)
+                .unwrap()
+                .with_keyframe(4000, 1.0, crate::Easing::Cubic)
+                .unwrap();
+            assert_eq!(strategy.spec(), Some(&StrategySpec::Keyframes(expected)));
+            assert_eq!(strategy.on_interrupt(), InterruptBehavior::Queue);
+
+            // Over MQTT through the command topic
+            let cmd = parse_mqtt_command("command", json);
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::SetSpeed { .. })));
+        }
+
+        #[test]
+        fn test_parse_command_keyframes_too_many() {
+            let frame = r#"{"time_ms": 100, "fraction": 1.0},"#;
+            let json = format!(
+                r#"{{"command": {{"set_speed": {{"speed": 0.5, "strategy": {{"keyframes":
+                    {{"frames": [{}{{"time_ms": 200, "fraction": 1.0}}]}}}}}}}}}}"#,
+                frame.repeat(crate::traits::MAX_KEYFRAMES)
+            );
+            assert!(parse_command(json.as_bytes()).is_err());
+        }
+
+        #[test]
+        fn test_parse_command_keyframes_invalid_fraction() {
+            let json = br#"{"command": {"set_speed": {"speed": 0.5, "strategy": {"keyframes":
+                {"frames": [{"time_ms": 1000, "fraction": 1.5}]}}}}}"#;
+            assert!(parse_command(json).is_err());
+
+            let json = br#"{"command": {"set_speed": {"speed": 0.5, "strategy": {"keyframes":
+                {"frames": [{"time_ms": 1000, "fraction": -0.2}]}}}}}"#;
+            assert!(parse_command(json).is_err());
+        }
+
+        #[test]
+        fn test_parse_command_unit_variants() {
+            let cmd = parse_command(br#"{"version": 1, "command": "emergency_stop"}"#);
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::EmergencyStop)));
+
+            let cmd = parse_command(br#"{"version": 1, "command": "heartbeat"}"#);
+            assert!(matches!(cmd, Ok(ThrottleCommandDyn::Heartbeat)));
+        }
+
+        #[test]
+        fn test_parse_command_direction() {
+            let json = br#"{"command": {"set_direction": {"direction": "reverse"}}}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::SetDirection(Direction::Reverse))
+            ));
+        }
+
+        #[test]
+        fn test_parse_command_velocity() {
+            let json = br#"{"command": {"set_velocity": {"velocity": -0.3,
+                "strategy": {"linear": {"duration_ms": 1500}}}}}"#;
+            let Ok(ThrottleCommandDyn::SetVelocity { target, strategy }) = parse_command(json)
+            else {
+                panic!("expected set_velocity");
+            };
+            assert_eq!(target, -0.3);
+            assert_eq!(strategy.duration_ms(), Some(1500));
+
+            let json = br#"{"command": {"set_velocity": {"velocity": -1.2}}}"#;
+            assert_eq!(
+                parse_command(json).err(),
+                Some(MessageError::InvalidSpeed(SpeedError::VelocityOutOfRange))
+            );
+        }
+
+        #[test]
+        fn test_parse_command_service_brake() {
+            let json = br#"{"command": {"brake": {"hold": true}}}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::Brake {
+                    strategy: None,
+                    hold: true
+                })
+            ));
+
+            let json = br#"{"command": {"brake": {"strategy": {"linear": {"duration_ms": 1500, "lock": "hard"}}}}}"#;
+            let Ok(ThrottleCommandDyn::Brake { strategy, hold }) = parse_command(json) else {
+                panic!("expected brake");
+            };
+            assert!(!hold);
+            let strategy = strategy.expect("strategy should be carried");
+            assert_eq!(strategy.duration_ms(), Some(1500));
+            assert_eq!(strategy.lock(), TransitionLock::Hard);
+
+            let json = br#"{"command": "release_brake"}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::ReleaseBrake)
+            ));
+        }
+
+        #[test]
+        fn test_parse_command_queue() {
+            let json = br#"{"command": {"cancel_queued": {"id": 3}}}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::CancelQueued(3))
+            ));
+
+            let json = br#"{"command": "clear_queue"}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::ClearQueue)
+            ));
+        }
+
+        #[test]
+        fn test_parse_command_pause_resume() {
+            let json = br#"{"command": "pause"}"#;
+            assert!(matches!(parse_command(json), Ok(ThrottleCommandDyn::Pause)));
+
+            let json = br#"{"version": 1, "command": "resume"}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::Resume)
+            ));
+        }
+
+        #[test]
+        fn test_parse_command_force_release() {
+            let json = br#"{"command": "force_release"}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::ForceRelease)
+            ));
+        }
+
+        #[test]
+        fn test_parse_command_client() {
+            let json = br#"{"version": 1, "client": "alice-phone", "command": "pause"}"#;
+            let parsed = parse_mqtt_command_as("command", json).unwrap();
+            assert!(matches!(parsed.command, ThrottleCommandDyn::Pause));
+            assert_eq!(parsed.client, Some(ClientId::new("alice-phone").unwrap()));
+
+            let parsed = parse_command_as(br#"{"command": "pause"}"#).unwrap();
+            assert_eq!(parsed.client, None);
+            let parsed = parse_mqtt_command_as("pause", b"").unwrap();
+            assert_eq!(parsed.client, None);
+
+            let json = br#"{"client": "no spaces", "command": "pause"}"#;
+            assert_eq!(parse_command(json).err(), Some(MessageError::InvalidJson));
+
+            // Round trips through the envelope writer
+            let envelope = CommandEnvelope::new(CommandMessage::Resume)
+                .with_client(ClientId::new("bob").unwrap());
+            let mut buf = [0u8; 64];
+            let len = write_command(&envelope, &mut buf).unwrap();
+            assert_eq!(
+                &buf[..len],
+                br#"{"version":1,"client":"bob","command":"resume"}"#
+            );
+        }
+
+        #[test]
+        fn test_parse_command_correlation_id() {
+            let json = br#"{"correlation_id": "req-42", "command": "emergency_stop"}"#;
+            let parsed = parse_mqtt_command_as("command", json).unwrap();
+            assert!(matches!(parsed.command, ThrottleCommandDyn::EmergencyStop));
+            assert_eq!(parsed.correlation_id.unwrap().as_str(), "req-42");
+            assert_eq!(parsed.client, None);
+
+            let parsed = parse_mqtt_command_as("estop", b"").unwrap();
+            assert_eq!(parsed.correlation_id, None);
+
+            let json = br#"{"correlation_id": "", "command": "pause"}"#;
+            assert_eq!(parse_command(json).err(), Some(MessageError::InvalidJson));
+
+            let envelope = CommandEnvelope::new(CommandMessage::Pause)
+                .with_correlation_id(CorrelationId::new("req-43").unwrap());
+            let mut buf = [0u8; 64];
+            let len = write_command(&envelope, &mut buf).unwrap();
+            assert_eq!(
+                &buf[..len],
+                br#"{"version":1,"correlation_id":"req-43","command":"pause"}"#
+            );
+        }
+
+        #[test]
+        fn test_parse_command_cab_controls() {
+            let json = br#"{"command": {"set_notch": {"notch": 2}}}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::SetNotch(n)) if n.get() == 2
+            ));
+
+            let json = br#"{"command": {"set_brake": {"brake": "release"}}}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::SetBrake(BrakeSetting::Release))
+            ));
+
+            let json = br#"{"command": {"set_driving_mode": {"mode": "cab"}}}"#;
+            assert!(matches!(
+                parse_command(json),
+                Ok(ThrottleCommandDyn::SetDrivingMode(DrivingMode::Cab))
+            ));
+
+            let json = br#"{"command": {"set_notch": {"notch": 10}}}"#;
+            assert_eq!(parse_command(json).err(), Some(MessageError::InvalidNotch));
+        }
+
+        #[test]
+        fn test_parse_command_errors() {
+            assert_eq!(
+                parse_command(b"not json").err(),
+                Some(MessageError::InvalidJson)
+            );
+
+            let json = br#"{"version": 99, "command": "heartbeat"}"#;
+            assert_eq!(
+                parse_command(json).err(),
+                Some(MessageError::UnsupportedVersion(99))
+            );
+
+            let json = br#"{"command": {"set_speed": {"speed": 2.0}}}"#;
+            assert_eq!(
+                parse_command(json).err(),
+                Some(MessageError::InvalidSpeed(SpeedError::OutOfRange))
+            );
+
+            let json = br#"{"command": {"set_max_speed": {"max_speed": 1e40}}}"#;
+            assert_eq!(
+                parse_command(json).err(),
+                Some(MessageError::InvalidSpeed(SpeedError::NotFinite))
+            );
+
+            let json = br#"{"command": {"set_direction": {"direction": "sideways"}}}"#;
+            assert_eq!(parse_command(json).err(), Some(MessageError::InvalidJson));
+        }
+
+        #[test]
+        fn test_parse_requests_reject_invalid_speed() {
+            assert_eq!(
+                parse_speed_request(br#"{"speed": -0.5, "duration_ms": 1000}"#),
+                Err(MessageError::InvalidSpeed(SpeedError::OutOfRange))
+            );
+            assert_eq!(
+                parse_speed_request(br#"{"speed": "fast"}"#),
+                Err(MessageError::InvalidJson)
+            );
+            assert_eq!(
+                parse_max_speed_request(br#"{"max_speed": 1.01}"#),
+                Err(MessageError::InvalidSpeed(SpeedError::OutOfRange))
+            );
+        }
+
+        #[test]
+        fn test_write_command_roundtrip() {
+            let envelope = CommandEnvelope::new(CommandMessage::SetSpeed {
+                speed: Speed::literal(0.5),
+                strategy: StrategySpec::Linear(crate::Linear::source_locked(1500)),
+            });
+            let mut buf = [0u8; 256];
+            let len = write_command(&envelope, &mut buf).unwrap();
+
+            let (back, _): (CommandEnvelope, _) = serde_json_core::from_slice(&buf[..len]).unwrap();
+            assert_eq!(back, envelope);
+        }
+
+        #[test]
+        fn test_write_command_buffer_full() {
+            let envelope = CommandEnvelope::new(CommandMessage::Heartbeat);
+            let mut buf = [0u8; 4];
+            assert_eq!(
+                write_command(&envelope, &mut buf),
+                Err(MessageError::BufferFull)
+            );
         }
     }
 }
@@ -6,9 +6,12 @@
 //!
 //! # Key Components
 //!
-//! - [`CommandQueue`]: Priority queue for commands using a binary heap
+//! - [`CommandQueue`]: Priority queue for commands using a min-max heap
 //! - [`SourceLockout`]: Prevents lower-priority sources from interrupting
 //! - [`CommandProcessor`]: Combines queue and lockout for complete processing
+//! - [`HeartbeatLease`]: Dead-man timer that stops the train if a remote source goes quiet
+//! - [`RateLimiter`]: Per-source token buckets that drop command floods
+//! - [`AuditLog`]: Recent commands with the source and client that sent them
 //!
 //! # Source Lockout
 //!
@@ -22,14 +25,14 @@
 //! ```rust
 //! use rs_trainz::priority::SourceLockout;
 //! use rs_trainz::commands::{PrioritizedCommand, ThrottleCommandDyn, CommandSource};
-//! use rs_trainz::AnyStrategy;
+//! use rs_trainz::{AnyStrategy, Speed};
 //! use rs_trainz::traits::Immediate;
 //!
 //! let mut lockout = SourceLockout::new(2000); // 2 second lockout
 //!
 //! // Physical command creates lockout
 //! let physical = PrioritizedCommand::new(
-//!     ThrottleCommandDyn::SetSpeed { target: 0.5, strategy: AnyStrategy::new(Immediate) },
+//!     ThrottleCommandDyn::SetSpeed { target: Speed::new(0.5).unwrap(), strategy: AnyStrategy::new(Immediate) },
 //!     CommandSource::Physical,
 //!     0,
 //! );
@@ -37,7 +40,7 @@
 //!
 //! // MQTT command rejected during lockout
 //! let mqtt = PrioritizedCommand::new(
-//!     ThrottleCommandDyn::SetSpeed { target: 0.8, strategy: AnyStrategy::new(Immediate) },
+//!     ThrottleCommandDyn::SetSpeed { target: Speed::new(0.8).unwrap(), strategy: AnyStrategy::new(Immediate) },
 //!     CommandSource::Mqtt,
 //!     100,
 //! );
@@ -45,44 +48,67 @@
 //!
 //! // After lockout expires, MQTT accepted
 //! let mqtt2 = PrioritizedCommand::new(
-//!     ThrottleCommandDyn::SetSpeed { target: 0.3, strategy: AnyStrategy::new(Immediate) },
+//!     ThrottleCommandDyn::SetSpeed { target: Speed::new(0.3).unwrap(), strategy: AnyStrategy::new(Immediate) },
 //!     CommandSource::Mqtt,
 //!     2100,
 //! );
 //! assert!(lockout.should_accept(&mqtt2, 2100));
 //! ```
 //!
+//! # Source Ranking
+//!
+//! Sources are compared by a [`SourcePriorities`] table, which defaults to
+//! the declaration order of [`CommandSource`]. Give [`CommandProcessor`]
+//! (or its parts) another table with `with_priorities` to re-rank them.
+//!
 //! # E-Stop Exception
 //!
 //! E-stop commands always bypass lockout and clear it. This ensures the
 //! emergency stop function works regardless of what source is controlling.
 
-use crate::commands::{CommandSource, PrioritizedCommand, ThrottleCommandDyn};
-use heapless::binary_heap::{BinaryHeap, Max};
+use crate::commands::{
+    ClientId, CommandSource, PrioritizedCommand, RejectReason, SourcePriorities, ThrottleCommandDyn,
+};
+use crate::config::ThrottleConfig;
+use core::cmp::Ordering;
+use heapless::Vec as HVec;
 
 /// Command queue with priority ordering.
 ///
-/// Uses a max-heap to always serve the highest-priority command first.
-/// When full, lower-priority commands can be displaced by higher-priority ones.
+/// Uses a min-max heap to serve the highest-priority command first while
+/// keeping the lowest-priority one at hand for eviction. Push, pop and
+/// eviction are all O(log n).
+///
+/// # Ordering
+///
+/// Commands pop by priority, then in the order they were pushed: each push
+/// stamps the command with the queue's next sequence number (see
+/// [`PrioritizedCommand::sequence`]).
 ///
 /// # Capacity
 ///
 /// The queue has a fixed capacity `N` (const generic). When full:
 /// - Higher priority commands displace the lowest priority item
+///   (the last to arrive among equals)
 /// - Equal or lower priority commands are rejected
 ///
+/// # Coalescing
+///
+/// A `SetSpeed` replaces any `SetSpeed` still queued from the same source,
+/// so a flood of speed updates keeps only the latest.
+///
 /// # Example
 ///
 /// ```rust
 /// use rs_trainz::priority::CommandQueue;
 /// use rs_trainz::commands::{PrioritizedCommand, ThrottleCommandDyn, CommandSource};
-/// use rs_trainz::AnyStrategy;
+/// use rs_trainz::{AnyStrategy, Speed};
 /// use rs_trainz::traits::Immediate;
 ///
 /// let mut queue: CommandQueue<4> = CommandQueue::new();
 ///
 /// let cmd = PrioritizedCommand::new(
-///     ThrottleCommandDyn::SetSpeed { target: 0.5, strategy: AnyStrategy::new(Immediate) },
+///     ThrottleCommandDyn::SetSpeed { target: Speed::new(0.5).unwrap(), strategy: AnyStrategy::new(Immediate) },
 ///     CommandSource::Physical,
 ///     0,
 /// );
@@ -93,87 +119,102 @@ use heapless::binary_heap::{BinaryHeap, Max};
 /// assert_eq!(popped.source, CommandSource::Physical);
 /// ```
 pub struct CommandQueue<const N: usize> {
-    heap: BinaryHeap<PrioritizedCommand, Max, N>,
+    heap: MinMaxHeap<PrioritizedCommand, N>,
+    priorities: SourcePriorities,
+    next_sequence: u64,
+    coalesced: u32,
+    dropped: u32,
 }
 
 impl<const N: usize> CommandQueue<N> {
     /// Creates a new empty command queue with capacity N.
     pub fn new() -> Self {
         Self {
-            heap: BinaryHeap::new(),
+            heap: MinMaxHeap::new(),
+            priorities: SourcePriorities::default(),
+            next_sequence: 0,
+            coalesced: 0,
+            dropped: 0,
         }
     }
 
+    /// Rank sources with `priorities` instead of the default table
+    ///
+    /// Applies to commands pushed from now on; call on an empty queue.
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
     /// Push a command onto the queue
     ///
     /// If the queue is full, only accepts if higher priority than lowest in queue.
     /// When accepted, the lowest priority item is dropped to make room.
     #[must_use]
     pub fn push(&mut self, cmd: PrioritizedCommand) -> bool {
-        if self.heap.len() < self.heap.capacity() {
+        let cmd = cmd
+            .with_priorities(self.priorities)
+            .with_sequence(self.next_sequence);
+        self.next_sequence += 1;
+        if matches!(cmd.command, ThrottleCommandDyn::SetSpeed { .. }) {
+            self.coalesce(cmd.source);
+        }
+        if self.heap.len() < N {
             return self.heap.push(cmd).is_ok();
         }
 
-        // Queue full - drain heap to find minimum and decide whether to accept
-        let new_priority = cmd.priority();
-
-        // Drain all items to find the minimum priority
-        let mut items: heapless::Vec<PrioritizedCommand, N> = heapless::Vec::new();
-        while let Some(item) = self.heap.pop() {
-            let _ = items.push(item);
+        // Queue full - accept only if strictly above the lowest priority
+        let displaces = self
+            .heap
+            .peek_min()
+            .is_some_and(|min| cmd.priority() > min.priority());
+        self.dropped = self.dropped.saturating_add(1);
+        if displaces {
+            let _ = self.heap.pop_min();
+            self.heap.push(cmd).is_ok()
+        } else {
+            false
         }
+    }
 
-        // Find minimum priority
-        let min_priority = items.iter().map(|c| c.priority()).min();
+    /// Drop queued `SetSpeed` commands from `source`
+    fn coalesce(&mut self, source: CommandSource) {
+        let before = self.heap.len();
+        self.heap.retain(|c| {
+            c.source != source || !matches!(c.command, ThrottleCommandDyn::SetSpeed { .. })
+        });
+        let removed = (before - self.heap.len()) as u32;
+        self.coalesced = self.coalesced.saturating_add(removed);
+    }
 
-        match min_priority {
-            Some(min_p) if new_priority > min_p => {
-                // New command has higher priority than minimum - accept it
-                // Rebuild heap without ONE lowest priority item
-                let mut dropped_one = false;
-                for item in items {
-                    if !dropped_one && item.priority() == min_p {
-                        dropped_one = true; // Drop this lowest priority item
-                    } else {
-                        let _ = self.heap.push(item);
-                    }
-                }
-                self.heap.push(cmd).is_ok()
-            }
-            _ => {
-                // New command has equal or lower priority - reject and restore heap
-                for item in items {
-                    let _ = self.heap.push(item);
-                }
-                false
-            }
+    /// Commands coalesced away and dropped for lack of room so far
+    pub fn metrics(&self) -> CommandMetrics {
+        CommandMetrics {
+            coalesced: self.coalesced,
+            queue_full: self.dropped,
+            ..CommandMetrics::default()
         }
     }
 
     /// Pop the highest priority command
     pub fn pop(&mut self) -> Option<PrioritizedCommand> {
-        self.heap.pop()
+        self.heap.pop_max()
     }
 
     /// Peek at the highest priority command without removing it
     pub fn peek(&self) -> Option<&PrioritizedCommand> {
-        self.heap.peek()
+        self.heap.peek_max()
     }
 
     /// Clear all commands below a certain source priority
     pub fn clear_below(&mut self, source: CommandSource) {
-        let mut temp: BinaryHeap<PrioritizedCommand, Max, N> = BinaryHeap::new();
-        while let Some(cmd) = self.heap.pop() {
-            if cmd.priority().0 >= source {
-                let _ = temp.push(cmd);
-            }
-        }
-        self.heap = temp;
+        let min_rank = self.priorities.rank(source);
+        self.heap.retain(|cmd| cmd.priority().0 >= min_rank);
     }
 
     /// Clear all commands
     pub fn clear(&mut self) {
-        while self.heap.pop().is_some() {}
+        self.heap.clear();
     }
 
     /// Returns the number of commands in the queue.
@@ -183,12 +224,12 @@ impl<const N: usize> CommandQueue<N> {
 
     /// Returns true if the queue is empty.
     pub fn is_empty(&self) -> bool {
-        self.heap.is_empty()
+        self.heap.len() == 0
     }
 
     /// Returns true if the queue is at capacity.
     pub fn is_full(&self) -> bool {
-        self.heap.len() == self.heap.capacity()
+        self.heap.len() == N
     }
 }
 
@@ -198,6 +239,143 @@ impl<const N: usize> Default for CommandQueue<N> {
     }
 }
 
+/// Fixed-capacity min-max heap.
+///
+/// Even levels are ordered as a min-heap and odd levels as a max-heap, so
+/// both ends are found in O(1) and removed in O(log n).
+#[derive(Debug)]
+struct MinMaxHeap<T, const N: usize> {
+    items: HVec<T, N>,
+}
+
+impl<T: Ord, const N: usize> MinMaxHeap<T, N> {
+    const fn new() -> Self {
+        Self { items: HVec::new() }
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    fn push(&mut self, item: T) -> Result<(), T> {
+        self.items.push(item)?;
+        self.bubble_up(self.items.len() - 1);
+        Ok(())
+    }
+
+    fn peek_min(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|i| &self.items[i])
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    fn pop_max(&mut self) -> Option<T> {
+        self.max_index().and_then(|i| self.remove(i))
+    }
+
+    /// Keep only the items matching `keep`, then restore the heap in O(n)
+    fn retain(&mut self, keep: impl FnMut(&T) -> bool) {
+        self.items.retain(keep);
+        for i in (0..self.items.len() / 2).rev() {
+            self.trickle_down(i);
+        }
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        match self.items.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ if self.items[2] > self.items[1] => Some(2),
+            _ => Some(1),
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.items.len() {
+            return None;
+        }
+        let item = self.items.swap_remove(index);
+        if index < self.items.len() {
+            self.trickle_down(index);
+        }
+        Some(item)
+    }
+
+    /// Ordering that holds between an item on `index`'s level and its descendants
+    fn level_order(index: usize) -> Ordering {
+        if (index + 1).ilog2() & 1 == 0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    fn bubble_up(&mut self, mut index: usize) {
+        if index == 0 {
+            return;
+        }
+        let mut order = Self::level_order(index);
+        let parent = (index - 1) / 2;
+        if self.items[parent].cmp(&self.items[index]) == order {
+            // Belongs on the parent's levels
+            self.items.swap(index, parent);
+            index = parent;
+            order = order.reverse();
+        }
+        while index >= 3 {
+            let grandparent = (index - 3) / 4;
+            if self.items[index].cmp(&self.items[grandparent]) != order {
+                break;
+            }
+            self.items.swap(index, grandparent);
+            index = grandparent;
+        }
+    }
+
+    fn trickle_down(&mut self, mut index: usize) {
+        let order = Self::level_order(index);
+        let len = self.items.len();
+        loop {
+            let first_child = 2 * index + 1;
+            if first_child >= len {
+                return;
+            }
+            let first_grandchild = 4 * index + 3;
+            let descendants = (first_child..(first_child + 2).min(len))
+                .chain(first_grandchild..(first_grandchild + 4).min(len));
+            let mut best = first_child;
+            for i in descendants {
+                if self.items[i].cmp(&self.items[best]) == order {
+                    best = i;
+                }
+            }
+            if self.items[best].cmp(&self.items[index]) != order {
+                return;
+            }
+            self.items.swap(best, index);
+            if best < first_grandchild {
+                return;
+            }
+            let parent = (best - 1) / 2;
+            if self.items[parent].cmp(&self.items[best]) == order {
+                self.items.swap(best, parent);
+            }
+            index = best;
+        }
+    }
+}
+
 /// Source lockout system - prevents lower priority sources from interrupting.
 ///
 /// When a high-priority source (Physical or above) sends a command, the lockout
@@ -206,7 +384,7 @@ impl<const N: usize> Default for CommandQueue<N> {
 ///
 /// # Lockout Rules
 ///
-/// - Sources below [`Physical`](CommandSource::Physical) don't create lockouts
+/// - Sources ranked below [`Physical`](CommandSource::Physical) don't create lockouts
 /// - Same or higher priority commands extend the lockout timer
 /// - E-stop commands always bypass and clear the lockout
 /// - Lockout expires after the configured duration with no high-priority commands
@@ -227,6 +405,7 @@ pub struct SourceLockout {
     active_source: Option<CommandSource>,
     lockout_until_ms: u64,
     lockout_duration_ms: u64,
+    priorities: SourcePriorities,
 }
 
 impl SourceLockout {
@@ -239,18 +418,43 @@ impl SourceLockout {
             active_source: None,
             lockout_until_ms: 0,
             lockout_duration_ms,
+            priorities: SourcePriorities::default(),
         }
     }
 
+    /// Create a lockout from throttle configuration
+    pub fn from_config(config: &ThrottleConfig) -> Self {
+        Self::new(config.lockout_ms as u64).with_priorities(config.priorities)
+    }
+
+    /// Rank sources with `priorities` instead of the default table
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
     /// Check if a command should be accepted
     ///
     /// Returns true if the command is accepted, false if rejected due to lockout
     #[must_use]
     pub fn should_accept(&mut self, cmd: &PrioritizedCommand, now_ms: u64) -> bool {
+        self.check(&cmd.command, cmd.source, now_ms).is_ok()
+    }
+
+    /// Check `command` from `source` against the lockout, starting or
+    /// extending it if accepted
+    ///
+    /// Fails with [`RejectReason::LowerPriority`] during another source's lockout.
+    pub fn check(
+        &mut self,
+        command: &ThrottleCommandDyn,
+        source: CommandSource,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
         // E-stop always accepted, clears lockout
-        if cmd.command.is_estop() {
+        if command.is_estop() {
             self.active_source = None;
-            return true;
+            return Ok(());
         }
 
         // Check if lockout expired
@@ -261,56 +465,823 @@ impl SourceLockout {
         match self.active_source {
             None => {
                 // No lockout - accept and maybe start one
-                if cmd.source >= CommandSource::Physical {
-                    self.active_source = Some(cmd.source);
+                if !self.priorities.is_below(source, CommandSource::Physical) {
+                    self.active_source = Some(source);
                     self.lockout_until_ms = now_ms + self.lockout_duration_ms;
                 }
-                true
+                Ok(())
             }
             Some(locked_source) => {
-                if cmd.source >= locked_source {
+                if !self.priorities.is_below(source, locked_source) {
                     // Same or higher priority - accept and extend lockout
-                    self.active_source = Some(cmd.source);
+                    self.active_source = Some(source);
                     self.lockout_until_ms = now_ms + self.lockout_duration_ms;
-                    true
+                    Ok(())
                 } else {
                     // Lower priority - reject during lockout
-                    false
+                    Err(RejectReason::LowerPriority)
+                }
+            }
+        }
+    }
+
+    /// Clear the current lockout
+    pub fn clear(&mut self) {
+        self.active_source = None;
+    }
+
+    /// Get the current lockout status
+    pub fn status(&self, now_ms: u64) -> Option<LockoutStatus> {
+        if now_ms >= self.lockout_until_ms {
+            return None;
+        }
+        self.active_source.map(|source| LockoutStatus {
+            source,
+            expires_ms: self.lockout_until_ms,
+            remaining_ms: self.lockout_until_ms.saturating_sub(now_ms),
+        })
+    }
+}
+
+/// Information about an active lockout.
+///
+/// Returned by [`SourceLockout::status`] when a lockout is active.
+/// Useful for UI feedback showing when remote control will be available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LockoutStatus {
+    /// The source that holds the lockout.
+    pub source: CommandSource,
+    /// Timestamp when the lockout expires (milliseconds since start).
+    pub expires_ms: u64,
+    /// Time remaining until lockout expires (milliseconds).
+    pub remaining_ms: u64,
+}
+
+// ============================================================================
+// Heartbeat Lease
+// ============================================================================
+
+/// Action taken when a remote source's heartbeat lease lapses.
+///
+/// Configured per controller via [`HeartbeatLease::new`] or
+/// [`ThrottleConfig::safe_stop`](crate::config::ThrottleConfig::safe_stop).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SafeStop {
+    /// Stop immediately, exactly like an e-stop.
+    EmergencyStop,
+    /// Ramp down to zero over the given duration.
+    ///
+    /// Falls back to [`EmergencyStop`](Self::EmergencyStop) if the active
+    /// transition holds a hard lock.
+    Ramp {
+        /// Ramp duration in milliseconds.
+        duration_ms: u32,
+    },
+}
+
+impl Default for SafeStop {
+    fn default() -> Self {
+        Self::Ramp { duration_ms: 1000 }
+    }
+}
+
+/// Dead-man lease for remote control sources.
+///
+/// While a remote source ([`Mqtt`](CommandSource::Mqtt),
+/// [`WebApi`](CommandSource::WebApi) or [`WebLocal`](CommandSource::WebLocal))
+/// has control, it must keep refreshing the lease - either with an explicit
+/// heartbeat or implicitly with each command. If the lease lapses, the
+/// controller performs the configured [`SafeStop`].
+///
+/// # Lease Rules
+///
+/// - A timeout of `0` disables the lease entirely
+/// - Any command from a remote source takes (or renews) the lease
+/// - Only the current holder can renew it with a heartbeat
+/// - Commands from [`Physical`](CommandSource::Physical) or above release it,
+///   so physical control is never subject to a heartbeat
+/// - A lapse is reported once, then the lease is released
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::priority::{HeartbeatLease, SafeStop};
+/// use rs_trainz::CommandSource;
+///
+/// let mut lease = HeartbeatLease::new(3000, SafeStop::EmergencyStop);
+///
+/// // Web UI takes control
+/// lease.on_command(CommandSource::WebLocal, 0);
+///
+/// // ... and keeps it alive
+/// assert!(lease.refresh(CommandSource::WebLocal, 2000));
+/// assert!(lease.check_expired(4000).is_none());
+///
+/// // Phone drops off Wi-Fi: lease lapses
+/// assert_eq!(lease.check_expired(5000), Some(CommandSource::WebLocal));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeartbeatLease {
+    holder: Option<CommandSource>,
+    expires_ms: u64,
+    timeout_ms: u64,
+    safe_stop: SafeStop,
+}
+
+impl HeartbeatLease {
+    /// Create a new lease
+    ///
+    /// # Arguments
+    /// * `timeout_ms` - How long a remote source keeps control without a heartbeat (0 = disabled)
+    /// * `safe_stop` - What to do when the lease lapses
+    pub fn new(timeout_ms: u64, safe_stop: SafeStop) -> Self {
+        Self {
+            holder: None,
+            expires_ms: 0,
+            timeout_ms,
+            safe_stop,
+        }
+    }
+
+    /// Create a disabled lease (remote sources never time out)
+    pub fn disabled() -> Self {
+        Self::new(0, SafeStop::default())
+    }
+
+    /// Create a lease from throttle configuration
+    pub fn from_config(config: &ThrottleConfig) -> Self {
+        Self::new(config.heartbeat_timeout_ms as u64, config.safe_stop)
+    }
+
+    /// Returns true if the lease is enforced.
+    pub fn is_enabled(&self) -> bool {
+        self.timeout_ms > 0
+    }
+
+    /// Returns the configured safe stop action.
+    pub fn safe_stop(&self) -> SafeStop {
+        self.safe_stop
+    }
+
+    /// Record an accepted command from `source`
+    ///
+    /// Remote sources take or renew the lease; higher sources release it.
+    pub fn on_command(&mut self, source: CommandSource, now_ms: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        if source.is_remote() {
+            self.holder = Some(source);
+            self.expires_ms = now_ms + self.timeout_ms;
+        } else {
+            self.holder = None;
+        }
+    }
+
+    /// Renew the lease with an explicit heartbeat
+    ///
+    /// Returns true if `source` holds the lease and it was renewed.
+    pub fn refresh(&mut self, source: CommandSource, now_ms: u64) -> bool {
+        if self.holder != Some(source) || now_ms >= self.expires_ms {
+            return false;
+        }
+        self.expires_ms = now_ms + self.timeout_ms;
+        true
+    }
+
+    /// Check whether the lease has lapsed
+    ///
+    /// Returns the source that lost control, once, then releases the lease.
+    #[must_use]
+    pub fn check_expired(&mut self, now_ms: u64) -> Option<CommandSource> {
+        if now_ms < self.expires_ms {
+            return None;
+        }
+        self.holder.take()
+    }
+
+    /// Release the lease without triggering a safe stop
+    pub fn clear(&mut self) {
+        self.holder = None;
+    }
+
+    /// Get the current lease status
+    pub fn status(&self, now_ms: u64) -> Option<HeartbeatStatus> {
+        if now_ms >= self.expires_ms {
+            return None;
+        }
+        self.holder.map(|source| HeartbeatStatus {
+            source,
+            expires_ms: self.expires_ms,
+            remaining_ms: self.expires_ms.saturating_sub(now_ms),
+        })
+    }
+}
+
+impl Default for HeartbeatLease {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+/// Information about a held heartbeat lease.
+///
+/// Returned by [`HeartbeatLease::status`] while a remote source has control.
+/// Useful for UI feedback showing how long until the safe stop kicks in.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeartbeatStatus {
+    /// The remote source holding the lease.
+    pub source: CommandSource,
+    /// Timestamp when the lease lapses (milliseconds since start).
+    pub expires_ms: u64,
+    /// Time remaining until the lease lapses (milliseconds).
+    pub remaining_ms: u64,
+}
+
+/// Information about a held throttle lease.
+///
+/// Reported in [`ThrottleState`](crate::ThrottleState) by services that
+/// let clients acquire the throttle before driving, see [`ThrottleLease`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeaseStatus {
+    /// The source holding the lease.
+    pub source: CommandSource,
+    /// The client within the source holding the lease.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub client: Option<ClientId>,
+    /// Timestamp when the lease lapses (milliseconds since start).
+    pub expires_ms: u64,
+    /// Time remaining until the lease lapses (milliseconds).
+    pub remaining_ms: u64,
+}
+
+/// Default time a throttle lease lasts without commands from its holder.
+pub const DEFAULT_LEASE_MS: u64 = 60_000;
+
+/// Exclusive control of the throttle by one remote client.
+///
+/// A client [acquires](Self::acquire) the lease before driving. While it is
+/// held, remote commands from other clients are rejected with
+/// [`RejectReason::NotLeaseHolder`]; physical controls, faults and e-stops
+/// are never blocked. Each accepted command from the holder renews the
+/// lease, and it lapses after `duration_ms` without one.
+///
+/// A holder is a source and an optional client: a lease acquired without a
+/// client is shared by every anonymous client of that source.
+///
+/// By default the lease is optional, so anyone may drive while it is free.
+/// Use [`with_required`](Self::with_required) to make remote clients acquire
+/// it first.
+///
+/// Stealing the lease needs a source that strictly outranks the holder's,
+/// unless [`with_steal_same_rank`](Self::with_steal_same_rank) lets equal
+/// sources take over too.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::{ClientId, CommandSource, RejectReason, SourcePriorities, ThrottleLease};
+///
+/// let alice = Some(ClientId::new("alice").unwrap());
+/// let bob = Some(ClientId::new("bob").unwrap());
+/// let priorities = SourcePriorities::default();
+/// let mut lease = ThrottleLease::new(30_000);
+///
+/// assert!(lease.acquire(CommandSource::WebApi, alice, 0).is_ok());
+/// assert_eq!(
+///     lease.acquire(CommandSource::WebApi, bob, 1000),
+///     Err(RejectReason::LeaseHeld)
+/// );
+///
+/// // Same priority: bob must wait, but a local client may take over
+/// assert_eq!(
+///     lease.steal(CommandSource::WebApi, bob, &priorities, 2000),
+///     Err(RejectReason::LowerPriority)
+/// );
+/// assert!(lease.steal(CommandSource::WebLocal, bob, &priorities, 2000).is_ok());
+/// assert_eq!(lease.status(2000).unwrap().client, bob);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ThrottleLease {
+    holder: Option<(CommandSource, Option<ClientId>)>,
+    expires_ms: u64,
+    duration_ms: u64,
+    required: bool,
+    steal_same_rank: bool,
+}
+
+impl ThrottleLease {
+    /// Create an optional lease that lapses after `duration_ms` without
+    /// commands from its holder
+    pub fn new(duration_ms: u64) -> Self {
+        Self {
+            holder: None,
+            expires_ms: 0,
+            duration_ms,
+            required: false,
+            steal_same_rank: false,
+        }
+    }
+
+    /// Create a lease from the throttle configuration
+    pub fn from_config(config: &ThrottleConfig) -> Self {
+        Self::new(config.lease_ms as u64)
+            .with_required(config.lease_required)
+            .with_steal_same_rank(config.lease_steal_same_rank)
+    }
+
+    /// Require remote clients to hold the lease before driving
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Returns true if remote clients must hold the lease to drive.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Let a source steal the lease from a holder of equal priority
+    pub fn with_steal_same_rank(mut self, allow: bool) -> Self {
+        self.steal_same_rank = allow;
+        self
+    }
+
+    /// Take the lease if it is free or lapsed, or renew it for its holder
+    ///
+    /// Fails with [`RejectReason::LeaseHeld`] while someone else holds it.
+    pub fn acquire(
+        &mut self,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<LeaseStatus, RejectReason> {
+        match self.current(now_ms) {
+            Some(holder) if holder != (source, client) => Err(RejectReason::LeaseHeld),
+            _ => Ok(self.grant(source, client, now_ms)),
+        }
+    }
+
+    /// Give up the lease
+    ///
+    /// Fails with [`RejectReason::LeaseHeld`] if someone else holds it, and
+    /// [`RejectReason::NotLeaseHolder`] if nobody does.
+    pub fn release(
+        &mut self,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
+        match self.current(now_ms) {
+            Some(holder) if holder == (source, client) => {
+                self.holder = None;
+                Ok(())
+            }
+            Some(_) => Err(RejectReason::LeaseHeld),
+            None => Err(RejectReason::NotLeaseHolder),
+        }
+    }
+
+    /// Take the lease from its holder
+    ///
+    /// Fails with [`RejectReason::LowerPriority`] unless `source` outranks
+    /// the holder's source, or ranks equal with
+    /// [`with_steal_same_rank`](Self::with_steal_same_rank) set. Holders
+    /// stealing from themselves just renew.
+    pub fn steal(
+        &mut self,
+        source: CommandSource,
+        client: Option<ClientId>,
+        priorities: &SourcePriorities,
+        now_ms: u64,
+    ) -> Result<LeaseStatus, RejectReason> {
+        match self.current(now_ms) {
+            Some(holder) if holder == (source, client) => Ok(self.grant(source, client, now_ms)),
+            Some((holder, _)) => {
+                let allowed = priorities.outranks(source, holder)
+                    || (self.steal_same_rank && !priorities.is_below(source, holder));
+                if allowed {
+                    Ok(self.grant(source, client, now_ms))
+                } else {
+                    Err(RejectReason::LowerPriority)
                 }
             }
+            None => Ok(self.grant(source, client, now_ms)),
+        }
+    }
+
+    /// Check a command against the lease
+    ///
+    /// Only remote sources are checked, and e-stops always pass. Call
+    /// [`renew`](Self::renew) once the command has been accepted.
+    pub fn check(
+        &self,
+        cmd: &ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
+        if !source.is_remote() || matches!(cmd, ThrottleCommandDyn::EmergencyStop) {
+            return Ok(());
+        }
+        match self.current(now_ms) {
+            Some(holder) if holder == (source, client) => Ok(()),
+            Some(_) => Err(RejectReason::NotLeaseHolder),
+            None if self.required => Err(RejectReason::NotLeaseHolder),
+            None => Ok(()),
+        }
+    }
+
+    /// Renew the lease if `source` and `client` hold it
+    pub fn renew(&mut self, source: CommandSource, client: Option<ClientId>, now_ms: u64) {
+        if self.current(now_ms) == Some((source, client)) {
+            self.expires_ms = now_ms + self.duration_ms;
+        }
+    }
+
+    /// Get the current lease holder
+    pub fn status(&self, now_ms: u64) -> Option<LeaseStatus> {
+        self.current(now_ms).map(|(source, client)| LeaseStatus {
+            source,
+            client,
+            expires_ms: self.expires_ms,
+            remaining_ms: self.expires_ms.saturating_sub(now_ms),
+        })
+    }
+
+    /// The holder, unless the lease has lapsed.
+    fn current(&self, now_ms: u64) -> Option<(CommandSource, Option<ClientId>)> {
+        self.holder.filter(|_| now_ms < self.expires_ms)
+    }
+
+    fn grant(
+        &mut self,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> LeaseStatus {
+        self.holder = Some((source, client));
+        self.expires_ms = now_ms + self.duration_ms;
+        LeaseStatus {
+            source,
+            client,
+            expires_ms: self.expires_ms,
+            remaining_ms: self.duration_ms,
         }
     }
+}
+
+impl Default for ThrottleLease {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEASE_MS)
+    }
+}
+
+// ============================================================================
+// Rate Limiting
+// ============================================================================
+
+/// Token bucket limit for one command source.
+///
+/// A source may send `burst` commands back to back, then one more each
+/// time a token refills at `per_second`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateLimit {
+    /// Tokens regained per second.
+    pub per_second: u16,
+    /// Bucket size: commands accepted back to back.
+    pub burst: u16,
+}
+
+impl RateLimit {
+    /// Allow `per_second` commands on average, with bursts of up to `burst`
+    pub const fn new(per_second: u16, burst: u16) -> Self {
+        Self { per_second, burst }
+    }
+}
+
+/// Rate limit of each configurable command source (`None` = unlimited).
+///
+/// [`Fault`](CommandSource::Fault) and [`Emergency`](CommandSource::Emergency)
+/// are never limited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RateLimits {
+    /// Limit for [`CommandSource::Mqtt`].
+    pub mqtt: Option<RateLimit>,
+    /// Limit for [`CommandSource::WebApi`].
+    pub web_api: Option<RateLimit>,
+    /// Limit for [`CommandSource::WebLocal`].
+    pub web_local: Option<RateLimit>,
+    /// Limit for [`CommandSource::Physical`].
+    pub physical: Option<RateLimit>,
+}
+
+impl RateLimits {
+    /// Limit `source`; ignored for Fault and Emergency
+    pub fn with_limit(mut self, source: CommandSource, limit: RateLimit) -> Self {
+        if let Some(slot) = self.slot_mut(source) {
+            *slot = Some(limit);
+        }
+        self
+    }
+
+    /// The limit for `source`, if any
+    pub fn get(&self, source: CommandSource) -> Option<RateLimit> {
+        match source {
+            CommandSource::Mqtt => self.mqtt,
+            CommandSource::WebApi => self.web_api,
+            CommandSource::WebLocal => self.web_local,
+            CommandSource::Physical => self.physical,
+            CommandSource::Fault | CommandSource::Emergency => None,
+        }
+    }
+
+    fn slot_mut(&mut self, source: CommandSource) -> Option<&mut Option<RateLimit>> {
+        match source {
+            CommandSource::Mqtt => Some(&mut self.mqtt),
+            CommandSource::WebApi => Some(&mut self.web_api),
+            CommandSource::WebLocal => Some(&mut self.web_local),
+            CommandSource::Physical => Some(&mut self.physical),
+            CommandSource::Fault | CommandSource::Emergency => None,
+        }
+    }
+}
+
+/// A count per configurable command source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceCounts {
+    /// Count for [`CommandSource::Mqtt`].
+    pub mqtt: u32,
+    /// Count for [`CommandSource::WebApi`].
+    pub web_api: u32,
+    /// Count for [`CommandSource::WebLocal`].
+    pub web_local: u32,
+    /// Count for [`CommandSource::Physical`].
+    pub physical: u32,
+}
+
+impl SourceCounts {
+    /// The count for `source` (always 0 for Fault and Emergency)
+    pub fn get(&self, source: CommandSource) -> u32 {
+        match source {
+            CommandSource::Mqtt => self.mqtt,
+            CommandSource::WebApi => self.web_api,
+            CommandSource::WebLocal => self.web_local,
+            CommandSource::Physical => self.physical,
+            CommandSource::Fault | CommandSource::Emergency => 0,
+        }
+    }
+
+    /// Sum over all sources
+    pub fn total(&self) -> u32 {
+        self.mqtt
+            .saturating_add(self.web_api)
+            .saturating_add(self.web_local)
+            .saturating_add(self.physical)
+    }
+
+    fn increment(&mut self, source: CommandSource) {
+        let count = match source {
+            CommandSource::Mqtt => &mut self.mqtt,
+            CommandSource::WebApi => &mut self.web_api,
+            CommandSource::WebLocal => &mut self.web_local,
+            CommandSource::Physical => &mut self.physical,
+            CommandSource::Fault | CommandSource::Emergency => return,
+        };
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Token bucket state, in thousandths of a token
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    millitokens: u32,
+    updated_ms: u64,
+}
+
+/// Per-source token bucket rate limiter.
+///
+/// Each limited source starts with a full bucket; every accepted command
+/// takes a token. E-stops and heartbeats are never limited.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::priority::{RateLimit, RateLimiter, RateLimits};
+/// use rs_trainz::{CommandSource, ThrottleCommandDyn};
+///
+/// let limits = RateLimits::default().with_limit(CommandSource::Mqtt, RateLimit::new(10, 2));
+/// let mut limiter = RateLimiter::new(limits);
+/// let cmd = ThrottleCommandDyn::ClearQueue;
+///
+/// assert!(limiter.allow(&cmd, CommandSource::Mqtt, 0));
+/// assert!(limiter.allow(&cmd, CommandSource::Mqtt, 0));
+/// assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 0));
+///
+/// // One token back after 100 ms
+/// assert!(limiter.allow(&cmd, CommandSource::Mqtt, 100));
+/// assert_eq!(limiter.dropped().get(CommandSource::Mqtt), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: [Bucket; 4],
+    dropped: SourceCounts,
+}
+
+impl RateLimiter {
+    /// Create a limiter with full buckets
+    pub fn new(limits: RateLimits) -> Self {
+        let full = |source| Bucket {
+            millitokens: limits
+                .get(source)
+                .map_or(0, |limit: RateLimit| limit.burst as u32 * 1000),
+            updated_ms: 0,
+        };
+        Self {
+            limits,
+            buckets: [
+                full(CommandSource::Mqtt),
+                full(CommandSource::WebApi),
+                full(CommandSource::WebLocal),
+                full(CommandSource::Physical),
+            ],
+            dropped: SourceCounts::default(),
+        }
+    }
+
+    /// Create a limiter that accepts everything
+    pub fn disabled() -> Self {
+        Self::new(RateLimits::default())
+    }
+
+    /// Create a limiter from throttle configuration
+    pub fn from_config(config: &ThrottleConfig) -> Self {
+        Self::new(config.rate_limits)
+    }
+
+    /// The configured limits
+    pub fn limits(&self) -> &RateLimits {
+        &self.limits
+    }
+
+    /// Take a token for `command` from `source`
+    ///
+    /// Returns false, and counts the drop, if the source's bucket is empty.
+    #[must_use]
+    pub fn allow(
+        &mut self,
+        command: &ThrottleCommandDyn,
+        source: CommandSource,
+        now_ms: u64,
+    ) -> bool {
+        if matches!(
+            command,
+            ThrottleCommandDyn::EmergencyStop | ThrottleCommandDyn::Heartbeat
+        ) {
+            return true;
+        }
+        let (Some(limit), Some(index)) = (self.limits.get(source), bucket_index(source)) else {
+            return true;
+        };
+        let bucket = &mut self.buckets[index];
+        let elapsed = now_ms.saturating_sub(bucket.updated_ms);
+        let refill = elapsed.saturating_mul(limit.per_second as u64);
+        let capacity = limit.burst as u32 * 1000;
+        bucket.millitokens = (bucket.millitokens as u64)
+            .saturating_add(refill)
+            .min(capacity as u64) as u32;
+        bucket.updated_ms = bucket.updated_ms.max(now_ms);
+
+        if bucket.millitokens >= 1000 {
+            bucket.millitokens -= 1000;
+            true
+        } else {
+            self.dropped.increment(source);
+            false
+        }
+    }
+
+    /// Commands dropped so far, per source
+    pub fn dropped(&self) -> SourceCounts {
+        self.dropped
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+fn bucket_index(source: CommandSource) -> Option<usize> {
+    match source {
+        CommandSource::Mqtt => Some(0),
+        CommandSource::WebApi => Some(1),
+        CommandSource::WebLocal => Some(2),
+        CommandSource::Physical => Some(3),
+        CommandSource::Fault | CommandSource::Emergency => None,
+    }
+}
 
-    /// Clear the current lockout
-    pub fn clear(&mut self) {
-        self.active_source = None;
+/// Counts of commands dropped before they reached the throttle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandMetrics {
+    /// Commands refused by their source's rate limit.
+    pub rate_limited: SourceCounts,
+    /// Held or queued speed commands replaced by a newer one from the same
+    /// source.
+    pub coalesced: u32,
+    /// Commands rejected or displaced because a queue was full.
+    pub queue_full: u32,
+}
+
+/// How many commands an [`AuditLog`] remembers
+pub const AUDIT_DEPTH: usize = 16;
+
+/// One command as seen by the throttle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// When the command arrived (milliseconds since start).
+    pub timestamp_ms: u64,
+    /// Source category that sent it.
+    pub source: CommandSource,
+    /// Client within the source that sent it, if known.
+    pub client: Option<ClientId>,
+    /// The command's name (see [`ThrottleCommandDyn::name`]).
+    pub command: &'static str,
+    /// Why the command was refused, if it was.
+    pub rejected: Option<RejectReason>,
+}
+
+/// Trail of the most recent commands and who sent them.
+///
+/// Keeps the last [`AUDIT_DEPTH`] entries, dropping the oldest.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::priority::AuditLog;
+/// use rs_trainz::{ClientId, CommandSource, ThrottleCommandDyn};
+///
+/// let mut log = AuditLog::new();
+/// let phone = ClientId::new("alice-phone").unwrap();
+/// let pause = ThrottleCommandDyn::Pause;
+/// log.record(pause.name(), CommandSource::WebApi, Some(phone), None, 100);
+///
+/// let entry = log.entries().next().unwrap();
+/// assert_eq!(entry.command, "pause");
+/// assert_eq!(entry.client, Some(phone));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    entries: heapless::Deque<AuditEntry, AUDIT_DEPTH>,
+}
+
+impl AuditLog {
+    /// Create an empty log
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Get the current lockout status
-    pub fn status(&self, now_ms: u64) -> Option<LockoutStatus> {
-        if now_ms >= self.lockout_until_ms {
-            return None;
+    /// Record a command and whether it was rejected
+    pub fn record(
+        &mut self,
+        command: &'static str,
+        source: CommandSource,
+        client: Option<ClientId>,
+        rejected: Option<RejectReason>,
+        now_ms: u64,
+    ) {
+        if self.entries.is_full() {
+            self.entries.pop_front();
         }
-        self.active_source.map(|source| LockoutStatus {
+        let _ = self.entries.push_back(AuditEntry {
+            timestamp_ms: now_ms,
             source,
-            expires_ms: self.lockout_until_ms,
-            remaining_ms: self.lockout_until_ms.saturating_sub(now_ms),
-        })
+            client,
+            command,
+            rejected,
+        });
     }
-}
 
-/// Information about an active lockout.
-///
-/// Returned by [`SourceLockout::status`] when a lockout is active.
-/// Useful for UI feedback showing when remote control will be available.
-#[derive(Clone, Debug)]
-pub struct LockoutStatus {
-    /// The source that holds the lockout.
-    pub source: CommandSource,
-    /// Timestamp when the lockout expires (milliseconds since start).
-    pub expires_ms: u64,
-    /// Time remaining until lockout expires (milliseconds).
-    pub remaining_ms: u64,
+    /// Recorded commands, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter()
+    }
 }
 
 /// Combined command processor with queue and lockout.
@@ -323,14 +1294,14 @@ pub struct LockoutStatus {
 /// ```rust
 /// use rs_trainz::priority::CommandProcessor;
 /// use rs_trainz::commands::{PrioritizedCommand, ThrottleCommandDyn, CommandSource};
-/// use rs_trainz::AnyStrategy;
+/// use rs_trainz::{AnyStrategy, Speed};
 /// use rs_trainz::traits::Immediate;
 ///
 /// let mut processor: CommandProcessor<8> = CommandProcessor::new(2000);
 ///
 /// // Submit commands
 /// let cmd = PrioritizedCommand::new(
-///     ThrottleCommandDyn::SetSpeed { target: 0.5, strategy: AnyStrategy::new(Immediate) },
+///     ThrottleCommandDyn::SetSpeed { target: Speed::new(0.5).unwrap(), strategy: AnyStrategy::new(Immediate) },
 ///     CommandSource::Physical,
 ///     0,
 /// );
@@ -344,6 +1315,7 @@ pub struct LockoutStatus {
 pub struct CommandProcessor<const N: usize> {
     queue: CommandQueue<N>,
     lockout: SourceLockout,
+    limiter: RateLimiter,
 }
 
 impl<const N: usize> CommandProcessor<N> {
@@ -352,14 +1324,31 @@ impl<const N: usize> CommandProcessor<N> {
         Self {
             queue: CommandQueue::new(),
             lockout: SourceLockout::new(lockout_duration_ms),
+            limiter: RateLimiter::disabled(),
         }
     }
 
+    /// Drop commands from sources that exceed their rate limit
+    pub fn with_rate_limits(mut self, limits: RateLimits) -> Self {
+        self.limiter = RateLimiter::new(limits);
+        self
+    }
+
+    /// Rank sources with `priorities` in both the queue and the lockout
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.queue = self.queue.with_priorities(priorities);
+        self.lockout = self.lockout.with_priorities(priorities);
+        self
+    }
+
     /// Submit a command for processing
     ///
     /// Returns true if accepted into queue, false if rejected
     #[must_use]
     pub fn submit(&mut self, cmd: PrioritizedCommand, now_ms: u64) -> bool {
+        if !self.limiter.allow(&cmd.command, cmd.source, now_ms) {
+            return false;
+        }
         if self.lockout.should_accept(&cmd, now_ms) {
             self.queue.push(cmd)
         } else {
@@ -393,6 +1382,14 @@ impl<const N: usize> CommandProcessor<N> {
     pub fn lockout_status(&self, now_ms: u64) -> Option<LockoutStatus> {
         self.lockout.status(now_ms)
     }
+
+    /// Returns counts of commands dropped by rate limits and the queue.
+    pub fn metrics(&self) -> CommandMetrics {
+        CommandMetrics {
+            rate_limited: self.limiter.dropped(),
+            ..self.queue.metrics()
+        }
+    }
 }
 
 impl<const N: usize> Default for CommandProcessor<N> {
@@ -408,13 +1405,14 @@ impl<const N: usize> Default for CommandProcessor<N> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::speed::Speed;
     use crate::strategy_dyn::AnyStrategy;
-    use crate::traits::Immediate;
+    use crate::traits::{Direction, Immediate};
 
     fn make_cmd(source: CommandSource, timestamp: u64) -> PrioritizedCommand {
         PrioritizedCommand::new(
             ThrottleCommandDyn::SetSpeed {
-                target: 0.5,
+                target: Speed::new(0.5).unwrap(),
                 strategy: AnyStrategy::new(Immediate),
             },
             source,
@@ -529,6 +1527,324 @@ mod tests {
         assert!(q.is_empty());
     }
 
+    fn exhibition() -> SourcePriorities {
+        // Automation above the public web UI
+        SourcePriorities::default()
+            .with_rank(CommandSource::Mqtt, 2)
+            .with_rank(CommandSource::WebLocal, 0)
+    }
+
+    #[test]
+    fn queue_orders_by_configured_priorities() {
+        let mut q: CommandQueue<4> = CommandQueue::new().with_priorities(exhibition());
+        let _ = q.push(make_cmd(CommandSource::WebLocal, 0));
+        let _ = q.push(make_cmd(CommandSource::Mqtt, 0));
+        let _ = q.push(make_cmd(CommandSource::WebApi, 0));
+
+        assert_eq!(q.pop().unwrap().source, CommandSource::Mqtt);
+        assert_eq!(q.pop().unwrap().source, CommandSource::WebApi);
+        assert_eq!(q.pop().unwrap().source, CommandSource::WebLocal);
+    }
+
+    #[test]
+    fn queue_clear_below_uses_configured_priorities() {
+        let mut q: CommandQueue<4> = CommandQueue::new().with_priorities(exhibition());
+        let _ = q.push(make_cmd(CommandSource::WebLocal, 0));
+        let _ = q.push(make_cmd(CommandSource::Mqtt, 0));
+
+        q.clear_below(CommandSource::WebApi);
+        assert_eq!(q.len(), 1);
+        assert_eq!(q.pop().unwrap().source, CommandSource::Mqtt);
+    }
+
+    fn make_speed(source: CommandSource, target: f32, timestamp: u64) -> PrioritizedCommand {
+        PrioritizedCommand::new(
+            ThrottleCommandDyn::SetSpeed {
+                target: Speed::new(target).unwrap(),
+                strategy: AnyStrategy::new(Immediate),
+            },
+            source,
+            timestamp,
+        )
+    }
+
+    #[test]
+    fn queue_coalesces_speed_from_same_source() {
+        let mut q: CommandQueue<4> = CommandQueue::new();
+        for (i, target) in [0.2, 0.4, 0.6].into_iter().enumerate() {
+            assert!(q.push(make_speed(CommandSource::Mqtt, target, i as u64)));
+        }
+
+        assert_eq!(q.len(), 1);
+        assert_eq!(q.metrics().coalesced, 2);
+        match q.pop().unwrap().command {
+            ThrottleCommandDyn::SetSpeed { target, .. } => assert_eq!(target, 0.6),
+            other => panic!("expected SetSpeed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn queue_coalesce_keeps_other_sources_and_commands() {
+        let mut q: CommandQueue<4> = CommandQueue::new();
+        let _ = q.push(make_speed(CommandSource::Mqtt, 0.2, 0));
+        let _ = q.push(make_speed(CommandSource::WebApi, 0.3, 0));
+        let _ = q.push(make_estop(CommandSource::Mqtt, 0));
+        let _ = q.push(make_speed(CommandSource::Mqtt, 0.5, 1));
+
+        assert_eq!(q.len(), 3);
+        assert_eq!(q.metrics().coalesced, 1);
+    }
+
+    #[test]
+    fn queue_full_drops_are_counted() {
+        let mut q: CommandQueue<2> = CommandQueue::new();
+        let _ = q.push(make_cmd(CommandSource::WebApi, 0));
+        let _ = q.push(make_cmd(CommandSource::WebLocal, 0));
+
+        // Rejected
+        assert!(!q.push(make_cmd(CommandSource::Mqtt, 1)));
+        // Accepted, displacing the WebApi command
+        assert!(q.push(make_cmd(CommandSource::Physical, 1)));
+
+        assert_eq!(q.metrics().queue_full, 2);
+        assert_eq!(q.metrics().coalesced, 0);
+    }
+
+    #[test]
+    fn queue_equal_priority_pops_in_arrival_order() {
+        let mut q: CommandQueue<8> = CommandQueue::new();
+        // Same timestamp, so only arrival order can tell them apart
+        for source in [CommandSource::Mqtt, CommandSource::WebApi] {
+            for _ in 0..3 {
+                let _ = q.push(PrioritizedCommand::new(
+                    ThrottleCommandDyn::ClearQueue,
+                    source,
+                    0,
+                ));
+            }
+        }
+
+        let popped: Vec<_> = core::iter::from_fn(|| q.pop())
+            .map(|c| (c.source, c.sequence()))
+            .collect();
+        assert_eq!(
+            popped,
+            [
+                (CommandSource::WebApi, 3),
+                (CommandSource::WebApi, 4),
+                (CommandSource::WebApi, 5),
+                (CommandSource::Mqtt, 0),
+                (CommandSource::Mqtt, 1),
+                (CommandSource::Mqtt, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn queue_full_evicts_last_arrival_of_lowest_priority() {
+        let mut q: CommandQueue<3> = CommandQueue::new();
+        for source in [
+            CommandSource::Mqtt,
+            CommandSource::Mqtt,
+            CommandSource::WebApi,
+        ] {
+            let _ = q.push(PrioritizedCommand::new(
+                ThrottleCommandDyn::ClearQueue,
+                source,
+                0,
+            ));
+        }
+
+        assert!(q.push(make_cmd(CommandSource::Physical, 0)));
+        let sequences: Vec<_> = core::iter::from_fn(|| q.pop())
+            .map(|c| c.sequence())
+            .collect();
+        assert_eq!(sequences, [3, 2, 0]);
+    }
+
+    #[test]
+    fn queue_sequence_survives_clear() {
+        let mut q: CommandQueue<4> = CommandQueue::new();
+        let _ = q.push(make_cmd(CommandSource::Mqtt, 0));
+        q.clear();
+        let _ = q.push(make_cmd(CommandSource::Mqtt, 0));
+        assert_eq!(q.peek().unwrap().sequence(), 1);
+    }
+
+    // === Model Tests ===
+
+    /// xorshift64* generator, so runs are reproducible from the seed
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    const SOURCES: [CommandSource; 6] = [
+        CommandSource::Mqtt,
+        CommandSource::WebApi,
+        CommandSource::WebLocal,
+        CommandSource::Physical,
+        CommandSource::Fault,
+        CommandSource::Emergency,
+    ];
+
+    fn random_command(rng: &mut Rng) -> PrioritizedCommand {
+        let command = match rng.below(4) {
+            0 | 1 => ThrottleCommandDyn::SetSpeed {
+                target: Speed::new(rng.below(11) as f32 / 10.0).unwrap(),
+                strategy: AnyStrategy::new(Immediate),
+            },
+            2 => ThrottleCommandDyn::ClearQueue,
+            _ => ThrottleCommandDyn::EmergencyStop,
+        };
+        let source = SOURCES[rng.below(4) as usize];
+        PrioritizedCommand::new(command, source, rng.below(3))
+    }
+
+    /// Reference queue: an unsorted list searched linearly
+    #[derive(Default)]
+    struct ModelQueue {
+        items: Vec<PrioritizedCommand>,
+        next_sequence: u64,
+        coalesced: u32,
+        dropped: u32,
+    }
+
+    impl ModelQueue {
+        fn push(
+            &mut self,
+            cmd: PrioritizedCommand,
+            priorities: SourcePriorities,
+            cap: usize,
+        ) -> bool {
+            let cmd = cmd
+                .with_priorities(priorities)
+                .with_sequence(self.next_sequence);
+            self.next_sequence += 1;
+            if matches!(cmd.command, ThrottleCommandDyn::SetSpeed { .. }) {
+                let before = self.items.len();
+                self.items.retain(|c| {
+                    c.source != cmd.source
+                        || !matches!(c.command, ThrottleCommandDyn::SetSpeed { .. })
+                });
+                self.coalesced += (before - self.items.len()) as u32;
+            }
+            if self.items.len() < cap {
+                self.items.push(cmd);
+                return true;
+            }
+            self.dropped += 1;
+            let (min_index, min) = self
+                .items
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.cmp(b.1))
+                .unwrap();
+            if cmd.priority() > min.priority() {
+                self.items.remove(min_index);
+                self.items.push(cmd);
+                true
+            } else {
+                false
+            }
+        }
+
+        fn pop(&mut self) -> Option<PrioritizedCommand> {
+            let (index, _) = self.items.iter().enumerate().max_by(|a, b| a.1.cmp(b.1))?;
+            Some(self.items.remove(index))
+        }
+    }
+
+    fn check_against_model<const N: usize>(seed: u64, priorities: SourcePriorities) {
+        let mut rng = Rng(seed);
+        let mut queue: CommandQueue<N> = CommandQueue::new().with_priorities(priorities);
+        let mut model = ModelQueue::default();
+
+        for step in 0..500 {
+            match rng.below(10) {
+                0..=5 => {
+                    let cmd = random_command(&mut rng);
+                    assert_eq!(
+                        queue.push(cmd.clone()),
+                        model.push(cmd, priorities, N),
+                        "push at step {step} (seed {seed})"
+                    );
+                }
+                6..=8 => {
+                    let expected = model.pop().map(|c| (c.source, c.sequence()));
+                    assert_eq!(
+                        queue.peek().map(|c| (c.source, c.sequence())),
+                        expected,
+                        "peek at step {step} (seed {seed})"
+                    );
+                    assert_eq!(
+                        queue.pop().map(|c| (c.source, c.sequence())),
+                        expected,
+                        "pop at step {step} (seed {seed})"
+                    );
+                }
+                _ => {
+                    let source = SOURCES[rng.below(6) as usize];
+                    queue.clear_below(source);
+                    let min_rank = priorities.rank(source);
+                    model.items.retain(|c| c.priority().0 >= min_rank);
+                }
+            }
+            assert_eq!(queue.len(), model.items.len());
+            assert_eq!(queue.is_full(), model.items.len() == N);
+        }
+
+        let metrics = queue.metrics();
+        assert_eq!(metrics.coalesced, model.coalesced);
+        assert_eq!(metrics.queue_full, model.dropped);
+    }
+
+    #[test]
+    fn queue_matches_model() {
+        for seed in 1..=50 {
+            check_against_model::<1>(seed, SourcePriorities::default());
+            check_against_model::<4>(seed, SourcePriorities::default());
+            check_against_model::<7>(seed, exhibition());
+            check_against_model::<16>(seed, SourcePriorities::default());
+        }
+    }
+
+    #[test]
+    fn min_max_heap_matches_sorted_list() {
+        let mut rng = Rng(0x5EED);
+        for _ in 0..200 {
+            let mut heap: MinMaxHeap<u64, 32> = MinMaxHeap::new();
+            let mut model = Vec::new();
+            for _ in 0..rng.below(33) {
+                let value = rng.below(20);
+                heap.push(value).unwrap();
+                model.push(value);
+            }
+            model.sort_unstable();
+
+            while !model.is_empty() {
+                if rng.below(2) == 0 {
+                    assert_eq!(heap.peek_min(), model.first());
+                    assert_eq!(heap.pop_min(), Some(model.remove(0)));
+                } else {
+                    assert_eq!(heap.peek_max(), model.last());
+                    assert_eq!(heap.pop_max(), model.pop());
+                }
+            }
+            assert_eq!(heap.pop_max(), None);
+        }
+    }
+
     // === SourceLockout Tests ===
     #[test]
     fn lockout_accepts_first_command() {
@@ -666,6 +1982,195 @@ mod tests {
         assert_eq!(status.expires_ms, 3500);
     }
 
+    #[test]
+    fn lockout_uses_configured_priorities() {
+        let priorities = exhibition().with_rank(CommandSource::Mqtt, 3);
+        let mut lockout = SourceLockout::new(2000).with_priorities(priorities);
+
+        // Mqtt now ranks with Physical, so it takes the lockout
+        let _ = lockout.should_accept(&make_cmd(CommandSource::Mqtt, 0), 0);
+        assert_eq!(lockout.status(0).unwrap().source, CommandSource::Mqtt);
+        assert!(!lockout.should_accept(&make_cmd(CommandSource::WebLocal, 100), 100));
+        assert!(lockout.should_accept(&make_cmd(CommandSource::Physical, 200), 200));
+    }
+
+    // === HeartbeatLease Tests ===
+    #[test]
+    fn heartbeat_disabled_never_expires() {
+        let mut lease = HeartbeatLease::disabled();
+        lease.on_command(CommandSource::WebApi, 0);
+
+        assert!(!lease.is_enabled());
+        assert!(lease.status(0).is_none());
+        assert!(lease.check_expired(1_000_000).is_none());
+    }
+
+    #[test]
+    fn heartbeat_remote_command_takes_lease() {
+        let mut lease = HeartbeatLease::new(3000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::Mqtt, 100);
+
+        let status = lease.status(1100).unwrap();
+        assert_eq!(status.source, CommandSource::Mqtt);
+        assert_eq!(status.expires_ms, 3100);
+        assert_eq!(status.remaining_ms, 2000);
+    }
+
+    #[test]
+    fn heartbeat_refresh_extends_lease() {
+        let mut lease = HeartbeatLease::new(3000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::WebLocal, 0);
+
+        assert!(lease.refresh(CommandSource::WebLocal, 2500));
+        assert!(lease.check_expired(5000).is_none());
+        assert_eq!(lease.check_expired(5500), Some(CommandSource::WebLocal));
+    }
+
+    #[test]
+    fn heartbeat_refresh_from_non_holder_ignored() {
+        let mut lease = HeartbeatLease::new(3000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::WebLocal, 0);
+
+        assert!(!lease.refresh(CommandSource::Mqtt, 2500));
+        assert_eq!(lease.check_expired(3000), Some(CommandSource::WebLocal));
+    }
+
+    #[test]
+    fn heartbeat_refresh_after_lapse_fails() {
+        let mut lease = HeartbeatLease::new(1000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::WebApi, 0);
+
+        assert!(!lease.refresh(CommandSource::WebApi, 1500));
+        assert_eq!(lease.check_expired(1500), Some(CommandSource::WebApi));
+    }
+
+    #[test]
+    fn heartbeat_expiry_reported_once() {
+        let mut lease = HeartbeatLease::new(1000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::Mqtt, 0);
+
+        assert_eq!(lease.check_expired(1000), Some(CommandSource::Mqtt));
+        assert!(lease.check_expired(2000).is_none());
+        assert!(lease.status(2000).is_none());
+    }
+
+    #[test]
+    fn heartbeat_physical_releases_lease() {
+        let mut lease = HeartbeatLease::new(1000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::WebLocal, 0);
+        lease.on_command(CommandSource::Physical, 500);
+
+        assert!(lease.status(500).is_none());
+        assert!(lease.check_expired(5000).is_none());
+    }
+
+    #[test]
+    fn heartbeat_new_remote_source_takes_over() {
+        let mut lease = HeartbeatLease::new(1000, SafeStop::EmergencyStop);
+        lease.on_command(CommandSource::WebLocal, 0);
+        lease.on_command(CommandSource::Mqtt, 500);
+
+        assert_eq!(lease.status(600).unwrap().source, CommandSource::Mqtt);
+        assert!(!lease.refresh(CommandSource::WebLocal, 700));
+    }
+
+    #[test]
+    fn heartbeat_from_config() {
+        let config = ThrottleConfig::default()
+            .with_heartbeat_timeout_ms(4000)
+            .with_safe_stop(SafeStop::EmergencyStop);
+        let lease = HeartbeatLease::from_config(&config);
+
+        assert!(lease.is_enabled());
+        assert_eq!(lease.safe_stop(), SafeStop::EmergencyStop);
+    }
+
+    // === RateLimiter Tests ===
+    fn mqtt_limited(per_second: u16, burst: u16) -> RateLimiter {
+        RateLimiter::new(
+            RateLimits::default()
+                .with_limit(CommandSource::Mqtt, RateLimit::new(per_second, burst)),
+        )
+    }
+
+    #[test]
+    fn limiter_allows_burst_then_drops() {
+        let mut limiter = mqtt_limited(5, 3);
+        let cmd = make_cmd(CommandSource::Mqtt, 0).command;
+
+        for _ in 0..3 {
+            assert!(limiter.allow(&cmd, CommandSource::Mqtt, 0));
+        }
+        assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 0));
+        assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 10));
+        assert_eq!(limiter.dropped().mqtt, 2);
+        assert_eq!(limiter.dropped().total(), 2);
+    }
+
+    #[test]
+    fn limiter_refills_at_rate() {
+        let mut limiter = mqtt_limited(5, 1);
+        let cmd = make_cmd(CommandSource::Mqtt, 0).command;
+
+        assert!(limiter.allow(&cmd, CommandSource::Mqtt, 0));
+        // 5 per second = one token every 200ms
+        assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 199));
+        assert!(limiter.allow(&cmd, CommandSource::Mqtt, 200));
+        assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 300));
+    }
+
+    #[test]
+    fn limiter_refill_caps_at_burst() {
+        let mut limiter = mqtt_limited(10, 2);
+        let cmd = make_cmd(CommandSource::Mqtt, 0).command;
+
+        // A long quiet spell refills only up to the burst size
+        assert!(limiter.allow(&cmd, CommandSource::Mqtt, 60_000));
+        assert!(limiter.allow(&cmd, CommandSource::Mqtt, 60_000));
+        assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 60_000));
+    }
+
+    #[test]
+    fn limiter_ignores_unlimited_sources() {
+        let mut limiter = mqtt_limited(1, 1);
+        let cmd = make_cmd(CommandSource::WebApi, 0).command;
+
+        for _ in 0..100 {
+            assert!(limiter.allow(&cmd, CommandSource::WebApi, 0));
+        }
+        assert!(RateLimiter::disabled().allow(&cmd, CommandSource::Mqtt, 0));
+    }
+
+    #[test]
+    fn limiter_never_drops_estop_or_heartbeat() {
+        let mut limiter = mqtt_limited(1, 1);
+        let cmd = make_cmd(CommandSource::Mqtt, 0).command;
+        assert!(limiter.allow(&cmd, CommandSource::Mqtt, 0));
+        assert!(!limiter.allow(&cmd, CommandSource::Mqtt, 0));
+
+        assert!(limiter.allow(&ThrottleCommandDyn::EmergencyStop, CommandSource::Mqtt, 0));
+        assert!(limiter.allow(&ThrottleCommandDyn::Heartbeat, CommandSource::Mqtt, 0));
+        assert_eq!(limiter.dropped().mqtt, 1);
+    }
+
+    #[test]
+    fn rate_limits_ignore_fault_and_emergency() {
+        let limits = RateLimits::default().with_limit(CommandSource::Fault, RateLimit::new(1, 1));
+        assert_eq!(limits, RateLimits::default());
+        assert_eq!(limits.get(CommandSource::Emergency), None);
+    }
+
+    #[test]
+    fn limiter_from_config() {
+        let config =
+            ThrottleConfig::default().with_rate_limit(CommandSource::WebApi, RateLimit::new(2, 4));
+        let limiter = RateLimiter::from_config(&config);
+        assert_eq!(
+            limiter.limits().get(CommandSource::WebApi),
+            Some(RateLimit::new(2, 4))
+        );
+    }
+
     // === CommandProcessor Tests ===
     #[test]
     fn processor_submit_and_process() {
@@ -736,4 +2241,216 @@ mod tests {
         // Should be unlocked at 2001ms
         assert!(proc.submit(make_cmd(CommandSource::Mqtt, 0), 2001));
     }
+
+    #[test]
+    fn processor_with_priorities() {
+        let priorities = SourcePriorities::default().with_rank(CommandSource::Physical, 0);
+        let mut proc: CommandProcessor<4> = CommandProcessor::new(2000).with_priorities(priorities);
+
+        // Demoted physical controls still lock out, but no longer outrank Mqtt
+        assert!(proc.submit(make_cmd(CommandSource::Physical, 0), 0));
+        assert!(proc.submit(make_cmd(CommandSource::Mqtt, 0), 100));
+        assert_eq!(
+            proc.lockout_status(100).unwrap().source,
+            CommandSource::Mqtt
+        );
+        assert_eq!(proc.queue_len(), 2);
+    }
+
+    #[test]
+    fn processor_drops_rate_limited_commands() {
+        let limits = RateLimits::default().with_limit(CommandSource::Mqtt, RateLimit::new(10, 2));
+        let mut proc: CommandProcessor<4> = CommandProcessor::new(2000).with_rate_limits(limits);
+
+        // A 100Hz flood: two accepted from the burst, one coalesced away
+        for i in 0..10 {
+            let _ = proc.submit(
+                make_speed(CommandSource::Mqtt, 0.1 * i as f32, i * 10),
+                i * 10,
+            );
+        }
+
+        let metrics = proc.metrics();
+        assert_eq!(proc.queue_len(), 1);
+        assert_eq!(metrics.rate_limited.mqtt, 8);
+        assert_eq!(metrics.coalesced, 1);
+        assert_eq!(metrics.queue_full, 0);
+    }
+
+    #[test]
+    fn audit_log_keeps_latest_entries() {
+        let mut log = AuditLog::new();
+        let phone = ClientId::new("alice-phone").unwrap();
+
+        for i in 0..AUDIT_DEPTH as u64 + 2 {
+            log.record(
+                "pause",
+                CommandSource::WebApi,
+                Some(phone),
+                Some(RejectReason::NotTransitioning),
+                i,
+            );
+        }
+
+        // Oldest entries dropped
+        assert_eq!(log.entries().count(), AUDIT_DEPTH);
+        let first = log.entries().next().unwrap();
+        assert_eq!(first.timestamp_ms, 2);
+        assert_eq!(first.command, "pause");
+        assert_eq!(first.client, Some(phone));
+        assert_eq!(first.rejected, Some(RejectReason::NotTransitioning));
+    }
+
+    #[test]
+    fn test_lease_acquire_and_release() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let mut lease = ThrottleLease::new(10_000);
+
+        let status = lease.acquire(CommandSource::WebApi, alice, 0).unwrap();
+        assert_eq!(status.client, alice);
+        assert_eq!(status.remaining_ms, 10_000);
+
+        assert_eq!(
+            lease.acquire(CommandSource::WebApi, bob, 100),
+            Err(RejectReason::LeaseHeld)
+        );
+        assert_eq!(
+            lease.release(CommandSource::WebApi, bob, 100),
+            Err(RejectReason::LeaseHeld)
+        );
+
+        // Re-acquiring renews
+        let status = lease.acquire(CommandSource::WebApi, alice, 5000).unwrap();
+        assert_eq!(status.expires_ms, 15_000);
+
+        assert!(lease.release(CommandSource::WebApi, alice, 6000).is_ok());
+        assert!(lease.status(6000).is_none());
+        assert_eq!(
+            lease.release(CommandSource::WebApi, alice, 6000),
+            Err(RejectReason::NotLeaseHolder)
+        );
+    }
+
+    #[test]
+    fn test_lease_expires_without_commands() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let cmd = ThrottleCommandDyn::Heartbeat;
+        let mut lease = ThrottleLease::new(1000);
+
+        lease.acquire(CommandSource::Mqtt, alice, 0).unwrap();
+        // Checking doesn't renew, accepted commands from the holder do
+        assert!(lease.check(&cmd, CommandSource::Mqtt, alice, 900).is_ok());
+        assert_eq!(lease.status(900).unwrap().remaining_ms, 100);
+        lease.renew(CommandSource::Mqtt, bob, 900);
+        assert_eq!(lease.status(900).unwrap().remaining_ms, 100);
+        lease.renew(CommandSource::Mqtt, alice, 900);
+        assert_eq!(lease.status(1800).unwrap().remaining_ms, 100);
+
+        assert!(lease.status(1900).is_none());
+        assert!(lease.acquire(CommandSource::Mqtt, bob, 1900).is_ok());
+    }
+
+    #[test]
+    fn test_lease_steal_checks_priority() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let priorities = SourcePriorities::default();
+        let mut lease = ThrottleLease::new(10_000);
+
+        lease.acquire(CommandSource::WebLocal, alice, 0).unwrap();
+        assert_eq!(
+            lease.steal(CommandSource::Mqtt, bob, &priorities, 100),
+            Err(RejectReason::LowerPriority)
+        );
+
+        // Equal priority isn't enough
+        assert_eq!(
+            lease.steal(CommandSource::WebLocal, bob, &priorities, 200),
+            Err(RejectReason::LowerPriority)
+        );
+
+        // Reranked table lets MQTT take over
+        let priorities = priorities.with_rank(CommandSource::Mqtt, 10);
+        let status = lease
+            .steal(CommandSource::Mqtt, bob, &priorities, 300)
+            .unwrap();
+        assert_eq!(status.client, bob);
+    }
+
+    #[test]
+    fn test_lease_steal_same_rank_option() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let priorities = SourcePriorities::default();
+        let mut lease = ThrottleLease::new(10_000).with_steal_same_rank(true);
+
+        lease.acquire(CommandSource::WebLocal, alice, 0).unwrap();
+        assert_eq!(
+            lease.steal(CommandSource::Mqtt, bob, &priorities, 100),
+            Err(RejectReason::LowerPriority)
+        );
+        let status = lease
+            .steal(CommandSource::WebLocal, bob, &priorities, 200)
+            .unwrap();
+        assert_eq!(status.client, bob);
+    }
+
+    #[test]
+    fn test_lease_from_config() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let config = ThrottleConfig::default()
+            .with_lease_ms(5000)
+            .with_lease_required(true)
+            .with_lease_steal_same_rank(true);
+        let mut lease = ThrottleLease::from_config(&config);
+        assert!(lease.is_required());
+
+        let status = lease.acquire(CommandSource::WebApi, alice, 0).unwrap();
+        assert_eq!(status.expires_ms, 5000);
+        assert!(lease
+            .steal(CommandSource::WebApi, bob, &config.priorities, 100)
+            .is_ok());
+
+        // Defaults: optional, strict steals
+        let lease = ThrottleLease::from_config(&ThrottleConfig::default());
+        assert!(!lease.is_required());
+        assert_eq!(lease.duration_ms, DEFAULT_LEASE_MS);
+    }
+
+    #[test]
+    fn test_lease_check_blocks_other_remote_clients() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let cmd = ThrottleCommandDyn::SetDirection(Direction::Forward);
+        let estop = ThrottleCommandDyn::EmergencyStop;
+        let mut lease = ThrottleLease::new(10_000);
+
+        // Optional lease: anyone drives while it's free
+        assert!(lease.check(&cmd, CommandSource::WebApi, bob, 0).is_ok());
+
+        lease.acquire(CommandSource::WebApi, alice, 0).unwrap();
+        assert_eq!(
+            lease.check(&cmd, CommandSource::WebApi, bob, 100),
+            Err(RejectReason::NotLeaseHolder)
+        );
+        assert_eq!(
+            lease.check(&cmd, CommandSource::Mqtt, alice, 100),
+            Err(RejectReason::NotLeaseHolder)
+        );
+        assert!(lease.check(&estop, CommandSource::WebApi, bob, 100).is_ok());
+        assert!(lease
+            .check(&cmd, CommandSource::Physical, None, 100)
+            .is_ok());
+
+        // Required lease: nobody drives remotely without it
+        let lease = ThrottleLease::new(10_000).with_required(true);
+        assert_eq!(
+            lease.check(&cmd, CommandSource::WebApi, bob, 0),
+            Err(RejectReason::NotLeaseHolder)
+        );
+        assert!(lease.check(&cmd, CommandSource::Physical, None, 0).is_ok());
+    }
 }
@@ -0,0 +1,688 @@
+//! Composite strategies that chain, delay and hold other strategies.
+//!
+//! A [`Sequence`] runs several moves back to back as a single transition,
+//! so the whole profile shares one [`TransitionLock`]: a locked sequence
+//! can't be broken up halfway by a lower-priority command. Each move
+//! carries its own target speed; the last one usually heads for the
+//! commanded target.
+//!
+//! [`Delay`] and [`Hold`] wrap a single strategy to wait before it starts
+//! or to stay at the target (still locked) after it finishes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rs_trainz::sequence::Sequence;
+//! use rs_trainz::traits::{EaseInOut, Linear};
+//! use rs_trainz::{ExecutionStrategy, Speed};
+//!
+//! // Ramp to 0.3 over 2s, hold 5s, then ease up to the target over 4s
+//! let run: Sequence = Sequence::locked()
+//!     .then(Speed::new(0.3).unwrap(), Linear::new(2000))
+//!     .hold(5000)
+//!     .finish(EaseInOut::new(4000));
+//!
+//! assert_eq!(run.duration_for(0.0, 0.7), Some(11_000));
+//! let (value, _) = run.interpolate(0.0, 0.7, 4000);
+//! assert!((value - 0.3).abs() < 0.001);
+//! assert_eq!(run.segment(0.0, 0.7, 4000).unwrap().index, 1);
+//! ```
+//!
+//! # Storage
+//!
+//! Steps are stored inline (up to [`MAX_SEQUENCE_STEPS`]). Mixing strategy
+//! types uses the default `Sequence<AnyStrategy>`; handing a sequence to the
+//! controller as a [`ThrottleCommandDyn`] requires the `alloc` feature, like
+//! any other custom strategy.
+//!
+//! [`ThrottleCommandDyn`]: crate::commands::ThrottleCommandDyn
+
+use crate::fixed::Q16;
+use crate::speed::Speed;
+use crate::strategy_dyn::AnyStrategy;
+use crate::traits::{ExecutionStrategy, InterruptBehavior, SegmentProgress, TransitionLock};
+
+/// Maximum number of steps in a [`Sequence`]
+pub const MAX_SEQUENCE_STEPS: usize = 8;
+
+/// Upper bound when searching for the end of a move with unknown duration
+const MAX_SEARCH_MS: u64 = 1 << 32;
+
+/// How long `strategy` takes to move from `from` to `to`.
+///
+/// Uses the reported duration when known, otherwise searches for the point
+/// where interpolation completes.
+fn completion_ms<S: ExecutionStrategy>(strategy: &S, from: f32, to: f32) -> u64 {
+    if let Some(ms) = strategy.duration_for(from, to) {
+        return ms;
+    }
+
+    let done = |ms| strategy.interpolate(from, to, ms).1;
+    if done(0) {
+        return 0;
+    }
+    let mut high = 1;
+    while !done(high) {
+        if high >= MAX_SEARCH_MS {
+            return MAX_SEARCH_MS;
+        }
+        high *= 2;
+    }
+    let mut low = high / 2;
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if done(mid) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    high
+}
+
+// ============================================================================
+// Sequence
+// ============================================================================
+
+/// One step of a [`Sequence`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SequenceStep<S> {
+    /// Move to `target` (the commanded target if `None`) using `strategy`.
+    Move {
+        /// Speed this step ends at.
+        target: Option<Speed>,
+        /// How to get there.
+        strategy: S,
+    },
+    /// Stay at the current speed.
+    Hold {
+        /// How long to stay (milliseconds).
+        duration_ms: u64,
+    },
+}
+
+/// Several moves and holds run back to back as one transition.
+///
+/// The sequence starts from the current speed and runs its steps in order.
+/// Explicit step targets are limited to the higher of the start speed and
+/// the commanded target, so a sequence never exceeds the controller's max
+/// speed. The transition always ends at the commanded target: end with
+/// [`finish`](Self::finish) to move there smoothly.
+///
+/// The sequence's own lock and interrupt behavior apply for its whole
+/// duration; those of the individual steps are ignored. Presets are only
+/// resolved for whole commands, so steps should use concrete strategies.
+///
+/// # Constructors
+///
+/// - [`new`](Self::new): No lock, interruptible
+/// - [`locked`](Self::locked): Hard lock, rejects interrupts
+/// - [`source_locked`](Self::source_locked): Source lock, allows same/higher priority
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sequence<S = AnyStrategy> {
+    /// Steps in the order they run.
+    pub steps: heapless::Vec<SequenceStep<S>, MAX_SEQUENCE_STEPS>,
+    /// Lock level for the whole sequence.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock: TransitionLock,
+    /// Behavior when interrupted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interrupt: InterruptBehavior,
+}
+
+/// Where a sequence is at some point in time
+struct Position<'a, S> {
+    index: usize,
+    from: f32,
+    to: f32,
+    elapsed_ms: u64,
+    duration_ms: u64,
+    /// `None` while holding
+    strategy: Option<&'a S>,
+}
+
+impl<S> Default for Sequence<S> {
+    fn default() -> Self {
+        Self {
+            steps: heapless::Vec::new(),
+            lock: TransitionLock::None,
+            interrupt: InterruptBehavior::Replace,
+        }
+    }
+}
+
+impl<S: ExecutionStrategy> Sequence<S> {
+    /// Creates an empty sequence with no lock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a hard-locked sequence that rejects interrupts.
+    pub fn locked() -> Self {
+        Self {
+            lock: TransitionLock::Hard,
+            interrupt: InterruptBehavior::Reject,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a source-locked sequence.
+    pub fn source_locked() -> Self {
+        Self {
+            lock: TransitionLock::Source,
+            ..Self::default()
+        }
+    }
+
+    /// Move to `target` (ignored once [`MAX_SEQUENCE_STEPS`] are set)
+    pub fn then(self, target: Speed, strategy: impl Into<S>) -> Self {
+        self.with_step(SequenceStep::Move {
+            target: Some(target),
+            strategy: strategy.into(),
+        })
+    }
+
+    /// Stay at the current speed (ignored once [`MAX_SEQUENCE_STEPS`] are set)
+    pub fn hold(self, duration_ms: u64) -> Self {
+        self.with_step(SequenceStep::Hold { duration_ms })
+    }
+
+    /// Move to the commanded target (ignored once [`MAX_SEQUENCE_STEPS`] are set)
+    pub fn finish(self, strategy: impl Into<S>) -> Self {
+        self.with_step(SequenceStep::Move {
+            target: None,
+            strategy: strategy.into(),
+        })
+    }
+
+    fn with_step(mut self, step: SequenceStep<S>) -> Self {
+        let _ = self.steps.push(step);
+        self
+    }
+
+    /// Step in progress at `elapsed_ms`, or `None` once all steps are done
+    fn position(&self, from: f32, to: f32, elapsed_ms: u64) -> Option<Position<'_, S>> {
+        let limit = if from > to { from } else { to };
+        let mut cursor = from;
+        let mut started_ms = 0;
+        for (index, step) in self.steps.iter().enumerate() {
+            let (target, duration_ms, strategy) = match step {
+                SequenceStep::Move { target, strategy } => {
+                    let target = target.map_or(to, |t| t.get().min(limit));
+                    (
+                        target,
+                        completion_ms(strategy, cursor, target),
+                        Some(strategy),
+                    )
+                }
+                SequenceStep::Hold { duration_ms } => (cursor, *duration_ms, None),
+            };
+            if elapsed_ms < started_ms + duration_ms {
+                return Some(Position {
+                    index,
+                    from: cursor,
+                    to: target,
+                    elapsed_ms: elapsed_ms - started_ms,
+                    duration_ms,
+                    strategy,
+                });
+            }
+            cursor = target;
+            started_ms += duration_ms;
+        }
+        None
+    }
+
+    /// Total duration of the sequence for a move from `from` to `to`
+    fn total_ms(&self, from: f32, to: f32) -> u64 {
+        let limit = if from > to { from } else { to };
+        let mut cursor = from;
+        let mut total = 0;
+        for step in &self.steps {
+            total += match step {
+                SequenceStep::Move { target, strategy } => {
+                    let target = target.map_or(to, |t| t.get().min(limit));
+                    let ms = completion_ms(strategy, cursor, target);
+                    cursor = target;
+                    ms
+                }
+                SequenceStep::Hold { duration_ms } => *duration_ms,
+            };
+        }
+        total
+    }
+}
+
+impl<S: ExecutionStrategy> ExecutionStrategy for Sequence<S> {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        match self.position(from, to, elapsed_ms) {
+            Some(Position {
+                strategy: Some(strategy),
+                from,
+                to,
+                elapsed_ms,
+                ..
+            }) => (strategy.interpolate(from, to, elapsed_ms).0, false),
+            Some(hold) => (hold.from, false),
+            None => (to, true),
+        }
+    }
+
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        match self.position(from.to_f32(), to.to_f32(), elapsed_ms) {
+            Some(Position {
+                strategy: Some(strategy),
+                from,
+                to,
+                elapsed_ms,
+                ..
+            }) => {
+                let from = Q16::from_f32(from);
+                let to = Q16::from_f32(to);
+                (strategy.interpolate_q16(from, to, elapsed_ms).0, false)
+            }
+            Some(hold) => (Q16::from_f32(hold.from), false),
+            None => (to, true),
+        }
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        self.steps.iter().try_fold(0, |total, step| match step {
+            SequenceStep::Move { strategy, .. } => Some(total + strategy.duration_ms()?),
+            SequenceStep::Hold { duration_ms } => Some(total + duration_ms),
+        })
+    }
+
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        Some(self.total_ms(from, to))
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        match self.position(from, to, elapsed_ms) {
+            Some(Position {
+                strategy: Some(strategy),
+                from,
+                to,
+                elapsed_ms,
+                ..
+            }) => strategy.rate(from, to, elapsed_ms),
+            _ => 0.0,
+        }
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        match self.position(from.to_f32(), to.to_f32(), elapsed_ms) {
+            Some(Position {
+                strategy: Some(strategy),
+                from,
+                to,
+                elapsed_ms,
+                ..
+            }) => strategy.rate_q16(Q16::from_f32(from), Q16::from_f32(to), elapsed_ms),
+            _ => Q16::ZERO,
+        }
+    }
+
+    fn segment(&self, from: f32, to: f32, elapsed_ms: u64) -> Option<SegmentProgress> {
+        self.position(from, to, elapsed_ms)
+            .map(|position| SegmentProgress {
+                index: position.index,
+                count: self.steps.len(),
+                target: position.to,
+                elapsed_ms: position.elapsed_ms,
+                duration_ms: position.duration_ms,
+            })
+    }
+
+    fn lock(&self) -> TransitionLock {
+        self.lock
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        self.interrupt
+    }
+}
+
+// ============================================================================
+// Delay and Hold
+// ============================================================================
+
+/// Wait at the current speed before running a strategy.
+///
+/// The wrapped strategy's lock applies from the start, including the wait.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::sequence::Delay;
+/// use rs_trainz::traits::Linear;
+/// use rs_trainz::ExecutionStrategy;
+///
+/// let delayed = Delay::new(1000, Linear::new(2000));
+/// assert_eq!(delayed.interpolate(0.2, 0.8, 500), (0.2, false));
+/// assert_eq!(delayed.duration_ms(), Some(3000));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delay<S = AnyStrategy> {
+    /// Wait before starting (milliseconds).
+    pub delay_ms: u64,
+    /// Strategy run after the wait.
+    pub strategy: S,
+}
+
+impl<S: ExecutionStrategy> Delay<S> {
+    /// Run `strategy` after waiting `delay_ms`.
+    pub fn new(delay_ms: u64, strategy: S) -> Self {
+        Self { delay_ms, strategy }
+    }
+}
+
+impl<S: ExecutionStrategy> ExecutionStrategy for Delay<S> {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        match elapsed_ms.checked_sub(self.delay_ms) {
+            Some(elapsed) => self.strategy.interpolate(from, to, elapsed),
+            None => (from, false),
+        }
+    }
+
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        match elapsed_ms.checked_sub(self.delay_ms) {
+            Some(elapsed) => self.strategy.interpolate_q16(from, to, elapsed),
+            None => (from, false),
+        }
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        Some(self.delay_ms + self.strategy.duration_ms()?)
+    }
+
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        Some(self.delay_ms + completion_ms(&self.strategy, from, to))
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        elapsed_ms
+            .checked_sub(self.delay_ms)
+            .map_or(0.0, |elapsed| self.strategy.rate(from, to, elapsed))
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        elapsed_ms
+            .checked_sub(self.delay_ms)
+            .map_or(Q16::ZERO, |elapsed| {
+                self.strategy.rate_q16(from, to, elapsed)
+            })
+    }
+
+    fn lock(&self) -> TransitionLock {
+        self.strategy.lock()
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        self.strategy.on_interrupt()
+    }
+}
+
+/// Stay at the target for a while after a strategy finishes.
+///
+/// The transition (and the wrapped strategy's lock) stays active until the
+/// hold is over.
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::sequence::Hold;
+/// use rs_trainz::traits::EaseInOut;
+/// use rs_trainz::ExecutionStrategy;
+///
+/// let dwell = Hold::new(EaseInOut::arrival(2000), 5000);
+/// assert_eq!(dwell.interpolate(0.5, 0.0, 3000), (0.0, false));
+/// assert_eq!(dwell.interpolate(0.5, 0.0, 7000), (0.0, true));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hold<S = AnyStrategy> {
+    /// Strategy run first.
+    pub strategy: S,
+    /// How long to stay at the target afterwards (milliseconds).
+    pub hold_ms: u64,
+}
+
+impl<S: ExecutionStrategy> Hold<S> {
+    /// Run `strategy`, then stay at the target for `hold_ms`.
+    pub fn new(strategy: S, hold_ms: u64) -> Self {
+        Self { strategy, hold_ms }
+    }
+}
+
+impl<S: ExecutionStrategy> ExecutionStrategy for Hold<S> {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        let end = completion_ms(&self.strategy, from, to);
+        if elapsed_ms < end {
+            (self.strategy.interpolate(from, to, elapsed_ms).0, false)
+        } else {
+            (to, elapsed_ms >= end + self.hold_ms)
+        }
+    }
+
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        let end = completion_ms(&self.strategy, from.to_f32(), to.to_f32());
+        if elapsed_ms < end {
+            (self.strategy.interpolate_q16(from, to, elapsed_ms).0, false)
+        } else {
+            (to, elapsed_ms >= end + self.hold_ms)
+        }
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        Some(self.strategy.duration_ms()? + self.hold_ms)
+    }
+
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        Some(completion_ms(&self.strategy, from, to) + self.hold_ms)
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        self.strategy.rate(from, to, elapsed_ms)
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        self.strategy.rate_q16(from, to, elapsed_ms)
+    }
+
+    fn lock(&self) -> TransitionLock {
+        self.strategy.lock()
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        self.strategy.on_interrupt()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{EaseInOut, Immediate, Linear};
+
+    /// Finishes after a fixed time but doesn't report a duration
+    #[derive(Clone)]
+    struct Unreported(u64);
+
+    impl ExecutionStrategy for Unreported {
+        fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+            Linear::new(self.0).interpolate(from, to, elapsed_ms)
+        }
+
+        fn duration_ms(&self) -> Option<u64> {
+            None
+        }
+    }
+
+    fn ramp_hold_ramp() -> Sequence {
+        Sequence::locked()
+            .then(Speed::literal(0.3), Linear::new(2000))
+            .hold(5000)
+            .finish(Linear::new(4000))
+    }
+
+    // === Sequence ===
+    #[test]
+    fn sequence_runs_steps_in_order() {
+        let s = ramp_hold_ramp();
+        let at = |ms| s.interpolate(0.0, 0.7, ms);
+
+        assert!((at(1000).0 - 0.15).abs() < 0.001);
+        assert!((at(2000).0 - 0.3).abs() < 0.001);
+        assert!((at(6000).0 - 0.3).abs() < 0.001);
+        assert!((at(9000).0 - 0.5).abs() < 0.001);
+        assert!(!at(10_999).1);
+        assert_eq!(at(11_000), (0.7, true));
+    }
+
+    #[test]
+    fn sequence_total_duration() {
+        let s = ramp_hold_ramp();
+        assert_eq!(s.duration_ms(), Some(11_000));
+        assert_eq!(s.duration_for(0.0, 0.7), Some(11_000));
+    }
+
+    #[test]
+    fn sequence_reports_segments() {
+        let s = ramp_hold_ramp();
+
+        let first = s.segment(0.0, 0.7, 500).unwrap();
+        assert_eq!((first.index, first.count), (0, 3));
+        assert!((first.target - 0.3).abs() < 0.001);
+        assert_eq!((first.elapsed_ms, first.duration_ms), (500, 2000));
+
+        let hold = s.segment(0.0, 0.7, 3000).unwrap();
+        assert_eq!(hold.index, 1);
+        assert_eq!((hold.elapsed_ms, hold.duration_ms), (1000, 5000));
+
+        let last = s.segment(0.0, 0.7, 8000).unwrap();
+        assert_eq!(last.index, 2);
+        assert!((last.target - 0.7).abs() < 0.001);
+
+        assert!(s.segment(0.0, 0.7, 11_000).is_none());
+    }
+
+    #[test]
+    fn sequence_rate_follows_active_step() {
+        let s = ramp_hold_ramp();
+        assert!((s.rate(0.0, 0.7, 1000) - 0.15).abs() < 0.001);
+        assert_eq!(s.rate(0.0, 0.7, 3000), 0.0);
+        assert!((s.rate(0.0, 0.7, 9000) - 0.1).abs() < 0.001);
+    }
+
+    #[test]
+    fn sequence_limits_waypoints_to_move_range() {
+        // Waypoint above both ends is capped so max speed still applies
+        let s: Sequence = Sequence::new()
+            .then(Speed::literal(0.9), Linear::new(1000))
+            .finish(Linear::new(1000));
+        let (value, _) = s.interpolate(0.2, 0.5, 1000);
+        assert!((value - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn sequence_always_ends_at_target() {
+        let s: Sequence = Sequence::new().then(Speed::literal(0.3), Linear::new(1000));
+        assert_eq!(s.interpolate(0.0, 0.8, 1000), (0.8, true));
+    }
+
+    #[test]
+    fn sequence_skips_instant_steps() {
+        let s: Sequence = Sequence::new()
+            .then(Speed::literal(0.5), Immediate)
+            .hold(1000)
+            .finish(Immediate);
+        assert_eq!(s.interpolate(0.0, 1.0, 0), (0.5, false));
+        assert_eq!(s.segment(0.0, 1.0, 0).unwrap().index, 1);
+        assert_eq!(s.interpolate(0.0, 1.0, 1000), (1.0, true));
+    }
+
+    #[test]
+    fn sequence_finds_unreported_durations() {
+        let s: Sequence<Unreported> = Sequence::new().finish(Unreported(1500)).hold(500);
+        assert_eq!(s.duration_ms(), None);
+        assert_eq!(s.duration_for(0.0, 1.0), Some(2000));
+        assert_eq!(s.interpolate(0.0, 1.0, 1700), (1.0, false));
+    }
+
+    #[test]
+    fn sequence_ignores_steps_beyond_capacity() {
+        let mut s: Sequence = Sequence::new();
+        for _ in 0..MAX_SEQUENCE_STEPS + 2 {
+            s = s.hold(100);
+        }
+        assert_eq!(s.steps.len(), MAX_SEQUENCE_STEPS);
+    }
+
+    #[test]
+    fn sequence_lock_overrides_steps() {
+        let s: Sequence = Sequence::new().finish(Linear::locked(1000));
+        assert_eq!(s.lock(), TransitionLock::None);
+
+        let s: Sequence = Sequence::locked().finish(Linear::new(1000));
+        assert_eq!(s.lock(), TransitionLock::Hard);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Reject);
+
+        let s: Sequence = Sequence::source_locked().finish(Linear::new(1000));
+        assert_eq!(s.lock(), TransitionLock::Source);
+    }
+
+    #[test]
+    fn sequence_q16_matches_f32() {
+        let s = ramp_hold_ramp();
+        for ms in [0, 1000, 2500, 8000, 11_000] {
+            let (value, done) = s.interpolate(0.0, 0.7, ms);
+            let (fixed, fixed_done) = s.interpolate_q16(Q16::ZERO, Q16::from_f32(0.7), ms);
+            assert!((fixed.to_f32() - value).abs() < 0.001);
+            assert_eq!(fixed_done, done);
+        }
+    }
+
+    // === Delay ===
+    #[test]
+    fn delay_waits_before_starting() {
+        let d = Delay::new(1000, Linear::new(2000));
+        assert_eq!(d.interpolate(0.2, 0.8, 999), (0.2, false));
+        assert!((d.interpolate(0.2, 0.8, 2000).0 - 0.5).abs() < 0.001);
+        assert_eq!(d.interpolate(0.2, 0.8, 3000), (0.8, true));
+        assert_eq!(d.rate(0.2, 0.8, 500), 0.0);
+        assert_eq!(d.duration_for(0.2, 0.8), Some(3000));
+    }
+
+    #[test]
+    fn delay_uses_inner_lock() {
+        let d = Delay::new(1000, EaseInOut::departure(1000));
+        assert_eq!(d.lock(), TransitionLock::Hard);
+        assert_eq!(d.on_interrupt(), InterruptBehavior::Reject);
+    }
+
+    // === Hold ===
+    #[test]
+    fn hold_stays_at_target() {
+        let h = Hold::new(Linear::new(1000), 2000);
+        assert!((h.interpolate(0.0, 1.0, 500).0 - 0.5).abs() < 0.001);
+        assert_eq!(h.interpolate(0.0, 1.0, 1000), (1.0, false));
+        assert_eq!(h.interpolate(0.0, 1.0, 2999), (1.0, false));
+        assert_eq!(h.interpolate(0.0, 1.0, 3000), (1.0, true));
+        assert_eq!(h.duration_ms(), Some(3000));
+    }
+
+    #[test]
+    fn hold_around_unreported_duration() {
+        let h = Hold::new(Unreported(800), 200);
+        assert_eq!(h.duration_for(0.0, 1.0), Some(1000));
+        assert_eq!(h.interpolate(0.0, 1.0, 900), (1.0, false));
+    }
+}
@@ -0,0 +1,374 @@
+//! Command acknowledgements.
+//!
+//! Every command applied through
+//! [`SharedThrottleState::submit`](super::SharedThrottleState::submit) gets a
+//! command id, and each step of its lifecycle is recorded as a
+//! [`CommandAck`]:
+//!
+//! ```text
+//! accepted ─┬─> completed                  (applied at once)
+//!           ├─> started ─┬─> completed     (speed transition ran to the end)
+//!           │            └─> interrupted   (superseded, cancelled or e-stopped)
+//!           ├─> queued ─┬─> started ─> ... (waited behind another transition,
+//!           │           └─> interrupted     or for a burst of speed commands)
+//!           └─> rejected                   (with a RejectReason)
+//! ```
+//!
+//! Acks carry the client's [`CorrelationId`] so a client can match them to
+//! the commands it sent. They are published on `{prefix}/ack` over MQTT and
+//! the latest one per command is served by `GET /api/commands/{id}`.
+//!
+//! Speed transitions finish long after the command that started them, so
+//! [`CommandTracker`] maps transition ids back to command ids and follows
+//! the controller's [`TransitionEvent`]s.
+
+use std::collections::VecDeque;
+
+use crate::{
+    ClientId, CommandOutcome, CommandSource, CorrelationId, RejectReason, TransitionEvent,
+    TransitionResult,
+};
+
+/// Commands remembered for `GET /api/commands/{id}`, and acks kept for
+/// late readers of [`CommandTracker::acks_since`]
+pub const ACK_HISTORY: usize = 64;
+
+/// Where a command is in its lifecycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// Received and about to be applied
+    Accepted,
+    /// Waiting behind the active speed transition, or held back while a
+    /// burst of speed commands settles
+    Queued,
+    /// Its speed transition is running
+    Started,
+    /// Applied, or its speed transition finished
+    Completed,
+    /// Its speed transition was superseded, cancelled or e-stopped
+    Interrupted,
+    /// Refused without effect
+    Rejected(RejectReason),
+}
+
+impl CommandStatus {
+    /// Returns the status as a snake_case string, as used in JSON.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accepted => "accepted",
+            Self::Queued => "queued",
+            Self::Started => "started",
+            Self::Completed => "completed",
+            Self::Interrupted => "interrupted",
+            Self::Rejected(_) => "rejected",
+        }
+    }
+
+    /// Whether the command is done and will get no more acks
+    pub const fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Self::Completed | Self::Interrupted | Self::Rejected(_)
+        )
+    }
+}
+
+/// One lifecycle update for a command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandAck {
+    /// Position in the ack log, increasing by one per ack
+    pub seq: u64,
+    /// Command id returned when the command was submitted
+    pub id: u32,
+    /// Tag the client sent with the command
+    pub correlation_id: Option<CorrelationId>,
+    /// The command's name (see [`ThrottleCommandDyn::name`](crate::ThrottleCommandDyn::name))
+    pub command: &'static str,
+    /// Source category that sent it
+    pub source: CommandSource,
+    /// Client within the source that sent it, if known
+    pub client: Option<ClientId>,
+    /// Where the command is now
+    pub status: CommandStatus,
+    /// When the command reached this status (milliseconds since start)
+    pub timestamp_ms: u64,
+}
+
+/// A submitted command's id and what happened when it was applied.
+#[derive(Clone, Debug)]
+pub struct CommandReceipt {
+    /// Command id to look up with `GET /api/commands/{id}`, `None` if the
+    /// state provider doesn't track commands
+    pub id: Option<u32>,
+    /// Tag the client sent with the command
+    pub correlation_id: Option<CorrelationId>,
+    /// Outcome of applying the command
+    pub outcome: CommandOutcome,
+}
+
+/// Assigns command ids and records their acknowledgements.
+///
+/// Keeps the latest ack of the last [`ACK_HISTORY`] commands, and the last
+/// [`ACK_HISTORY`] acks in order for readers that poll with
+/// [`acks_since`](Self::acks_since).
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::services::{CommandStatus, CommandTracker};
+/// use rs_trainz::{CommandOutcome, CommandSource};
+///
+/// let mut tracker = CommandTracker::new();
+/// let id = tracker.submit("pause", CommandSource::WebApi, None, None, 100);
+/// tracker.resolve(id, &CommandOutcome::Applied, None, 100);
+///
+/// assert_eq!(tracker.get(id).unwrap().status, CommandStatus::Completed);
+/// let statuses: Vec<_> = tracker.acks_since(0).map(|ack| ack.status.as_str()).collect();
+/// assert_eq!(statuses, ["accepted", "completed"]);
+/// ```
+#[derive(Debug)]
+pub struct CommandTracker {
+    next_id: u32,
+    next_seq: u64,
+    /// Latest ack per command, oldest command first
+    commands: VecDeque<CommandAck>,
+    /// Every ack, oldest first
+    log: VecDeque<CommandAck>,
+    /// (transition id, command id) for commands with a running or queued transition
+    transitions: Vec<(u32, u32)>,
+}
+
+impl CommandTracker {
+    /// Create a tracker with no commands
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            next_seq: 1,
+            commands: VecDeque::with_capacity(ACK_HISTORY),
+            log: VecDeque::with_capacity(ACK_HISTORY),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Record a command as accepted and return its id
+    pub fn submit(
+        &mut self,
+        command: &'static str,
+        source: CommandSource,
+        client: Option<ClientId>,
+        correlation_id: Option<CorrelationId>,
+        now_ms: u64,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+
+        if self.commands.len() == ACK_HISTORY {
+            if let Some(evicted) = self.commands.pop_front() {
+                self.transitions
+                    .retain(|&(_, command)| command != evicted.id);
+            }
+        }
+        self.commands.push_back(CommandAck {
+            seq: 0,
+            id,
+            correlation_id,
+            command,
+            source,
+            client,
+            status: CommandStatus::Accepted,
+            timestamp_ms: now_ms,
+        });
+        self.record(id, CommandStatus::Accepted, now_ms);
+        id
+    }
+
+    /// Record what happened when command `id` was applied
+    ///
+    /// `transition` is the controller's active transition id after applying,
+    /// which belongs to this command when the outcome says its transition
+    /// started. A transition that started and is already gone (an e-stop)
+    /// counts as completed.
+    pub fn resolve(
+        &mut self,
+        id: u32,
+        outcome: &CommandOutcome,
+        transition: Option<u32>,
+        now_ms: u64,
+    ) {
+        let status = match outcome {
+            CommandOutcome::Applied => CommandStatus::Completed,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason }) => {
+                CommandStatus::Rejected(reason.clone())
+            }
+            CommandOutcome::SpeedTransition(TransitionResult::Queued { id: queued }) => {
+                self.transitions.push((*queued, id));
+                CommandStatus::Queued
+            }
+            CommandOutcome::SpeedTransition(TransitionResult::Deferred) => CommandStatus::Queued,
+            CommandOutcome::SpeedTransition(_) => match transition {
+                Some(transition) => {
+                    self.transitions.push((transition, id));
+                    CommandStatus::Started
+                }
+                None => CommandStatus::Completed,
+            },
+        };
+        self.record(id, status, now_ms);
+    }
+
+    /// Mark command `id` interrupted: a newer command replaced it before
+    /// it was applied
+    pub fn supersede(&mut self, id: u32, now_ms: u64) {
+        self.record(id, CommandStatus::Interrupted, now_ms);
+    }
+
+    /// Follow a speed transition event to the command that started it
+    pub fn on_transition(&mut self, event: TransitionEvent, now_ms: u64) {
+        let Some(index) = self
+            .transitions
+            .iter()
+            .position(|&(transition, _)| transition == event.id())
+        else {
+            return;
+        };
+        let id = self.transitions[index].1;
+        let status = match event {
+            TransitionEvent::Started { .. } => CommandStatus::Started,
+            TransitionEvent::Completed { .. } => CommandStatus::Completed,
+            TransitionEvent::Interrupted { .. } => CommandStatus::Interrupted,
+        };
+        if status.is_final() {
+            self.transitions.swap_remove(index);
+        }
+        self.record(id, status, now_ms);
+    }
+
+    /// Latest ack of command `id`, if it is still remembered
+    pub fn get(&self, id: u32) -> Option<&CommandAck> {
+        self.commands.iter().find(|ack| ack.id == id)
+    }
+
+    /// Acks after `seq`, oldest first
+    ///
+    /// Pass the `seq` of the last ack read, or 0 for everything still kept.
+    pub fn acks_since(&self, seq: u64) -> impl Iterator<Item = &CommandAck> {
+        self.log.iter().filter(move |ack| ack.seq > seq)
+    }
+
+    /// `seq` of the newest ack, 0 if there are none
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    /// Move command `id` to `status`, logging an ack if it changed
+    fn record(&mut self, id: u32, status: CommandStatus, now_ms: u64) {
+        let Some(latest) = self.commands.iter_mut().find(|ack| ack.id == id) else {
+            return;
+        };
+        if latest.seq != 0 && latest.status == status {
+            return;
+        }
+        latest.seq = self.next_seq;
+        latest.status = status;
+        latest.timestamp_ms = now_ms;
+        self.next_seq += 1;
+
+        if self.log.len() == ACK_HISTORY {
+            self.log.pop_front();
+        }
+        self.log.push_back(latest.clone());
+    }
+}
+
+impl Default for CommandTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Speed;
+
+    fn statuses(tracker: &CommandTracker, id: u32) -> Vec<&'static str> {
+        tracker
+            .acks_since(0)
+            .filter(|ack| ack.id == id)
+            .map(|ack| ack.status.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn transition_lifecycle() {
+        let mut tracker = CommandTracker::new();
+        let tag = CorrelationId::new("req-1").unwrap();
+        let id = tracker.submit("set_speed", CommandSource::Mqtt, None, Some(tag), 0);
+        let started = CommandOutcome::SpeedTransition(TransitionResult::Started);
+        tracker.resolve(id, &started, Some(7), 0);
+
+        // Events for other transitions are ignored
+        tracker.on_transition(TransitionEvent::Completed { id: 3 }, 10);
+        tracker.on_transition(TransitionEvent::Started { id: 7 }, 10);
+        tracker.on_transition(TransitionEvent::Completed { id: 7 }, 500);
+
+        assert_eq!(statuses(&tracker, id), ["accepted", "started", "completed"]);
+        let ack = tracker.get(id).unwrap();
+        assert_eq!(ack.correlation_id, Some(tag));
+        assert_eq!(ack.timestamp_ms, 500);
+        assert_eq!(ack.seq, tracker.last_seq());
+    }
+
+    #[test]
+    fn queued_then_interrupted() {
+        let mut tracker = CommandTracker::new();
+        let id = tracker.submit("set_speed", CommandSource::WebApi, None, None, 0);
+        let queued = CommandOutcome::SpeedTransition(TransitionResult::Queued { id: 2 });
+        tracker.resolve(id, &queued, Some(1), 0);
+        tracker.on_transition(TransitionEvent::Started { id: 2 }, 100);
+        tracker.on_transition(TransitionEvent::Interrupted { id: 2 }, 200);
+        tracker.on_transition(TransitionEvent::Completed { id: 2 }, 300);
+
+        assert_eq!(
+            statuses(&tracker, id),
+            ["accepted", "queued", "started", "interrupted"]
+        );
+    }
+
+    #[test]
+    fn rejected_and_estop() {
+        let mut tracker = CommandTracker::new();
+        let rejected = tracker.submit("set_speed", CommandSource::WebApi, None, None, 0);
+        let reason = RejectReason::LowerPriority;
+        let outcome = CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: reason.clone(),
+        });
+        tracker.resolve(rejected, &outcome, Some(1), 0);
+        assert_eq!(
+            tracker.get(rejected).unwrap().status,
+            CommandStatus::Rejected(reason)
+        );
+
+        let estop = tracker.submit("emergency_stop", CommandSource::Mqtt, None, None, 0);
+        let outcome = CommandOutcome::SpeedTransition(TransitionResult::Interrupted {
+            previous_target: Speed::new(0.5).unwrap(),
+        });
+        tracker.resolve(estop, &outcome, None, 0);
+        assert_eq!(statuses(&tracker, estop), ["accepted", "completed"]);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut tracker = CommandTracker::new();
+        let first = tracker.submit("pause", CommandSource::WebApi, None, None, 0);
+        for _ in 0..ACK_HISTORY {
+            let id = tracker.submit("pause", CommandSource::WebApi, None, None, 0);
+            tracker.resolve(id, &CommandOutcome::Applied, None, 0);
+        }
+
+        assert!(tracker.get(first).is_none());
+        assert_eq!(tracker.acks_since(0).count(), ACK_HISTORY);
+        let last = tracker.last_seq();
+        assert_eq!(tracker.acks_since(last - 2).count(), 2);
+    }
+}
@@ -2,7 +2,11 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::{CommandSource, Direction, FaultKind, ThrottleState};
+use crate::{
+    CabStatus, ClientId, CommandMetrics, CommandSource, Direction, FaultKind, LeaseStatus,
+    LockRelease, QueuedCommand, SegmentProgress, ServiceBrakeStatus, Speed, ThrottleState,
+    TransitionLock,
+};
 
 // Re-export shared request types from messages module
 pub use crate::messages::{SetDirectionRequest, SetMaxSpeedRequest, SetSpeedRequest};
@@ -54,6 +58,8 @@ pub struct StateResponse {
     pub target_speed: Option<f32>,
     /// Current direction
     pub direction: Direction,
+    /// Signed velocity (-1.0 to 1.0, negative is reverse)
+    pub velocity: f32,
     /// Maximum allowed speed
     pub max_speed: f32,
     /// Active fault condition
@@ -67,17 +73,44 @@ pub struct StateResponse {
     /// Transition progress information
     #[serde(skip_serializing_if = "Option::is_none")]
     pub progress: Option<ProgressResponse>,
+    /// Cab controls (cab driving mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cab: Option<CabStatus>,
+    /// Active service brake application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_brake: Option<ServiceBrakeStatus>,
+    /// Client holding the throttle lease
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease: Option<LeaseStatus>,
+    /// Speed commands waiting behind the active transition, next first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queue: Vec<QueuedCommand>,
+    /// Counts of commands dropped by rate limits or full queues
+    #[serde(default)]
+    pub metrics: CommandMetrics,
 }
 
 /// Lock status response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockStatusResponse {
+    /// Lock level in effect
+    #[serde(default)]
+    pub lock: TransitionLock,
     /// Lock source
     pub source: CommandSource,
+    /// Client within the source that owns the lock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientId>,
     /// Target speed of locked transition
     pub target: f32,
     /// Whether there's a queued command
     pub has_queued: bool,
+    /// Time left before the lock degrades (if limited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_ms: Option<u64>,
+    /// Why the lock was released early
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub released: Option<LockRelease>,
 }
 
 /// Transition progress response
@@ -97,33 +130,51 @@ pub struct ProgressResponse {
     /// Progress percentage (0.0 to 1.0)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub percent: Option<f32>,
+    /// Current segment of a multi-segment transition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment: Option<SegmentProgress>,
+    /// Whether the transition is paused
+    #[serde(default)]
+    pub paused: bool,
 }
 
 impl From<&ThrottleState> for StateResponse {
     fn from(state: &ThrottleState) -> Self {
         Self {
-            speed: state.speed,
-            target_speed: state.target_speed,
+            speed: state.speed.get(),
+            target_speed: state.target_speed.map(Speed::get),
             direction: state.direction,
-            max_speed: state.max_speed,
+            velocity: state.velocity.get(),
+            max_speed: state.max_speed.get(),
             fault: state.fault,
             transitioning: state.target_speed.is_some(),
             lock_status: state.lock_status.as_ref().map(|l| LockStatusResponse {
+                lock: l.lock,
                 source: l.source,
-                target: l.target,
+                client: l.client,
+                target: l.target.get(),
                 has_queued: l.has_queued,
+                remaining_ms: l.remaining_ms,
+                released: l.released,
             }),
             progress: state
                 .transition_progress
                 .as_ref()
                 .map(|p| ProgressResponse {
-                    from: p.from,
-                    to: p.to,
-                    current: p.current,
+                    from: p.from.get(),
+                    to: p.to.get(),
+                    current: p.current.get(),
                     elapsed_ms: p.elapsed_ms,
                     total_ms: p.estimated_total_ms,
                     percent: p.percent(),
+                    segment: p.segment,
+                    paused: p.paused,
                 }),
+            cab: state.cab,
+            service_brake: state.service_brake,
+            lease: state.lease,
+            queue: state.queue.to_vec(),
+            metrics: state.metrics,
         }
     }
 }
@@ -142,6 +193,7 @@ mod tests {
     use super::*;
     use crate::traits::TransitionLock;
     use crate::transition::{LockStatus, TransitionProgress};
+    use crate::Velocity;
 
     // ========================================================================
     // Request Types Tests
@@ -151,9 +203,10 @@ mod tests {
     fn test_set_speed_request_serde() {
         // Test full serialization/deserialization
         let req = SetSpeedRequest {
-            speed: 0.75,
+            speed: Speed::literal(0.75),
             duration_ms: 2000,
             smooth: true,
+            preset: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         let deserialized: SetSpeedRequest = serde_json::from_str(&json).unwrap();
@@ -191,7 +244,9 @@ mod tests {
 
     #[test]
     fn test_set_max_speed_request_serde() {
-        let req = SetMaxSpeedRequest { max_speed: 0.8 };
+        let req = SetMaxSpeedRequest {
+            max_speed: Speed::literal(0.8),
+        };
         let json = serde_json::to_string(&req).unwrap();
         let deserialized: SetMaxSpeedRequest = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.max_speed, 0.8);
@@ -326,13 +381,21 @@ mod tests {
     fn test_state_response_from_throttle_state_minimal() {
         // Test minimal state with no transition or fault
         let state = ThrottleState {
-            speed: 0.5,
+            speed: Speed::literal(0.5),
             target_speed: None,
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -350,13 +413,21 @@ mod tests {
     fn test_state_response_from_throttle_state_with_target() {
         // Test state with target speed (transitioning)
         let state = ThrottleState {
-            speed: 0.3,
-            target_speed: Some(0.8),
+            speed: Speed::literal(0.3),
+            target_speed: Some(Speed::literal(0.8)),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.3).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -369,13 +440,21 @@ mod tests {
     fn test_state_response_from_throttle_state_with_fault() {
         // Test state with fault
         let state = ThrottleState {
-            speed: 0.0,
+            speed: Speed::literal(0.0),
             target_speed: None,
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::ZERO,
+            max_speed: Speed::literal(1.0),
             fault: Some(FaultKind::Overcurrent),
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -388,18 +467,29 @@ mod tests {
         let lock = LockStatus {
             lock: TransitionLock::Hard,
             source: CommandSource::Physical,
-            target: 0.7,
+            client: None,
+            target: Speed::literal(0.7),
             has_queued: true,
+            remaining_ms: Some(2500),
+            released: None,
         };
 
         let state = ThrottleState {
-            speed: 0.5,
-            target_speed: Some(0.7),
+            speed: Speed::literal(0.5),
+            target_speed: Some(Speed::literal(0.7)),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: Some(lock),
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -408,27 +498,39 @@ mod tests {
         assert_eq!(lock_resp.source, CommandSource::Physical);
         assert_eq!(lock_resp.target, 0.7);
         assert!(lock_resp.has_queued);
+        assert_eq!(lock_resp.lock, TransitionLock::Hard);
+        assert_eq!(lock_resp.remaining_ms, Some(2500));
     }
 
     #[test]
     fn test_state_response_from_throttle_state_with_progress() {
         // Test state with transition progress
         let progress = TransitionProgress {
-            from: 0.2,
-            to: 0.8,
-            current: 0.5,
+            from: Speed::literal(0.2),
+            to: Speed::literal(0.8),
+            current: Speed::literal(0.5),
             elapsed_ms: 1000,
             estimated_total_ms: Some(2000),
+            segment: None,
+            paused: false,
         };
 
         let state = ThrottleState {
-            speed: 0.5,
-            target_speed: Some(0.8),
+            speed: Speed::literal(0.5),
+            target_speed: Some(Speed::literal(0.8)),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: Some(progress),
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -446,21 +548,31 @@ mod tests {
     fn test_state_response_from_throttle_state_with_progress_no_total() {
         // Test state with transition progress but no estimated total (e.g., Momentum)
         let progress = TransitionProgress {
-            from: 0.0,
-            to: 1.0,
-            current: 0.3,
+            from: Speed::literal(0.0),
+            to: Speed::literal(1.0),
+            current: Speed::literal(0.3),
             elapsed_ms: 500,
             estimated_total_ms: None,
+            segment: None,
+            paused: false,
         };
 
         let state = ThrottleState {
-            speed: 0.3,
-            target_speed: Some(1.0),
+            speed: Speed::literal(0.3),
+            target_speed: Some(Speed::literal(1.0)),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.3).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: Some(progress),
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -476,26 +588,39 @@ mod tests {
         let lock = LockStatus {
             lock: TransitionLock::Source,
             source: CommandSource::WebLocal,
-            target: 0.9,
+            client: None,
+            target: Speed::literal(0.9),
             has_queued: false,
+            remaining_ms: None,
+            released: None,
         };
 
         let progress = TransitionProgress {
-            from: 0.1,
-            to: 0.9,
-            current: 0.6,
+            from: Speed::literal(0.1),
+            to: Speed::literal(0.9),
+            current: Speed::literal(0.6),
             elapsed_ms: 1500,
             estimated_total_ms: Some(3000),
+            segment: None,
+            paused: false,
         };
 
         let state = ThrottleState {
-            speed: 0.6,
-            target_speed: Some(0.9),
+            speed: Speed::literal(0.6),
+            target_speed: Some(Speed::literal(0.9)),
             direction: Direction::Reverse,
-            max_speed: 0.95,
+            velocity: Velocity::new(-0.6).unwrap(),
+            max_speed: Speed::literal(0.95),
             fault: Some(FaultKind::ShortCircuit),
             lock_status: Some(lock),
             transition_progress: Some(progress),
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -523,13 +648,21 @@ mod tests {
     #[test]
     fn test_state_response_serde() {
         let state = ThrottleState {
-            speed: 0.5,
-            target_speed: Some(0.8),
+            speed: Speed::literal(0.5),
+            target_speed: Some(Speed::literal(0.8)),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -546,13 +679,21 @@ mod tests {
     fn test_state_response_skip_serializing_none() {
         // Verify that None fields are omitted from JSON
         let state = ThrottleState {
-            speed: 0.5,
+            speed: Speed::literal(0.5),
             target_speed: None,
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             fault: None,
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         };
 
         let response = StateResponse::from(&state);
@@ -570,17 +711,30 @@ mod tests {
     #[test]
     fn test_lock_status_response_serde() {
         let lock = LockStatusResponse {
+            lock: TransitionLock::None,
             source: CommandSource::Physical,
+            client: None,
             target: 0.75,
             has_queued: true,
+            remaining_ms: None,
+            released: Some(LockRelease::Forced {
+                by: CommandSource::Physical,
+            }),
         };
 
         let json = serde_json::to_string(&lock).unwrap();
+        assert!(json.contains(r#""released":{"forced":{"by":"physical"}}"#));
         let deserialized: LockStatusResponse = serde_json::from_str(&json).unwrap();
 
         assert_eq!(deserialized.source, CommandSource::Physical);
         assert_eq!(deserialized.target, 0.75);
         assert!(deserialized.has_queued);
+        assert_eq!(
+            deserialized.released,
+            Some(LockRelease::Forced {
+                by: CommandSource::Physical
+            })
+        );
     }
 
     // ========================================================================
@@ -596,6 +750,8 @@ mod tests {
             elapsed_ms: 1000,
             total_ms: Some(2000),
             percent: Some(0.5),
+            segment: None,
+            paused: false,
         };
 
         let json = serde_json::to_string(&progress).unwrap();
@@ -618,6 +774,8 @@ mod tests {
             elapsed_ms: 500,
             total_ms: None,
             percent: None,
+            segment: None,
+            paused: false,
         };
 
         let json = serde_json::to_string(&progress).unwrap();
@@ -640,6 +798,8 @@ mod tests {
             elapsed_ms: 500,
             total_ms: None,
             percent: None,
+            segment: None,
+            paused: false,
         };
 
         let json = serde_json::to_string(&progress).unwrap();
@@ -0,0 +1,540 @@
+//! Typed state events for the `/api/events` Server-Sent Events stream.
+//!
+//! [`EventLog`] compares successive [`ThrottleState`] snapshots and records
+//! what changed as numbered [`StateEvent`]s:
+//!
+//! | Event        | Data                                                      |
+//! |--------------|-----------------------------------------------------------|
+//! | `speed`      | `{"speed","target_speed","velocity"}`                     |
+//! | `direction`  | `{"direction"}`                                           |
+//! | `fault`      | `{"fault"}`, `null` once cleared                          |
+//! | `lock`       | `{"lock"}`, the transition lock as in `/api/state`        |
+//! | `lease`      | `{"lease"}`, when the throttle lease changes hands        |
+//! | `lockout`    | `{"lockout"}`, when a source lockout engages or clears    |
+//! | `transition` | `{"active","from","to","current","percent","paused"}`     |
+//!
+//! Event ids are shared by every client, so a client that reconnects with
+//! the id of the last event it saw gets exactly what it missed, as long as
+//! it is still among the last [`EVENT_HISTORY`] events.
+//!
+//! Speed and transition progress change on every tick of a ramp. They are
+//! recorded at most once per [`DEFAULT_EVENT_INTERVAL_MS`], except when the
+//! target, pause state or activity changes, so the start and end of a ramp
+//! are never delayed. [`coalesce`] thins a backlog further for slow clients.
+
+use std::collections::VecDeque;
+
+use super::http_handler::{lease_to_json, lock_to_json, lockout_to_json};
+use crate::ThrottleState;
+
+/// Events kept for clients resuming with `Last-Event-ID`
+pub const EVENT_HISTORY: usize = 256;
+
+/// Shortest time between two speed, or two transition, events
+pub const DEFAULT_EVENT_INTERVAL_MS: u64 = 100;
+
+/// What a [`StateEvent`] reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// Speed or velocity changed
+    Speed,
+    /// Direction changed
+    Direction,
+    /// A fault was raised or cleared
+    Fault,
+    /// A transition lock was taken, released or changed
+    Lock,
+    /// The throttle lease was acquired, released, stolen or lapsed
+    ///
+    /// This is the [`ThrottleLease`](super::ThrottleLease) holder, not a
+    /// physical control's [`SourceLockout`](crate::SourceLockout).
+    Lease,
+    /// A physical control's [`SourceLockout`](crate::SourceLockout) engaged,
+    /// changed hands or cleared
+    Lockout,
+    /// A transition started, progressed, paused or ended
+    Transition,
+}
+
+impl EventKind {
+    /// Every kind, in the order changes are recorded
+    pub const ALL: [EventKind; 7] = [
+        Self::Direction,
+        Self::Speed,
+        Self::Transition,
+        Self::Lock,
+        Self::Lease,
+        Self::Lockout,
+        Self::Fault,
+    ];
+
+    /// Returns the kind as used for the SSE `event` field.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Speed => "speed",
+            Self::Direction => "direction",
+            Self::Fault => "fault",
+            Self::Lock => "lock",
+            Self::Lease => "lease",
+            Self::Lockout => "lockout",
+            Self::Transition => "transition",
+        }
+    }
+
+    /// Whether the kind changes continuously during a ramp
+    pub const fn is_continuous(&self) -> bool {
+        matches!(self, Self::Speed | Self::Transition)
+    }
+}
+
+/// One recorded change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateEvent {
+    /// Increasing id, for `Last-Event-ID`
+    pub id: u64,
+    /// What changed
+    pub kind: EventKind,
+    /// The new value, as a JSON object
+    pub data: String,
+}
+
+/// Last recorded value of one kind
+#[derive(Debug)]
+struct Recorded {
+    /// Changes to this are recorded at once, even for continuous kinds
+    key: String,
+    data: String,
+    at_ms: u64,
+}
+
+/// Numbers state changes and keeps the most recent ones.
+///
+/// # Example
+///
+/// ```
+/// use rs_trainz::hal::MockMotor;
+/// use rs_trainz::services::{EventKind, EventLog};
+/// use rs_trainz::{CommandSource, Direction, ThrottleCommandDyn, ThrottleController};
+///
+/// let mut controller = ThrottleController::new(MockMotor::new());
+/// let mut log = EventLog::new();
+/// log.observe(&controller.state(0), 0);
+///
+/// let cmd = ThrottleCommandDyn::SetDirection(Direction::Forward);
+/// controller.apply_command(cmd, CommandSource::WebApi, 10).unwrap();
+/// log.observe(&controller.state(10), 10);
+///
+/// let events = log.since(0).unwrap();
+/// assert_eq!(events[0].kind, EventKind::Direction);
+/// assert_eq!(events[0].data, r#"{"direction":"forward"}"#);
+/// ```
+#[derive(Debug)]
+pub struct EventLog {
+    next_id: u64,
+    interval_ms: u64,
+    events: VecDeque<StateEvent>,
+    /// Indexed like [`EventKind::ALL`], `None` before the first observation
+    recorded: [Option<Recorded>; 7],
+}
+
+impl EventLog {
+    /// Create a log with no events
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            interval_ms: DEFAULT_EVENT_INTERVAL_MS,
+            events: VecDeque::with_capacity(EVENT_HISTORY),
+            recorded: Default::default(),
+        }
+    }
+
+    /// Record speed and transition progress at most once per `interval_ms`
+    pub fn with_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.interval_ms = interval_ms;
+        self
+    }
+
+    /// Record what changed since the last observed state.
+    ///
+    /// The first state observed is the baseline and records nothing.
+    pub fn observe(&mut self, state: &ThrottleState, now_ms: u64) {
+        for (index, kind) in EventKind::ALL.into_iter().enumerate() {
+            let (key, data) = describe(kind, state);
+            let changed = match &self.recorded[index] {
+                None => false,
+                Some(last) if last.key != key => true,
+                Some(last) => {
+                    kind.is_continuous()
+                        && last.data != data
+                        && now_ms >= last.at_ms + self.interval_ms
+                }
+            };
+            if changed {
+                self.push(kind, data.clone());
+            }
+            if changed || self.recorded[index].is_none() {
+                self.recorded[index] = Some(Recorded {
+                    key,
+                    data,
+                    at_ms: now_ms,
+                });
+            }
+        }
+    }
+
+    /// Events after `id`, oldest first.
+    ///
+    /// Returns `None` if some of them are no longer kept, or `id` is from
+    /// another log, so the reader has to start over from the full state.
+    pub fn since(&self, id: u64) -> Option<Vec<StateEvent>> {
+        let oldest = self.events.front().map_or(self.next_id, |event| event.id);
+        if id > self.last_id() || id + 1 < oldest {
+            return None;
+        }
+        Some(
+            self.events
+                .iter()
+                .filter(|event| event.id > id)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Id of the newest event, 0 if there are none
+    pub fn last_id(&self) -> u64 {
+        self.next_id - 1
+    }
+
+    fn push(&mut self, kind: EventKind, data: String) {
+        if self.events.len() == EVENT_HISTORY {
+            self.events.pop_front();
+        }
+        self.events.push_back(StateEvent {
+            id: self.next_id,
+            kind,
+            data,
+        });
+        self.next_id += 1;
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drop all but the newest speed and transition event.
+///
+/// Other events are kept, in order: each is a step a client may act on.
+pub fn coalesce(events: Vec<StateEvent>) -> Vec<StateEvent> {
+    let mut newest: Vec<(EventKind, u64)> = Vec::new();
+    for event in events.iter().filter(|event| event.kind.is_continuous()) {
+        match newest.iter_mut().find(|(kind, _)| *kind == event.kind) {
+            Some((_, id)) => *id = event.id,
+            None => newest.push((event.kind, event.id)),
+        }
+    }
+    events
+        .into_iter()
+        .filter(|event| !event.kind.is_continuous() || newest.contains(&(event.kind, event.id)))
+        .collect()
+}
+
+/// The change key and event data of one kind in `state`
+fn describe(kind: EventKind, state: &ThrottleState) -> (String, String) {
+    match kind {
+        EventKind::Speed => {
+            let key = format!("{:?}", state.target_speed.map(|speed| speed.get()));
+            let data = format!(
+                r#"{{"speed":{:.2},"target_speed":{:.2},"velocity":{:.2}}}"#,
+                state.speed,
+                state.target_speed.unwrap_or(state.speed),
+                state.velocity
+            );
+            (key, data)
+        }
+        EventKind::Direction => {
+            let data = format!(r#"{{"direction":"{}"}}"#, state.direction.as_str());
+            (data.clone(), data)
+        }
+        EventKind::Fault => {
+            let data = match state.fault {
+                Some(fault) => format!(r#"{{"fault":"{}"}}"#, fault.as_str()),
+                None => String::from(r#"{"fault":null}"#),
+            };
+            (data.clone(), data)
+        }
+        EventKind::Lock => {
+            // The countdown alone isn't a change
+            let mut lock = state.lock_status.clone();
+            let data = format!(r#"{{"lock":{}}}"#, lock_to_json(lock.as_ref()));
+            if let Some(lock) = &mut lock {
+                lock.remaining_ms = None;
+            }
+            (lock_to_json(lock.as_ref()), data)
+        }
+        EventKind::Lease => {
+            // Only the holder matters: each of their commands renews the lease
+            let key = format!(
+                "{:?}",
+                state.lease.map(|lease| (lease.source, lease.client))
+            );
+            let data = format!(r#"{{"lease":{}}}"#, lease_to_json(state.lease.as_ref()));
+            (key, data)
+        }
+        EventKind::Lockout => {
+            // Each command from the owner extends the lockout
+            let key = format!("{:?}", state.lockout.map(|lockout| lockout.source));
+            let data = format!(
+                r#"{{"lockout":{}}}"#,
+                lockout_to_json(state.lockout.as_ref())
+            );
+            (key, data)
+        }
+        EventKind::Transition => match &state.transition_progress {
+            Some(progress) => {
+                let key = format!("{:.2},{}", progress.to, progress.paused);
+                let percent = match progress.percent() {
+                    Some(percent) => format!("{:.2}", percent),
+                    None => String::from("null"),
+                };
+                let data = format!(
+                    r#"{{"active":true,"from":{:.2},"to":{:.2},"current":{:.2},"percent":{},"paused":{}}}"#,
+                    progress.from, progress.to, progress.current, percent, progress.paused
+                );
+                (key, data)
+            }
+            None => (String::new(), String::from(r#"{"active":false}"#)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::MockMotor;
+    use crate::services::SharedThrottleState;
+    use crate::traits::Linear;
+    use crate::{
+        CommandOutcome, CommandSource, Direction, FaultKind, LeaseStatus, RejectReason,
+        SourceLockout, Speed, ThrottleCommand, ThrottleCommandDyn, ThrottleController,
+        TransitionResult,
+    };
+
+    fn kinds(events: &[StateEvent]) -> Vec<&'static str> {
+        events.iter().map(|event| event.kind.as_str()).collect()
+    }
+
+    #[test]
+    fn records_typed_changes() {
+        let mut controller = ThrottleController::new(MockMotor::new());
+        let mut log = EventLog::new();
+        log.observe(&controller.state(0), 0);
+        log.observe(&controller.state(0), 0);
+        assert_eq!(log.last_id(), 0);
+
+        let cmd = ThrottleCommandDyn::SetDirection(Direction::Reverse);
+        controller
+            .apply_command(cmd, CommandSource::WebApi, 200)
+            .unwrap();
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
+        controller
+            .apply_command(cmd.into(), CommandSource::WebApi, 200)
+            .unwrap();
+        controller.update(200).unwrap();
+        log.observe(&controller.state(200), 200);
+
+        controller.handle_fault(FaultKind::Overcurrent).unwrap();
+        log.observe(&controller.state(400), 400);
+
+        let events = log.since(0).unwrap();
+        assert_eq!(kinds(&events), ["direction", "speed", "speed", "fault"]);
+        assert_eq!(events[0].data, r#"{"direction":"reverse"}"#);
+        assert_eq!(
+            events[1].data,
+            r#"{"speed":0.50,"target_speed":0.50,"velocity":-0.50}"#
+        );
+        assert_eq!(events[3].data, r#"{"fault":"overcurrent"}"#);
+        let ids: Vec<u64> = events.iter().map(|event| event.id).collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn rate_limits_a_ramp() {
+        let mut controller = ThrottleController::new(MockMotor::new());
+        let mut log = EventLog::new();
+        log.observe(&controller.state(0), 0);
+
+        let cmd = ThrottleCommand::SetSpeed {
+            target: Speed::literal(0.8),
+            strategy: Linear::locked(1000),
+        };
+        controller
+            .apply_command(cmd.into(), CommandSource::WebApi, 0)
+            .unwrap();
+        // A 50Hz update loop
+        for now_ms in (0..=1100).step_by(20) {
+            controller.update(now_ms).unwrap();
+            log.observe(&controller.state(now_ms), now_ms);
+        }
+
+        let events = log.since(0).unwrap();
+        let speeds: Vec<_> = events
+            .iter()
+            .filter(|e| e.kind == EventKind::Speed)
+            .collect();
+        assert!(speeds.len() <= 13, "{} speed events", speeds.len());
+        assert!(speeds.len() >= 5, "{} speed events", speeds.len());
+        let last = speeds.last().unwrap();
+        assert_eq!(
+            last.data,
+            r#"{"speed":0.80,"target_speed":0.80,"velocity":0.00}"#
+        );
+
+        // The start, lock and end of the ramp aren't held back
+        let transitions: Vec<_> = events
+            .iter()
+            .filter(|e| e.kind == EventKind::Transition)
+            .collect();
+        assert!(transitions[0]
+            .data
+            .starts_with(r#"{"active":true,"from":0.00,"to":0.80"#));
+        assert_eq!(transitions.last().unwrap().data, r#"{"active":false}"#);
+        let locks: Vec<_> = events
+            .iter()
+            .filter(|e| e.kind == EventKind::Lock)
+            .collect();
+        assert_eq!(locks.len(), 2);
+        assert!(locks[0].data.contains(r#""level":"hard""#));
+    }
+
+    #[test]
+    fn records_lease_changes_not_countdowns() {
+        let controller = ThrottleController::new(MockMotor::new());
+        let mut log = EventLog::new();
+        let mut state = controller.state(0);
+        log.observe(&state, 0);
+
+        let mut lease = LeaseStatus {
+            source: CommandSource::WebApi,
+            client: None,
+            expires_ms: 30_000,
+            remaining_ms: 30_000,
+        };
+        state.lease = Some(lease);
+        log.observe(&state, 0);
+        lease.remaining_ms = 29_000;
+        state.lease = Some(lease);
+        log.observe(&state, 1000);
+        state.lease = None;
+        log.observe(&state, 2000);
+
+        let events = log.since(0).unwrap();
+        assert_eq!(kinds(&events), ["lease", "lease"]);
+        assert_eq!(
+            events[0].data,
+            r#"{"lease":{"owner":"web_api","remaining_ms":30000}}"#
+        );
+        assert_eq!(events[1].data, r#"{"lease":null}"#);
+    }
+
+    #[test]
+    fn records_lockout_engaging_and_clearing() {
+        let state = SharedThrottleState::new(ThrottleController::new(MockMotor::new()))
+            .with_lockout(SourceLockout::new(60_000));
+        let (last, _) = state.event_snapshot();
+
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.3));
+        state
+            .apply_command_as(cmd.into(), CommandSource::Physical, None)
+            .unwrap();
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.6));
+        let outcome = state
+            .apply_command_as(cmd.into(), CommandSource::WebApi, None)
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::LowerPriority
+            })
+        ));
+        // Another command from the owner only extends it
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.4));
+        state
+            .apply_command_as(cmd.into(), CommandSource::Physical, None)
+            .unwrap();
+        let events = state.events_since(last).unwrap();
+        let lockouts: Vec<_> = events
+            .iter()
+            .filter(|e| e.kind == EventKind::Lockout)
+            .collect();
+        assert_eq!(lockouts.len(), 1);
+        assert!(lockouts[0]
+            .data
+            .starts_with(r#"{"lockout":{"owner":"physical","remaining_ms":"#));
+
+        // An e-stop clears it
+        let last = events.last().unwrap().id;
+        state
+            .apply_command_as(
+                ThrottleCommandDyn::EmergencyStop,
+                CommandSource::WebApi,
+                None,
+            )
+            .unwrap();
+        let events = state.events_since(last).unwrap();
+        let lockouts: Vec<_> = events
+            .iter()
+            .filter(|e| e.kind == EventKind::Lockout)
+            .collect();
+        assert_eq!(lockouts.len(), 1);
+        assert_eq!(lockouts[0].data, r#"{"lockout":null}"#);
+    }
+
+    #[test]
+    fn since_reports_gaps() {
+        let mut controller = ThrottleController::new(MockMotor::new());
+        let mut log = EventLog::new().with_interval_ms(0);
+        log.observe(&controller.state(0), 0);
+        assert_eq!(log.since(0), Some(Vec::new()));
+
+        for step in 1..=EVENT_HISTORY as u64 + 10 {
+            let target = Speed::literal((step % 100) as f32 / 100.0);
+            let cmd = ThrottleCommand::speed_immediate(target);
+            controller
+                .apply_command(cmd.into(), CommandSource::WebApi, step)
+                .unwrap();
+            controller.update(step).unwrap();
+            log.observe(&controller.state(step), step);
+        }
+
+        let last = log.last_id();
+        assert_eq!(log.since(last), Some(Vec::new()));
+        assert_eq!(log.since(last - 2).unwrap().len(), 2);
+        assert!(log.since(last - EVENT_HISTORY as u64).is_some());
+        assert!(log.since(last - EVENT_HISTORY as u64 - 1).is_none());
+        // An id from before a restart
+        assert!(log.since(last + 1).is_none());
+    }
+
+    #[test]
+    fn coalesce_keeps_newest_continuous_events() {
+        let event = |id, kind| StateEvent {
+            id,
+            kind,
+            data: String::new(),
+        };
+        let events = vec![
+            event(1, EventKind::Speed),
+            event(2, EventKind::Transition),
+            event(3, EventKind::Direction),
+            event(4, EventKind::Speed),
+            event(5, EventKind::Lock),
+            event(6, EventKind::Speed),
+        ];
+
+        let ids: Vec<u64> = coalesce(events).iter().map(|event| event.id).collect();
+        assert_eq!(ids, [2, 3, 5, 6]);
+    }
+}
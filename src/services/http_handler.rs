@@ -26,11 +26,22 @@
 
 use alloc::format;
 use alloc::string::String;
-
-use crate::messages::{parse_direction_request, parse_max_speed_request, parse_speed_request};
-use crate::traits::{EaseInOut, Immediate, Linear};
-use crate::{CommandOutcome, CommandSource, Direction, ThrottleCommand, ThrottleState};
-
+use alloc::vec::Vec;
+
+use crate::messages::{
+    parse_brake_request, parse_cancel_queued_request, parse_command_as, parse_direction_request,
+    parse_driving_mode_request, parse_lease_payload, parse_max_speed_request, parse_notch_request,
+    parse_preview_request, parse_service_brake_request, parse_speed_request,
+    parse_velocity_request, CommandMessage, LeaseAction, MessageError,
+};
+use crate::traits::Immediate;
+use crate::{
+    preview, AnyStrategy, AuditEntry, ClientId, CommandMetrics, CommandOutcome, CommandSource,
+    CorrelationId, LeaseStatus, LockRelease, LockStatus, LockoutStatus, QueuedCommand, SpeedError,
+    ThrottleCommand, ThrottleCommandDyn, ThrottleState, TransitionResult,
+};
+
+use super::ack::{CommandAck, CommandReceipt, CommandStatus};
 use super::shared::StateProvider;
 
 extern crate alloc;
@@ -112,14 +123,55 @@ impl axum::response::IntoResponse for ApiResult {
 ///
 /// Contains the business logic for all REST API endpoints. Platform-specific
 /// HTTP servers (Axum, esp-idf-svc) call these methods and adapt the results.
+///
+/// Commands are applied as [`CommandSource::WebApi`], on behalf of the
+/// client set with [`with_client`](Self::with_client) if any. Responses to
+/// commands include the `command_id` to follow with `GET /api/commands/{id}`
+/// and echo the [`with_correlation_id`](Self::with_correlation_id) tag.
 pub struct HttpApiHandler<S: StateProvider> {
     state: S,
+    client: Option<ClientId>,
+    correlation_id: Option<CorrelationId>,
 }
 
 impl<S: StateProvider> HttpApiHandler<S> {
     /// Create a new handler with the given state provider.
     pub fn new(state: S) -> Self {
-        Self { state }
+        Self {
+            state,
+            client: None,
+            correlation_id: None,
+        }
+    }
+
+    /// Apply commands on behalf of `client` (e.g. from an `X-Client-Id` header).
+    pub fn with_client(mut self, client: Option<ClientId>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Tag commands so their acknowledgements can be matched to them
+    /// (e.g. from an `X-Correlation-Id` header).
+    pub fn with_correlation_id(mut self, correlation_id: Option<CorrelationId>) -> Self {
+        self.correlation_id = correlation_id;
+        self
+    }
+
+    /// Apply a command from this handler's client.
+    fn apply(&self, cmd: ThrottleCommandDyn) -> Result<CommandReceipt, ()> {
+        self.state
+            .submit(cmd, CommandSource::WebApi, self.client, self.correlation_id)
+    }
+
+    /// Apply a command and describe what happened.
+    ///
+    /// `applied` names the result when the command wasn't rejected or
+    /// queued; without it the outcome is described.
+    fn respond(&self, cmd: ThrottleCommandDyn, applied: Option<&str>) -> ApiResult {
+        match self.apply(cmd) {
+            Ok(receipt) => ApiResult::ok(receipt_to_json(&receipt, applied)),
+            Err(_) => ApiResult::error(500, r#"{"error":"controller error"}"#),
+        }
     }
 
     /// GET /api/state - Get current throttle state.
@@ -137,81 +189,285 @@ impl<S: StateProvider> HttpApiHandler<S> {
     /// - Linear transition: `{"speed": 0.5, "duration_ms": 1000}`
     /// - Smooth transition: `{"speed": 0.5, "duration_ms": 1000, "smooth": true}`
     pub fn handle_set_speed(&self, body: &str) -> ApiResult {
-        let Some(req) = parse_speed_request(body.as_bytes()) else {
-            return ApiResult::bad_request(r#"{"error":"invalid speed request"}"#);
+        let req = match parse_speed_request(body.as_bytes()) {
+            Ok(req) => req,
+            Err(MessageError::InvalidSpeed(e)) => return invalid_speed(e),
+            Err(_) => return ApiResult::bad_request(r#"{"error":"invalid speed request"}"#),
         };
 
-        if !(0.0..=1.0).contains(&req.speed) {
-            return ApiResult::bad_request(r#"{"error":"speed must be between 0.0 and 1.0"}"#);
-        }
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, None)
+    }
 
-        let cmd = if req.duration_ms > 0 {
-            if req.smooth {
-                ThrottleCommand::SetSpeed {
-                    target: req.speed,
-                    strategy: EaseInOut::new(req.duration_ms),
-                }
-                .into()
-            } else {
-                ThrottleCommand::SetSpeed {
-                    target: req.speed,
-                    strategy: Linear::new(req.duration_ms),
-                }
-                .into()
-            }
-        } else {
-            ThrottleCommand::speed_immediate(req.speed).into()
+    /// POST /api/velocity - Set signed velocity with optional transition.
+    ///
+    /// Accepts JSON: `{"velocity": -0.5}`, with the same `duration_ms`,
+    /// `smooth` and `preset` fields as `/api/speed`.
+    pub fn handle_set_velocity(&self, body: &str) -> ApiResult {
+        let req = match parse_velocity_request(body.as_bytes()) {
+            Ok(req) => req,
+            Err(MessageError::InvalidSpeed(e)) => return invalid_speed(e),
+            Err(_) => return ApiResult::bad_request(r#"{"error":"invalid velocity request"}"#),
         };
 
-        match self.state.apply_command(cmd, CommandSource::WebApi) {
-            Ok(outcome) => ApiResult::ok(command_outcome_to_json(outcome)),
-            Err(_) => ApiResult::error(500, r#"{"error":"controller error"}"#),
-        }
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, None)
     }
 
     /// POST /api/direction - Set direction.
     ///
     /// Accepts JSON: `{"direction": "forward"}`, `{"direction": "reverse"}`, or `{"direction": "stopped"}`
     pub fn handle_set_direction(&self, body: &str) -> ApiResult {
-        let Some(req) = parse_direction_request(body.as_bytes()) else {
+        let Ok(req) = parse_direction_request(body.as_bytes()) else {
             return ApiResult::bad_request(r#"{"error":"invalid direction"}"#);
         };
 
         let cmd = ThrottleCommand::<Immediate>::SetDirection(req.direction).into();
-        match self.state.apply_command(cmd, CommandSource::WebApi) {
-            Ok(_) => ApiResult::ok(r#"{"ok":true,"result":"direction_set"}"#),
-            Err(_) => ApiResult::error(500, r#"{"error":"controller error"}"#),
-        }
+        self.respond(cmd, Some("direction_set"))
     }
 
     /// POST /api/estop - Emergency stop.
     pub fn handle_estop(&self) -> ApiResult {
         let cmd = ThrottleCommand::estop().into();
-        match self.state.apply_command(cmd, CommandSource::WebApi) {
-            Ok(_) => ApiResult::ok(r#"{"ok":true,"result":"emergency_stop"}"#),
-            Err(_) => ApiResult::error(500, r#"{"error":"controller error"}"#),
+        self.respond(cmd, Some("emergency_stop"))
+    }
+
+    /// POST /api/service-brake - Apply the service brake.
+    ///
+    /// Accepts an empty body or JSON: `{"hold": true}`, with optional
+    /// `duration_ms` or `preset` overriding the configured profile.
+    pub fn handle_service_brake(&self, body: &str) -> ApiResult {
+        let Ok(req) = parse_service_brake_request(body.as_bytes()) else {
+            return ApiResult::bad_request(r#"{"error":"invalid brake request"}"#);
+        };
+
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, None)
+    }
+
+    /// POST /api/service-brake/release - Release a held service brake.
+    pub fn handle_release_brake(&self) -> ApiResult {
+        let cmd = ThrottleCommand::<Immediate>::ReleaseBrake.into();
+        self.respond(cmd, Some("brake_released"))
+    }
+
+    /// GET /api/queue - List speed commands waiting behind a locked transition.
+    pub fn handle_get_queue(&self) -> String {
+        let state = self.state.state();
+        format!(r#"{{"queue":{}}}"#, queue_to_json(&state.queue))
+    }
+
+    /// GET /api/metrics - Counts of dropped commands.
+    pub fn handle_get_metrics(&self) -> String {
+        metrics_to_json(&self.state.state().metrics)
+    }
+
+    /// GET /api/audit - Recent commands and the source and client that sent them.
+    pub fn handle_get_audit(&self) -> String {
+        format!(r#"{{"audit":{}}}"#, audit_to_json(&self.state.audit()))
+    }
+
+    /// GET /api/lease - Current throttle lease holder.
+    pub fn handle_get_lease(&self) -> String {
+        let state = self.state.state();
+        format!(r#"{{"lease":{}}}"#, lease_to_json(state.lease.as_ref()))
+    }
+
+    /// POST /api/lease/acquire, /api/lease/release, /api/lease/steal - Manage the throttle lease.
+    ///
+    /// Accepts an optional JSON body `{"client": "cab-3"}`, used if the
+    /// request has no client. Refusals are 409 with the [`RejectReason`].
+    ///
+    /// [`RejectReason`]: crate::RejectReason
+    pub fn handle_lease(&self, action: LeaseAction, body: &str) -> ApiResult {
+        let Ok(client) = parse_lease_payload(body.as_bytes()) else {
+            return ApiResult::bad_request(r#"{"error":"invalid lease request"}"#);
+        };
+
+        let client = self.client.or(client);
+        let result = match action {
+            LeaseAction::Acquire => "acquired",
+            LeaseAction::Release => "released",
+            LeaseAction::Steal => "stolen",
+        };
+        match self.state.lease(action, CommandSource::WebApi, client) {
+            Ok(lease) => ApiResult::ok(format!(
+                r#"{{"ok":true,"result":"{}","lease":{}}}"#,
+                result,
+                lease_to_json(lease.as_ref())
+            )),
+            Err(reason) => ApiResult::error(409, format!(r#"{{"error":"{}"}}"#, reason.as_str())),
         }
     }
 
+    /// POST /api/queue/cancel - Cancel one queued speed command.
+    ///
+    /// Accepts JSON: `{"id": 3}`
+    pub fn handle_cancel_queued(&self, body: &str) -> ApiResult {
+        let Ok(req) = parse_cancel_queued_request(body.as_bytes()) else {
+            return ApiResult::bad_request(r#"{"error":"invalid queue id"}"#);
+        };
+
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, None)
+    }
+
+    /// POST /api/queue/clear - Cancel all queued speed commands.
+    ///
+    /// Commands queued by higher-priority sources are kept.
+    pub fn handle_clear_queue(&self) -> ApiResult {
+        let cmd = ThrottleCommand::<Immediate>::ClearQueue.into();
+        self.respond(cmd, Some("queue_cleared"))
+    }
+
+    /// POST /api/pause - Freeze the active speed transition.
+    pub fn handle_pause(&self) -> ApiResult {
+        let cmd = ThrottleCommand::<Immediate>::Pause.into();
+        self.respond(cmd, Some("paused"))
+    }
+
+    /// POST /api/resume - Continue a paused speed transition.
+    pub fn handle_resume(&self) -> ApiResult {
+        let cmd = ThrottleCommand::<Immediate>::Resume.into();
+        self.respond(cmd, Some("resumed"))
+    }
+
+    /// POST /api/lock/release - Force release the active transition lock.
+    ///
+    /// Rejected unless the controller's force release source is at or
+    /// below [`CommandSource::WebApi`].
+    pub fn handle_force_release(&self) -> ApiResult {
+        let cmd = ThrottleCommand::<Immediate>::ForceRelease.into();
+        self.respond(cmd, Some("released"))
+    }
+
     /// POST /api/max-speed - Set maximum speed limit.
     ///
     /// Accepts JSON: `{"max_speed": 0.8}`
     pub fn handle_set_max_speed(&self, body: &str) -> ApiResult {
-        let Some(req) = parse_max_speed_request(body.as_bytes()) else {
-            return ApiResult::bad_request(r#"{"error":"invalid max_speed"}"#);
+        let req = match parse_max_speed_request(body.as_bytes()) {
+            Ok(req) => req,
+            Err(MessageError::InvalidSpeed(e)) => return invalid_speed(e),
+            Err(_) => return ApiResult::bad_request(r#"{"error":"invalid max_speed"}"#),
         };
 
-        if !(0.0..=1.0).contains(&req.max_speed) {
-            return ApiResult::bad_request(r#"{"error":"max_speed must be between 0.0 and 1.0"}"#);
-        }
-
         let cmd = ThrottleCommand::<Immediate>::SetMaxSpeed(req.max_speed).into();
-        match self.state.apply_command(cmd, CommandSource::WebApi) {
-            Ok(_) => ApiResult::ok(r#"{"ok":true,"result":"max_speed_set"}"#),
+        self.respond(cmd, Some("max_speed_set"))
+    }
+
+    /// POST /api/notch - Select a throttle notch (cab driving mode).
+    ///
+    /// Accepts JSON: `{"notch": 3}`
+    pub fn handle_set_notch(&self, body: &str) -> ApiResult {
+        let req = match parse_notch_request(body.as_bytes()) {
+            Ok(req) => req,
+            Err(MessageError::InvalidNotch) => {
+                return ApiResult::bad_request(format!(
+                    r#"{{"error":"{}"}}"#,
+                    MessageError::InvalidNotch
+                ))
+            }
+            Err(_) => return ApiResult::bad_request(r#"{"error":"invalid notch request"}"#),
+        };
+
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, None)
+    }
+
+    /// POST /api/brake - Move the brake handle (cab driving mode).
+    ///
+    /// Accepts JSON: `{"brake": "release"}`, `{"brake": "lap"}`,
+    /// `{"brake": "apply"}` or `{"brake": "emergency"}`
+    pub fn handle_set_brake(&self, body: &str) -> ApiResult {
+        let Ok(req) = parse_brake_request(body.as_bytes()) else {
+            return ApiResult::bad_request(r#"{"error":"invalid brake setting"}"#);
+        };
+
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, None)
+    }
+
+    /// POST /api/mode - Switch driving mode.
+    ///
+    /// Accepts JSON: `{"mode": "direct"}` or `{"mode": "cab"}`
+    pub fn handle_set_driving_mode(&self, body: &str) -> ApiResult {
+        let Ok(req) = parse_driving_mode_request(body.as_bytes()) else {
+            return ApiResult::bad_request(r#"{"error":"invalid driving mode"}"#);
+        };
+
+        let cmd = CommandMessage::from(req).into();
+        self.respond(cmd, Some("driving_mode_set"))
+    }
+
+    /// POST /api/command - Apply a versioned command envelope.
+    ///
+    /// Accepts any [`CommandEnvelope`](crate::messages::CommandEnvelope), e.g.
+    /// `{"version": 1, "command": {"set_speed": {"speed": 0.5, "strategy": {"linear": {"duration_ms": 1000}}}}}`
+    ///
+    /// A `client` or `correlation_id` named in the envelope is used if the
+    /// request has none.
+    pub fn handle_command(&self, body: &str) -> ApiResult {
+        let parsed = match parse_command_as(body.as_bytes()) {
+            Ok(parsed) => parsed,
+            Err(e) => return ApiResult::bad_request(format!(r#"{{"error":"{}"}}"#, e)),
+        };
+
+        let client = self.client.or(parsed.client);
+        let correlation_id = self.correlation_id.or(parsed.correlation_id);
+        match self.state.submit(
+            parsed.command,
+            CommandSource::WebApi,
+            client,
+            correlation_id,
+        ) {
+            Ok(receipt) => ApiResult::ok(receipt_to_json(&receipt, None)),
             Err(_) => ApiResult::error(500, r#"{"error":"controller error"}"#),
         }
     }
 
+    /// GET /api/commands/{id} - Latest acknowledgement of a command.
+    ///
+    /// 404 once the command is too old to be remembered.
+    pub fn handle_get_command(&self, id: u32) -> ApiResult {
+        match self.state.command(id) {
+            Some(ack) => ApiResult::ok(ack_to_json(&ack)),
+            None => ApiResult::error(404, r#"{"error":"unknown command"}"#),
+        }
+    }
+
+    /// POST /api/preview - Sample a strategy's speed curve without moving the train.
+    ///
+    /// Accepts JSON: `{"from": 0.0, "to": 0.8, "strategy": {"linear": {"duration_ms": 1000}}, "resolution_ms": 100}`.
+    /// Named presets resolve against the controller's registry.
+    pub fn handle_preview(&self, body: &str) -> ApiResult {
+        let Ok(req) = parse_preview_request(body.as_bytes()) else {
+            return ApiResult::bad_request(r#"{"error":"invalid preview request"}"#);
+        };
+        let Some(strategy) = self.state.resolve_preset(&req.strategy) else {
+            return ApiResult::bad_request(r#"{"error":"unknown preset"}"#);
+        };
+
+        let curve = preview(
+            &AnyStrategy::from(strategy),
+            req.from,
+            req.to,
+            req.resolution_ms,
+        );
+        match serde_json::to_string(&curve) {
+            Ok(json) => ApiResult::ok(json),
+            Err(_) => ApiResult::error(500, r#"{"error":"serialization error"}"#),
+        }
+    }
+
+    /// POST /api/heartbeat - Renew the heartbeat lease.
+    ///
+    /// Clients holding control must call this periodically when the
+    /// controller enforces a [`HeartbeatLease`](crate::priority::HeartbeatLease).
+    pub fn handle_heartbeat(&self) -> ApiResult {
+        let cmd = ThrottleCommand::<Immediate>::Heartbeat.into();
+        self.respond(cmd, Some("heartbeat"))
+    }
+
     /// GET / - Get web UI HTML.
     pub fn handle_index(&self) -> &'static str {
         include_str!("../../www/index.html")
@@ -222,43 +478,261 @@ impl<S: StateProvider> HttpApiHandler<S> {
 // JSON Helpers
 // ============================================================================
 
+/// 400 response for a speed that failed validation.
+fn invalid_speed(e: SpeedError) -> ApiResult {
+    ApiResult::bad_request(format!(r#"{{"error":"{}"}}"#, e))
+}
+
 /// Convert throttle state to JSON string.
 pub fn state_to_json(state: &ThrottleState) -> String {
+    let members: Vec<String> = state_members(state)
+        .iter()
+        .map(|(name, value)| format!(r#""{}":{}"#, name, value))
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+/// The members of [`state_to_json`] as names and JSON values, in order.
+pub fn state_members(state: &ThrottleState) -> Vec<(&'static str, String)> {
     let target = state.target_speed.unwrap_or(state.speed);
     let is_transitioning = state.transition_progress.is_some();
+    let is_paused = state.transition_progress.as_ref().is_some_and(|p| p.paused);
+    let service_brake = match state.service_brake {
+        Some(brake) if brake.hold => "hold",
+        Some(_) => "applied",
+        None => "off",
+    };
+
+    let mut members = Vec::from([
+        ("speed", format!("{:.2}", state.speed)),
+        ("target_speed", format!("{:.2}", target)),
+        ("direction", format!(r#""{}""#, state.direction.as_str())),
+        ("velocity", format!("{:.2}", state.velocity)),
+        ("max_speed", format!("{:.2}", state.max_speed)),
+        ("is_transitioning", format!("{}", is_transitioning)),
+        ("is_paused", format!("{}", is_paused)),
+        ("service_brake", format!(r#""{}""#, service_brake)),
+        ("queue", queue_to_json(&state.queue)),
+        ("lock", lock_to_json(state.lock_status.as_ref())),
+        ("lease", lease_to_json(state.lease.as_ref())),
+        ("lockout", lockout_to_json(state.lockout.as_ref())),
+    ]);
+    match &state.cab {
+        Some(cab) => members.extend([
+            ("mode", String::from(r#""cab""#)),
+            ("notch", format!("{}", cab.notch)),
+            ("brake", format!(r#""{}""#, cab.brake.as_str())),
+            ("brake_level", format!("{:.2}", cab.brake_level)),
+        ]),
+        None => members.push(("mode", String::from(r#""direct""#))),
+    }
+    members
+}
+
+/// Convert the transition lock status to a JSON object, or `null`.
+pub fn lock_to_json(lock: Option<&LockStatus>) -> String {
+    let Some(lock) = lock else {
+        return String::from("null");
+    };
+    let remaining_ms = match lock.remaining_ms {
+        Some(ms) => format!("{}", ms),
+        None => String::from("null"),
+    };
+    let released = match lock.released {
+        Some(LockRelease::Forced { by }) => {
+            format!(r#"{{"reason":"forced","by":"{}"}}"#, by.as_str())
+        }
+        Some(release) => format!(r#"{{"reason":"{}"}}"#, release.as_str()),
+        None => String::from("null"),
+    };
+    format!(
+        r#"{{"level":"{}","owner":"{}"{},"remaining_ms":{},"released":{}}}"#,
+        lock.lock.as_str(),
+        lock.source.as_str(),
+        client_to_json(lock.client),
+        remaining_ms,
+        released
+    )
+}
+
+/// Convert the throttle lease holder to a JSON object, or `null`.
+pub fn lease_to_json(lease: Option<&LeaseStatus>) -> String {
+    let Some(lease) = lease else {
+        return String::from("null");
+    };
+    format!(
+        r#"{{"owner":"{}"{},"remaining_ms":{}}}"#,
+        lease.source.as_str(),
+        client_to_json(lease.client),
+        lease.remaining_ms
+    )
+}
+
+/// Convert the physical control lockout to a JSON object, or `null`.
+pub fn lockout_to_json(lockout: Option<&LockoutStatus>) -> String {
+    let Some(lockout) = lockout else {
+        return String::from("null");
+    };
+    format!(
+        r#"{{"owner":"{}","remaining_ms":{}}}"#,
+        lockout.source.as_str(),
+        lockout.remaining_ms
+    )
+}
+
+/// Convert queued speed commands to a JSON array.
+pub fn queue_to_json(queue: &[QueuedCommand]) -> String {
+    let entries: Vec<String> = queue
+        .iter()
+        .map(|q| {
+            format!(
+                r#"{{"id":{},"target":{:.2},"source":"{}"{}}}"#,
+                q.id,
+                q.target,
+                q.source.as_str(),
+                client_to_json(q.client)
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
 
+/// A `,"client":"..."` member, or nothing if the client is unknown.
+fn client_to_json(client: Option<ClientId>) -> String {
+    match client {
+        Some(client) => format!(r#","client":"{}""#, client),
+        None => String::new(),
+    }
+}
+
+/// Convert the audit trail to a JSON array, oldest first.
+pub fn audit_to_json(audit: &[AuditEntry]) -> String {
+    let entries: Vec<String> = audit
+        .iter()
+        .map(|entry| {
+            let rejected = match &entry.rejected {
+                Some(reason) => format!(r#""{}""#, reason.as_str()),
+                None => String::from("null"),
+            };
+            format!(
+                r#"{{"timestamp_ms":{},"source":"{}"{},"command":"{}","rejected":{}}}"#,
+                entry.timestamp_ms,
+                entry.source.as_str(),
+                client_to_json(entry.client),
+                entry.command,
+                rejected
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+/// Convert dropped command counts to a JSON object.
+pub fn metrics_to_json(metrics: &CommandMetrics) -> String {
+    let limited = &metrics.rate_limited;
     format!(
-        r#"{{"speed":{:.2},"target_speed":{:.2},"direction":"{}","max_speed":{:.2},"is_transitioning":{}}}"#,
-        state.speed,
-        target,
-        state.direction.as_str(),
-        state.max_speed,
-        is_transitioning
+        r#"{{"rate_limited":{{"mqtt":{},"web_api":{},"web_local":{},"physical":{},"total":{}}},"coalesced":{},"queue_full":{}}}"#,
+        limited.mqtt,
+        limited.web_api,
+        limited.web_local,
+        limited.physical,
+        limited.total(),
+        metrics.coalesced,
+        metrics.queue_full
     )
 }
 
-/// Convert direction to string.
-/// Convert command outcome to JSON.
-fn command_outcome_to_json(outcome: CommandOutcome) -> String {
+/// The `"result"` member describing an outcome, and its details.
+fn outcome_members(outcome: &CommandOutcome) -> String {
     let result = match outcome {
         CommandOutcome::Applied => "applied",
         CommandOutcome::SpeedTransition(r) => match r {
-            crate::TransitionResult::Started => "transition_started",
-            crate::TransitionResult::Queued => "queued",
-            crate::TransitionResult::Interrupted { .. } => "interrupted_previous",
-            crate::TransitionResult::Rejected { .. } => "rejected",
+            TransitionResult::Started => "transition_started",
+            TransitionResult::Queued { id } => {
+                return format!(r#""result":"queued","id":{}"#, id);
+            }
+            TransitionResult::Deferred => "deferred",
+            TransitionResult::Interrupted { .. } => "interrupted_previous",
+            TransitionResult::Rejected { reason } => {
+                return format!(r#""result":"rejected","reason":"{}""#, reason.as_str());
+            }
         },
     };
-    format!(r#"{{"ok":true,"result":"{}"}}"#, result)
+    format!(r#""result":"{}""#, result)
+}
+
+/// Convert a command receipt to JSON.
+///
+/// `applied` names the result unless the command was rejected, queued or
+/// deferred.
+/// The command id and correlation id are included when known.
+fn receipt_to_json(receipt: &CommandReceipt, applied: Option<&str>) -> String {
+    let result = match (&receipt.outcome, applied) {
+        (CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. }), _)
+        | (CommandOutcome::SpeedTransition(TransitionResult::Queued { .. }), _)
+        | (CommandOutcome::SpeedTransition(TransitionResult::Deferred), _)
+        | (_, None) => outcome_members(&receipt.outcome),
+        (_, Some(applied)) => format!(r#""result":"{}""#, applied),
+    };
+    let id = match receipt.id {
+        Some(id) => format!(r#","command_id":{}"#, id),
+        None => String::new(),
+    };
+    format!(
+        r#"{{"ok":true,{}{}{}}}"#,
+        result,
+        id,
+        correlation_to_json(receipt.correlation_id)
+    )
+}
+
+/// A `,"correlation_id":"..."` member, or nothing without one.
+fn correlation_to_json(correlation_id: Option<CorrelationId>) -> String {
+    match correlation_id {
+        Some(correlation_id) => format!(r#","correlation_id":"{}""#, correlation_id),
+        None => String::new(),
+    }
+}
+
+/// Convert a command acknowledgement to JSON.
+///
+/// Also the payload published on the `{prefix}/ack` MQTT topic.
+pub fn ack_to_json(ack: &CommandAck) -> String {
+    let reason = match &ack.status {
+        CommandStatus::Rejected(reason) => format!(r#","reason":"{}""#, reason.as_str()),
+        _ => String::new(),
+    };
+    format!(
+        r#"{{"seq":{},"command_id":{}{},"command":"{}","source":"{}"{},"status":"{}"{},"timestamp_ms":{}}}"#,
+        ack.seq,
+        ack.id,
+        correlation_to_json(ack.correlation_id),
+        ack.command,
+        ack.source.as_str(),
+        client_to_json(ack.client),
+        ack.status.as_str(),
+        reason,
+        ack.timestamp_ms
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{RejectReason, TransitionResult};
+    use crate::{Direction, RejectReason, Speed, TransitionResult, Velocity};
     use alloc::sync::Arc;
     use std::sync::Mutex;
 
+    /// Describe an untracked command's outcome.
+    fn outcome_json(outcome: CommandOutcome) -> String {
+        let receipt = CommandReceipt {
+            id: None,
+            correlation_id: None,
+            outcome,
+        };
+        receipt_to_json(&receipt, None)
+    }
+
     // ========================================================================
     // MockStateProvider for testing HttpApiHandler
     // ========================================================================
@@ -274,13 +748,21 @@ mod tests {
         fn new() -> Self {
             Self {
                 state: Mutex::new(ThrottleState {
-                    speed: 0.0,
+                    speed: Speed::literal(0.0),
                     direction: Direction::Stopped,
-                    max_speed: 1.0,
+                    velocity: Velocity::ZERO,
+                    max_speed: Speed::literal(1.0),
                     target_speed: None,
                     transition_progress: None,
                     fault: None,
                     lock_status: None,
+                    queue: heapless::Vec::new(),
+                    heartbeat: None,
+                    lease: None,
+                    lockout: None,
+                    cab: None,
+                    service_brake: None,
+                    metrics: Default::default(),
                 }),
                 command_result: Mutex::new(Ok(CommandOutcome::Applied)),
                 last_command: Mutex::new(None),
@@ -289,7 +771,7 @@ mod tests {
 
         fn with_state(self, speed: f32, direction: Direction) -> Self {
             let mut state = self.state.lock().unwrap();
-            state.speed = speed;
+            state.speed = Speed::new(speed).unwrap();
             state.direction = direction;
             drop(state);
             self
@@ -297,13 +779,16 @@ mod tests {
 
         fn with_transition(self, target: f32, progress: f32) -> Self {
             let mut state = self.state.lock().unwrap();
+            let target = Speed::new(target).unwrap();
             state.target_speed = Some(target);
             state.transition_progress = Some(crate::TransitionProgress {
-                from: 0.0,
+                from: Speed::ZERO,
                 to: target,
-                current: progress * target,
+                current: Speed::clamped(progress * target.get()),
                 elapsed_ms: (progress * 1000.0) as u64,
                 estimated_total_ms: Some(1000),
+                segment: None,
+                paused: false,
             });
             drop(state);
             self
@@ -382,13 +867,21 @@ mod tests {
     #[test]
     fn test_state_to_json_basic() {
         let state = ThrottleState {
-            speed: 0.5,
+            speed: Speed::literal(0.5),
             direction: Direction::Forward,
-            max_speed: 1.0,
+            velocity: Velocity::new(0.5).unwrap(),
+            max_speed: Speed::literal(1.0),
             target_speed: None,
             transition_progress: None,
             fault: None,
             lock_status: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: Default::default(),
         };
 
         let json = state_to_json(&state);
@@ -398,22 +891,158 @@ mod tests {
         assert!(json.contains("\"is_transitioning\":false"));
     }
 
+    #[test]
+    fn test_state_to_json_cab_mode() {
+        let mut state = ThrottleState::default();
+        let json = state_to_json(&state);
+        assert!(json.contains("\"mode\":\"direct\""));
+        assert!(json.contains("\"service_brake\":\"off\""));
+        assert!(!json.contains("notch"));
+
+        state.cab = Some(crate::CabStatus {
+            notch: crate::Notch::new(3).unwrap(),
+            brake: crate::BrakeSetting::Apply,
+            brake_level: 0.5,
+        });
+        let json = state_to_json(&state);
+        assert!(json.contains("\"mode\":\"cab\""));
+        assert!(json.contains("\"notch\":3"));
+        assert!(json.contains("\"brake\":\"apply\""));
+        assert!(json.contains("\"brake_level\":0.50"));
+    }
+
+    #[test]
+    fn test_state_to_json_service_brake() {
+        let mut state = ThrottleState {
+            service_brake: Some(crate::ServiceBrakeStatus {
+                source: CommandSource::WebApi,
+                hold: true,
+            }),
+            ..ThrottleState::default()
+        };
+        assert!(state_to_json(&state).contains("\"service_brake\":\"hold\""));
+
+        state.service_brake = Some(crate::ServiceBrakeStatus {
+            source: CommandSource::WebApi,
+            hold: false,
+        });
+        assert!(state_to_json(&state).contains("\"service_brake\":\"applied\""));
+    }
+
+    #[test]
+    fn test_state_to_json_queue() {
+        let mut state = ThrottleState::default();
+        assert!(state_to_json(&state).contains("\"queue\":[]"));
+
+        state
+            .queue
+            .push(crate::QueuedCommand {
+                id: 7,
+                target: Speed::literal(0.25),
+                source: CommandSource::WebApi,
+                client: None,
+                lock: crate::TransitionLock::None,
+                duration_ms: None,
+            })
+            .unwrap();
+        let json = state_to_json(&state);
+        assert!(json.contains(r#""queue":[{"id":7,"target":0.25,"source":"web_api"}]"#));
+    }
+
+    #[test]
+    fn test_state_to_json_lock() {
+        let mut state = ThrottleState::default();
+        assert!(state_to_json(&state).contains("\"lock\":null"));
+
+        state.lock_status = Some(LockStatus {
+            lock: crate::TransitionLock::Hard,
+            source: CommandSource::Mqtt,
+            client: None,
+            target: Speed::literal(0.8),
+            has_queued: false,
+            remaining_ms: Some(1500),
+            released: None,
+        });
+        let json = state_to_json(&state);
+        assert!(json.contains(
+            r#""lock":{"level":"hard","owner":"mqtt","remaining_ms":1500,"released":null}"#
+        ));
+
+        state.lock_status = Some(LockStatus {
+            lock: crate::TransitionLock::None,
+            source: CommandSource::Mqtt,
+            client: None,
+            target: Speed::literal(0.8),
+            has_queued: false,
+            remaining_ms: None,
+            released: Some(LockRelease::Forced {
+                by: CommandSource::Physical,
+            }),
+        });
+        let json = state_to_json(&state);
+        assert!(json.contains(r#""released":{"reason":"forced","by":"physical"}"#));
+    }
+
+    #[test]
+    fn test_state_to_json_lease() {
+        let mut state = ThrottleState::default();
+        assert!(state_to_json(&state).contains("\"lease\":null"));
+
+        state.lease = Some(LeaseStatus {
+            source: CommandSource::WebApi,
+            client: Some(ClientId::new("cab-3").unwrap()),
+            expires_ms: 61_000,
+            remaining_ms: 60_000,
+        });
+        let json = state_to_json(&state);
+        assert!(
+            json.contains(r#""lease":{"owner":"web_api","client":"cab-3","remaining_ms":60000}"#)
+        );
+    }
+
+    #[test]
+    fn test_state_to_json_paused() {
+        let mut state = ThrottleState::default();
+        assert!(state_to_json(&state).contains("\"is_paused\":false"));
+
+        state.transition_progress = Some(crate::TransitionProgress {
+            from: Speed::ZERO,
+            to: Speed::FULL,
+            current: Speed::literal(0.4),
+            elapsed_ms: 400,
+            estimated_total_ms: Some(1000),
+            segment: None,
+            paused: true,
+        });
+        assert!(state_to_json(&state).contains("\"is_paused\":true"));
+    }
+
     #[test]
     fn test_state_to_json_with_transition() {
         let state = ThrottleState {
-            speed: 0.3,
+            speed: Speed::literal(0.3),
             direction: Direction::Reverse,
-            max_speed: 0.8,
-            target_speed: Some(0.7),
+            velocity: Velocity::new(-0.3).unwrap(),
+            max_speed: Speed::literal(0.8),
+            target_speed: Some(Speed::literal(0.7)),
             transition_progress: Some(crate::TransitionProgress {
-                from: 0.0,
-                to: 0.7,
-                current: 0.3,
+                from: Speed::literal(0.0),
+                to: Speed::literal(0.7),
+                current: Speed::literal(0.3),
                 elapsed_ms: 500,
                 estimated_total_ms: Some(1000),
+                segment: None,
+                paused: false,
             }),
             fault: None,
             lock_status: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: Default::default(),
         };
 
         let json = state_to_json(&state);
@@ -425,30 +1054,37 @@ mod tests {
 
     #[test]
     fn test_command_outcome_to_json_applied() {
-        let json = command_outcome_to_json(CommandOutcome::Applied);
+        let json = outcome_json(CommandOutcome::Applied);
         assert!(json.contains("\"ok\":true"));
         assert!(json.contains("\"result\":\"applied\""));
     }
 
     #[test]
     fn test_command_outcome_to_json_transition_started() {
-        let json =
-            command_outcome_to_json(CommandOutcome::SpeedTransition(TransitionResult::Started));
+        let json = outcome_json(CommandOutcome::SpeedTransition(TransitionResult::Started));
         assert!(json.contains("\"result\":\"transition_started\""));
     }
 
     #[test]
     fn test_command_outcome_to_json_queued() {
-        let json =
-            command_outcome_to_json(CommandOutcome::SpeedTransition(TransitionResult::Queued));
+        let json = outcome_json(CommandOutcome::SpeedTransition(TransitionResult::Queued {
+            id: 7,
+        }));
         assert!(json.contains("\"result\":\"queued\""));
+        assert!(json.contains("\"id\":7"));
+    }
+
+    #[test]
+    fn test_command_outcome_to_json_deferred() {
+        let json = outcome_json(CommandOutcome::SpeedTransition(TransitionResult::Deferred));
+        assert!(json.contains("\"result\":\"deferred\""));
     }
 
     #[test]
     fn test_command_outcome_to_json_interrupted() {
-        let json = command_outcome_to_json(CommandOutcome::SpeedTransition(
+        let json = outcome_json(CommandOutcome::SpeedTransition(
             TransitionResult::Interrupted {
-                previous_target: 0.5,
+                previous_target: Speed::literal(0.5),
             },
         ));
         assert!(json.contains("\"result\":\"interrupted_previous\""));
@@ -456,12 +1092,13 @@ mod tests {
 
     #[test]
     fn test_command_outcome_to_json_rejected() {
-        let json = command_outcome_to_json(CommandOutcome::SpeedTransition(
+        let json = outcome_json(CommandOutcome::SpeedTransition(
             TransitionResult::Rejected {
                 reason: RejectReason::TransitionLocked,
             },
         ));
         assert!(json.contains("\"result\":\"rejected\""));
+        assert!(json.contains("\"reason\":\"transition_locked\""));
     }
 
     // ========================================================================
@@ -504,7 +1141,7 @@ mod tests {
         assert!(matches!(source, CommandSource::WebApi));
         match cmd {
             crate::ThrottleCommandDyn::SetSpeed { target, .. } => {
-                assert!((target - 0.5).abs() < 0.001);
+                assert!((target.get() - 0.5).abs() < 0.001);
             }
             _ => panic!("expected SetSpeed command"),
         }
@@ -542,6 +1179,109 @@ mod tests {
         assert!(result.body().contains("controller error"));
     }
 
+    #[test]
+    fn test_handle_set_velocity_valid() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_set_velocity(r#"{"velocity": -0.5, "duration_ms": 1000}"#);
+        assert!(result.is_ok());
+
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        match cmd {
+            crate::ThrottleCommandDyn::SetVelocity { target, strategy } => {
+                assert_eq!(target, -0.5);
+                assert_eq!(strategy.duration_ms(), Some(1000));
+            }
+            _ => panic!("expected SetVelocity command"),
+        }
+    }
+
+    #[test]
+    fn test_handle_set_velocity_out_of_range() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_set_velocity(r#"{"velocity": -1.5}"#);
+        assert_eq!(result.status(), 400);
+        assert!(result.body().contains("velocity must be between"));
+        assert!(provider.last_command().is_none());
+    }
+
+    #[test]
+    fn test_handle_set_notch_valid() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_set_notch(r#"{"notch": 4}"#);
+        assert!(result.is_ok());
+
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::SetNotch(n) if n.get() == 4));
+        assert_eq!(source, CommandSource::WebApi);
+    }
+
+    #[test]
+    fn test_handle_set_notch_out_of_range() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_set_notch(r#"{"notch": 9}"#);
+        assert_eq!(result.status(), 400);
+        assert!(result.body().contains("notch must be between 0 and 8"));
+        assert!(provider.last_command().is_none());
+    }
+
+    #[test]
+    fn test_handle_set_notch_wrong_mode() {
+        let provider = Arc::new(MockStateProvider::new().with_command_result(Ok(
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::WrongDrivingMode,
+            }),
+        )));
+        let handler = HttpApiHandler::new(provider);
+
+        let result = handler.handle_set_notch(r#"{"notch": 2}"#);
+        assert!(result.body().contains("rejected"));
+    }
+
+    #[test]
+    fn test_handle_set_brake() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_set_brake(r#"{"brake": "emergency"}"#);
+        assert!(result.is_ok());
+
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        assert!(matches!(
+            cmd,
+            crate::ThrottleCommandDyn::SetBrake(crate::BrakeSetting::Emergency)
+        ));
+
+        let result = handler.handle_set_brake(r#"{"brake": "handbrake"}"#);
+        assert_eq!(result.status(), 400);
+    }
+
+    #[test]
+    fn test_handle_set_driving_mode() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_set_driving_mode(r#"{"mode": "cab"}"#);
+        assert!(result.is_ok());
+        assert!(result.body().contains("driving_mode_set"));
+
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        assert!(matches!(
+            cmd,
+            crate::ThrottleCommandDyn::SetDrivingMode(crate::DrivingMode::Cab)
+        ));
+
+        let result = handler.handle_set_driving_mode(r#"{"mode": "autopilot"}"#);
+        assert_eq!(result.status(), 400);
+    }
+
     #[test]
     fn test_handle_set_direction_forward() {
         let provider = Arc::new(MockStateProvider::new());
@@ -595,6 +1335,307 @@ mod tests {
         assert!(matches!(cmd, crate::ThrottleCommandDyn::EmergencyStop));
     }
 
+    #[test]
+    fn test_handle_command_valid() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_command(
+            r#"{"version": 1, "command": {"set_speed": {"speed": 0.4, "strategy": {"ease_in_out": {"duration_ms": 2000, "lock": "hard"}}}}}"#,
+        );
+        assert!(result.is_ok());
+
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert_eq!(source, CommandSource::WebApi);
+        let crate::ThrottleCommandDyn::SetSpeed { target, strategy } = cmd else {
+            panic!("expected set_speed");
+        };
+        assert!((target.get() - 0.4).abs() < 0.001);
+        assert_eq!(strategy.lock(), crate::TransitionLock::Hard);
+    }
+
+    #[test]
+    fn test_handle_command_keyframes_upload() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_command(
+            r#"{"command": {"set_speed": {"speed": 0.8, "strategy": {"keyframes": {"frames": [
+                {"time_ms": 500, "fraction": 0.1, "easing": "smoothstep"},
+                {"time_ms": 2500, "fraction": 0.1},
+                {"time_ms": 4500, "fraction": 1.0, "easing": "cubic"}], "lock": "hard"}}}}}"#,
+        );
+        assert!(result.is_ok());
+
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        let crate::ThrottleCommandDyn::SetSpeed { strategy, .. } = cmd else {
+            panic!("expected set_speed");
+        };
+        assert_eq!(strategy.duration_ms(), Some(4500));
+        assert_eq!(strategy.lock(), crate::TransitionLock::Hard);
+    }
+
+    #[test]
+    fn test_handle_command_errors() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_command("not json");
+        assert_eq!(result.status(), 400);
+
+        let result = handler.handle_command(r#"{"version": 99, "command": "heartbeat"}"#);
+        assert_eq!(result.status(), 400);
+        assert!(result.body().contains("unsupported schema version 99"));
+
+        assert!(provider.last_command().is_none());
+    }
+
+    #[test]
+    fn test_handle_lease_without_leases() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        // Providers without leases grant every request and hold nothing
+        let result = handler.handle_lease(LeaseAction::Acquire, r#"{"client": "cab-3"}"#);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.body(),
+            r#"{"ok":true,"result":"acquired","lease":null}"#
+        );
+
+        let result = handler.handle_lease(LeaseAction::Steal, "not a client");
+        assert_eq!(result.status(), 400);
+
+        assert_eq!(handler.handle_get_lease(), r#"{"lease":null}"#);
+    }
+
+    #[test]
+    fn test_handle_heartbeat() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_heartbeat();
+        assert!(result.is_ok());
+        assert!(result.body().contains("heartbeat"));
+
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::Heartbeat));
+        assert_eq!(source, CommandSource::WebApi);
+    }
+
+    #[test]
+    fn test_handle_service_brake() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_service_brake("");
+        assert!(result.is_ok());
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert!(matches!(
+            cmd,
+            crate::ThrottleCommandDyn::Brake {
+                strategy: None,
+                hold: false
+            }
+        ));
+        assert_eq!(source, CommandSource::WebApi);
+
+        let result = handler.handle_service_brake(r#"{"hold": true, "duration_ms": 1500}"#);
+        assert!(result.is_ok());
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        let crate::ThrottleCommandDyn::Brake { strategy, hold } = cmd else {
+            panic!("expected Brake command");
+        };
+        assert!(hold);
+        assert_eq!(strategy.and_then(|s| s.duration_ms()), Some(1500));
+
+        let result = handler.handle_service_brake("not json");
+        assert_eq!(result.status(), 400);
+    }
+
+    #[test]
+    fn test_handle_release_brake() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_release_brake();
+        assert!(result.body().contains("brake_released"));
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::ReleaseBrake));
+    }
+
+    #[test]
+    fn test_handle_get_queue() {
+        let provider = MockStateProvider::new();
+        provider
+            .state
+            .lock()
+            .unwrap()
+            .queue
+            .push(crate::QueuedCommand {
+                id: 3,
+                target: Speed::literal(0.5),
+                source: CommandSource::Mqtt,
+                client: None,
+                lock: crate::TransitionLock::None,
+                duration_ms: Some(1000),
+            })
+            .unwrap();
+        let handler = HttpApiHandler::new(Arc::new(provider));
+
+        let json = handler.handle_get_queue();
+        assert_eq!(
+            json,
+            r#"{"queue":[{"id":3,"target":0.50,"source":"mqtt"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_handle_get_metrics() {
+        let provider = MockStateProvider::new();
+        {
+            let mut state = provider.state.lock().unwrap();
+            state.metrics.rate_limited.mqtt = 7;
+            state.metrics.rate_limited.web_api = 2;
+            state.metrics.queue_full = 1;
+        }
+        let handler = HttpApiHandler::new(Arc::new(provider));
+
+        assert_eq!(
+            handler.handle_get_metrics(),
+            r#"{"rate_limited":{"mqtt":7,"web_api":2,"web_local":0,"physical":0,"total":9},"coalesced":0,"queue_full":1}"#
+        );
+    }
+
+    #[test]
+    fn test_handle_cancel_queued_valid() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_cancel_queued(r#"{"id":3}"#);
+        assert!(result.is_ok());
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::CancelQueued(3)));
+        assert_eq!(source, CommandSource::WebApi);
+    }
+
+    #[test]
+    fn test_handle_cancel_queued_invalid() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_cancel_queued(r#"{"id":"x"}"#);
+        assert_eq!(result.status(), 400);
+        assert!(provider.last_command().is_none());
+    }
+
+    #[test]
+    fn test_handle_clear_queue() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_clear_queue();
+        assert!(result.body().contains("queue_cleared"));
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::ClearQueue));
+    }
+
+    #[test]
+    fn test_handle_pause_resume() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_pause();
+        assert!(result.body().contains("\"result\":\"paused\""));
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::Pause));
+        assert_eq!(source, CommandSource::WebApi);
+
+        let result = handler.handle_resume();
+        assert!(result.body().contains("\"result\":\"resumed\""));
+        let (cmd, _) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::Resume));
+    }
+
+    #[test]
+    fn test_handle_pause_rejected() {
+        let provider = Arc::new(MockStateProvider::new().with_command_result(Ok(
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::NotTransitioning,
+            }),
+        )));
+        let handler = HttpApiHandler::new(provider);
+
+        let result = handler.handle_pause();
+        assert!(result.body().contains("rejected"));
+    }
+
+    #[test]
+    fn test_handle_force_release() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let result = handler.handle_force_release();
+        assert!(result.body().contains("\"result\":\"released\""));
+        let (cmd, source) = provider.last_command().expect("command should be captured");
+        assert!(matches!(cmd, crate::ThrottleCommandDyn::ForceRelease));
+        assert_eq!(source, CommandSource::WebApi);
+
+        let provider = Arc::new(MockStateProvider::new().with_command_result(Ok(
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::LowerPriority,
+            }),
+        )));
+        let handler = HttpApiHandler::new(provider);
+        let result = handler.handle_force_release();
+        assert!(result.body().contains("rejected"));
+    }
+
+    #[test]
+    fn test_handle_preview() {
+        let provider = Arc::new(MockStateProvider::new());
+        let handler = HttpApiHandler::new(provider.clone());
+
+        let body = r#"{"from":0.0,"to":0.8,"strategy":{"linear":{"duration_ms":1000,"lock":"source"}},"resolution_ms":250}"#;
+        let result = handler.handle_preview(body);
+        assert!(result.is_ok());
+        let json: serde_json::Value = serde_json::from_str(result.body()).unwrap();
+        assert_eq!(json["duration_ms"], 1000);
+        assert_eq!(json["lock"], "source");
+        assert_eq!(json["on_interrupt"], "replace");
+        assert_eq!(json["points"].as_array().unwrap().len(), 5);
+        assert_eq!(json["points"][4]["elapsed_ms"], 1000);
+
+        // Nothing was sent to the controller
+        assert!(provider.last_command().is_none());
+    }
+
+    #[test]
+    fn test_handle_preview_resolves_presets() {
+        let handler = HttpApiHandler::new(Arc::new(MockStateProvider::new()));
+
+        let body = r#"{"from":0.0,"to":1.0,"strategy":{"preset":"express"}}"#;
+        let result = handler.handle_preview(body);
+        let json: serde_json::Value = serde_json::from_str(result.body()).unwrap();
+        assert!(json["duration_ms"].as_u64().unwrap() > 0);
+
+        let body = r#"{"from":0.0,"to":1.0,"strategy":{"preset":"warp"}}"#;
+        let result = handler.handle_preview(body);
+        assert_eq!(result.status(), 400);
+        assert!(result.body().contains("unknown preset"));
+    }
+
+    #[test]
+    fn test_handle_preview_invalid() {
+        let handler = HttpApiHandler::new(Arc::new(MockStateProvider::new()));
+
+        assert_eq!(handler.handle_preview(r#"{"to":0.5}"#).status(), 400);
+        assert_eq!(
+            handler.handle_preview(r#"{"from":0.0,"to":1.5}"#).status(),
+            400
+        );
+    }
+
     #[test]
     fn test_handle_set_max_speed_valid() {
         let provider = Arc::new(MockStateProvider::new());
@@ -605,7 +1646,7 @@ mod tests {
 
         let (cmd, _) = provider.last_command().expect("command should be captured");
         assert!(
-            matches!(cmd, crate::ThrottleCommandDyn::SetMaxSpeed(s) if (s - 0.8).abs() < 0.001)
+            matches!(cmd, crate::ThrottleCommandDyn::SetMaxSpeed(s) if (s.get() - 0.8).abs() < 0.001)
         );
     }
 
@@ -29,6 +29,14 @@
 #[cfg(any(feature = "web", feature = "mqtt"))]
 pub mod shared;
 
+// Command acknowledgements
+#[cfg(any(feature = "web", feature = "mqtt"))]
+pub mod ack;
+
+// State events for Server-Sent Events
+#[cfg(any(feature = "web", feature = "mqtt"))]
+pub mod events;
+
 // API types are shared between web and mqtt
 #[cfg(any(feature = "web", feature = "mqtt"))]
 pub mod api;
@@ -55,6 +63,12 @@ pub mod physical;
 #[cfg(any(feature = "web", feature = "mqtt"))]
 pub use shared::*;
 
+#[cfg(any(feature = "web", feature = "mqtt"))]
+pub use ack::*;
+
+#[cfg(any(feature = "web", feature = "mqtt"))]
+pub use events::*;
+
 #[cfg(any(feature = "web", feature = "mqtt"))]
 pub use api::*;
 
@@ -4,14 +4,26 @@
 //!
 //! **Subscribe Topics:**
 //! - `train/speed/set` - Set speed `{"speed": 0.5, "duration_ms": 1000}`
+//! - `train/velocity/set` - Set signed velocity `{"velocity": -0.5, "duration_ms": 1000}`
 //! - `train/direction/set` - Set direction `"forward"`, `"reverse"`, or `"stopped"`
 //! - `train/estop` - Emergency stop (any payload)
 //! - `train/max-speed/set` - Set max speed `{"max_speed": 0.8}`
+//! - `train/heartbeat` - Renew the remote control heartbeat lease (any payload)
+//! - `train/queue/cancel` - Cancel a queued speed command `3` or `{"id": 3}`
+//! - `train/queue/clear` - Cancel all queued speed commands (any payload)
+//! - `train/pause` - Freeze the active speed transition (any payload)
+//! - `train/resume` - Continue a paused speed transition (any payload)
+//! - `train/lock/release` - Force release the transition lock (any payload)
+//! - `train/lease/acquire` - Acquire the throttle lease `"cab-3"` or `{"client": "cab-3"}`
+//! - `train/lease/release` - Release the throttle lease (same payload)
+//! - `train/lease/steal` - Take the throttle lease from its holder (same payload)
+//! - `train/command` - Versioned command envelope (see [`crate::messages::CommandEnvelope`])
 //!
 //! **Publish Topics:**
 //! - `train/state` - Full state JSON (on change + heartbeat)
 //! - `train/speed` - Current speed value (retained)
 //! - `train/direction` - Current direction (retained)
+//! - `train/ack` - Command acknowledgements (see [`super::ack`])
 //!
 //! # Shared State
 //!
@@ -30,10 +42,12 @@ use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
 use tokio::sync::mpsc;
 
 use crate::config::MqttConfig as SharedMqttConfig;
-use crate::traits::{EaseInOut, Immediate, Linear, MotorController};
-use crate::{CommandSource, Direction, ThrottleCommand, ThrottleController};
+use crate::messages::{parse_mqtt_command_as, parse_mqtt_lease, MessageError};
+use crate::traits::MotorController;
+use crate::{CommandSource, ThrottleController};
 
-use super::api::{SetDirectionRequest, SetMaxSpeedRequest, SetSpeedRequest, StateResponse};
+use super::api::StateResponse;
+use super::http_handler::ack_to_json;
 use super::shared::SharedThrottleState;
 
 // ============================================================================
@@ -126,6 +140,9 @@ impl MqttRuntimeConfig {
 // MQTT Handler
 // ============================================================================
 
+/// How often new command acknowledgements are published
+const ACK_POLL_MS: u64 = 50;
+
 /// Legacy type alias for backward compatibility.
 ///
 /// New code should use `SharedThrottleState` directly.
@@ -185,9 +202,25 @@ impl<M: MotorController + Send + 'static> MqttHandler<M> {
         // Subscribe to command topics
         let topics = [
             self.config.topic("speed/set"),
+            self.config.topic("velocity/set"),
             self.config.topic("direction/set"),
             self.config.topic("estop"),
             self.config.topic("max-speed/set"),
+            self.config.topic("heartbeat"),
+            self.config.topic("service-brake"),
+            self.config.topic("service-brake/release"),
+            self.config.topic("notch/set"),
+            self.config.topic("brake/set"),
+            self.config.topic("mode/set"),
+            self.config.topic("queue/cancel"),
+            self.config.topic("queue/clear"),
+            self.config.topic("pause"),
+            self.config.topic("resume"),
+            self.config.topic("lock/release"),
+            self.config.topic("lease/acquire"),
+            self.config.topic("lease/release"),
+            self.config.topic("lease/steal"),
+            self.config.topic("command"),
         ];
 
         for topic in &topics {
@@ -222,6 +255,22 @@ impl<M: MotorController + Send + 'static> MqttHandler<M> {
             }
         });
 
+        // Spawn ack task: transitions complete in the update loop, not here.
+        // Acks from before the client connected were for someone else.
+        let ack_tx = tx.clone();
+        let state_for_acks = Arc::clone(&self.state);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(ACK_POLL_MS));
+            let mut last_seq = state_for_acks.last_ack_seq();
+            loop {
+                interval.tick().await;
+                for ack in state_for_acks.acks_since(last_seq) {
+                    last_seq = ack.seq;
+                    let _ = ack_tx.send(StateUpdate::Ack(ack_to_json(&ack))).await;
+                }
+            }
+        });
+
         // Spawn publisher task
         let client_for_publish = client.clone();
         let config_for_publish = self.config.clone();
@@ -231,6 +280,17 @@ impl<M: MotorController + Send + 'static> MqttHandler<M> {
                     StateUpdate::Changed(s) | StateUpdate::Heartbeat(s) => {
                         serde_json::to_string(s).unwrap_or_default()
                     }
+                    StateUpdate::Ack(json) => {
+                        let _ = client_for_publish
+                            .publish(
+                                config_for_publish.topic("ack"),
+                                QoS::AtLeastOnce,
+                                false,
+                                json.as_bytes(),
+                            )
+                            .await;
+                        continue;
+                    }
                 };
 
                 // Always publish full state
@@ -291,78 +351,39 @@ impl<M: MotorController + Send + 'static> MqttHandler<M> {
             .map(|s| s.trim_start_matches('/'))
             .unwrap_or(topic);
 
-        let now_ms = self.state.now_ms();
-
-        match suffix {
-            "speed/set" => {
-                if let Ok(req) = serde_json::from_slice::<SetSpeedRequest>(payload) {
-                    let cmd = if req.duration_ms == 0 {
-                        ThrottleCommand::speed_immediate(req.speed).into()
-                    } else if req.smooth {
-                        ThrottleCommand::SetSpeed {
-                            target: req.speed,
-                            strategy: EaseInOut::new(req.duration_ms),
-                        }
-                        .into()
-                    } else {
-                        ThrottleCommand::SetSpeed {
-                            target: req.speed,
-                            strategy: Linear::new(req.duration_ms),
-                        }
-                        .into()
-                    };
-                    self.state.with_controller(|controller| {
-                        let _ = controller.apply_command(cmd, CommandSource::Mqtt, now_ms);
-                    });
-                    self.check_and_publish_changes(tx).await;
-                }
-            }
-
-            "direction/set" => {
-                // Accept either JSON or plain text
-                let direction =
-                    if let Ok(req) = serde_json::from_slice::<SetDirectionRequest>(payload) {
-                        Some(req.direction)
-                    } else if let Ok(text) = std::str::from_utf8(payload) {
-                        match text.trim().to_lowercase().as_str() {
-                            "forward" => Some(Direction::Forward),
-                            "reverse" => Some(Direction::Reverse),
-                            "stopped" | "stop" => Some(Direction::Stopped),
-                            _ => None,
-                        }
-                    } else {
-                        None
-                    };
-
-                if let Some(dir) = direction {
-                    let cmd = ThrottleCommand::<Immediate>::SetDirection(dir).into();
-                    self.state.with_controller(|controller| {
-                        let _ = controller.apply_command(cmd, CommandSource::Mqtt, now_ms);
-                    });
-                    self.check_and_publish_changes(tx).await;
+        if suffix.starts_with("lease/") {
+            match parse_mqtt_lease(suffix, payload) {
+                Ok((action, client)) => {
+                    if let Err(reason) = self.state.lease(action, CommandSource::Mqtt, client) {
+                        eprintln!(
+                            "MQTT: lease {} refused: {}",
+                            action.as_str(),
+                            reason.as_str()
+                        );
+                    }
                 }
+                Err(MessageError::UnknownTopic) => {}
+                Err(e) => eprintln!("MQTT: rejected {}: {}", topic, e),
             }
+            return;
+        }
 
-            "estop" => {
-                let cmd = ThrottleCommand::estop().into();
-                self.state.with_controller(|controller| {
-                    let _ = controller.apply_command(cmd, CommandSource::Mqtt, now_ms);
-                });
-                self.check_and_publish_changes(tx).await;
-            }
-
-            "max-speed/set" => {
-                if let Ok(req) = serde_json::from_slice::<SetMaxSpeedRequest>(payload) {
-                    let cmd = ThrottleCommand::<Immediate>::SetMaxSpeed(req.max_speed).into();
-                    self.state.with_controller(|controller| {
-                        let _ = controller.apply_command(cmd, CommandSource::Mqtt, now_ms);
-                    });
-                    self.check_and_publish_changes(tx).await;
-                }
+        let parsed = match parse_mqtt_command_as(suffix, payload) {
+            Ok(parsed) => parsed,
+            Err(MessageError::UnknownTopic) => return,
+            Err(e) => {
+                eprintln!("MQTT: rejected {}: {}", topic, e);
+                return;
             }
+        };
 
-            _ => {}
-        }
+        let _ = self.state.submit(
+            parsed.command,
+            CommandSource::Mqtt,
+            parsed.client,
+            parsed.correlation_id,
+        );
+        self.check_and_publish_changes(tx).await;
     }
 
     /// Check for state changes and publish if changed.
@@ -376,6 +397,7 @@ impl<M: MotorController + Send + 'static> MqttHandler<M> {
 enum StateUpdate {
     Changed(StateResponse),
     Heartbeat(StateResponse),
+    Ack(String),
 }
 
 impl From<crate::ThrottleState> for StateResponse {
@@ -399,7 +421,9 @@ pub enum MqttError {
 mod tests {
     use super::*;
     use crate::hal::MockMotor;
-    use crate::ThrottleController;
+    use crate::{
+        Direction, HeartbeatLease, SafeStop, Speed, ThrottleCommand, ThrottleController, Velocity,
+    };
 
     // ========================================================================
     // MqttRuntimeConfig tests
@@ -544,14 +568,14 @@ mod tests {
         // Modify state via handler
         let now = state.now_ms();
         handler.state().with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.75).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.75)).into();
             let _ = c.apply_command(cmd, CommandSource::Mqtt, now);
             let _ = c.update(now);
         });
 
         // Original state should see the change
         let snapshot = state.state();
-        assert!((snapshot.speed - 0.75).abs() < 0.01);
+        assert!((snapshot.speed.get() - 0.75).abs() < 0.01);
     }
 
     // ========================================================================
@@ -561,13 +585,21 @@ mod tests {
     #[test]
     fn test_state_update_from_throttle_state() {
         let throttle_state = crate::ThrottleState {
-            speed: 0.42,
+            speed: Speed::literal(0.42),
             direction: Direction::Forward,
-            max_speed: 0.8,
-            target_speed: Some(0.6),
+            velocity: Velocity::new(0.42).unwrap(),
+            max_speed: Speed::literal(0.8),
+            target_speed: Some(Speed::literal(0.6)),
             transition_progress: None,
             fault: None,
             lock_status: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: Default::default(),
         };
 
         let state_response: StateResponse = throttle_state.into();
@@ -663,7 +695,7 @@ mod tests {
         // Make a change
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Mqtt, now);
             let _ = c.update(now);
         });
@@ -682,6 +714,7 @@ mod tests {
                 assert!((state_response.speed - 0.5).abs() < 0.01);
             }
             StateUpdate::Heartbeat(_) => panic!("Expected Changed, got Heartbeat"),
+            StateUpdate::Ack(_) => panic!("Expected Changed, got Ack"),
         }
     }
 
@@ -706,7 +739,7 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!((current.speed - 0.75).abs() < 0.01);
+        assert!((current.speed.get() - 0.75).abs() < 0.01);
     }
 
     #[tokio::test]
@@ -723,7 +756,27 @@ mod tests {
         handler.handle_message("train/speed/set", payload.as_bytes(), &tx).await;
 
         let current = state.state();
-        assert_eq!(current.target_speed, Some(0.8));
+        assert_eq!(current.target_speed, Some(Speed::literal(0.8)));
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_velocity_set() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = MqttRuntimeConfig::default();
+        let handler = MqttHandler::with_shared_state(state.clone(), config);
+
+        let (tx, _rx) = mpsc::channel::<StateUpdate>(32);
+
+        handler.handle_message("train/velocity/set", b"-0.5", &tx).await;
+
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now).unwrap());
+
+        let current = state.state();
+        assert_eq!(current.direction, Direction::Reverse);
+        assert_eq!(current.velocity, -0.5);
     }
 
     #[tokio::test]
@@ -740,7 +793,7 @@ mod tests {
         handler.handle_message("train/speed/set", payload.as_bytes(), &tx).await;
 
         let current = state.state();
-        assert_eq!(current.target_speed, Some(0.6));
+        assert_eq!(current.target_speed, Some(Speed::literal(0.6)));
     }
 
     #[tokio::test]
@@ -864,7 +917,7 @@ mod tests {
         // Set initial speed
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.7).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.7)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -876,7 +929,29 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!(current.speed.get().abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_heartbeat() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor)
+            .with_heartbeat(HeartbeatLease::new(60_000, SafeStop::EmergencyStop));
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = MqttRuntimeConfig::default();
+        let handler = MqttHandler::with_shared_state(state.clone(), config);
+
+        let (tx, _rx) = mpsc::channel::<StateUpdate>(32);
+
+        handler.handle_message("train/speed/set", br#"{"speed": 0.5}"#, &tx).await;
+        let before = state.state().heartbeat.expect("lease should be held");
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        handler.handle_message("train/heartbeat", b"", &tx).await;
+
+        let after = state.state().heartbeat.expect("lease should be held");
+        assert_eq!(after.source, CommandSource::Mqtt);
+        assert!(after.expires_ms > before.expires_ms);
     }
 
     #[tokio::test]
@@ -893,7 +968,7 @@ mod tests {
         handler.handle_message("train/max-speed/set", payload.as_bytes(), &tx).await;
 
         let current = state.state();
-        assert!((current.max_speed - 0.85).abs() < 0.01);
+        assert!((current.max_speed.get() - 0.85).abs() < 0.01);
     }
 
     #[tokio::test]
@@ -931,7 +1006,7 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!((current.speed - 0.6).abs() < 0.01);
+        assert!((current.speed.get() - 0.6).abs() < 0.01);
     }
 
     #[tokio::test]
@@ -15,16 +15,17 @@
 //! // In main loop:
 //! runner.poll()?;                    // Process incoming messages
 //! runner.publish_if_changed()?;      // Publish state changes
+//! runner.publish_acks()?;            // Publish command acknowledgements
 //! ```
 
 use std::sync::Arc;
 
 use crate::config::MqttConfig;
-use crate::messages::parse_mqtt_command;
+use crate::messages::{parse_mqtt_command_as, parse_mqtt_lease, LeaseAction, ParsedCommand};
 use crate::traits::{MotorController, MqttClient};
-use crate::{CommandSource, Direction, ThrottleCommandDyn};
+use crate::{ClientId, CommandSource, Direction};
 
-use super::http_handler::state_to_json;
+use super::http_handler::{ack_to_json, state_to_json};
 use super::SharedThrottleState;
 
 // ============================================================================
@@ -37,6 +38,7 @@ use super::SharedThrottleState;
 /// - Message polling with automatic command parsing
 /// - State change publishing
 /// - Heartbeat publishing
+/// - Command acknowledgements on `{prefix}/ack`
 pub struct MqttServiceRunner<M, C>
 where
     M: MotorController + Send + 'static,
@@ -47,6 +49,7 @@ where
     config: MqttConfig,
     last_published_speed: f32,
     last_published_direction: Direction,
+    last_ack_seq: u64,
 }
 
 impl<M, C> MqttServiceRunner<M, C>
@@ -55,13 +58,17 @@ where
     C: MqttClient,
 {
     /// Create a new MQTT service runner.
+    ///
+    /// Only acknowledgements of commands submitted from now on are published.
     pub fn new(state: Arc<SharedThrottleState<M>>, client: C, config: MqttConfig) -> Self {
+        let last_ack_seq = state.last_ack_seq();
         Self {
             state,
             client,
             config,
             last_published_speed: 0.0,
             last_published_direction: Direction::Stopped,
+            last_ack_seq,
         }
     }
 
@@ -78,31 +85,53 @@ where
     /// Poll for incoming MQTT messages and apply commands.
     ///
     /// This should be called regularly in the main loop. It processes
-    /// all pending messages and applies any valid commands to the controller.
+    /// all pending messages, applies any valid commands to the controller
+    /// and publishes their acknowledgements.
     pub fn poll(&mut self) -> Result<(), C::Error> {
         while let Some(msg) = self.client.try_recv() {
-            if let Some(cmd) = self.parse_message(&msg.topic, &msg.payload) {
-                let now_ms = self.state.now_ms();
-                self.state.with_controller(|controller| {
-                    let _ = controller.apply_command(cmd, CommandSource::Mqtt, now_ms);
-                });
+            if let Some((action, client)) = self.parse_lease(&msg.topic, &msg.payload) {
+                let _ = self.state.lease(action, CommandSource::Mqtt, client);
+            } else if let Some(parsed) = self.parse_message(&msg.topic, &msg.payload) {
+                let _ = self.state.submit(
+                    parsed.command,
+                    CommandSource::Mqtt,
+                    parsed.client,
+                    parsed.correlation_id,
+                );
             }
         }
+        self.publish_acks()?;
         Ok(())
     }
 
+    /// Publish command acknowledgements not yet published to `{prefix}/ack`.
+    ///
+    /// Speed transitions complete during `update()`, so call this regularly
+    /// in the main loop as well as after [`poll`](Self::poll). Returns the
+    /// number of acks published.
+    pub fn publish_acks(&mut self) -> Result<usize, C::Error> {
+        let acks = self.state.acks_since(self.last_ack_seq);
+        let ack_topic = self.topic("ack");
+        for ack in &acks {
+            self.client
+                .publish(&ack_topic, ack_to_json(ack).as_bytes(), false)?;
+            self.last_ack_seq = ack.seq;
+        }
+        Ok(acks.len())
+    }
+
     /// Publish current state if it has changed since last publish.
     ///
     /// Returns `true` if state was published, `false` if unchanged.
     pub fn publish_if_changed(&mut self) -> Result<bool, C::Error> {
         let current_state = self.state.state();
 
-        let speed_changed = (current_state.speed - self.last_published_speed).abs() > 0.001;
+        let speed_changed = (current_state.speed.get() - self.last_published_speed).abs() > 0.001;
         let direction_changed = current_state.direction != self.last_published_direction;
 
         if speed_changed || direction_changed {
             self.publish_state_internal(&current_state)?;
-            self.last_published_speed = current_state.speed;
+            self.last_published_speed = current_state.speed.get();
             self.last_published_direction = current_state.direction;
             Ok(true)
         } else {
@@ -114,7 +143,7 @@ where
     pub fn publish_state(&mut self) -> Result<(), C::Error> {
         let current_state = self.state.state();
         self.publish_state_internal(&current_state)?;
-        self.last_published_speed = current_state.speed;
+        self.last_published_speed = current_state.speed.get();
         self.last_published_direction = current_state.direction;
         Ok(())
     }
@@ -142,7 +171,28 @@ where
 
     /// Subscribe to control topics.
     pub fn subscribe_control_topics(&mut self) -> Result<(), C::Error> {
-        let topics = ["speed/set", "direction/set", "estop", "max-speed/set"];
+        let topics = [
+            "speed/set",
+            "velocity/set",
+            "direction/set",
+            "estop",
+            "max-speed/set",
+            "heartbeat",
+            "service-brake",
+            "service-brake/release",
+            "notch/set",
+            "brake/set",
+            "mode/set",
+            "queue/cancel",
+            "queue/clear",
+            "pause",
+            "resume",
+            "lock/release",
+            "lease/acquire",
+            "lease/release",
+            "lease/steal",
+            "command",
+        ];
         for suffix in topics {
             let topic = self.topic(suffix);
             self.client.subscribe(&topic)?;
@@ -155,13 +205,21 @@ where
         self.config.topic(suffix).to_string()
     }
 
-    /// Parse an MQTT message into a command.
+    /// Parse an MQTT message into a command and what the sender said about it.
     ///
-    /// Delegates to the consolidated `parse_mqtt_command` function in `messages.rs`.
-    fn parse_message(&self, topic: &str, payload: &[u8]) -> Option<ThrottleCommandDyn> {
+    /// Delegates to the consolidated `parse_mqtt_command_as` function in `messages.rs`.
+    /// Rejected payloads (e.g. an out-of-range speed) are dropped.
+    fn parse_message(&self, topic: &str, payload: &[u8]) -> Option<ParsedCommand> {
         let prefix = self.config.topic_prefix.as_str();
         let suffix = topic.strip_prefix(prefix)?.strip_prefix('/')?;
-        parse_mqtt_command(suffix, payload)
+        parse_mqtt_command_as(suffix, payload).ok()
+    }
+
+    /// Parse a lease topic into a lease action and the client asking.
+    fn parse_lease(&self, topic: &str, payload: &[u8]) -> Option<(LeaseAction, Option<ClientId>)> {
+        let prefix = self.config.topic_prefix.as_str();
+        let suffix = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+        parse_mqtt_lease(suffix, payload).ok()
     }
 }
 
@@ -169,7 +227,7 @@ where
 mod tests {
     use super::*;
     use crate::hal::{MockMotor, MockMqtt};
-    use crate::ThrottleController;
+    use crate::{Speed, ThrottleCommand, ThrottleController};
 
     fn setup() -> (Arc<SharedThrottleState<MockMotor>>, MockMqtt, MqttConfig) {
         let motor = MockMotor::new();
@@ -203,6 +261,9 @@ mod tests {
         assert!(client
             .subscriptions
             .contains(&"train/speed/set".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/velocity/set".to_string()));
         assert!(client
             .subscriptions
             .contains(&"train/direction/set".to_string()));
@@ -210,6 +271,43 @@ mod tests {
         assert!(client
             .subscriptions
             .contains(&"train/max-speed/set".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/heartbeat".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/service-brake".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/service-brake/release".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/notch/set".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/brake/set".to_string()));
+        assert!(client.subscriptions.contains(&"train/mode/set".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/queue/cancel".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/queue/clear".to_string()));
+        assert!(client.subscriptions.contains(&"train/pause".to_string()));
+        assert!(client.subscriptions.contains(&"train/resume".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/lock/release".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/lease/acquire".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/lease/release".to_string()));
+        assert!(client
+            .subscriptions
+            .contains(&"train/lease/steal".to_string()));
+        assert!(client.subscriptions.contains(&"train/command".to_string()));
     }
 
     // ========================================================================
@@ -235,7 +333,7 @@ mod tests {
 
         // Check that speed was set
         let current = state.state();
-        assert!((current.speed - 0.75).abs() < 0.01);
+        assert!((current.speed.get() - 0.75).abs() < 0.01);
     }
 
     #[test]
@@ -245,7 +343,7 @@ mod tests {
         // First set a non-zero speed
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = crate::ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = crate::ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -260,41 +358,113 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!(current.speed.get().abs() < 0.01);
     }
 
     #[test]
-    fn test_poll_with_speed_clamped_high() {
+    fn test_poll_with_speed_rejected_high() {
         let (state, mut mqtt, config) = setup();
 
-        // Send speed > 1.0, should be clamped
+        // Speed > 1.0 is rejected, leaving the previous speed in place
+        mqtt.queue_message("train/speed/set", b"0.4".to_vec());
         mqtt.queue_message("train/speed/set", b"1.5".to_vec());
         let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
 
+        runner.poll().unwrap();
         runner.poll().unwrap();
 
         let now = state.now_ms();
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!((current.speed - 1.0).abs() < 0.01);
+        assert!((current.speed.get() - 0.4).abs() < 0.01);
     }
 
     #[test]
-    fn test_poll_with_speed_clamped_negative() {
+    fn test_poll_with_speed_rejected_negative() {
         let (state, mut mqtt, config) = setup();
 
-        // Send negative speed, should be clamped to 0
+        // Negative speed is rejected rather than clamped to 0
+        mqtt.queue_message("train/speed/set", b"0.4".to_vec());
         mqtt.queue_message("train/speed/set", b"-0.5".to_vec());
         let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
 
+        runner.poll().unwrap();
         runner.poll().unwrap();
 
         let now = state.now_ms();
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!((current.speed.get() - 0.4).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_poll_with_velocity_reverses_through_zero() {
+        let (state, mut mqtt, config) = setup();
+
+        mqtt.queue_message("train/velocity/set", b"0.4".to_vec());
+        let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
+
+        runner.poll().unwrap();
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now).unwrap());
+        assert_eq!(state.state().direction, Direction::Forward);
+
+        // First leg stops the train, then the direction flips at zero
+        runner
+            .client_mut()
+            .queue_message("train/velocity/set", b"-0.3".to_vec());
+        runner.poll().unwrap();
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now).unwrap());
+        assert_eq!(state.state().speed.get(), 0.0);
+        assert_eq!(state.state().direction, Direction::Reverse);
+
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now).unwrap());
+        let current = state.state();
+        assert_eq!(current.direction, Direction::Reverse);
+        assert!((current.velocity.get() + 0.3).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_poll_with_service_brake() {
+        let (state, mut mqtt, config) = setup();
+
+        mqtt.queue_message("train/speed/set", b"0.5".to_vec());
+        mqtt.queue_message("train/service-brake", b"hold".to_vec());
+        let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
+
+        runner.poll().unwrap();
+        let brake = state
+            .state()
+            .service_brake
+            .expect("brake should be applied");
+        assert!(brake.hold);
+        assert_eq!(brake.source, CommandSource::Mqtt);
+
+        runner
+            .client_mut()
+            .queue_message("train/service-brake/release", Vec::new());
+        runner.poll().unwrap();
+        assert!(state.state().service_brake.is_none());
+    }
+
+    #[test]
+    fn test_poll_with_cab_controls() {
+        let (state, mut mqtt, config) = setup();
+
+        mqtt.queue_message("train/mode/set", b"cab".to_vec());
+        mqtt.queue_message("train/notch/set", b"5".to_vec());
+        mqtt.queue_message("train/brake/set", br#"{"brake": "lap"}"#.to_vec());
+        let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
+
+        runner.poll().unwrap();
+
+        let cab = state.state().cab.unwrap();
+        assert_eq!(cab.notch.get(), 5);
+        assert_eq!(cab.brake, crate::BrakeSetting::Lap);
     }
 
     // ========================================================================
@@ -315,7 +485,7 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!((current.speed - 0.65).abs() < 0.01);
+        assert!((current.speed.get() - 0.65).abs() < 0.01);
     }
 
     #[test]
@@ -333,7 +503,7 @@ mod tests {
 
         // Should have target_speed set (transition in progress)
         let current = state.state();
-        assert_eq!(current.target_speed, Some(0.8));
+        assert_eq!(current.target_speed, Some(Speed::literal(0.8)));
     }
 
     #[test]
@@ -351,7 +521,7 @@ mod tests {
 
         // Should have target_speed set (transition in progress)
         let current = state.state();
-        assert_eq!(current.target_speed, Some(0.9));
+        assert_eq!(current.target_speed, Some(Speed::literal(0.9)));
     }
 
     #[test]
@@ -390,13 +560,90 @@ mod tests {
         runner.poll().unwrap();
 
         let current = state.state();
-        assert!((current.max_speed - 0.85).abs() < 0.01);
+        assert!((current.max_speed.get() - 0.85).abs() < 0.01);
     }
 
     // ========================================================================
     // Direction command tests
     // ========================================================================
 
+    #[test]
+    fn test_poll_with_lease() {
+        let (state, mut mqtt, config) = setup();
+
+        mqtt.queue_message("train/lease/acquire", b"cab-3".to_vec());
+        mqtt.queue_message("train/direction/set", b"forward".to_vec());
+        let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
+
+        runner.poll().unwrap();
+
+        // Anonymous commands are refused while cab-3 holds the lease
+        let current = state.state();
+        assert_eq!(current.lease.unwrap().client.unwrap().as_str(), "cab-3");
+        assert_eq!(current.direction, Direction::Stopped);
+
+        runner
+            .client_mut()
+            .queue_message("train/lease/release", br#"{"client": "cab-3"}"#.to_vec());
+        runner
+            .client_mut()
+            .queue_message("train/direction/set", b"forward".to_vec());
+        runner.poll().unwrap();
+
+        let current = state.state();
+        assert!(current.lease.is_none());
+        assert_eq!(current.direction, Direction::Forward);
+    }
+
+    #[test]
+    fn test_poll_publishes_acks() {
+        let (state, mut mqtt, config) = setup();
+
+        mqtt.queue_message(
+            "train/command",
+            br#"{"correlation_id": "req-7", "command": {"set_speed": {"speed": 0.5, "strategy": {"linear": {"duration_ms": 100}}}}}"#.to_vec(),
+        );
+        let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
+
+        runner.poll().unwrap();
+        let acks = runner.client().published_to("train/ack");
+        assert_eq!(acks.len(), 2);
+        let accepted = core::str::from_utf8(&acks[0].1).unwrap();
+        assert!(accepted.contains(r#""correlation_id":"req-7""#));
+        assert!(accepted.contains(r#""status":"accepted""#));
+        let started = core::str::from_utf8(&acks[1].1).unwrap();
+        assert!(started.contains(r#""status":"started""#));
+        assert!(!acks[1].2, "acks are not retained");
+
+        // Completion is published once the transition finishes
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now + 200)).unwrap();
+        assert_eq!(runner.publish_acks().unwrap(), 1);
+        assert_eq!(runner.publish_acks().unwrap(), 0);
+        let acks = runner.client().published_to("train/ack");
+        let completed = core::str::from_utf8(&acks[2].1).unwrap();
+        assert!(completed.contains(r#""status":"completed""#));
+        assert!(completed.contains(r#""command":"set_speed""#));
+    }
+
+    #[test]
+    fn test_publish_acks_skips_earlier_commands() {
+        let (state, mqtt, config) = setup();
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.3));
+        state
+            .submit(cmd.into(), CommandSource::WebApi, None, None)
+            .unwrap();
+
+        let mut runner = MqttServiceRunner::new(state.clone(), mqtt, config);
+        assert_eq!(runner.publish_acks().unwrap(), 0);
+
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.6));
+        state
+            .submit(cmd.into(), CommandSource::WebApi, None, None)
+            .unwrap();
+        assert!(runner.publish_acks().unwrap() > 0);
+    }
+
     #[test]
     fn test_poll_with_direction_forward() {
         let (state, mut mqtt, config) = setup();
@@ -492,7 +739,7 @@ mod tests {
         // First set a speed
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = crate::ThrottleCommand::speed_immediate(0.8).into();
+            let cmd = crate::ThrottleCommand::speed_immediate(Speed::literal(0.8)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -507,7 +754,10 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01, "E-stop should set speed to 0");
+        assert!(
+            current.speed.get().abs() < 0.01,
+            "E-stop should set speed to 0"
+        );
     }
 
     #[test]
@@ -517,7 +767,7 @@ mod tests {
         // Set a speed first
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = crate::ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = crate::ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -532,7 +782,7 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!(current.speed.get().abs() < 0.01);
     }
 
     // ========================================================================
@@ -549,7 +799,7 @@ mod tests {
         runner.poll().unwrap();
 
         let current = state.state();
-        assert!((current.max_speed - 0.75).abs() < 0.01);
+        assert!((current.max_speed.get() - 0.75).abs() < 0.01);
     }
 
     #[test]
@@ -562,7 +812,7 @@ mod tests {
         runner.poll().unwrap();
 
         let current = state.state();
-        assert!((current.max_speed - 1.0).abs() < 0.01);
+        assert!((current.max_speed.get() - 1.0).abs() < 0.01);
     }
 
     // ========================================================================
@@ -581,7 +831,7 @@ mod tests {
 
         // Speed should remain at 0 (unchanged)
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!(current.speed.get().abs() < 0.01);
     }
 
     #[test]
@@ -621,7 +871,7 @@ mod tests {
         runner.poll().unwrap();
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!(current.speed.get().abs() < 0.01);
     }
 
     #[test]
@@ -646,7 +896,7 @@ mod tests {
         runner.poll().unwrap();
 
         let current = state.state();
-        assert!(current.speed.abs() < 0.01);
+        assert!(current.speed.get().abs() < 0.01);
     }
 
     // ========================================================================
@@ -666,7 +916,7 @@ mod tests {
         // Change state
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = crate::ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = crate::ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -706,7 +956,7 @@ mod tests {
         // Set specific state
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = crate::ThrottleCommand::speed_immediate(0.42).into();
+            let cmd = crate::ThrottleCommand::speed_immediate(Speed::literal(0.42)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.apply_command(
                 crate::ThrottleCommandDyn::SetDirection(Direction::Reverse),
@@ -780,7 +1030,7 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = state.state();
-        assert!((current.speed - 0.6).abs() < 0.01);
+        assert!((current.speed.get() - 0.6).abs() < 0.01);
     }
 
     #[test]
@@ -803,6 +1053,6 @@ mod tests {
 
         let current = state.state();
         assert_eq!(current.direction, Direction::Forward);
-        assert!((current.speed - 0.8).abs() < 0.01);
+        assert!((current.speed.get() - 0.8).abs() < 0.01);
     }
 }
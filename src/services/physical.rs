@@ -1,7 +1,8 @@
 //! Physical input handler for encoder-based throttle control.
 //!
 //! This module provides a handler for physical rotary encoders and buttons
-//! that integrates with the shared throttle state.
+//! that integrates with the shared throttle state. In cab driving mode the
+//! encoder moves the throttle notch instead of setting speed directly.
 //!
 //! # Usage
 //!
@@ -20,14 +21,32 @@
 use std::sync::Arc;
 
 use crate::traits::{EncoderInput, MotorController};
-use crate::{CommandSource, ThrottleCommand};
+use crate::{CommandSource, Speed, ThrottleCommand, ThrottleCommandDyn};
 
 use super::shared::SharedThrottleState;
 
+/// What the encoder button does when pressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ButtonAction {
+    /// Emergency stop (instant).
+    #[default]
+    EmergencyStop,
+    /// Service brake using the controller's deceleration profile.
+    ///
+    /// With `hold`, a second press while the brake is holding releases it.
+    ServiceBrake {
+        /// Hold the train once stopped.
+        hold: bool,
+    },
+    /// Pause the active speed transition; a second press resumes it.
+    PauseResume,
+}
+
 /// Handler for physical encoder input.
 ///
 /// Polls an encoder and applies speed changes to the shared throttle state
-/// using `CommandSource::Physical` priority (higher than web/MQTT).
+/// using `CommandSource::Physical` priority (higher than web/MQTT). In cab
+/// driving mode each click moves the throttle notch by one.
 pub struct PhysicalInputHandler<M: MotorController, E: EncoderInput> {
     /// Shared throttle state
     state: Arc<SharedThrottleState<M>>,
@@ -37,6 +56,8 @@ pub struct PhysicalInputHandler<M: MotorController, E: EncoderInput> {
     sensitivity: f32,
     /// Dead zone for filtering encoder noise (minimum delta to register)
     dead_zone: i32,
+    /// Action bound to the encoder button
+    button_action: ButtonAction,
 }
 
 impl<M: MotorController, E: EncoderInput> PhysicalInputHandler<M, E> {
@@ -54,6 +75,7 @@ impl<M: MotorController, E: EncoderInput> PhysicalInputHandler<M, E> {
             encoder,
             sensitivity: 0.05,
             dead_zone: 0,
+            button_action: ButtonAction::default(),
         }
     }
 
@@ -75,17 +97,48 @@ impl<M: MotorController, E: EncoderInput> PhysicalInputHandler<M, E> {
         self
     }
 
-    /// Poll the encoder and apply any speed changes.
+    /// Set what the encoder button does (e-stop by default).
+    pub fn with_button_action(mut self, action: ButtonAction) -> Self {
+        self.button_action = action;
+        self
+    }
+
+    /// Poll the encoder and apply any speed or notch changes.
     ///
     /// Call this frequently (e.g., every 10-20ms) in your main loop.
     /// Returns `true` if a command was applied, `false` otherwise.
     pub fn poll(&mut self) -> bool {
         let now_ms = self.state.now_ms();
 
-        // Check for button press (e-stop)
+        // Check for button press
         if self.encoder.button_just_pressed() {
-            let cmd = ThrottleCommand::estop().into();
+            let action = self.button_action;
             self.state.with_controller(|controller| {
+                let cmd = match action {
+                    ButtonAction::EmergencyStop => ThrottleCommand::estop().into(),
+                    ButtonAction::ServiceBrake { hold } => {
+                        let holding = controller
+                            .state(now_ms)
+                            .service_brake
+                            .is_some_and(|brake| brake.hold);
+                        if holding {
+                            ThrottleCommandDyn::ReleaseBrake
+                        } else {
+                            ThrottleCommand::brake(hold).into()
+                        }
+                    }
+                    ButtonAction::PauseResume => {
+                        let paused = controller
+                            .state(now_ms)
+                            .transition_progress
+                            .is_some_and(|progress| progress.paused);
+                        if paused {
+                            ThrottleCommandDyn::Resume
+                        } else {
+                            ThrottleCommandDyn::Pause
+                        }
+                    }
+                };
                 let _ = controller.apply_command(cmd, CommandSource::Physical, now_ms);
             });
             return true;
@@ -102,11 +155,20 @@ impl<M: MotorController, E: EncoderInput> PhysicalInputHandler<M, E> {
         let speed_delta = delta as f32 * self.sensitivity;
 
         self.state.with_controller(|controller| {
+            if let Some(cab) = controller.cab_status() {
+                let notch = cab.notch.step(delta);
+                if notch != cab.notch {
+                    let cmd = ThrottleCommandDyn::SetNotch(notch);
+                    let _ = controller.apply_command(cmd, CommandSource::Physical, now_ms);
+                }
+                return;
+            }
+
             let current = controller.current_speed();
-            let new_speed = (current + speed_delta).clamp(0.0, 1.0);
+            let new_speed = Speed::clamped(current.get() + speed_delta);
 
             // Only apply if speed actually changed
-            if (new_speed - current).abs() > 0.001 {
+            if (new_speed.get() - current.get()).abs() > 0.001 {
                 let cmd = ThrottleCommand::speed_immediate(new_speed).into();
                 let _ = controller.apply_command(cmd, CommandSource::Physical, now_ms);
             }
@@ -134,6 +196,11 @@ impl<M: MotorController, E: EncoderInput> PhysicalInputHandler<M, E> {
     pub fn dead_zone(&self) -> i32 {
         self.dead_zone
     }
+
+    /// Get the action bound to the encoder button.
+    pub fn button_action(&self) -> ButtonAction {
+        self.button_action
+    }
 }
 
 #[cfg(test)]
@@ -164,7 +231,7 @@ mod tests {
 
         // Should have increased speed by 30%
         let current = state.state().speed;
-        assert!((current - 0.3).abs() < 0.01);
+        assert!((current.get() - 0.3).abs() < 0.01);
     }
 
     #[test]
@@ -176,7 +243,7 @@ mod tests {
         // Set initial speed
         let now_ms = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now_ms);
         });
 
@@ -192,6 +259,66 @@ mod tests {
         assert!(current < 0.01);
     }
 
+    #[test]
+    fn test_button_service_brake_toggles_hold() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+
+        let now_ms = state.now_ms();
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
+            let _ = c.apply_command(cmd, CommandSource::Physical, now_ms);
+            let _ = c.update(now_ms);
+        });
+
+        let encoder = MockEncoder::new();
+        let mut handler = PhysicalInputHandler::new(Arc::clone(&state), encoder)
+            .with_button_action(ButtonAction::ServiceBrake { hold: true });
+
+        // First press brakes (gradually, unlike e-stop) and holds
+        handler.encoder_mut().press_button();
+        assert!(handler.poll());
+        let current = state.state();
+        assert_eq!(current.speed, Speed::literal(0.5));
+        assert!(current.service_brake.is_some_and(|b| b.hold));
+
+        // Second press releases
+        handler.encoder_mut().press_button();
+        assert!(handler.poll());
+        assert!(state.state().service_brake.is_none());
+    }
+
+    #[test]
+    fn test_button_pause_resume_toggles() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+
+        let now_ms = state.now_ms();
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::SetSpeed {
+                target: Speed::literal(1.0),
+                strategy: crate::traits::Linear::new(60_000),
+            };
+            let _ = c.apply_command(cmd.into(), CommandSource::WebApi, now_ms);
+        });
+
+        let encoder = MockEncoder::new();
+        let mut handler = PhysicalInputHandler::new(Arc::clone(&state), encoder)
+            .with_button_action(ButtonAction::PauseResume);
+
+        handler.encoder_mut().press_button();
+        assert!(handler.poll());
+        let progress = state.state().transition_progress.unwrap();
+        assert!(progress.paused);
+
+        handler.encoder_mut().press_button();
+        assert!(handler.poll());
+        let progress = state.state().transition_progress.unwrap();
+        assert!(!progress.paused);
+    }
+
     #[test]
     fn test_dead_zone() {
         let motor = MockMotor::new();
@@ -210,6 +337,29 @@ mod tests {
         assert!(handler.poll());
     }
 
+    #[test]
+    fn test_encoder_moves_notch_in_cab_mode() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor).with_driving_mode(crate::DrivingMode::Cab);
+        let state = Arc::new(SharedThrottleState::new(controller));
+
+        let encoder = MockEncoder::new();
+        let mut handler = PhysicalInputHandler::new(Arc::clone(&state), encoder);
+
+        handler.encoder_mut().queue_delta(3);
+        assert!(handler.poll());
+        assert_eq!(state.state().cab.unwrap().notch.get(), 3);
+
+        // Notch saturates at the top of the quadrant
+        handler.encoder_mut().queue_delta(20);
+        assert!(handler.poll());
+        assert_eq!(state.state().cab.unwrap().notch.get(), 8);
+
+        handler.encoder_mut().queue_delta(-2);
+        assert!(handler.poll());
+        assert_eq!(state.state().cab.unwrap().notch.get(), 6);
+    }
+
     #[test]
     fn test_speed_clamping() {
         let motor = MockMotor::new();
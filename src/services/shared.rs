@@ -24,15 +24,60 @@
 //!     // Publish changed_state to MQTT
 //! }
 //! ```
-
-use std::sync::{Arc, Mutex};
+//!
+//! # Throttle Leases
+//!
+//! For operating sessions, a [`ThrottleLease`] makes drivers acquire the
+//! throttle before driving, as in JMRI. While a client holds the lease,
+//! remote commands from anyone else are rejected with
+//! [`RejectReason::NotLeaseHolder`]. Physical controls and e-stops are
+//! never blocked.
+//!
+//! # Source Lockout
+//!
+//! With a [`SourceLockout`] set by [`SharedThrottleState::with_lockout`],
+//! commands from physical controls lock out lower-priority sources for a
+//! while, and those are rejected with [`RejectReason::LowerPriority`]. The
+//! lockout is off by default.
+//!
+//! # Command Acknowledgements
+//!
+//! Commands applied with [`SharedThrottleState::submit`] get a command id,
+//! and their lifecycle is tracked by a [`CommandTracker`]. Speed transition
+//! events are collected from the controller whenever a command is applied
+//! and whenever acks are read, so a transition that finishes during
+//! `update()` is acknowledged by the next reader.
+//!
+//! # Speed Command Coalescing
+//!
+//! A slider or MQTT client can send speed commands far faster than a
+//! transition can follow, and each one would restart the transition. With
+//! a window set by [`SharedThrottleState::with_speed_coalescing`], usually
+//! from [`ThrottleConfig::speed_coalesce_ms`](crate::ThrottleConfig), a
+//! `SetSpeed` arriving within the window of the last one from the same
+//! source and client is held back, answered with
+//! [`TransitionResult::Deferred`], and replaced by any newer one. The
+//! latest is applied once the window has passed, by the next command or
+//! [`SharedThrottleState::update`]. Replaced commands are counted in
+//! [`CommandMetrics::coalesced`](crate::CommandMetrics) and acknowledged
+//! as interrupted. Coalescing is off by default.
+
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Instant;
 
-use crate::traits::MotorController;
+use super::ack::{CommandAck, CommandReceipt, CommandTracker};
+use super::events::{EventLog, StateEvent};
+use crate::messages::LeaseAction;
+use crate::traits::{MotorController, StrategySpec};
 use crate::{
-    CommandOutcome, CommandSource, Direction, ThrottleCommandDyn, ThrottleController, ThrottleState,
+    AuditEntry, ClientId, CommandOutcome, CommandSource, CorrelationId, Direction, LeaseStatus,
+    LockoutStatus, RejectReason, SourceLockout, StrategyPresets, ThrottleCommandDyn,
+    ThrottleController, ThrottleState, TransitionResult,
 };
 
+// The lease is core so ESP32 builds can use it too
+pub use crate::priority::{ThrottleLease, DEFAULT_LEASE_MS};
+
 // ============================================================================
 // State Provider Trait
 // ============================================================================
@@ -54,6 +99,84 @@ pub trait StateProvider: Send + Sync {
         cmd: ThrottleCommandDyn,
         source: CommandSource,
     ) -> Result<CommandOutcome, ()>;
+
+    /// Apply a command on behalf of a named client.
+    ///
+    /// The default ignores the client and calls [`apply_command`](Self::apply_command).
+    #[allow(clippy::result_unit_err)]
+    fn apply_command_as(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<CommandOutcome, ()> {
+        let _ = client;
+        self.apply_command(cmd, source)
+    }
+
+    /// Apply a command and track its lifecycle.
+    ///
+    /// The default doesn't track commands: it calls
+    /// [`apply_command_as`](Self::apply_command_as) and returns no command id.
+    #[allow(clippy::result_unit_err)]
+    fn submit(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        correlation_id: Option<CorrelationId>,
+    ) -> Result<CommandReceipt, ()> {
+        let outcome = self.apply_command_as(cmd, source, client)?;
+        Ok(CommandReceipt {
+            id: None,
+            correlation_id,
+            outcome,
+        })
+    }
+
+    /// Latest acknowledgement of command `id`, if it is still remembered.
+    ///
+    /// The default tracks no commands.
+    fn command(&self, id: u32) -> Option<CommandAck> {
+        let _ = id;
+        None
+    }
+
+    /// Acknowledgements after `seq`, oldest first.
+    ///
+    /// The default tracks no commands.
+    fn acks_since(&self, seq: u64) -> Vec<CommandAck> {
+        let _ = seq;
+        Vec::new()
+    }
+
+    /// Recent commands and who sent them, oldest first.
+    ///
+    /// The default keeps no audit trail.
+    fn audit(&self) -> Vec<AuditEntry> {
+        Vec::new()
+    }
+
+    /// Acquire, release or steal the throttle lease.
+    ///
+    /// Returns the lease now held, `None` after a release. The default has
+    /// no leases, so every request succeeds and nothing is held.
+    fn lease(
+        &self,
+        action: LeaseAction,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<Option<LeaseStatus>, RejectReason> {
+        let _ = (action, source, client);
+        Ok(None)
+    }
+
+    /// Resolve a strategy that may name a preset (`None` if it's unknown).
+    ///
+    /// The default uses the built-in [`StrategyPresets`].
+    fn resolve_preset(&self, spec: &StrategySpec) -> Option<StrategySpec> {
+        StrategyPresets::default().resolve(spec)
+    }
 }
 
 // ============================================================================
@@ -67,6 +190,10 @@ pub struct ChangeDetection {
     pub last_speed: f32,
     /// Last published direction
     pub last_direction: Direction,
+    /// Ids of the last published transition queue
+    pub last_queue: Vec<u32>,
+    /// Whether the last published transition was paused
+    pub last_paused: bool,
 }
 
 impl Default for ChangeDetection {
@@ -74,7 +201,105 @@ impl Default for ChangeDetection {
         Self {
             last_speed: 0.0,
             last_direction: Direction::Stopped,
+            last_queue: Vec::new(),
+            last_paused: false,
+        }
+    }
+}
+
+/// Whether the state's transition is paused.
+fn is_paused(state: &ThrottleState) -> bool {
+    state.transition_progress.as_ref().is_some_and(|p| p.paused)
+}
+
+// ============================================================================
+// Throttle Leases
+// ============================================================================
+
+/// A speed command waiting out its sender's coalescing window.
+#[derive(Debug)]
+struct HeldSpeed {
+    cmd: ThrottleCommandDyn,
+    source: CommandSource,
+    client: Option<ClientId>,
+    correlation_id: Option<CorrelationId>,
+    id: u32,
+}
+
+impl HeldSpeed {
+    fn sender(&self) -> Sender {
+        (self.source, self.client)
+    }
+}
+
+/// Who sent a speed command: a source and an optional client
+type Sender = (CommandSource, Option<ClientId>);
+
+/// Holds back bursts of speed commands from each sender, keeping the latest.
+#[derive(Debug)]
+struct SpeedCoalescer {
+    window_ms: u64,
+    /// When each sender's last speed command reached the controller
+    applied: Vec<(Sender, u64)>,
+    held: Vec<HeldSpeed>,
+    coalesced: u32,
+}
+
+impl SpeedCoalescer {
+    fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            applied: Vec::new(),
+            held: Vec::new(),
+            coalesced: 0,
+        }
+    }
+
+    /// Whether a speed command from `sender` arriving now must wait
+    fn must_hold(&self, sender: Sender, now_ms: u64) -> bool {
+        self.window_ms > 0
+            && self
+                .applied
+                .iter()
+                .any(|&(applied, at_ms)| applied == sender && now_ms < at_ms + self.window_ms)
+    }
+
+    /// Note that a speed command from `sender` reached the controller
+    fn applied(&mut self, sender: Sender, now_ms: u64) {
+        match self
+            .applied
+            .iter_mut()
+            .find(|(applied, _)| *applied == sender)
+        {
+            Some((_, at_ms)) => *at_ms = now_ms,
+            None => self.applied.push((sender, now_ms)),
+        }
+    }
+
+    /// Hold `speed`, returning the command from the same sender it replaces
+    fn hold(&mut self, speed: HeldSpeed) -> Option<HeldSpeed> {
+        let replaced = self
+            .held
+            .iter()
+            .position(|held| held.sender() == speed.sender())
+            .map(|index| self.held.remove(index));
+        if replaced.is_some() {
+            self.coalesced = self.coalesced.saturating_add(1);
+        }
+        self.held.push(speed);
+        replaced
+    }
+
+    /// Take the held commands whose window has passed
+    fn take_due(&mut self, now_ms: u64) -> Vec<HeldSpeed> {
+        let (due, held): (Vec<_>, Vec<_>) = std::mem::take(&mut self.held)
+            .into_iter()
+            .partition(|held| !self.must_hold(held.sender(), now_ms));
+        self.held = held;
+        for speed in &due {
+            self.applied(speed.sender(), now_ms);
         }
+        due
     }
 }
 
@@ -103,6 +328,21 @@ pub struct SharedThrottleState<M: MotorController> {
 
     /// Change detection for MQTT publishing (separate lock for less contention)
     change_detection: Mutex<ChangeDetection>,
+
+    /// Throttle lease for operating sessions
+    lease: Mutex<ThrottleLease>,
+
+    /// Physical control lockout
+    lockout: Mutex<SourceLockout>,
+
+    /// Command ids and acknowledgements
+    tracker: Mutex<CommandTracker>,
+
+    /// Speed commands held back to coalesce bursts
+    speeds: Mutex<SpeedCoalescer>,
+
+    /// State events for Server-Sent Events clients
+    events: Mutex<EventLog>,
 }
 
 impl<M: MotorController> SharedThrottleState<M> {
@@ -115,9 +355,40 @@ impl<M: MotorController> SharedThrottleState<M> {
             controller: Mutex::new(controller),
             start_time: Instant::now(),
             change_detection: Mutex::new(ChangeDetection::default()),
+            lease: Mutex::new(ThrottleLease::default()),
+            lockout: Mutex::new(SourceLockout::new(0)),
+            tracker: Mutex::new(CommandTracker::new()),
+            speeds: Mutex::new(SpeedCoalescer::new(0)),
+            events: Mutex::new(EventLog::new()),
         }
     }
 
+    /// Use `lease` to control who may drive (default: optional, 60s).
+    pub fn with_lease(mut self, lease: ThrottleLease) -> Self {
+        self.lease = Mutex::new(lease);
+        self
+    }
+
+    /// Use `lockout` to hold off lower-priority sources after physical
+    /// control commands (default: off).
+    pub fn with_lockout(mut self, lockout: SourceLockout) -> Self {
+        self.lockout = Mutex::new(lockout);
+        self
+    }
+
+    /// Hold speed commands arriving within `window_ms` of the last one from
+    /// their source and client (default: off, as with 0).
+    pub fn with_speed_coalescing(mut self, window_ms: u64) -> Self {
+        self.speeds = Mutex::new(SpeedCoalescer::new(window_ms));
+        self
+    }
+
+    /// Use `events` to record state events (default: speed at most every 100ms).
+    pub fn with_events(mut self, events: EventLog) -> Self {
+        self.events = Mutex::new(events);
+        self
+    }
+
     /// Get current timestamp in milliseconds since state creation.
     ///
     /// This is the unified time source for all services. Using the same time base
@@ -159,14 +430,297 @@ impl<M: MotorController> SharedThrottleState<M> {
     /// This acquires the controller lock briefly to get the current state.
     /// Preferred for web GET requests where you just need the current values.
     pub fn state(&self) -> ThrottleState {
+        self.snapshot(self.now_ms())
+    }
+
+    /// Apply a command on behalf of a named client.
+    ///
+    /// Remote commands from anyone but the [`ThrottleLease`] holder are
+    /// rejected with [`RejectReason::NotLeaseHolder`] and audited, as are
+    /// commands held off by the [`SourceLockout`].
+    pub fn apply_command_as(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<CommandOutcome, M::Error> {
+        self.submit(cmd, source, client, None)
+            .map(|receipt| receipt.outcome)
+    }
+
+    /// Apply a command on behalf of a named client and track its lifecycle.
+    ///
+    /// Like [`apply_command_as`](Self::apply_command_as), but the receipt
+    /// carries a command id whose acknowledgements can be read with
+    /// [`command`](Self::command) and [`acks_since`](Self::acks_since).
+    /// Heartbeats are not tracked.
+    ///
+    /// Speed commands in a burst from one source are coalesced: see the
+    /// [module docs](self#speed-command-coalescing). An e-stop drops any
+    /// held speed commands.
+    pub fn submit(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        correlation_id: Option<CorrelationId>,
+    ) -> Result<CommandReceipt, M::Error> {
         let now_ms = self.now_ms();
-        let controller = self.controller.lock().unwrap();
-        controller.state(now_ms)
+        self.apply_held_speeds(now_ms)?;
+        match cmd {
+            ThrottleCommandDyn::EmergencyStop => self.drop_held_speeds(now_ms),
+            ThrottleCommandDyn::SetSpeed { .. } => {
+                let mut speeds = self.speeds.lock().unwrap();
+                let allowed = self.check(&cmd, source, client, now_ms).is_ok();
+                if allowed && speeds.must_hold((source, client), now_ms) {
+                    drop(speeds);
+                    let held = HeldSpeed {
+                        cmd,
+                        source,
+                        client,
+                        correlation_id,
+                        id: 0,
+                    };
+                    return Ok(self.hold_speed(held, now_ms));
+                }
+                speeds.applied((source, client), now_ms);
+            }
+            _ => {}
+        }
+        self.dispatch(cmd, source, client, correlation_id, None, now_ms)
+    }
+
+    /// Apply held speed commands that are due and advance the controller.
+    ///
+    /// Call this from the update loop rather than
+    /// [`ThrottleController::update`], so the last command of a burst isn't
+    /// left waiting for the next one.
+    pub fn update(&self) -> Result<(), M::Error> {
+        let now_ms = self.now_ms();
+        self.apply_held_speeds(now_ms)?;
+        self.with_controller(|controller| controller.update(now_ms))
+    }
+
+    /// Apply `cmd` to the controller and record its outcome.
+    ///
+    /// `id` is the command id of a held speed command, which already has one.
+    fn dispatch(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        correlation_id: Option<CorrelationId>,
+        id: Option<u32>,
+        now_ms: u64,
+    ) -> Result<CommandReceipt, M::Error> {
+        let name = cmd.name();
+        let tracked = !matches!(cmd, ThrottleCommandDyn::Heartbeat);
+        let checked = self.check(&cmd, source, client, now_ms);
+        let (outcome, transition, events) = self.with_controller(|controller| {
+            let outcome = match checked {
+                Ok(()) => controller.apply_command_as(cmd, source, client, now_ms)?,
+                Err(reason) => {
+                    controller.audit_rejected(&cmd, source, client, reason.clone(), now_ms);
+                    CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason })
+                }
+            };
+            let events: Vec<_> =
+                core::iter::from_fn(|| controller.pop_transition_event()).collect();
+            Ok((outcome, controller.transition_id(), events))
+        })?;
+        if !matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })
+        ) {
+            self.lease.lock().unwrap().renew(source, client, now_ms);
+        }
+
+        let mut tracker = self.tracker.lock().unwrap();
+        for event in events {
+            tracker.on_transition(event, now_ms);
+        }
+        let id = id.or_else(|| {
+            tracked.then(|| tracker.submit(name, source, client, correlation_id, now_ms))
+        });
+        if let Some(id) = id {
+            tracker.resolve(id, &outcome, transition, now_ms);
+        }
+        Ok(CommandReceipt {
+            id,
+            correlation_id,
+            outcome,
+        })
+    }
+
+    /// Check `cmd` against the source lockout, then the lease.
+    fn check(
+        &self,
+        cmd: &ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
+        self.lockout.lock().unwrap().check(cmd, source, now_ms)?;
+        self.lease
+            .lock()
+            .unwrap()
+            .check(cmd, source, client, now_ms)
+    }
+
+    /// Hold a speed command back, replacing any held from the same sender.
+    fn hold_speed(&self, mut held: HeldSpeed, now_ms: u64) -> CommandReceipt {
+        let outcome = CommandOutcome::SpeedTransition(TransitionResult::Deferred);
+        let mut tracker = self.tracker.lock().unwrap();
+        held.id = tracker.submit(
+            held.cmd.name(),
+            held.source,
+            held.client,
+            held.correlation_id,
+            now_ms,
+        );
+        tracker.resolve(held.id, &outcome, None, now_ms);
+        let receipt = CommandReceipt {
+            id: Some(held.id),
+            correlation_id: held.correlation_id,
+            outcome,
+        };
+        if let Some(replaced) = self.speeds.lock().unwrap().hold(held) {
+            tracker.supersede(replaced.id, now_ms);
+        }
+        receipt
+    }
+
+    /// Apply the held speed commands whose window has passed.
+    fn apply_held_speeds(&self, now_ms: u64) -> Result<(), M::Error> {
+        let due = self.speeds.lock().unwrap().take_due(now_ms);
+        for held in due {
+            self.dispatch(
+                held.cmd,
+                held.source,
+                held.client,
+                held.correlation_id,
+                Some(held.id),
+                now_ms,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Drop every held speed command.
+    fn drop_held_speeds(&self, now_ms: u64) {
+        let dropped = std::mem::take(&mut self.speeds.lock().unwrap().held);
+        let mut tracker = self.tracker.lock().unwrap();
+        for held in dropped {
+            tracker.supersede(held.id, now_ms);
+        }
+    }
+
+    /// Latest acknowledgement of command `id`, if it is still remembered.
+    pub fn command(&self, id: u32) -> Option<CommandAck> {
+        self.sync_acks();
+        self.tracker.lock().unwrap().get(id).cloned()
+    }
+
+    /// Acknowledgements after `seq`, oldest first.
+    ///
+    /// Pass the `seq` of the last ack read, or 0 for all that are kept.
+    pub fn acks_since(&self, seq: u64) -> Vec<CommandAck> {
+        self.sync_acks();
+        self.tracker
+            .lock()
+            .unwrap()
+            .acks_since(seq)
+            .cloned()
+            .collect()
+    }
+
+    /// `seq` of the newest acknowledgement, for readers that only want what follows.
+    pub fn last_ack_seq(&self) -> u64 {
+        self.tracker.lock().unwrap().last_seq()
+    }
+
+    /// Follow speed transitions that started or finished since the last look.
+    fn sync_acks(&self) {
+        let now_ms = self.now_ms();
+        let events: Vec<_> = self.with_controller(|controller| {
+            core::iter::from_fn(|| controller.pop_transition_event()).collect()
+        });
+        let mut tracker = self.tracker.lock().unwrap();
+        for event in events {
+            tracker.on_transition(event, now_ms);
+        }
+    }
+
+    /// State events after `id`, oldest first.
+    ///
+    /// Returns `None` if some were already dropped; start over from
+    /// [`event_snapshot`](Self::event_snapshot).
+    pub fn events_since(&self, id: u64) -> Option<Vec<StateEvent>> {
+        let (events, _) = self.observe_events();
+        events.since(id)
+    }
+
+    /// Current state and the id of the newest event it includes.
+    pub fn event_snapshot(&self) -> (u64, ThrottleState) {
+        let (events, state) = self.observe_events();
+        (events.last_id(), state)
+    }
+
+    /// Record state events up to now.
+    ///
+    /// The event log is locked before taking the snapshot, so concurrent
+    /// readers can't record an older state after a newer one.
+    fn observe_events(&self) -> (MutexGuard<'_, EventLog>, ThrottleState) {
+        let mut events = self.events.lock().unwrap();
+        let now_ms = self.now_ms();
+        let state = self.snapshot(now_ms);
+        events.observe(&state, now_ms);
+        (events, state)
+    }
+
+    /// Acquire, release or steal the throttle lease.
+    ///
+    /// Returns the lease now held, `None` after a release. Steals are ranked
+    /// with the controller's [`SourcePriorities`](crate::SourcePriorities).
+    pub fn lease(
+        &self,
+        action: LeaseAction,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<Option<LeaseStatus>, RejectReason> {
+        let now_ms = self.now_ms();
+        let priorities = self.with_controller(|controller| *controller.priorities());
+        let mut lease = self.lease.lock().unwrap();
+        match action {
+            LeaseAction::Acquire => lease.acquire(source, client, now_ms).map(Some),
+            LeaseAction::Release => lease.release(source, client, now_ms).map(|()| None),
+            LeaseAction::Steal => lease.steal(source, client, &priorities, now_ms).map(Some),
+        }
+    }
+
+    /// Get the current throttle lease holder.
+    pub fn lease_status(&self) -> Option<LeaseStatus> {
+        self.lease.lock().unwrap().status(self.now_ms())
+    }
+
+    /// Get the current source lockout, if any.
+    pub fn lockout_status(&self) -> Option<LockoutStatus> {
+        self.lockout.lock().unwrap().status(self.now_ms())
+    }
+
+    /// Controller state with the lease holder, lockout and coalescing count filled in.
+    fn snapshot(&self, now_ms: u64) -> ThrottleState {
+        let mut state = self.controller.lock().unwrap().state(now_ms);
+        state.lease = self.lease.lock().unwrap().status(now_ms);
+        state.lockout = self.lockout.lock().unwrap().status(now_ms);
+        state.metrics.coalesced = self.speeds.lock().unwrap().coalesced;
+        state
     }
 
     /// Check for state changes since last check and update detection state.
     ///
-    /// Returns `Some(ThrottleState)` if speed or direction changed since the last call,
+    /// Returns `Some(ThrottleState)` if speed, direction or the transition queue
+    /// changed since the last call,
     /// `None` if unchanged. Used by MQTT to publish only when state changes.
     ///
     /// # Example
@@ -181,19 +735,22 @@ impl<M: MotorController> SharedThrottleState<M> {
         let now_ms = self.now_ms();
 
         // Get current state (brief lock)
-        let state = {
-            let controller = self.controller.lock().unwrap();
-            controller.state(now_ms)
-        };
+        let state = self.snapshot(now_ms);
 
         // Check for changes (separate lock)
         let mut detection = self.change_detection.lock().unwrap();
-        let speed_changed = (state.speed - detection.last_speed).abs() > 0.001;
+        let speed_changed = (state.speed.get() - detection.last_speed).abs() > 0.001;
         let direction_changed = state.direction != detection.last_direction;
+        let queue: Vec<u32> = state.queue.iter().map(|q| q.id).collect();
+        let queue_changed = queue != detection.last_queue;
+        let paused = is_paused(&state);
+        let paused_changed = paused != detection.last_paused;
 
-        if speed_changed || direction_changed {
-            detection.last_speed = state.speed;
+        if speed_changed || direction_changed || queue_changed || paused_changed {
+            detection.last_speed = state.speed.get();
             detection.last_direction = state.direction;
+            detection.last_queue = queue;
+            detection.last_paused = paused;
             Some(state)
         } else {
             None
@@ -212,8 +769,10 @@ impl<M: MotorController> SharedThrottleState<M> {
         };
 
         let mut detection = self.change_detection.lock().unwrap();
-        detection.last_speed = state.speed;
+        detection.last_speed = state.speed.get();
         detection.last_direction = state.direction;
+        detection.last_queue = state.queue.iter().map(|q| q.id).collect();
+        detection.last_paused = is_paused(&state);
     }
 
     /// Get current change detection values (for debugging/testing).
@@ -240,12 +799,51 @@ impl<M: MotorController + Send + 'static> StateProvider for Arc<SharedThrottleSt
         cmd: ThrottleCommandDyn,
         source: CommandSource,
     ) -> Result<CommandOutcome, ()> {
-        let now_ms = self.now_ms();
-        self.with_controller(|controller| {
-            controller
-                .apply_command(cmd, source, now_ms)
-                .map_err(|_| ())
-        })
+        self.apply_command_as(cmd, source, None)
+    }
+
+    fn apply_command_as(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<CommandOutcome, ()> {
+        SharedThrottleState::apply_command_as(self, cmd, source, client).map_err(|_| ())
+    }
+
+    fn submit(
+        &self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        correlation_id: Option<CorrelationId>,
+    ) -> Result<CommandReceipt, ()> {
+        SharedThrottleState::submit(self, cmd, source, client, correlation_id).map_err(|_| ())
+    }
+
+    fn command(&self, id: u32) -> Option<CommandAck> {
+        SharedThrottleState::command(self, id)
+    }
+
+    fn acks_since(&self, seq: u64) -> Vec<CommandAck> {
+        SharedThrottleState::acks_since(self, seq)
+    }
+
+    fn audit(&self) -> Vec<AuditEntry> {
+        self.with_controller(|controller| controller.audit().cloned().collect())
+    }
+
+    fn lease(
+        &self,
+        action: LeaseAction,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<Option<LeaseStatus>, RejectReason> {
+        SharedThrottleState::lease(self, action, source, client)
+    }
+
+    fn resolve_preset(&self, spec: &StrategySpec) -> Option<StrategySpec> {
+        self.with_controller(|controller| controller.presets().resolve(spec))
     }
 }
 
@@ -253,7 +851,9 @@ impl<M: MotorController + Send + 'static> StateProvider for Arc<SharedThrottleSt
 mod tests {
     use super::*;
     use crate::hal::MockMotor;
-    use crate::{CommandSource, ThrottleCommand};
+    use crate::services::CommandStatus;
+    use crate::traits::{EaseInOut, Linear};
+    use crate::{CommandSource, Speed, ThrottleCommand, ThrottleCommandDyn, Velocity};
 
     // ========================================================================
     // SharedThrottleState tests
@@ -276,7 +876,7 @@ mod tests {
 
         // Should be able to access controller mutably
         state.with_controller(|c| {
-            assert_eq!(c.current_speed(), 0.0);
+            assert_eq!(c.current_speed().get(), 0.0);
         });
     }
 
@@ -305,7 +905,7 @@ mod tests {
         // Apply speed change and update to apply it
         let now_ms = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now_ms);
             let _ = c.update(now_ms); // Apply the command
         });
@@ -313,13 +913,85 @@ mod tests {
         // Should detect change
         let changed = state.check_changes();
         assert!(changed.is_some());
-        assert!((changed.unwrap().speed - 0.5).abs() < 0.01);
+        assert!((changed.unwrap().speed.get() - 0.5).abs() < 0.01);
 
         // No further change
         let no_change = state.check_changes();
         assert!(no_change.is_none());
     }
 
+    #[test]
+    fn test_change_detection_queue() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = SharedThrottleState::new(controller);
+
+        let now_ms = state.now_ms();
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::SetSpeed {
+                target: Speed::literal(0.0),
+                strategy: EaseInOut::arrival(60_000),
+            };
+            let _ = c.apply_command(cmd.into(), CommandSource::Physical, now_ms);
+        });
+        state.sync_change_detection();
+
+        // Queueing behind the arrival changes only the queue
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
+            let _ = c.apply_command(cmd, CommandSource::Mqtt, now_ms);
+        });
+        let changed = state.check_changes().unwrap();
+        assert_eq!(changed.queue.len(), 1);
+        assert!(state.check_changes().is_none());
+
+        state.with_controller(|c| {
+            let _ = c.apply_command(ThrottleCommandDyn::ClearQueue, CommandSource::Mqtt, now_ms);
+        });
+        assert!(state.check_changes().unwrap().queue.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_preset_uses_controller_registry() {
+        let presets = crate::StrategyPresets::empty()
+            .with_preset("shunt", Linear::new(4000))
+            .unwrap();
+        let controller = ThrottleController::new(MockMotor::new()).with_presets(presets);
+        let state = Arc::new(SharedThrottleState::new(controller));
+
+        let resolved = state.resolve_preset(&StrategySpec::preset("shunt").unwrap());
+        assert_eq!(resolved, Some(StrategySpec::Linear(Linear::new(4000))));
+        assert_eq!(
+            state.resolve_preset(&StrategySpec::preset("departure").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_change_detection_pause() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = SharedThrottleState::new(controller);
+
+        let now_ms = state.now_ms();
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::SetSpeed {
+                target: Speed::literal(1.0),
+                strategy: EaseInOut::new(60_000),
+            };
+            let _ = c.apply_command(cmd.into(), CommandSource::WebApi, now_ms);
+        });
+        state.sync_change_detection();
+
+        // Pausing holds the speed but is still a change worth publishing
+        state.with_controller(|c| {
+            let _ = c.apply_command(ThrottleCommandDyn::Pause, CommandSource::WebApi, now_ms);
+        });
+        let changed = state.check_changes().unwrap();
+        assert!(changed.transition_progress.unwrap().paused);
+        assert!(state.check_changes().is_none());
+    }
+
     #[test]
     fn test_sync_change_detection() {
         let motor = MockMotor::new();
@@ -329,7 +1001,7 @@ mod tests {
         // Apply a change
         let now_ms = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.7).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.7)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now_ms);
         });
 
@@ -400,7 +1072,7 @@ mod tests {
         let controller = ThrottleController::new(motor);
         let state = Arc::new(SharedThrottleState::new(controller));
 
-        let cmd = ThrottleCommand::speed_immediate(0.6).into();
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.6)).into();
         let result = StateProvider::apply_command(&state, cmd, CommandSource::WebApi);
 
         assert!(result.is_ok());
@@ -410,7 +1082,7 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = StateProvider::state(&state);
-        assert!((current.speed - 0.6).abs() < 0.01);
+        assert!((current.speed.get() - 0.6).abs() < 0.01);
     }
 
     #[test]
@@ -435,7 +1107,7 @@ mod tests {
         let state = Arc::new(SharedThrottleState::new(controller));
 
         // First set a speed
-        let cmd = ThrottleCommand::speed_immediate(0.8).into();
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.8)).into();
         let _ = StateProvider::apply_command(&state, cmd, CommandSource::Physical);
         let now = state.now_ms();
         state.with_controller(|c| c.update(now).unwrap());
@@ -450,7 +1122,10 @@ mod tests {
         state.with_controller(|c| c.update(now).unwrap());
 
         let current = StateProvider::state(&state);
-        assert!(current.speed.abs() < 0.01, "E-stop should set speed to 0");
+        assert!(
+            current.speed.get().abs() < 0.01,
+            "E-stop should set speed to 0"
+        );
     }
 
     #[test]
@@ -459,13 +1134,226 @@ mod tests {
         let controller = ThrottleController::new(motor);
         let state = Arc::new(SharedThrottleState::new(controller));
 
-        let cmd = ThrottleCommandDyn::SetMaxSpeed(0.75);
+        let cmd = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.75));
         let result = StateProvider::apply_command(&state, cmd, CommandSource::WebApi);
 
         assert!(result.is_ok());
 
         let current = StateProvider::state(&state);
-        assert!((current.max_speed - 0.75).abs() < 0.01);
+        assert!((current.max_speed.get() - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_state_provider_apply_command_as_client() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let alice = ClientId::new("alice").unwrap();
+
+        // Arrivals take a source lock
+        let cmd = ThrottleCommand::SetSpeed {
+            target: Speed::literal(0.0),
+            strategy: EaseInOut::arrival(60_000),
+        };
+        let _ = state.apply_command_as(cmd.into(), CommandSource::WebApi, Some(alice));
+
+        let lock = StateProvider::state(&state).lock_status.unwrap();
+        assert_eq!(lock.client, Some(alice));
+        let audit = StateProvider::audit(&state);
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].command, "set_speed");
+        assert_eq!(audit[0].client, Some(alice));
+    }
+
+    #[test]
+    fn test_submit_coalesces_speed_floods() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = SharedThrottleState::new(controller).with_speed_coalescing(200);
+        let set_speed = |target: f32| {
+            let cmd = ThrottleCommand::SetSpeed {
+                target: Speed::literal(target),
+                strategy: Linear::new(1000),
+            };
+            state
+                .submit(cmd.into(), CommandSource::Mqtt, None, None)
+                .unwrap()
+        };
+
+        // A flood: the first starts a transition, the rest wait and replace each other
+        let receipts: Vec<_> = (1..=10).map(|i| set_speed(i as f32 / 10.0)).collect();
+        assert!(matches!(
+            receipts[0].outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Started)
+        ));
+        assert!(receipts[1..].iter().all(|r| matches!(
+            r.outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Deferred)
+        )));
+        assert_eq!(state.state().metrics.coalesced, 8);
+        assert_eq!(state.state().target_speed, Some(Speed::literal(0.1)));
+        let superseded = receipts[1].id.unwrap();
+        assert_eq!(
+            state.command(superseded).unwrap().status,
+            CommandStatus::Interrupted
+        );
+
+        // Only the last target is applied, once the window has passed
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        state.update().unwrap();
+        assert_eq!(state.state().target_speed, Some(Speed::literal(1.0)));
+        let last = receipts[9].id.unwrap();
+        assert_eq!(state.command(last).unwrap().status, CommandStatus::Started);
+
+        // An e-stop drops held speed commands
+        let held = set_speed(0.3);
+        let _ = state.submit(
+            ThrottleCommandDyn::EmergencyStop,
+            CommandSource::Mqtt,
+            None,
+            None,
+        );
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        state.update().unwrap();
+        assert_eq!(state.state().target_speed, None);
+        assert_eq!(state.state().speed, Speed::ZERO);
+        assert_eq!(
+            state.command(held.id.unwrap()).unwrap().status,
+            CommandStatus::Interrupted
+        );
+        assert_eq!(state.state().metrics.coalesced, 8);
+    }
+
+    // ========================================================================
+    // Throttle lease tests
+    // ========================================================================
+
+    #[test]
+    fn test_submit_tracks_reversal_as_one_command() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        state.with_controller(|c| {
+            let _ = c.apply_command(
+                ThrottleCommand::velocity_immediate(Velocity::new(0.5).unwrap()).into(),
+                CommandSource::Physical,
+                0,
+            );
+            c.update(0).unwrap();
+        });
+        let tag = CorrelationId::new("flip").unwrap();
+
+        let cmd = ThrottleCommand::SetVelocity {
+            target: Velocity::new(-0.5).unwrap(),
+            strategy: Linear::new(100),
+        };
+        let receipt = state
+            .submit(cmd.into(), CommandSource::WebApi, None, Some(tag))
+            .unwrap();
+        let id = receipt.id.unwrap();
+        assert_eq!(receipt.correlation_id, Some(tag));
+        assert_eq!(state.command(id).unwrap().status, CommandStatus::Started);
+
+        // First leg done: still running, now in reverse
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now + 150)).unwrap();
+        assert_eq!(state.command(id).unwrap().status, CommandStatus::Started);
+
+        state.with_controller(|c| c.update(now + 300)).unwrap();
+        let ack = state.command(id).unwrap();
+        assert_eq!(ack.status, CommandStatus::Completed);
+        assert_eq!(ack.correlation_id, Some(tag));
+        let statuses: Vec<_> = state
+            .acks_since(0)
+            .iter()
+            .map(|ack| ack.status.as_str())
+            .collect();
+        assert_eq!(statuses, ["accepted", "started", "completed"]);
+
+        // Heartbeats aren't tracked
+        let receipt = state
+            .submit(
+                ThrottleCommandDyn::Heartbeat,
+                CommandSource::WebApi,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(receipt.id, None);
+    }
+
+    #[test]
+    fn test_rejected_commands_dont_renew_lease() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = SharedThrottleState::new(controller);
+        let alice = Some(ClientId::new("alice").unwrap());
+
+        let acquired = state
+            .lease(LeaseAction::Acquire, CommandSource::WebApi, alice)
+            .unwrap()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        // Nothing to resume: rejected, so the lease isn't renewed
+        let outcome =
+            state.apply_command_as(ThrottleCommandDyn::Resume, CommandSource::WebApi, alice);
+        assert!(matches!(
+            outcome,
+            Ok(CommandOutcome::SpeedTransition(
+                TransitionResult::Rejected { .. }
+            ))
+        ));
+        assert_eq!(
+            state.lease_status().unwrap().expires_ms,
+            acquired.expires_ms
+        );
+
+        let cmd = ThrottleCommandDyn::SetDirection(Direction::Forward);
+        let _ = state.apply_command_as(cmd, CommandSource::WebApi, alice);
+        assert!(state.lease_status().unwrap().expires_ms > acquired.expires_ms);
+    }
+
+    #[test]
+    fn test_state_provider_lease_rejects_non_holder() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+
+        let lease =
+            StateProvider::lease(&state, LeaseAction::Acquire, CommandSource::WebApi, alice)
+                .unwrap()
+                .unwrap();
+        assert_eq!(lease.client, alice);
+
+        let cmd = ThrottleCommandDyn::SetDirection(Direction::Reverse);
+        let outcome = state.apply_command_as(cmd, CommandSource::WebApi, bob);
+        assert!(matches!(
+            outcome,
+            Ok(CommandOutcome::SpeedTransition(
+                TransitionResult::Rejected {
+                    reason: RejectReason::NotLeaseHolder,
+                }
+            ))
+        ));
+        assert_eq!(state.state().direction, Direction::Stopped);
+
+        let cmd = ThrottleCommandDyn::SetDirection(Direction::Reverse);
+        let _ = state.apply_command_as(cmd, CommandSource::WebApi, alice);
+        assert_eq!(state.state().direction, Direction::Reverse);
+
+        // Holder shows in the state, and the refusal in the audit trail
+        assert_eq!(state.state().lease.unwrap().client, alice);
+        let audit = StateProvider::audit(&state);
+        assert_eq!(audit[0].client, bob);
+        assert_eq!(audit[0].rejected, Some(RejectReason::NotLeaseHolder));
+
+        let released =
+            StateProvider::lease(&state, LeaseAction::Release, CommandSource::WebApi, alice);
+        assert_eq!(released, Ok(None));
+        assert!(state.lease_status().is_none());
     }
 
     #[test]
@@ -475,10 +1363,10 @@ mod tests {
         let state = Arc::new(SharedThrottleState::new(controller));
 
         // Apply from different sources
-        let cmd1 = ThrottleCommand::speed_immediate(0.3).into();
+        let cmd1 = ThrottleCommand::speed_immediate(Speed::literal(0.3)).into();
         let _ = StateProvider::apply_command(&state, cmd1, CommandSource::WebApi);
 
-        let cmd2 = ThrottleCommand::speed_immediate(0.7).into();
+        let cmd2 = ThrottleCommand::speed_immediate(Speed::literal(0.7)).into();
         let _ = StateProvider::apply_command(&state, cmd2, CommandSource::Mqtt);
 
         let now = state.now_ms();
@@ -486,7 +1374,9 @@ mod tests {
 
         // Second command should have overwritten (unless priority lockout)
         let current = StateProvider::state(&state);
-        assert!((current.speed - 0.7).abs() < 0.01 || (current.speed - 0.3).abs() < 0.01);
+        assert!(
+            (current.speed.get() - 0.7).abs() < 0.01 || (current.speed.get() - 0.3).abs() < 0.01
+        );
     }
 
     #[test]
@@ -503,8 +1393,7 @@ mod tests {
         let handle1 = thread::spawn(move || {
             for i in 0..10 {
                 let _ = StateProvider::state(&state1);
-                let speed = (i as f32) / 20.0;
-                let cmd = ThrottleCommand::speed_immediate(speed).into();
+                let cmd = ThrottleCommand::speed_immediate(Speed::literal(i as f32 / 20.0)).into();
                 let _ = StateProvider::apply_command(&state1, cmd, CommandSource::WebApi);
             }
         });
@@ -7,30 +7,98 @@
 //!
 //! - GET `/api/state` - Current throttle state
 //! - POST `/api/speed` - Set speed with optional transition
+//! - POST `/api/velocity` - Set signed velocity, reversing through zero
 //! - POST `/api/direction` - Set direction
 //! - POST `/api/estop` - Emergency stop
 //! - POST `/api/max-speed` - Set maximum speed limit
+//! - POST `/api/service-brake` - Brake to a stop, optionally holding
+//! - POST `/api/service-brake/release` - Release a held service brake
+//! - POST `/api/notch` - Select a throttle notch (cab driving mode)
+//! - POST `/api/brake` - Move the brake handle (cab driving mode)
+//! - POST `/api/mode` - Switch between direct and cab driving mode
+//! - GET `/api/queue` - List queued speed commands
+//! - POST `/api/queue/cancel` - Cancel one queued speed command
+//! - POST `/api/queue/clear` - Cancel all queued speed commands
+//! - POST `/api/preview` - Sample a strategy's speed curve without moving the train
+//! - POST `/api/pause` - Freeze the active speed transition
+//! - POST `/api/resume` - Continue a paused speed transition
+//! - POST `/api/lock/release` - Force release the transition lock
+//! - GET `/api/metrics` - Counts of rate-limited, coalesced and queue-full drops
+//! - GET `/api/audit` - Recent commands with the source and client that sent them
+//! - GET `/api/lease` - Current throttle lease holder
+//! - POST `/api/lease/acquire` - Acquire the throttle lease before driving
+//! - POST `/api/lease/release` - Release the throttle lease
+//! - POST `/api/lease/steal` - Take the throttle lease from its holder
+//! - POST `/api/heartbeat` - Renew the remote control heartbeat lease
+//! - POST `/api/command` - Apply a versioned command envelope
+//! - GET `/api/commands/{id}` - Latest acknowledgement of a command
+//! - GET `/api/ws` - WebSocket with live state, acknowledgements and commands
+//! - GET `/api/events` - Server-Sent Events stream of state changes
 //! - GET `/` - Web UI (serves index.html)
+//!
+//! Command requests may name the sending client (browser session, script,
+//! API token) in an `X-Client-Id` header; see [`ClientId`]. They may also
+//! carry an `X-Correlation-Id` header, echoed back in the response and in
+//! the command's acknowledgements; see [`CorrelationId`].
+//!
+//! # WebSocket
+//!
+//! `/api/ws` replaces polling `/api/state`. Every frame is a JSON text
+//! message with a `type`:
+//!
+//! - `{"type":"state","state":{...}}` - full state, sent once on connect
+//! - `{"type":"delta","changes":{...}}` - state members that changed
+//! - `{"type":"ack","ack":{...}}` - a command acknowledgement, from any client
+//! - `{"type":"response","status":200,"body":{...}}` - reply to a command
+//!
+//! Clients send command envelopes, exactly as for `POST /api/command`, and
+//! get one `response` per envelope, in order. Changes are checked every
+//! [`WS_PUSH_INTERVAL_MS`], and only sent when there are some. The lock,
+//! lease and lockout countdowns alone don't count as a change.
+//!
+//! # Server-Sent Events
+//!
+//! `/api/events` is a read-only stream for dashboards and `curl -N`. It
+//! starts with a `state` event holding the full state, including any fault
+//! and transition progress, as published on the MQTT state topic (see
+//! [`StateResponse`]). It then sends typed events as things change:
+//! `speed`, `direction`, `fault`, `lock`, `lease` and `transition` (see
+//! [`super::events`]).
+//!
+//! Every event has an id. A client reconnecting with a `Last-Event-ID`
+//! header gets the events it missed instead of the full state, if they are
+//! still kept. Speed and transition progress are recorded at most every
+//! 100ms, and a client that falls behind only gets the newest of them.
 
+use std::collections::VecDeque;
+use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     body::Bytes,
-    extract::State,
-    http::StatusCode,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{FromRequestParts, Path, State},
+    http::{request::Parts, HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
     response::{Html, IntoResponse},
     routing::{get, post},
     Router,
 };
+use futures_util::stream::{self, Stream};
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::config::WebConfig;
+use crate::messages::LeaseAction;
 use crate::traits::MotorController;
-use crate::ThrottleController;
+use crate::{
+    ClientId, ClientIdError, CorrelationId, CorrelationIdError, ThrottleController, ThrottleState,
+};
 
-use super::api::ApiResponse;
-use super::http_handler::{ApiResult, HttpApiHandler};
+use super::api::{ApiResponse, StateResponse};
+use super::events::{coalesce, StateEvent};
+use super::http_handler::{ack_to_json, state_members, state_to_json, ApiResult, HttpApiHandler};
 use super::shared::SharedThrottleState;
 
 // ============================================================================
@@ -42,10 +110,115 @@ use super::shared::SharedThrottleState;
 /// New code should use `SharedThrottleState` directly for clarity.
 pub type AppState<M> = SharedThrottleState<M>;
 
+// ============================================================================
+// Client Identity
+// ============================================================================
+
+/// Header naming the client within [`CommandSource::WebApi`](crate::CommandSource::WebApi)
+pub const CLIENT_ID_HEADER: &str = "x-client-id";
+
+/// Client named by the request's [`CLIENT_ID_HEADER`], if any.
+///
+/// Requests with a malformed id are rejected with 400.
+struct Client(Option<ClientId>);
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Client {
+    type Rejection = ApiResult;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(CLIENT_ID_HEADER) else {
+            return Ok(Self(None));
+        };
+        match value.to_str().map(ClientId::new) {
+            Ok(Ok(client)) => Ok(Self(Some(client))),
+            _ => Err(ApiResult::bad_request(format!(
+                r#"{{"error":"{}"}}"#,
+                ClientIdError
+            ))),
+        }
+    }
+}
+
+/// Header tagging a command so its acknowledgements can be matched to it
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Correlation id from the request's [`CORRELATION_ID_HEADER`], if any.
+///
+/// Requests with a malformed id are rejected with 400.
+struct Correlation(Option<CorrelationId>);
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Correlation {
+    type Rejection = ApiResult;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(CORRELATION_ID_HEADER) else {
+            return Ok(Self(None));
+        };
+        match value.to_str().map(CorrelationId::new) {
+            Ok(Ok(correlation_id)) => Ok(Self(Some(correlation_id))),
+            _ => Err(ApiResult::bad_request(format!(
+                r#"{{"error":"{}"}}"#,
+                CorrelationIdError
+            ))),
+        }
+    }
+}
+
 // ============================================================================
 // Route Handlers (thin wrappers around HttpApiHandler)
 // ============================================================================
 
+/// GET /api/audit
+async fn get_audit<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state));
+    ApiResult::ok(handler.handle_get_audit())
+}
+
+/// GET /api/lease
+async fn get_lease<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state));
+    ApiResult::ok(handler.handle_get_lease())
+}
+
+/// POST /api/lease/acquire
+async fn acquire_lease<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state)).with_client(client);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_lease(LeaseAction::Acquire, body_str)
+}
+
+/// POST /api/lease/release
+async fn release_lease<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state)).with_client(client);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_lease(LeaseAction::Release, body_str)
+}
+
+/// POST /api/lease/steal
+async fn steal_lease<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state)).with_client(client);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_lease(LeaseAction::Steal, body_str)
+}
+
 /// GET /api/state
 async fn get_state<M: MotorController + Send + 'static>(
     State(state): State<Arc<SharedThrottleState<M>>>,
@@ -58,19 +231,41 @@ async fn get_state<M: MotorController + Send + 'static>(
 /// POST /api/speed
 async fn set_speed<M: MotorController + Send + 'static>(
     State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
     body: Bytes,
 ) -> impl IntoResponse {
-    let handler = HttpApiHandler::new(Arc::clone(&state));
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
     let body_str = std::str::from_utf8(&body).unwrap_or("");
     handler.handle_set_speed(body_str)
 }
 
+/// POST /api/velocity
+async fn set_velocity<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_set_velocity(body_str)
+}
+
 /// POST /api/direction
 async fn set_direction<M: MotorController + Send + 'static>(
     State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
     body: Bytes,
 ) -> impl IntoResponse {
-    let handler = HttpApiHandler::new(Arc::clone(&state));
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
     let body_str = std::str::from_utf8(&body).unwrap_or("");
     handler.handle_set_direction(body_str)
 }
@@ -78,21 +273,427 @@ async fn set_direction<M: MotorController + Send + 'static>(
 /// POST /api/estop
 async fn emergency_stop<M: MotorController + Send + 'static>(
     State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
 ) -> impl IntoResponse {
-    let handler = HttpApiHandler::new(Arc::clone(&state));
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
     handler.handle_estop()
 }
 
 /// POST /api/max-speed
 async fn set_max_speed<M: MotorController + Send + 'static>(
     State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
     body: Bytes,
 ) -> impl IntoResponse {
-    let handler = HttpApiHandler::new(Arc::clone(&state));
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
     let body_str = std::str::from_utf8(&body).unwrap_or("");
     handler.handle_set_max_speed(body_str)
 }
 
+/// POST /api/service-brake
+async fn service_brake<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_service_brake(body_str)
+}
+
+/// POST /api/service-brake/release
+async fn release_brake<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    handler.handle_release_brake()
+}
+
+/// GET /api/queue
+async fn get_queue<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state));
+    ApiResult::ok(handler.handle_get_queue())
+}
+
+/// GET /api/metrics
+async fn get_metrics<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state));
+    ApiResult::ok(handler.handle_get_metrics())
+}
+
+/// POST /api/queue/cancel
+async fn cancel_queued<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_cancel_queued(body_str)
+}
+
+/// POST /api/queue/clear
+async fn clear_queue<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    handler.handle_clear_queue()
+}
+
+/// POST /api/preview
+async fn preview<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state)).with_client(client);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_preview(body_str)
+}
+
+/// POST /api/pause
+async fn pause<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    handler.handle_pause()
+}
+
+/// POST /api/resume
+async fn resume<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    handler.handle_resume()
+}
+
+/// POST /api/lock/release
+async fn force_release<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    handler.handle_force_release()
+}
+
+/// POST /api/notch
+async fn set_notch<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_set_notch(body_str)
+}
+
+/// POST /api/brake
+async fn set_brake<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_set_brake(body_str)
+}
+
+/// POST /api/mode
+async fn set_driving_mode<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_set_driving_mode(body_str)
+}
+
+/// POST /api/command
+async fn command<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+    body: Bytes,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    let body_str = std::str::from_utf8(&body).unwrap_or("");
+    handler.handle_command(body_str)
+}
+
+/// GET /api/commands/{id}
+async fn get_command<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Path(id): Path<u32>,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state));
+    handler.handle_get_command(id)
+}
+
+/// POST /api/heartbeat
+async fn heartbeat<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    Correlation(correlation_id): Correlation,
+) -> impl IntoResponse {
+    let handler = HttpApiHandler::new(Arc::clone(&state))
+        .with_client(client)
+        .with_correlation_id(correlation_id);
+    handler.handle_heartbeat()
+}
+
+// ============================================================================
+// WebSocket
+// ============================================================================
+
+/// How often each WebSocket client is checked for state changes and acks
+pub const WS_PUSH_INTERVAL_MS: u64 = 50;
+
+/// GET /api/ws
+async fn websocket<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    Client(client): Client,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    upgrade.on_upgrade(move |socket| serve_websocket(socket, state, client))
+}
+
+/// Push state and acks to one WebSocket client and apply its commands.
+///
+/// Each client keeps its own view of the state, so a slow client only
+/// ever falls behind by the deltas it hasn't read yet.
+async fn serve_websocket<M: MotorController + Send + 'static>(
+    mut socket: WebSocket,
+    state: Arc<SharedThrottleState<M>>,
+    client: Option<ClientId>,
+) {
+    let handler = HttpApiHandler::new(Arc::clone(&state)).with_client(client);
+    let mut last_ack = state.last_ack_seq();
+    let snapshot = state.state();
+    let mut last_keys = change_keys(&snapshot);
+    let hello = format!(r#"{{"type":"state","state":{}}}"#, state_to_json(&snapshot));
+    if socket.send(Message::Text(hello)).await.is_err() {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_millis(WS_PUSH_INTERVAL_MS));
+    loop {
+        let frames = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let result = handler.handle_command(&text);
+                    vec![format!(
+                        r#"{{"type":"response","status":{},"body":{}}}"#,
+                        result.status(),
+                        result.body()
+                    )]
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum
+                Some(Ok(_)) => continue,
+            },
+            _ = interval.tick() => {
+                let mut frames = Vec::new();
+                let snapshot = state.state();
+                let keys = change_keys(&snapshot);
+                let changes: Vec<String> = state_members(&snapshot)
+                    .into_iter()
+                    .filter(|(name, _)| {
+                        let key = keys.iter().find(|(key, _)| key == name);
+                        key != last_keys.iter().find(|(key, _)| key == name)
+                    })
+                    .map(|(name, value)| format!(r#""{}":{}"#, name, value))
+                    .collect();
+                if !changes.is_empty() {
+                    frames.push(format!(
+                        r#"{{"type":"delta","changes":{{{}}}}}"#,
+                        changes.join(",")
+                    ));
+                    last_keys = keys;
+                }
+                for ack in state.acks_since(last_ack) {
+                    last_ack = ack.seq;
+                    frames.push(format!(r#"{{"type":"ack","ack":{}}}"#, ack_to_json(&ack)));
+                }
+                frames
+            }
+        };
+        for frame in frames {
+            if socket.send(Message::Text(frame)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// State members to diff for deltas.
+///
+/// The lock, lease and lockout countdowns are left out: a countdown alone
+/// isn't a change, the same as in the event log.
+fn change_keys(state: &ThrottleState) -> Vec<(&'static str, String)> {
+    let mut state = state.clone();
+    if let Some(lock) = &mut state.lock_status {
+        lock.remaining_ms = None;
+    }
+    if let Some(lease) = &mut state.lease {
+        lease.remaining_ms = 0;
+    }
+    if let Some(lockout) = &mut state.lockout {
+        lockout.remaining_ms = 0;
+    }
+    state_members(&state)
+}
+
+// ============================================================================
+// Server-Sent Events
+// ============================================================================
+
+/// How often each Server-Sent Events client is checked for new events
+pub const SSE_PUSH_INTERVAL_MS: u64 = 50;
+
+/// Header a reconnecting Server-Sent Events client sends its last event id in
+pub const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// GET /api/events
+async fn events<M: MotorController + Send + 'static>(
+    State(state): State<Arc<SharedThrottleState<M>>>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = EventStream::new(state, last_event_id(&headers));
+    let stream = stream::unfold(stream, |mut stream| async move {
+        let event = stream.next().await;
+        Some((Ok(event), stream))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// The events one Server-Sent Events client hasn't been sent yet.
+struct EventStream<M: MotorController> {
+    state: Arc<SharedThrottleState<M>>,
+    last_id: u64,
+    pending: VecDeque<Event>,
+    interval: tokio::time::Interval,
+}
+
+impl<M: MotorController + Send + 'static> EventStream<M> {
+    /// Start with the events after `resume_from`, or the full state if
+    /// there is no such id or the events are gone.
+    fn new(state: Arc<SharedThrottleState<M>>, resume_from: Option<u64>) -> Self {
+        let interval = tokio::time::interval(Duration::from_millis(SSE_PUSH_INTERVAL_MS));
+        let mut stream = Self {
+            state,
+            last_id: 0,
+            pending: VecDeque::new(),
+            interval,
+        };
+        match resume_from.and_then(|id| Some((id, stream.state.events_since(id)?))) {
+            Some((id, missed)) => {
+                stream.last_id = id;
+                stream.queue(missed);
+            }
+            None => stream.restart(),
+        }
+        stream
+    }
+
+    /// The next event, waiting for one if there are none.
+    async fn next(&mut self) -> Event {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return event;
+            }
+            self.interval.tick().await;
+            match self.state.events_since(self.last_id) {
+                Some(events) => self.queue(events),
+                // Fell too far behind to catch up event by event
+                None => self.restart(),
+            }
+        }
+    }
+
+    fn queue(&mut self, events: Vec<StateEvent>) {
+        if let Some(last) = events.last() {
+            self.last_id = last.id;
+        }
+        self.pending.extend(coalesce(events).iter().map(|event| {
+            Event::default()
+                .id(event.id.to_string())
+                .event(event.kind.as_str())
+                .data(&event.data)
+        }));
+    }
+
+    fn restart(&mut self) {
+        let (id, snapshot) = self.state.event_snapshot();
+        let data = serde_json::to_string(&StateResponse::from(&snapshot)).unwrap_or_default();
+        self.last_id = id;
+        self.pending.clear();
+        self.pending.push_back(
+            Event::default()
+                .id(id.to_string())
+                .event("state")
+                .data(data),
+        );
+    }
+}
+
+/// Id from the request's [`LAST_EVENT_ID_HEADER`], if it is a number.
+fn last_event_id(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(LAST_EVENT_ID_HEADER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
 /// GET / - Serve the web UI
 async fn index() -> impl IntoResponse {
     Html(include_str!("../../www/index.html"))
@@ -161,9 +762,33 @@ pub fn build_router<M: MotorController + Send + 'static>(
         // API routes
         .route("/api/state", get(get_state::<M>))
         .route("/api/speed", post(set_speed::<M>))
+        .route("/api/velocity", post(set_velocity::<M>))
         .route("/api/direction", post(set_direction::<M>))
         .route("/api/estop", post(emergency_stop::<M>))
         .route("/api/max-speed", post(set_max_speed::<M>))
+        .route("/api/service-brake", post(service_brake::<M>))
+        .route("/api/service-brake/release", post(release_brake::<M>))
+        .route("/api/queue", get(get_queue::<M>))
+        .route("/api/queue/cancel", post(cancel_queued::<M>))
+        .route("/api/queue/clear", post(clear_queue::<M>))
+        .route("/api/preview", post(preview::<M>))
+        .route("/api/pause", post(pause::<M>))
+        .route("/api/resume", post(resume::<M>))
+        .route("/api/lock/release", post(force_release::<M>))
+        .route("/api/notch", post(set_notch::<M>))
+        .route("/api/brake", post(set_brake::<M>))
+        .route("/api/mode", post(set_driving_mode::<M>))
+        .route("/api/metrics", get(get_metrics::<M>))
+        .route("/api/audit", get(get_audit::<M>))
+        .route("/api/lease", get(get_lease::<M>))
+        .route("/api/lease/acquire", post(acquire_lease::<M>))
+        .route("/api/lease/release", post(release_lease::<M>))
+        .route("/api/lease/steal", post(steal_lease::<M>))
+        .route("/api/heartbeat", post(heartbeat::<M>))
+        .route("/api/command", post(command::<M>))
+        .route("/api/commands/:id", get(get_command::<M>))
+        .route("/api/ws", get(websocket::<M>))
+        .route("/api/events", get(events::<M>))
         // Web UI
         .route("/", get(index))
         // Fallback
@@ -212,7 +837,7 @@ pub async fn run_server_with_state<M: MotorController + Send + 'static>(
 mod tests {
     use super::*;
     use crate::hal::MockMotor;
-    use crate::{CommandSource, Direction, ThrottleCommand};
+    use crate::{CommandSource, Direction, HeartbeatLease, SafeStop, Speed, ThrottleCommand};
     use axum::body::Body;
     use axum::http::Request;
     use tower::ServiceExt;
@@ -290,7 +915,7 @@ mod tests {
         // Set specific state
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.5).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -337,7 +962,112 @@ mod tests {
         let now = state.now_ms();
         state.with_controller(|c| c.update(now).unwrap());
         let current = state.state();
-        assert!((current.speed - 0.75).abs() < 0.01);
+        assert!((current.speed.get() - 0.75).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_set_velocity_reverse() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+        let app = build_router(state.clone(), &config);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/velocity")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"velocity": -0.4}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now).unwrap());
+        let current = state.state();
+        assert_eq!(current.direction, Direction::Reverse);
+        assert!((current.velocity.get() + 0.4).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_service_brake_hold_and_release() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+
+        let now = state.now_ms();
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::speed_immediate(Speed::new(0.5).unwrap());
+            c.apply_command(cmd.into(), CommandSource::WebApi, now)
+                .unwrap();
+            c.update(now).unwrap();
+        });
+
+        let response = build_router(state.clone(), &config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/service-brake")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"hold": true}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let brake = state
+            .state()
+            .service_brake
+            .expect("brake should be applied");
+        assert!(brake.hold);
+
+        let response = build_router(state.clone(), &config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/service-brake/release")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.state().service_brake.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cab_mode_notch() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+
+        for (uri, body) in [
+            ("/api/mode", r#"{"mode": "cab"}"#),
+            ("/api/notch", r#"{"notch": 6}"#),
+        ] {
+            let response = build_router(state.clone(), &config)
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let cab = state.state().cab.expect("cab mode should be active");
+        assert_eq!(cab.notch.get(), 6);
     }
 
     #[tokio::test]
@@ -490,7 +1220,7 @@ mod tests {
         // Set initial speed
         let now = state.now_ms();
         state.with_controller(|c| {
-            let cmd = ThrottleCommand::speed_immediate(0.8).into();
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.8)).into();
             let _ = c.apply_command(cmd, CommandSource::Physical, now);
             let _ = c.update(now);
         });
@@ -507,7 +1237,321 @@ mod tests {
 
         let now = state.now_ms();
         state.with_controller(|c| c.update(now).unwrap());
-        assert!(state.state().speed.abs() < 0.01);
+        assert!(state.state().speed.get().abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_keeps_lease_alive() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor)
+            .with_heartbeat(HeartbeatLease::new(60_000, SafeStop::EmergencyStop));
+        let state = Arc::new(SharedThrottleState::new(controller));
+
+        let now = state.now_ms();
+        state.with_controller(|c| {
+            let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
+            let _ = c.apply_command(cmd, CommandSource::WebApi, now);
+        });
+
+        let config = WebServerConfig::default();
+        let app = build_router(state.clone(), &config);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/heartbeat")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let heartbeat = state.state().heartbeat.expect("lease should be held");
+        assert_eq!(heartbeat.source, CommandSource::WebApi);
+    }
+
+    #[tokio::test]
+    async fn test_command_envelope() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+        let app = build_router(state.clone(), &config);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/command")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"version": 1, "command": {"set_speed": {"speed": 0.7, "strategy": {"linear": {"duration_ms": 1000}}}}}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.state().target_speed, Some(Speed::literal(0.7)));
+    }
+
+    #[tokio::test]
+    async fn test_client_id_header() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+        let app = build_router(state.clone(), &config);
+
+        let post = |client: &str, body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/command")
+                .header(CLIENT_ID_HEADER, client)
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        // Alice takes a source lock; Bob, on the same source, can't break it
+        let locked = r#"{"command": {"set_speed": {"speed": 0.7, "strategy": {"linear": {"duration_ms": 60000, "lock": "source"}}}}}"#;
+        let response = app.clone().oneshot(post("alice", locked)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(post("bob", r#"{"command": {"set_speed": {"speed": 0.2}}}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let lock = state.state().lock_status.unwrap();
+        assert_eq!(lock.client.unwrap().as_str(), "alice");
+        assert_eq!(state.state().target_speed, Some(Speed::literal(0.7)));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/audit")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let audit: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(audit["audit"][0]["client"], "alice");
+        assert_eq!(audit["audit"][1]["client"], "bob");
+        assert_eq!(audit["audit"][1]["rejected"], "lower_priority");
+
+        // Malformed ids are refused outright
+        let response = app.oneshot(post("not valid", locked)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_lease_endpoints() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+        let app = build_router(state.clone(), &config);
+
+        let post = |uri: &'static str, client: &str, body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(CLIENT_ID_HEADER, client)
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let json = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/lease/acquire", "alice", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(body["result"], "acquired");
+        assert_eq!(body["lease"]["client"], "alice");
+
+        // Bob can't take it or drive while Alice holds it
+        let response = app
+            .clone()
+            .oneshot(post("/api/lease/acquire", "bob", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(json(response).await["error"], "lease_held");
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/direction", "bob", r#"{"direction": "forward"}"#))
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(body["result"], "rejected");
+        assert_eq!(body["reason"], "not_lease_holder");
+        assert_eq!(state.state().direction, Direction::Stopped);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/state")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(body["lease"]["owner"], "web_api");
+        assert_eq!(body["lease"]["client"], "alice");
+
+        // Same source, so Bob may not steal it
+        let response = app
+            .clone()
+            .oneshot(post("/api/lease/steal", "bob", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(json(response).await["error"], "lower_priority");
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/lease/release", "bob", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/lease/release", "alice", ""))
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["lease"], serde_json::Value::Null);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/lease")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["lease"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_command_acknowledgements() {
+        let motor = MockMotor::new();
+        let controller = ThrottleController::new(motor);
+        let state = Arc::new(SharedThrottleState::new(controller));
+        let config = WebServerConfig::default();
+        let app = build_router(state.clone(), &config);
+
+        let get = |uri: String| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let json = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/speed")
+                    .header(CORRELATION_ID_HEADER, "req-1")
+                    .body(Body::from(r#"{"speed": 0.5, "duration_ms": 100}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(body["result"], "transition_started");
+        assert_eq!(body["correlation_id"], "req-1");
+        let id = body["command_id"].as_u64().unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(get(format!("/api/commands/{}", id)))
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(body["status"], "started");
+        assert_eq!(body["command"], "set_speed");
+        assert_eq!(body["correlation_id"], "req-1");
+
+        let now = state.now_ms();
+        state.with_controller(|c| c.update(now + 200)).unwrap();
+        let response = app
+            .clone()
+            .oneshot(get(format!("/api/commands/{}", id)))
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["status"], "completed");
+
+        // An envelope may carry its own correlation id
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/command")
+                    .body(Body::from(
+                        r#"{"correlation_id": "req-2", "command": "resume"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(body["reason"], "not_paused");
+        let id = body["command_id"].as_u64().unwrap();
+        let response = app
+            .clone()
+            .oneshot(get(format!("/api/commands/{}", id)))
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(body["status"], "rejected");
+        assert_eq!(body["reason"], "not_paused");
+        assert_eq!(body["correlation_id"], "req-2");
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/commands/999".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/estop")
+                    .header(CORRELATION_ID_HEADER, "not valid")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
@@ -531,7 +1575,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(response.status(), StatusCode::OK);
-        assert!((state.state().max_speed - 0.8).abs() < 0.01);
+        assert!((state.state().max_speed.get() - 0.8).abs() < 0.01);
     }
 
     #[tokio::test]
@@ -679,6 +1723,6 @@ mod tests {
 
         let current = state.state();
         assert_eq!(current.direction, Direction::Forward);
-        assert!((current.speed - 0.6).abs() < 0.01);
+        assert!((current.speed.get() - 0.6).abs() < 0.01);
     }
 }
@@ -0,0 +1,408 @@
+//! Validated throttle speed.
+//!
+//! [`Speed`] is a fraction of full throttle in the range 0.0 to 1.0. It can
+//! only be built through [`Speed::new`] (or its `TryFrom`/`FromStr`/serde
+//! equivalents), which reject NaN, infinity and out-of-range values with a
+//! [`SpeedError`], so every command that reaches the motor carries a sane
+//! value. Internal arithmetic that may drift slightly out of range uses
+//! [`Speed::clamped`] instead.
+//!
+//! [`Velocity`] is the signed single-axis form (-1.0 to 1.0, negative is
+//! reverse) used by center-off throttles and sliders. It splits into a
+//! [`Speed`] and a [`Direction`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use rs_trainz::{Direction, Speed, SpeedError, Velocity};
+//!
+//! let half = Speed::new(0.5).unwrap();
+//! assert_eq!(half.get(), 0.5);
+//!
+//! assert_eq!(Speed::new(1.5), Err(SpeedError::OutOfRange));
+//! assert_eq!(Speed::new(f32::NAN), Err(SpeedError::NotFinite));
+//! assert_eq!("NaN".parse::<Speed>(), Err(SpeedError::NotFinite));
+//! assert_eq!("fast".parse::<Speed>(), Err(SpeedError::Malformed));
+//!
+//! assert_eq!(Speed::clamped(1.2), Speed::FULL);
+//!
+//! let back = Velocity::new(-0.25).unwrap();
+//! assert_eq!(back.speed(), 0.25);
+//! assert_eq!(back.direction(), Direction::Reverse);
+//! ```
+
+use crate::fixed::Q16;
+use crate::traits::Direction;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+/// Throttle speed, guaranteed finite and within 0.0 to 1.0.
+///
+/// Serializes as a plain number; deserializing an invalid value fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f32", into = "f32"))]
+pub struct Speed(f32);
+
+impl Speed {
+    /// Stopped
+    pub const ZERO: Self = Self(0.0);
+    /// Full throttle
+    pub const FULL: Self = Self(1.0);
+
+    /// Validate a raw speed value
+    ///
+    /// Negative zero is normalized to zero.
+    pub fn new(value: f32) -> Result<Self, SpeedError> {
+        if !value.is_finite() {
+            return Err(SpeedError::NotFinite);
+        }
+        if !(0.0..=1.0).contains(&value) {
+            return Err(SpeedError::OutOfRange);
+        }
+        Ok(Self(value + 0.0))
+    }
+
+    /// Validate a speed written into the code, such as a constant or test value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not finite or is outside 0.0 to 1.0. In a const
+    /// item this is a compile error.
+    ///
+    /// ```rust
+    /// use rs_trainz::Speed;
+    ///
+    /// const CRAWL: Speed = Speed::literal(0.1);
+    /// assert_eq!(CRAWL, Speed::new(0.1).unwrap());
+    /// ```
+    pub const fn literal(value: f32) -> Self {
+        assert!(
+            value.is_finite() && value >= 0.0 && value <= 1.0,
+            "speed must be between 0.0 and 1.0"
+        );
+        Self(value + 0.0)
+    }
+
+    /// Clamp a computed value into range (NaN becomes zero)
+    pub fn clamped(value: f32) -> Self {
+        if value.is_nan() {
+            Self::ZERO
+        } else {
+            Self(value.clamp(0.0, 1.0) + 0.0)
+        }
+    }
+
+    /// The raw value (0.0 to 1.0)
+    pub const fn get(self) -> f32 {
+        self.0
+    }
+
+    /// Convert to fixed point
+    pub fn to_q16(self) -> Q16 {
+        Q16::from_f32(self.0)
+    }
+
+    /// Convert from fixed point, clamping into range
+    pub fn from_q16(value: Q16) -> Self {
+        Self::clamped(value.to_f32())
+    }
+}
+
+// Values are never NaN, so the ordering is total.
+impl Eq for Speed {}
+
+impl PartialOrd for Speed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Speed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialEq<f32> for Speed {
+    fn eq(&self, other: &f32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialOrd<f32> for Speed {
+    fn partial_cmp(&self, other: &f32) -> Option<Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl TryFrom<f32> for Speed {
+    type Error = SpeedError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Speed> for f32 {
+    fn from(speed: Speed) -> Self {
+        speed.0
+    }
+}
+
+impl FromStr for Speed {
+    type Err = SpeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: f32 = s.trim().parse().map_err(|_| SpeedError::Malformed)?;
+        Self::new(value)
+    }
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Signed throttle velocity, guaranteed finite and within -1.0 to 1.0.
+///
+/// Positive is forward, negative is reverse. Serializes as a plain number.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f32", into = "f32"))]
+pub struct Velocity(f32);
+
+impl Velocity {
+    /// Stopped
+    pub const ZERO: Self = Self(0.0);
+
+    /// Validate a raw velocity value
+    ///
+    /// Negative zero is normalized to zero.
+    pub fn new(value: f32) -> Result<Self, SpeedError> {
+        if !value.is_finite() {
+            return Err(SpeedError::NotFinite);
+        }
+        if !(-1.0..=1.0).contains(&value) {
+            return Err(SpeedError::VelocityOutOfRange);
+        }
+        Ok(Self(value + 0.0))
+    }
+
+    /// Combine a speed with a direction (`Stopped` gives zero)
+    pub fn from_parts(speed: Speed, direction: Direction) -> Self {
+        match direction {
+            Direction::Forward => Self(speed.get()),
+            Direction::Reverse => Self(-speed.get() + 0.0),
+            Direction::Stopped => Self::ZERO,
+        }
+    }
+
+    /// The raw value (-1.0 to 1.0)
+    pub const fn get(self) -> f32 {
+        self.0
+    }
+
+    /// Magnitude as a speed
+    pub fn speed(self) -> Speed {
+        Speed::clamped(self.0.abs())
+    }
+
+    /// Direction of travel (`Stopped` at zero)
+    pub fn direction(self) -> Direction {
+        if self.0 > 0.0 {
+            Direction::Forward
+        } else if self.0 < 0.0 {
+            Direction::Reverse
+        } else {
+            Direction::Stopped
+        }
+    }
+}
+
+impl PartialEq<f32> for Velocity {
+    fn eq(&self, other: &f32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl TryFrom<f32> for Velocity {
+    type Error = SpeedError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Velocity> for f32 {
+    fn from(velocity: Velocity) -> Self {
+        velocity.0
+    }
+}
+
+impl FromStr for Velocity {
+    type Err = SpeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: f32 = s.trim().parse().map_err(|_| SpeedError::Malformed)?;
+        Self::new(value)
+    }
+}
+
+impl fmt::Display for Velocity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Why a raw value isn't a valid [`Speed`] or [`Velocity`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SpeedError {
+    /// Text is not a number.
+    Malformed,
+    /// NaN or infinity.
+    NotFinite,
+    /// Outside 0.0 to 1.0.
+    OutOfRange,
+    /// Velocity outside -1.0 to 1.0.
+    VelocityOutOfRange,
+}
+
+impl fmt::Display for SpeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "speed must be a number"),
+            Self::NotFinite => write!(f, "speed must be finite"),
+            Self::OutOfRange => write!(f, "speed must be between 0.0 and 1.0"),
+            Self::VelocityOutOfRange => write!(f, "velocity must be between -1.0 and 1.0"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SpeedError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // === Validation Tests ===
+    #[test]
+    fn speed_accepts_range() {
+        assert_eq!(Speed::new(0.0).unwrap().get(), 0.0);
+        assert_eq!(Speed::new(0.5).unwrap().get(), 0.5);
+        assert_eq!(Speed::new(1.0).unwrap(), Speed::FULL);
+    }
+
+    #[test]
+    fn speed_rejects_invalid() {
+        assert_eq!(Speed::new(f32::NAN), Err(SpeedError::NotFinite));
+        assert_eq!(Speed::new(f32::INFINITY), Err(SpeedError::NotFinite));
+        assert_eq!(Speed::new(f32::NEG_INFINITY), Err(SpeedError::NotFinite));
+        assert_eq!(Speed::new(1.01), Err(SpeedError::OutOfRange));
+        assert_eq!(Speed::new(-0.1), Err(SpeedError::OutOfRange));
+    }
+
+    #[test]
+    fn speed_literal_matches_new() {
+        const HALF: Speed = Speed::literal(0.5);
+        assert_eq!(HALF, Speed::new(0.5).unwrap());
+        assert_eq!(Speed::literal(-0.0).get().to_bits(), 0.0f32.to_bits());
+    }
+
+    #[test]
+    #[should_panic(expected = "speed must be between 0.0 and 1.0")]
+    fn speed_literal_rejects_invalid() {
+        let _ = Speed::literal(1.5);
+    }
+
+    #[test]
+    fn speed_normalizes_negative_zero() {
+        let zero = Speed::new(-0.0).unwrap();
+        assert!(zero.get().is_sign_positive());
+        assert_eq!(zero, Speed::ZERO);
+        assert!(Speed::clamped(-0.0).get().is_sign_positive());
+    }
+
+    #[test]
+    fn speed_clamped() {
+        assert_eq!(Speed::clamped(f32::NAN), Speed::ZERO);
+        assert_eq!(Speed::clamped(f32::INFINITY), Speed::FULL);
+        assert_eq!(Speed::clamped(-3.0), Speed::ZERO);
+        assert_eq!(Speed::clamped(0.25), 0.25);
+    }
+
+    // === Conversion Tests ===
+    #[test]
+    fn speed_from_str() {
+        assert_eq!(" 0.75\n".parse::<Speed>().unwrap(), 0.75);
+        assert_eq!("inf".parse::<Speed>(), Err(SpeedError::NotFinite));
+        assert_eq!("2".parse::<Speed>(), Err(SpeedError::OutOfRange));
+        assert_eq!("".parse::<Speed>(), Err(SpeedError::Malformed));
+    }
+
+    #[test]
+    fn speed_ordering() {
+        let slow = Speed::new(0.2).unwrap();
+        let fast = Speed::new(0.8).unwrap();
+        assert!(slow < fast);
+        assert_eq!(slow.max(fast), fast);
+        assert!(fast > 0.5);
+    }
+
+    #[test]
+    fn speed_q16_round_trip() {
+        let speed = Speed::new(0.5).unwrap();
+        assert_eq!(speed.to_q16(), Q16::from_ratio(1, 2));
+        assert_eq!(Speed::from_q16(speed.to_q16()), speed);
+        assert_eq!(Speed::from_q16(Q16::from_f32(2.0)), Speed::FULL);
+    }
+
+    // === Velocity Tests ===
+    #[test]
+    fn velocity_validates() {
+        assert_eq!(Velocity::new(-1.0).unwrap().get(), -1.0);
+        assert_eq!(Velocity::new(1.5), Err(SpeedError::VelocityOutOfRange));
+        assert_eq!(Velocity::new(f32::NAN), Err(SpeedError::NotFinite));
+        assert_eq!("-0.5".parse::<Velocity>().unwrap(), -0.5);
+        assert!(Velocity::new(-0.0).unwrap().get().is_sign_positive());
+    }
+
+    #[test]
+    fn velocity_splits_into_parts() {
+        let back = Velocity::new(-0.4).unwrap();
+        assert_eq!(back.speed(), 0.4);
+        assert_eq!(back.direction(), Direction::Reverse);
+        assert_eq!(Velocity::new(0.3).unwrap().direction(), Direction::Forward);
+        assert_eq!(Velocity::ZERO.direction(), Direction::Stopped);
+    }
+
+    #[test]
+    fn velocity_from_parts() {
+        let speed = Speed::new(0.6).unwrap();
+        assert_eq!(Velocity::from_parts(speed, Direction::Forward), 0.6);
+        assert_eq!(Velocity::from_parts(speed, Direction::Reverse), -0.6);
+        assert_eq!(
+            Velocity::from_parts(speed, Direction::Stopped),
+            Velocity::ZERO
+        );
+        let zero = Velocity::from_parts(Speed::ZERO, Direction::Reverse);
+        assert!(zero.get().is_sign_positive());
+    }
+
+    #[cfg(feature = "serde-json-core")]
+    #[test]
+    fn speed_serde_validates() {
+        let (speed, _): (Speed, _) = serde_json_core::from_str("0.25").unwrap();
+        assert_eq!(speed, 0.25);
+        assert!(serde_json_core::from_str::<Speed>("1.5").is_err());
+
+        let mut buf = [0u8; 16];
+        let len = serde_json_core::to_slice(&speed, &mut buf).unwrap();
+        assert_eq!(&buf[..len], b"0.25");
+    }
+}
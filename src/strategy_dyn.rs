@@ -12,9 +12,11 @@
 //!
 //! # How It Works
 //!
-//! The [`ExecutionStrategyDyn`] trait is an object-safe version of
-//! [`ExecutionStrategy`]. [`AnyStrategy`] wraps any strategy implementing
-//! `ExecutionStrategy + Send + Sync + 'static` in an `Arc` for cheap cloning.
+//! Built-in strategies are stored inline as a [`StrategySpec`] and
+//! dispatched with a `match`, so they never allocate. With the `alloc`
+//! feature, [`AnyStrategy::new`] also accepts custom strategies: anything
+//! implementing `ExecutionStrategy + Send + Sync + 'static` is wrapped in an
+//! `Arc` behind the object-safe [`ExecutionStrategyDyn`] trait.
 //!
 //! ```rust
 //! use rs_trainz::{AnyStrategy, traits::{Linear, EaseInOut, Momentum}};
@@ -31,20 +33,51 @@
 //! }
 //! ```
 //!
+//! # Serialization
+//!
+//! Built-in strategies remember their [`StrategySpec`], so an `AnyStrategy`
+//! can be turned back into the wire format with [`AnyStrategy::spec`], and
+//! any `StrategySpec` converts into an `AnyStrategy`. Custom strategies have
+//! no spec.
+//!
+//! # Without `alloc`
+//!
+//! On targets without a heap, build built-in strategies with
+//! `AnyStrategy::from`; [`TransitionManager`], [`ThrottleCommandDyn`] and
+//! [`CommandQueue`] work unchanged.
+//!
+//! ```rust
+//! use rs_trainz::{AnyStrategy, traits::Linear};
+//!
+//! let strategy = AnyStrategy::from(Linear::new(1000));
+//! assert_eq!(strategy.duration_ms(), Some(1000));
+//! ```
+//!
 //! # Performance
 //!
-//! Type erasure adds a small overhead:
+//! Built-in strategies cost a `match` per call. Their parameters are
+//! converted to fixed point once, when the `AnyStrategy` is built, so the
+//! `_q16` methods need no floating-point math. Custom strategies add:
 //! - One allocation per strategy (amortized via Arc)
 //! - Virtual dispatch for trait methods
 //!
 //! For most throttle control applications, this overhead is negligible.
 //!
 //! [`ExecutionStrategy`]: crate::traits::ExecutionStrategy
-
-extern crate alloc;
-
-use crate::traits::{ExecutionStrategy, InterruptBehavior, TransitionLock};
+//! [`TransitionManager`]: crate::transition::TransitionManager
+//! [`ThrottleCommandDyn`]: crate::commands::ThrottleCommandDyn
+//! [`CommandQueue`]: crate::priority::CommandQueue
+
+use crate::fixed::Q16;
+use crate::traits::strategy::{KeyframesQ16, MomentumQ16, SCurveQ16};
+use crate::traits::{
+    EaseInOut, ExecutionStrategy, Immediate, InterruptBehavior, Keyframes, Linear, Momentum,
+    SCurve, SegmentProgress, StrategySpec, TransitionLock,
+};
+#[cfg(feature = "alloc")]
 use alloc::sync::Arc;
+#[cfg(feature = "alloc")]
+use core::any::Any;
 
 /// Object-safe version of [`ExecutionStrategy`].
 ///
@@ -59,8 +92,20 @@ use alloc::sync::Arc;
 pub trait ExecutionStrategyDyn: Send + Sync {
     /// Interpolate the value during a transition.
     fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool);
+    /// Interpolate the value during a transition, in fixed point.
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool);
     /// Estimated total duration in milliseconds (if known).
     fn duration_ms(&self) -> Option<u64>;
+    /// Duration of the move from `from` to `to` in milliseconds (if known).
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64>;
+    /// Rate of change in units per second at `elapsed_ms`.
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32;
+    /// Rate of change in units per second at `elapsed_ms`, in fixed point.
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16;
+    /// How long an inherited rate takes to fade out, in milliseconds.
+    fn blend_ms(&self, from: f32, to: f32) -> u64;
+    /// The segment in progress at `elapsed_ms`, for multi-segment strategies.
+    fn segment(&self, from: f32, to: f32, elapsed_ms: u64) -> Option<SegmentProgress>;
     /// What lock level does this transition require?
     fn lock(&self) -> TransitionLock;
     /// What happens if something tries to interrupt?
@@ -73,10 +118,34 @@ impl<S: ExecutionStrategy + Send + Sync + 'static> ExecutionStrategyDyn for S {
         ExecutionStrategy::interpolate(self, from, to, elapsed_ms)
     }
 
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        ExecutionStrategy::interpolate_q16(self, from, to, elapsed_ms)
+    }
+
     fn duration_ms(&self) -> Option<u64> {
         ExecutionStrategy::duration_ms(self)
     }
 
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        ExecutionStrategy::duration_for(self, from, to)
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        ExecutionStrategy::rate(self, from, to, elapsed_ms)
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        ExecutionStrategy::rate_q16(self, from, to, elapsed_ms)
+    }
+
+    fn blend_ms(&self, from: f32, to: f32) -> u64 {
+        ExecutionStrategy::blend_ms(self, from, to)
+    }
+
+    fn segment(&self, from: f32, to: f32, elapsed_ms: u64) -> Option<SegmentProgress> {
+        ExecutionStrategy::segment(self, from, to, elapsed_ms)
+    }
+
     fn lock(&self) -> TransitionLock {
         ExecutionStrategy::lock(self)
     }
@@ -88,8 +157,9 @@ impl<S: ExecutionStrategy + Send + Sync + 'static> ExecutionStrategyDyn for S {
 
 /// Type-erased wrapper for any execution strategy.
 ///
-/// Wraps any [`ExecutionStrategy`] implementation in an `Arc` for
-/// cheap cloning and dynamic dispatch.
+/// Holds built-in strategies inline and, with the `alloc` feature, any
+/// other [`ExecutionStrategy`] implementation in an `Arc` for cheap cloning
+/// and dynamic dispatch.
 ///
 /// # Example
 ///
@@ -110,7 +180,36 @@ impl<S: ExecutionStrategy + Send + Sync + 'static> ExecutionStrategyDyn for S {
 /// [`ExecutionStrategy`]: crate::traits::ExecutionStrategy
 #[derive(Clone)]
 pub struct AnyStrategy {
-    inner: Arc<dyn ExecutionStrategyDyn>,
+    inner: Inner,
+}
+
+#[derive(Clone)]
+enum Inner {
+    Builtin(StrategySpec, Prepared),
+    #[cfg(feature = "alloc")]
+    Custom(Arc<dyn ExecutionStrategyDyn>),
+}
+
+/// Fixed-point parameters of a built-in strategy, converted once so the
+/// per-tick `_q16` methods don't have to
+#[derive(Clone)]
+enum Prepared {
+    None,
+    Momentum(MomentumQ16),
+    SCurve(SCurveQ16),
+    Keyframes(KeyframesQ16),
+}
+
+impl Inner {
+    fn builtin(spec: StrategySpec) -> Self {
+        let prepared = match &spec {
+            StrategySpec::Momentum(s) => Prepared::Momentum(MomentumQ16::new(s)),
+            StrategySpec::SCurve(s) => Prepared::SCurve(SCurveQ16::new(s)),
+            StrategySpec::Keyframes(s) => Prepared::Keyframes(KeyframesQ16::new(s)),
+            _ => Prepared::None,
+        };
+        Self::Builtin(spec, prepared)
+    }
 }
 
 impl core::fmt::Debug for AnyStrategy {
@@ -125,41 +224,252 @@ impl core::fmt::Debug for AnyStrategy {
 
 impl AnyStrategy {
     /// Wrap a concrete strategy in a type-erased container
+    ///
+    /// Built-in strategies are stored inline; anything else is allocated.
+    #[cfg(feature = "alloc")]
     pub fn new<S: ExecutionStrategy + Send + Sync + 'static>(strategy: S) -> Self {
-        Self {
-            inner: Arc::new(strategy),
+        if let Some(any) = (&strategy as &dyn Any).downcast_ref::<AnyStrategy>() {
+            return any.clone();
+        }
+        let inner = match builtin_spec(&strategy) {
+            Some(spec) => Inner::builtin(spec),
+            None => Inner::Custom(Arc::new(strategy)),
+        };
+        Self { inner }
+    }
+
+    /// Serializable description of the wrapped strategy.
+    ///
+    /// Returns `None` for custom strategies that aren't one of the built-ins.
+    pub fn spec(&self) -> Option<&StrategySpec> {
+        match &self.inner {
+            Inner::Builtin(spec, _) => Some(spec),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(_) => None,
         }
     }
 
     /// Interpolates between values using the wrapped strategy.
     pub fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
-        self.inner.interpolate(from, to, elapsed_ms)
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::interpolate(spec, from, to, elapsed_ms),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.interpolate(from, to, elapsed_ms),
+        }
+    }
+
+    /// Interpolates between fixed-point values using the wrapped strategy.
+    pub fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        match &self.inner {
+            Inner::Builtin(_, Prepared::Momentum(s)) => s.interpolate(from, to, elapsed_ms),
+            Inner::Builtin(_, Prepared::SCurve(s)) => s.interpolate(from, to, elapsed_ms),
+            Inner::Builtin(StrategySpec::Keyframes(k), Prepared::Keyframes(s)) => {
+                s.interpolate(k, from, to, elapsed_ms)
+            }
+            Inner::Builtin(spec, _) => {
+                ExecutionStrategy::interpolate_q16(spec, from, to, elapsed_ms)
+            }
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.interpolate_q16(from, to, elapsed_ms),
+        }
     }
 
     /// Returns the estimated duration in milliseconds, if known.
     pub fn duration_ms(&self) -> Option<u64> {
-        self.inner.duration_ms()
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::duration_ms(spec),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.duration_ms(),
+        }
+    }
+
+    /// Returns the duration of the move from `from` to `to`, if known.
+    pub fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::duration_for(spec, from, to),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.duration_for(from, to),
+        }
+    }
+
+    /// Returns the rate of change in units per second at `elapsed_ms`.
+    pub fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::rate(spec, from, to, elapsed_ms),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.rate(from, to, elapsed_ms),
+        }
+    }
+
+    /// Returns the rate of change at `elapsed_ms` in fixed point.
+    pub fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        match &self.inner {
+            Inner::Builtin(_, Prepared::Momentum(s)) => s.rate(from, to, elapsed_ms),
+            Inner::Builtin(_, Prepared::SCurve(s)) => s.rate(from, to, elapsed_ms),
+            Inner::Builtin(StrategySpec::Keyframes(k), Prepared::Keyframes(s)) => {
+                s.rate(k, from, to, elapsed_ms)
+            }
+            Inner::Builtin(spec, _) => ExecutionStrategy::rate_q16(spec, from, to, elapsed_ms),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.rate_q16(from, to, elapsed_ms),
+        }
+    }
+
+    /// Returns how long an inherited rate takes to fade out.
+    pub fn blend_ms(&self, from: f32, to: f32) -> u64 {
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::blend_ms(spec, from, to),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.blend_ms(from, to),
+        }
+    }
+
+    /// Returns the segment in progress at `elapsed_ms`, if any.
+    pub fn segment(&self, from: f32, to: f32, elapsed_ms: u64) -> Option<SegmentProgress> {
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::segment(spec, from, to, elapsed_ms),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.segment(from, to, elapsed_ms),
+        }
     }
 
     /// Returns the transition lock level.
     pub fn lock(&self) -> TransitionLock {
-        self.inner.lock()
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::lock(spec),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.lock(),
+        }
     }
 
     /// Returns the interrupt behavior.
     pub fn on_interrupt(&self) -> InterruptBehavior {
-        self.inner.on_interrupt()
+        match &self.inner {
+            Inner::Builtin(spec, _) => ExecutionStrategy::on_interrupt(spec),
+            #[cfg(feature = "alloc")]
+            Inner::Custom(s) => s.on_interrupt(),
+        }
+    }
+}
+
+/// Lets `AnyStrategy` be used inside combinators such as
+/// [`Sequence`](crate::sequence::Sequence)
+impl ExecutionStrategy for AnyStrategy {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        AnyStrategy::interpolate(self, from, to, elapsed_ms)
+    }
+
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        AnyStrategy::interpolate_q16(self, from, to, elapsed_ms)
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        AnyStrategy::duration_ms(self)
+    }
+
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        AnyStrategy::duration_for(self, from, to)
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        AnyStrategy::rate(self, from, to, elapsed_ms)
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        AnyStrategy::rate_q16(self, from, to, elapsed_ms)
+    }
+
+    fn blend_ms(&self, from: f32, to: f32) -> u64 {
+        AnyStrategy::blend_ms(self, from, to)
+    }
+
+    fn segment(&self, from: f32, to: f32, elapsed_ms: u64) -> Option<SegmentProgress> {
+        AnyStrategy::segment(self, from, to, elapsed_ms)
+    }
+
+    fn lock(&self) -> TransitionLock {
+        AnyStrategy::lock(self)
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        AnyStrategy::on_interrupt(self)
+    }
+}
+
+impl From<StrategySpec> for AnyStrategy {
+    fn from(spec: StrategySpec) -> Self {
+        Self {
+            inner: Inner::builtin(spec),
+        }
+    }
+}
+
+impl From<Immediate> for AnyStrategy {
+    fn from(s: Immediate) -> Self {
+        StrategySpec::from(s).into()
+    }
+}
+
+impl From<Linear> for AnyStrategy {
+    fn from(s: Linear) -> Self {
+        StrategySpec::from(s).into()
+    }
+}
+
+impl From<EaseInOut> for AnyStrategy {
+    fn from(s: EaseInOut) -> Self {
+        StrategySpec::from(s).into()
+    }
+}
+
+impl From<Momentum> for AnyStrategy {
+    fn from(s: Momentum) -> Self {
+        StrategySpec::from(s).into()
+    }
+}
+
+impl From<SCurve> for AnyStrategy {
+    fn from(s: SCurve) -> Self {
+        StrategySpec::from(s).into()
+    }
+}
+
+impl From<Keyframes> for AnyStrategy {
+    fn from(s: Keyframes) -> Self {
+        StrategySpec::from(s).into()
+    }
+}
+
+/// Recover the [`StrategySpec`] of a built-in strategy
+#[cfg(feature = "alloc")]
+fn builtin_spec<S: Any>(strategy: &S) -> Option<StrategySpec> {
+    let any = strategy as &dyn Any;
+    if let Some(spec) = any.downcast_ref::<StrategySpec>() {
+        Some(spec.clone())
+    } else if any.is::<Immediate>() {
+        Some(StrategySpec::Immediate)
+    } else if let Some(s) = any.downcast_ref::<Linear>() {
+        Some(StrategySpec::Linear(s.clone()))
+    } else if let Some(s) = any.downcast_ref::<EaseInOut>() {
+        Some(StrategySpec::EaseInOut(s.clone()))
+    } else if let Some(s) = any.downcast_ref::<Momentum>() {
+        Some(StrategySpec::Momentum(s.clone()))
+    } else if let Some(s) = any.downcast_ref::<SCurve>() {
+        Some(StrategySpec::SCurve(s.clone()))
+    } else {
+        any.downcast_ref::<Keyframes>()
+            .map(|s| StrategySpec::Keyframes(s.clone()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{EaseInOut, Immediate, Linear, Momentum};
+    use crate::{EaseInOut, Easing, Immediate, Keyframes, Linear, Momentum, SCurve, Sequence};
 
     #[test]
     fn any_strategy_from_immediate() {
-        let strategy = AnyStrategy::new(Immediate::default());
+        let strategy = AnyStrategy::new(Immediate);
         assert_eq!(strategy.duration_ms(), Some(0));
         assert_eq!(strategy.lock(), TransitionLock::None);
         assert_eq!(strategy.on_interrupt(), InterruptBehavior::Replace);
@@ -223,6 +533,38 @@ mod tests {
         assert_eq!(strategy.lock(), TransitionLock::None);
     }
 
+    #[test]
+    fn any_strategy_from_s_curve() {
+        let strategy = AnyStrategy::new(SCurve::arrival(0.5, 0.25, 1.0));
+        assert_eq!(
+            strategy.spec(),
+            Some(&StrategySpec::SCurve(SCurve::arrival(0.5, 0.25, 1.0)))
+        );
+        assert_eq!(strategy.duration_ms(), None);
+        assert_eq!(strategy.duration_for(0.0, 1.0), Some(2500));
+        assert_eq!(strategy.duration_for(1.0, 0.0), Some(4250));
+        assert_eq!(strategy.lock(), TransitionLock::Source);
+        assert_eq!(strategy.on_interrupt(), InterruptBehavior::Queue);
+    }
+
+    #[test]
+    fn any_strategy_from_keyframes() {
+        let profile = Keyframes::arrival()
+            .with_keyframe(1000, 0.9, Easing::Linear)
+            .unwrap()
+            .with_keyframe(3000, 1.0, Easing::Smoothstep)
+            .unwrap();
+        let strategy = AnyStrategy::new(profile.clone());
+        assert_eq!(strategy.spec(), Some(&StrategySpec::Keyframes(profile)));
+        assert_eq!(strategy.duration_ms(), Some(3000));
+        assert_eq!(strategy.lock(), TransitionLock::Source);
+        assert_eq!(strategy.on_interrupt(), InterruptBehavior::Queue);
+
+        let (value, complete) = strategy.interpolate(1.0, 0.0, 500);
+        assert!((value - 0.55).abs() < 0.001);
+        assert!(!complete);
+    }
+
     #[test]
     fn any_strategy_clone() {
         let strategy1 = AnyStrategy::new(Linear::new(500));
@@ -282,4 +624,130 @@ mod tests {
         let (value, _) = strategy.interpolate(0.4, 0.5, 50);
         assert!((value - 0.45).abs() < 0.01);
     }
+
+    #[test]
+    fn any_strategy_remembers_builtin_spec() {
+        let strategy = AnyStrategy::new(EaseInOut::departure(2000));
+        assert_eq!(
+            strategy.spec(),
+            Some(&StrategySpec::EaseInOut(EaseInOut::departure(2000)))
+        );
+        assert_eq!(
+            AnyStrategy::new(Immediate).spec(),
+            Some(&StrategySpec::Immediate)
+        );
+    }
+
+    #[test]
+    fn any_strategy_not_wrapped_twice() {
+        let strategy = AnyStrategy::new(AnyStrategy::new(Linear::new(1000)));
+        assert_eq!(
+            strategy.spec(),
+            Some(&StrategySpec::Linear(Linear::new(1000)))
+        );
+    }
+
+    #[test]
+    fn any_strategy_reports_sequence_segment() {
+        let sequence: Sequence = Sequence::new().hold(500).finish(Linear::new(1000));
+        let strategy = AnyStrategy::new(sequence);
+        assert!(strategy.spec().is_none());
+        assert_eq!(strategy.duration_for(0.0, 1.0), Some(1500));
+        assert_eq!(strategy.segment(0.0, 1.0, 700).unwrap().index, 1);
+        assert!(AnyStrategy::new(Linear::new(1000))
+            .segment(0.0, 1.0, 500)
+            .is_none());
+    }
+
+    #[test]
+    fn any_strategy_spec_round_trip() {
+        let spec = StrategySpec::Momentum(Momentum::gentle());
+        let strategy = AnyStrategy::from(spec.clone());
+        assert_eq!(strategy.spec(), Some(&spec));
+        assert_eq!(strategy.duration_ms(), None);
+
+        let again = AnyStrategy::from(strategy.spec().unwrap().clone());
+        assert_eq!(again.spec(), Some(&spec));
+    }
+
+    #[test]
+    fn any_strategy_from_builtin_matches_new() {
+        let a = AnyStrategy::from(Linear::locked(800));
+        let b = AnyStrategy::new(Linear::locked(800));
+        assert_eq!(a.spec(), b.spec());
+        assert_eq!(a.interpolate(0.0, 1.0, 400), b.interpolate(0.0, 1.0, 400));
+    }
+
+    #[test]
+    fn any_strategy_prepared_q16_matches_strategy() {
+        let strategies = [
+            StrategySpec::from(Momentum::gentle()),
+            StrategySpec::from(Momentum::new(1.2, 0.4)),
+            StrategySpec::from(SCurve::new(0.5, 0.25, 1.0)),
+            StrategySpec::from(SCurve::new(0.4, 0.6, 0.0)),
+            StrategySpec::from(
+                Keyframes::new()
+                    .with_keyframe(500, 0.1, Easing::Smoothstep)
+                    .unwrap()
+                    .with_keyframe(300, 0.4, Easing::Linear)
+                    .unwrap()
+                    .with_keyframe(2500, 1.0, Easing::Cubic)
+                    .unwrap(),
+            ),
+        ];
+        let (from, to) = (Q16::from_f32(0.8), Q16::from_f32(0.1));
+        for spec in strategies {
+            let any = AnyStrategy::from(spec.clone());
+            for elapsed in (0..=3000).step_by(50) {
+                assert_eq!(
+                    any.interpolate_q16(from, to, elapsed),
+                    ExecutionStrategy::interpolate_q16(&spec, from, to, elapsed)
+                );
+                assert_eq!(
+                    any.rate_q16(to, from, elapsed),
+                    ExecutionStrategy::rate_q16(&spec, to, from, elapsed)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn any_strategy_custom_has_no_spec() {
+        #[derive(Clone)]
+        struct Custom;
+        impl ExecutionStrategy for Custom {
+            fn interpolate(&self, _from: f32, to: f32, _elapsed_ms: u64) -> (f32, bool) {
+                (to, true)
+            }
+            fn duration_ms(&self) -> Option<u64> {
+                Some(0)
+            }
+        }
+
+        assert!(AnyStrategy::new(Custom).spec().is_none());
+    }
+
+    #[test]
+    fn any_strategy_custom_rate_is_numeric_derivative() {
+        #[derive(Clone)]
+        struct Ramp;
+        impl ExecutionStrategy for Ramp {
+            fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+                let t = elapsed_ms as f32 / 2000.0;
+                if t >= 1.0 {
+                    (to, true)
+                } else {
+                    (from + (to - from) * t, false)
+                }
+            }
+            fn duration_ms(&self) -> Option<u64> {
+                Some(2000)
+            }
+        }
+
+        let strategy = AnyStrategy::new(Ramp);
+        assert!((strategy.rate(0.0, 1.0, 500) - 0.5).abs() < 0.01);
+        assert_eq!(strategy.rate(0.0, 1.0, 2000), 0.0);
+        assert_eq!(strategy.blend_ms(0.0, 1.0), 2000);
+    }
 }
@@ -15,7 +15,7 @@
 //!
 //! ```rust
 //! use rs_trainz::{
-//!     ThrottleController, ThrottleCommand, CommandSource,
+//!     ThrottleController, ThrottleCommand, CommandSource, Speed,
 //!     hal::MockMotor,
 //!     traits::{EaseInOut, MotorController},
 //! };
@@ -26,7 +26,7 @@
 //!
 //! // Apply a speed command with smooth transition
 //! let cmd = ThrottleCommand::SetSpeed {
-//!     target: 0.8,
+//!     target: Speed::new(0.8).unwrap(),
 //!     strategy: EaseInOut::departure(2000), // 2 second departure
 //! };
 //! controller.apply_command(cmd.into(), CommandSource::Physical, 0).unwrap();
@@ -38,7 +38,7 @@
 //!
 //! // Get current state for UI
 //! let state = controller.state(2000);
-//! println!("Speed: {:.1}%, Direction: {:?}", state.speed * 100.0, state.direction);
+//! println!("Speed: {:.1}%, Direction: {:?}", state.speed.get() * 100.0, state.direction);
 //! ```
 //!
 //! # Fault Handling
@@ -60,10 +60,23 @@
 //! assert!(!controller.has_fault());
 //! ```
 
-use crate::commands::{CommandOutcome, CommandSource, ThrottleCommandDyn};
+use crate::cab::{BrakeSetting, CabSimulation, CabStatus, DrivingMode, LocoProfile, Notch};
+use crate::commands::{
+    ClientId, CommandOutcome, CommandSource, RejectReason, SourcePriorities, ThrottleCommandDyn,
+    TransitionResult,
+};
+use crate::config::{default_service_brake, StrategyPresets};
+use crate::priority::{
+    AuditEntry, AuditLog, CommandMetrics, HeartbeatLease, HeartbeatStatus, LeaseStatus,
+    LockoutStatus, RateLimiter, RateLimits, SafeStop,
+};
+use crate::speed::{Speed, Velocity};
 use crate::strategy_dyn::AnyStrategy;
-use crate::traits::{Direction, FaultKind, Immediate, MotorController};
-use crate::transition::{LockStatus, TransitionManager, TransitionProgress};
+use crate::traits::{Direction, FaultKind, Immediate, Linear, MotorController, StrategySpec};
+use crate::transition::{
+    LockStatus, QueuedCommand, TransitionEvent, TransitionManager, TransitionProgress,
+    DEFAULT_QUEUE_DEPTH, MAX_QUEUE_DEPTH,
+};
 
 /// Main throttle controller.
 ///
@@ -73,6 +86,8 @@ use crate::transition::{LockStatus, TransitionManager, TransitionProgress};
 /// # Type Parameter
 ///
 /// - `M`: The motor controller implementation ([`MotorController`] trait)
+/// - `QUEUE`: How many speed commands can wait behind a locked transition
+///   (see [`with_queue_depth`](Self::with_queue_depth))
 ///
 /// # Thread Safety
 ///
@@ -80,12 +95,35 @@ use crate::transition::{LockStatus, TransitionManager, TransitionProgress};
 /// (e.g., web server + main loop), wrap in `Arc<Mutex<ThrottleController>>`
 /// or use the `SharedThrottleState` wrapper from the services module
 /// (requires `web` or `mqtt` feature).
-pub struct ThrottleController<M: MotorController> {
+pub struct ThrottleController<M: MotorController, const QUEUE: usize = DEFAULT_QUEUE_DEPTH> {
     motor: M,
-    speed_transition: TransitionManager,
+    speed_transition: TransitionManager<QUEUE>,
     direction: Direction,
-    max_speed: f32,
+    max_speed: Speed,
     fault: Option<FaultKind>,
+    heartbeat: HeartbeatLease,
+    presets: StrategyPresets,
+    reversal: Option<Reversal>,
+    loco: LocoProfile,
+    cab: Option<CabSimulation>,
+    brake_profile: StrategySpec,
+    service_brake: Option<ServiceBrakeStatus>,
+    force_release_source: CommandSource,
+    rate_limiter: RateLimiter,
+    queue_full: u32,
+    audit: AuditLog,
+}
+
+/// Second leg of a velocity change that passes through zero
+#[derive(Clone, Debug)]
+struct Reversal {
+    /// Transition id of the first leg, kept by the second
+    leg: Option<u32>,
+    direction: Direction,
+    target: Speed,
+    strategy: AnyStrategy,
+    source: CommandSource,
+    client: Option<ClientId>,
 }
 
 impl<M: MotorController> ThrottleController<M> {
@@ -93,76 +131,646 @@ impl<M: MotorController> ThrottleController<M> {
     pub fn new(motor: M) -> Self {
         Self {
             motor,
-            speed_transition: TransitionManager::new(0.0),
+            speed_transition: TransitionManager::new(Speed::ZERO),
             direction: Direction::Stopped,
-            max_speed: 1.0,
+            max_speed: Speed::FULL,
             fault: None,
+            heartbeat: HeartbeatLease::disabled(),
+            presets: StrategyPresets::default(),
+            reversal: None,
+            loco: LocoProfile::default(),
+            cab: None,
+            brake_profile: default_service_brake(),
+            service_brake: None,
+            force_release_source: CommandSource::Physical,
+            rate_limiter: RateLimiter::disabled(),
+            queue_full: 0,
+            audit: AuditLog::new(),
+        }
+    }
+}
+
+impl<M: MotorController, const QUEUE: usize> ThrottleController<M, QUEUE> {
+    /// Let up to `DEPTH` speed commands wait behind a locked transition
+    ///
+    /// Defaults to [`DEFAULT_QUEUE_DEPTH`]; at most [`MAX_QUEUE_DEPTH`].
+    pub fn with_queue_depth<const DEPTH: usize>(self) -> ThrottleController<M, DEPTH> {
+        ThrottleController {
+            motor: self.motor,
+            speed_transition: self.speed_transition.with_queue_depth(),
+            direction: self.direction,
+            max_speed: self.max_speed,
+            fault: self.fault,
+            heartbeat: self.heartbeat,
+            presets: self.presets,
+            reversal: self.reversal,
+            loco: self.loco,
+            cab: self.cab,
+            brake_profile: self.brake_profile,
+            service_brake: self.service_brake,
+            force_release_source: self.force_release_source,
+            rate_limiter: self.rate_limiter,
+            queue_full: self.queue_full,
+            audit: self.audit,
+        }
+    }
+
+    /// Enforce a heartbeat lease on remote sources
+    ///
+    /// See [`HeartbeatLease`] for the lease rules.
+    pub fn with_heartbeat(mut self, lease: HeartbeatLease) -> Self {
+        self.heartbeat = lease;
+        self
+    }
+
+    /// Degrade transition locks to unlocked after `max_ms`
+    ///
+    /// See [`TransitionManager::with_max_lock_duration`].
+    pub fn with_max_lock_duration(mut self, max_ms: u64) -> Self {
+        self.speed_transition = self.speed_transition.with_max_lock_duration(max_ms);
+        self
+    }
+
+    /// Rank command sources with `priorities` instead of the default table
+    ///
+    /// Used for transition locks, pause and resume, queue cancellation and
+    /// the force release threshold.
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.speed_transition = self.speed_transition.with_priorities(priorities);
+        self
+    }
+
+    /// Reject commands from sources that exceed their rate limit
+    ///
+    /// E-stops and heartbeats are never limited. See [`RateLimiter`].
+    pub fn with_rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limiter = RateLimiter::new(limits);
+        self
+    }
+
+    /// Lowest source allowed to send [`ThrottleCommand::ForceRelease`], or
+    /// to pause another source's hard locked transition
+    ///
+    /// Defaults to [`CommandSource::Physical`].
+    ///
+    /// [`ThrottleCommand::ForceRelease`]: crate::ThrottleCommand::ForceRelease
+    pub fn with_force_release_source(mut self, source: CommandSource) -> Self {
+        self.force_release_source = source;
+        self.speed_transition = self.speed_transition.with_force_release_source(source);
+        self
+    }
+
+    /// Use the given registry to resolve named strategy presets
+    ///
+    /// Defaults to the built-in presets of [`StrategyPresets::default`].
+    pub fn with_presets(mut self, presets: StrategyPresets) -> Self {
+        self.presets = presets;
+        self
+    }
+
+    /// The registry used to resolve named strategy presets
+    pub fn presets(&self) -> &StrategyPresets {
+        &self.presets
+    }
+
+    /// The table used to rank command sources
+    pub fn priorities(&self) -> &SourcePriorities {
+        self.speed_transition.priorities()
+    }
+
+    /// Simulate the given loco in cab driving mode
+    ///
+    /// Defaults to [`LocoProfile::default`].
+    pub fn with_loco(mut self, profile: LocoProfile) -> Self {
+        self.loco = profile;
+        if let Some(cab) = &mut self.cab {
+            cab.set_profile(profile);
+        }
+        self
+    }
+
+    /// Decelerate with `strategy` for service brake applications
+    ///
+    /// Used by [`ThrottleCommandDyn::Brake`] commands that don't carry
+    /// their own profile. May name a strategy preset.
+    pub fn with_service_brake(mut self, strategy: impl Into<StrategySpec>) -> Self {
+        self.brake_profile = strategy.into();
+        self
+    }
+
+    /// Start in the given driving mode
+    pub fn with_driving_mode(mut self, mode: DrivingMode) -> Self {
+        self.set_driving_mode(mode);
+        self
+    }
+
+    /// The current driving mode
+    pub fn driving_mode(&self) -> DrivingMode {
+        match self.cab {
+            Some(_) => DrivingMode::Cab,
+            None => DrivingMode::Direct,
+        }
+    }
+
+    /// Cab controls, if in cab driving mode
+    pub fn cab_status(&self) -> Option<CabStatus> {
+        self.cab.as_ref().map(CabSimulation::status)
+    }
+
+    /// Switch driving mode, keeping the train's current speed
+    ///
+    /// Entering cab mode cancels any transition and starts with idle
+    /// throttle and released brake.
+    fn set_driving_mode(&mut self, mode: DrivingMode) {
+        match mode {
+            DrivingMode::Cab if self.cab.is_none() => {
+                let current = self.speed_transition.current();
+                self.speed_transition.cancel_and_set(current);
+                self.reversal = None;
+                self.cab = Some(CabSimulation::new(self.loco, current));
+            }
+            DrivingMode::Cab => {}
+            DrivingMode::Direct => self.cab = None,
         }
     }
 
     /// Apply a command to the throttle
+    ///
+    /// Speed commands naming an unknown strategy preset are rejected with
+    /// [`RejectReason::UnknownPreset`]. Commands that don't fit the current
+    /// [`DrivingMode`] are rejected with [`RejectReason::WrongDrivingMode`].
     pub fn apply_command(
         &mut self,
         cmd: ThrottleCommandDyn,
         source: CommandSource,
         now_ms: u64,
     ) -> Result<CommandOutcome, M::Error> {
+        self.apply_command_as(cmd, source, None, now_ms)
+    }
+
+    /// Apply a command on behalf of a named client
+    ///
+    /// Like [`apply_command`](Self::apply_command), but speed transitions
+    /// record `client` (so a source lock they take holds off other clients
+    /// of the same source) and the [`audit`](Self::audit) trail names it.
+    /// Every command but heartbeats is audited.
+    pub fn apply_command_as(
+        &mut self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<CommandOutcome, M::Error> {
+        let name = cmd.name();
+        let heartbeat = matches!(cmd, ThrottleCommandDyn::Heartbeat);
+        let outcome = self.dispatch(cmd, source, client, now_ms)?;
+        if !heartbeat {
+            let rejected = match &outcome {
+                CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason }) => {
+                    Some(reason.clone())
+                }
+                _ => None,
+            };
+            self.audit.record(name, source, client, rejected, now_ms);
+        }
+        Ok(outcome)
+    }
+
+    fn dispatch(
+        &mut self,
+        cmd: ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<CommandOutcome, M::Error> {
+        let renews_lease = !matches!(
+            cmd,
+            ThrottleCommandDyn::Heartbeat | ThrottleCommandDyn::EmergencyStop
+        );
+        if !self.rate_limiter.allow(&cmd, source, now_ms) {
+            return Ok(CommandOutcome::SpeedTransition(
+                TransitionResult::Rejected {
+                    reason: RejectReason::RateLimited,
+                },
+            ));
+        }
         let outcome = match cmd {
+            ThrottleCommandDyn::SetSpeed { .. }
+            | ThrottleCommandDyn::SetVelocity { .. }
+            | ThrottleCommandDyn::SetNotch(_)
+                if self.service_brake.is_some_and(|brake| brake.hold) =>
+            {
+                CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                    reason: RejectReason::BrakeHeld,
+                })
+            }
+
+            ThrottleCommandDyn::SetSpeed { .. } | ThrottleCommandDyn::SetVelocity { .. }
+                if self.cab.is_some() =>
+            {
+                wrong_driving_mode()
+            }
+
             ThrottleCommandDyn::SetSpeed { target, strategy } => {
-                let clamped = target.clamp(0.0, self.max_speed);
-                let result = self.speed_transition.try_start(
-                    clamped, strategy, source, false, // not e-stop
-                    now_ms,
-                );
+                let limited = target.min(self.max_speed);
+                let result = match self.resolve_strategy(strategy) {
+                    Some(strategy) => self.speed_transition.try_start_as(
+                        limited, strategy, source, client, false, // not e-stop
+                        now_ms,
+                    ),
+                    None => TransitionResult::Rejected {
+                        reason: RejectReason::UnknownPreset,
+                    },
+                };
+                if !matches!(result, TransitionResult::Rejected { .. }) {
+                    self.reversal = None;
+                    self.service_brake = None;
+                }
+                CommandOutcome::SpeedTransition(result)
+            }
+
+            ThrottleCommandDyn::SetVelocity { target, strategy } => {
+                let result = match self.resolve_strategy(strategy) {
+                    Some(strategy) => {
+                        self.start_velocity(target, strategy, source, client, now_ms)?
+                    }
+                    None => TransitionResult::Rejected {
+                        reason: RejectReason::UnknownPreset,
+                    },
+                };
                 CommandOutcome::SpeedTransition(result)
             }
 
             ThrottleCommandDyn::EmergencyStop => {
                 let result = self.speed_transition.try_start(
-                    0.0,
-                    AnyStrategy::new(Immediate),
+                    Speed::ZERO,
+                    AnyStrategy::from(Immediate),
                     source,
                     true, // is e-stop
                     now_ms,
                 );
-                self.direction = Direction::Stopped;
-                self.motor.set_direction(Direction::Stopped)?;
+                self.reversal = None;
+                self.service_brake = None;
+                if let Some(cab) = &mut self.cab {
+                    cab.emergency_stop();
+                }
+                self.set_direction(Direction::Stopped)?;
                 self.motor.set_speed(0.0)?;
+                self.heartbeat.clear();
                 CommandOutcome::SpeedTransition(result)
             }
 
             ThrottleCommandDyn::SetDirection(dir) => {
-                self.direction = dir;
-                self.motor.set_direction(dir)?;
+                self.reversal = None;
+                self.set_direction(dir)?;
                 CommandOutcome::Applied
             }
 
             ThrottleCommandDyn::SetMaxSpeed(max) => {
-                self.max_speed = max.clamp(0.0, 1.0);
-                // If current target exceeds new max, adjust
-                if let Some(target) = self.speed_transition.target() {
-                    if target > self.max_speed {
-                        // Could trigger a new transition to max_speed here
+                self.max_speed = max;
+                if let Some(reversal) = &mut self.reversal {
+                    reversal.target = reversal.target.min(max);
+                }
+                // A transition past the new max is capped as it runs
+                self.speed_transition.set_max_speed(max);
+                CommandOutcome::Applied
+            }
+
+            ThrottleCommandDyn::Heartbeat => {
+                self.heartbeat.refresh(source, now_ms);
+                CommandOutcome::Applied
+            }
+
+            ThrottleCommandDyn::SetNotch(notch) => match &mut self.cab {
+                Some(cab) => {
+                    cab.set_notch(notch);
+                    CommandOutcome::Applied
+                }
+                None => wrong_driving_mode(),
+            },
+
+            ThrottleCommandDyn::SetBrake(brake) => match &mut self.cab {
+                Some(cab) => {
+                    cab.set_brake(brake);
+                    CommandOutcome::Applied
+                }
+                None => wrong_driving_mode(),
+            },
+
+            ThrottleCommandDyn::SetDrivingMode(mode) => {
+                self.set_driving_mode(mode);
+                CommandOutcome::Applied
+            }
+
+            ThrottleCommandDyn::Brake { strategy, hold } => {
+                self.apply_service_brake(strategy, hold, source, client, now_ms)
+            }
+
+            ThrottleCommandDyn::ReleaseBrake => {
+                if let (Some(_), Some(cab)) = (self.service_brake.take(), &mut self.cab) {
+                    cab.set_brake(BrakeSetting::Release);
+                }
+                CommandOutcome::Applied
+            }
+
+            ThrottleCommandDyn::CancelQueued(id) => {
+                match self.speed_transition.cancel_queued(id, source, client) {
+                    Ok(_) => CommandOutcome::Applied,
+                    Err(reason) => {
+                        CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason })
                     }
                 }
+            }
+
+            ThrottleCommandDyn::ClearQueue => {
+                self.speed_transition.clear_queue(source, client);
                 CommandOutcome::Applied
             }
+
+            ThrottleCommandDyn::Pause => {
+                match self.speed_transition.pause(source, client, now_ms) {
+                    Ok(()) => CommandOutcome::Applied,
+                    Err(reason) => {
+                        CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason })
+                    }
+                }
+            }
+
+            ThrottleCommandDyn::Resume => {
+                match self.speed_transition.resume(source, client, now_ms) {
+                    Ok(()) => CommandOutcome::Applied,
+                    Err(reason) => {
+                        CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason })
+                    }
+                }
+            }
+
+            ThrottleCommandDyn::ForceRelease => {
+                let priorities = self.speed_transition.priorities();
+                let result = if priorities.is_below(source, self.force_release_source) {
+                    Err(RejectReason::LowerPriority)
+                } else {
+                    self.speed_transition.release_lock(source, now_ms)
+                };
+                match result {
+                    Ok(()) => CommandOutcome::Applied,
+                    Err(reason) => {
+                        CommandOutcome::SpeedTransition(TransitionResult::Rejected { reason })
+                    }
+                }
+            }
         };
 
+        let rejected = matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })
+        );
+        if renews_lease && !rejected {
+            self.heartbeat.on_command(source, now_ms);
+        }
+        if matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::QueueFull
+            })
+        ) {
+            self.queue_full = self.queue_full.saturating_add(1);
+        }
+
         Ok(outcome)
     }
 
+    /// Start a service brake application
+    ///
+    /// In cab mode this closes the throttle and applies the brake handle;
+    /// otherwise the speed follows `strategy` (or the configured profile)
+    /// down to zero, subject to its lock.
+    fn apply_service_brake(
+        &mut self,
+        strategy: Option<AnyStrategy>,
+        hold: bool,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> CommandOutcome {
+        let brake = ServiceBrakeStatus { source, hold };
+        if let Some(cab) = &mut self.cab {
+            cab.set_notch(Notch::IDLE);
+            cab.set_brake(BrakeSetting::Apply);
+            self.service_brake = Some(brake);
+            return CommandOutcome::Applied;
+        }
+
+        let strategy = strategy.unwrap_or_else(|| AnyStrategy::from(self.brake_profile.clone()));
+        let result = match self.resolve_strategy(strategy) {
+            Some(strategy) => self.speed_transition.try_start_as(
+                Speed::ZERO,
+                strategy,
+                source,
+                client,
+                false,
+                now_ms,
+            ),
+            None => TransitionResult::Rejected {
+                reason: RejectReason::UnknownPreset,
+            },
+        };
+        if !matches!(result, TransitionResult::Rejected { .. }) {
+            self.reversal = None;
+            self.service_brake = Some(brake);
+        }
+        CommandOutcome::SpeedTransition(result)
+    }
+
+    /// Resolve a named strategy preset (`None` if the name is unknown)
+    fn resolve_strategy(&self, strategy: AnyStrategy) -> Option<AnyStrategy> {
+        match strategy.spec() {
+            Some(spec) if spec.preset_name().is_some() => {
+                self.presets.resolve(spec).map(AnyStrategy::from)
+            }
+            _ => Some(strategy),
+        }
+    }
+
+    /// Start a velocity change
+    ///
+    /// If the train is moving the other way it first decelerates to zero;
+    /// [`update`](Self::update) then flips the direction and starts the
+    /// second leg with the same strategy.
+    fn start_velocity(
+        &mut self,
+        target: Velocity,
+        strategy: AnyStrategy,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<TransitionResult, M::Error> {
+        let speed = target.speed().min(self.max_speed);
+        let direction = target.direction();
+        let moving = self.speed_transition.current() > Speed::ZERO
+            || self.speed_transition.target() > Some(Speed::ZERO);
+        let flips = direction != Direction::Stopped && direction != self.direction;
+        let reverses = flips && moving && self.direction != Direction::Stopped;
+
+        let leg = if reverses { Speed::ZERO } else { speed };
+        let result = self.speed_transition.try_start_as(
+            leg,
+            strategy.clone(),
+            source,
+            client,
+            false,
+            now_ms,
+        );
+        if matches!(result, TransitionResult::Rejected { .. }) {
+            return Ok(result);
+        }
+
+        self.reversal = None;
+        self.service_brake = None;
+        if reverses {
+            let leg = match result {
+                TransitionResult::Queued { id } => Some(id),
+                _ => self.speed_transition.active_id(),
+            };
+            self.reversal = Some(Reversal {
+                leg,
+                direction,
+                target: speed,
+                strategy,
+                source,
+                client,
+            });
+        } else if flips {
+            self.set_direction(direction)?;
+        }
+        Ok(result)
+    }
+
+    /// Flip direction at zero and start the second leg of a reversal
+    fn finish_reversal(&mut self, now_ms: u64) -> Result<(), M::Error> {
+        let at_rest = !self.speed_transition.is_transitioning()
+            && self.speed_transition.current() == Speed::ZERO;
+        if !at_rest {
+            return Ok(());
+        }
+        if let Some(reversal) = self.reversal.take() {
+            self.set_direction(reversal.direction)?;
+            let _ = self.speed_transition.try_start_as(
+                reversal.target,
+                reversal.strategy,
+                reversal.source,
+                reversal.client,
+                false,
+                now_ms,
+            );
+            if let Some(leg) = reversal.leg {
+                self.speed_transition.continue_as(leg);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_direction(&mut self, direction: Direction) -> Result<(), M::Error> {
+        self.direction = direction;
+        self.motor.set_direction(direction)
+    }
+
     /// Update the controller - call every tick (e.g., 20ms)
+    ///
+    /// Also enforces the heartbeat lease: if a remote source holding
+    /// control has gone quiet, the configured [`SafeStop`] is performed.
+    ///
+    /// Also completes a pending [`SetVelocity`] reversal once the train
+    /// has come to rest.
+    ///
+    /// In [`DrivingMode::Cab`] the speed comes from the cab simulation
+    /// instead of a transition.
+    ///
+    /// With the `fixed-point` feature, the speed is computed and handed to
+    /// the motor via [`MotorController::set_speed_q16`] in fixed point.
+    /// Built-in strategies then need no floating-point math per tick;
+    /// sequences, custom strategies and the cab simulation still use `f32`.
+    ///
+    /// [`SetVelocity`]: ThrottleCommandDyn::SetVelocity
     pub fn update(&mut self, now_ms: u64) -> Result<(), M::Error> {
-        let (speed, _complete) = self.speed_transition.update(now_ms);
-        self.motor.set_speed(speed)?;
+        if self.heartbeat.check_expired(now_ms).is_some() {
+            self.safe_stop(now_ms)?;
+        }
+        if let Some(cab) = &mut self.cab {
+            let speed = cab.update(now_ms, self.max_speed);
+            self.speed_transition.cancel_and_set(speed);
+        }
+        #[cfg(feature = "fixed-point")]
+        {
+            let (speed, _complete) = self.speed_transition.update_q16(now_ms);
+            self.motor.set_speed_q16(speed)?;
+        }
+        #[cfg(not(feature = "fixed-point"))]
+        {
+            let (speed, _complete) = self.speed_transition.update(now_ms);
+            self.motor.set_speed(speed.get())?;
+        }
+        if self.reversal.is_some() {
+            self.finish_reversal(now_ms)?;
+        }
+        self.finish_service_brake();
+        Ok(())
+    }
+
+    /// End a non-holding service brake application once the train is at rest
+    fn finish_service_brake(&mut self) {
+        let at_rest = !self.speed_transition.is_transitioning()
+            && self.speed_transition.current() == Speed::ZERO;
+        if at_rest && self.service_brake.is_some_and(|brake| !brake.hold) {
+            self.service_brake = None;
+        }
+    }
+
+    /// Perform the configured safe stop after a heartbeat lease lapses
+    fn safe_stop(&mut self, now_ms: u64) -> Result<(), M::Error> {
+        if self.speed_transition.current() == Speed::ZERO
+            && !self.speed_transition.is_transitioning()
+        {
+            return Ok(());
+        }
+        self.reversal = None;
+        if let Some(cab) = &mut self.cab {
+            // The simulated train can only be braked to a stop
+            if let SafeStop::Ramp { .. } = self.heartbeat.safe_stop() {
+                cab.set_notch(Notch::IDLE);
+                cab.set_brake(BrakeSetting::Apply);
+                return Ok(());
+            }
+        }
+        if let SafeStop::Ramp { duration_ms } = self.heartbeat.safe_stop() {
+            let result = self.speed_transition.try_start(
+                Speed::ZERO,
+                AnyStrategy::from(Linear::new(duration_ms as u64)),
+                CommandSource::Fault,
+                false,
+                now_ms,
+            );
+            if !matches!(result, TransitionResult::Rejected { .. }) {
+                return Ok(());
+            }
+        }
+        // E-stop, or a hard-locked transition refused the ramp
+        self.apply_command(
+            ThrottleCommandDyn::EmergencyStop,
+            CommandSource::Fault,
+            now_ms,
+        )?;
         Ok(())
     }
 
     /// Handle a detected fault
     pub fn handle_fault(&mut self, fault: FaultKind) -> Result<(), M::Error> {
         self.fault = Some(fault);
-        self.speed_transition.cancel_and_set(0.0);
+        self.reversal = None;
+        if let Some(cab) = &mut self.cab {
+            cab.emergency_stop();
+        }
+        self.speed_transition.cancel_and_set(Speed::ZERO);
         self.motor.set_speed(0.0)?;
         Ok(())
     }
@@ -178,15 +786,74 @@ impl<M: MotorController> ThrottleController<M> {
             speed: self.speed_transition.current(),
             target_speed: self.speed_transition.target(),
             direction: self.direction,
+            velocity: Velocity::from_parts(self.speed_transition.current(), self.direction),
             max_speed: self.max_speed,
             fault: self.fault,
-            lock_status: self.speed_transition.lock_status(),
+            lock_status: self.speed_transition.lock_status(now_ms),
             transition_progress: self.speed_transition.progress(now_ms),
+            queue: self.speed_transition.queued().collect(),
+            heartbeat: self.heartbeat.status(now_ms),
+            lease: None,
+            lockout: None,
+            cab: self.cab_status(),
+            service_brake: self.service_brake,
+            metrics: self.metrics(),
+        }
+    }
+
+    /// Counts of commands dropped by rate limits or a full queue
+    ///
+    /// Speed commands are coalesced before they reach the controller, so
+    /// `coalesced` is filled in by the service that does it (see
+    /// `services::SharedThrottleState`).
+    pub fn metrics(&self) -> CommandMetrics {
+        CommandMetrics {
+            rate_limited: self.rate_limiter.dropped(),
+            coalesced: 0,
+            queue_full: self.queue_full,
+        }
+    }
+
+    /// Recent commands with the source and client that sent them, oldest first
+    pub fn audit(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.audit.entries()
+    }
+
+    /// Id of the active speed transition
+    ///
+    /// Queued commands keep the id reported in [`TransitionResult::Queued`]
+    /// when they start, and a reversal keeps its first leg's id.
+    pub fn transition_id(&self) -> Option<u32> {
+        self.speed_transition.active_id()
+    }
+
+    /// Take the oldest speed transition lifecycle event not yet taken
+    ///
+    /// See [`TransitionManager::pop_event`].
+    pub fn pop_transition_event(&mut self) -> Option<TransitionEvent> {
+        self.speed_transition.pop_event()
+    }
+
+    /// Record a command that was refused before it reached the controller
+    ///
+    /// For checks kept outside the controller, such as throttle leases, so
+    /// the [`audit`](Self::audit) trail still shows who was turned away.
+    pub fn audit_rejected(
+        &mut self,
+        cmd: &ThrottleCommandDyn,
+        source: CommandSource,
+        client: Option<ClientId>,
+        reason: RejectReason,
+        now_ms: u64,
+    ) {
+        if !matches!(cmd, ThrottleCommandDyn::Heartbeat) {
+            self.audit
+                .record(cmd.name(), source, client, Some(reason), now_ms);
         }
     }
 
     /// Get just the current speed
-    pub fn current_speed(&self) -> f32 {
+    pub fn current_speed(&self) -> Speed {
         self.speed_transition.current()
     }
 
@@ -195,17 +862,34 @@ impl<M: MotorController> ThrottleController<M> {
         self.direction
     }
 
+    /// Get the current signed velocity
+    pub fn current_velocity(&self) -> Velocity {
+        Velocity::from_parts(self.speed_transition.current(), self.direction)
+    }
+
     /// Check if a transition is in progress
     pub fn is_transitioning(&self) -> bool {
         self.speed_transition.is_transitioning()
     }
 
+    /// Speed commands waiting behind the active transition, next first
+    pub fn queued(&self) -> impl Iterator<Item = QueuedCommand> + '_ {
+        self.speed_transition.queued()
+    }
+
     /// Check if there's an active fault
     pub fn has_fault(&self) -> bool {
         self.fault.is_some()
     }
 }
 
+/// Rejection for a command that doesn't fit the driving mode
+fn wrong_driving_mode() -> CommandOutcome {
+    CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+        reason: RejectReason::WrongDrivingMode,
+    })
+}
+
 /// Full state snapshot for UI/API.
 ///
 /// Contains all relevant throttle state for rendering UI or responding
@@ -228,32 +912,75 @@ impl<M: MotorController> ThrottleController<M> {
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ThrottleState {
-    /// Current speed (0.0 to 1.0).
-    pub speed: f32,
+    /// Current speed.
+    pub speed: Speed,
     /// Target speed if a transition is in progress.
-    pub target_speed: Option<f32>,
+    pub target_speed: Option<Speed>,
     /// Current direction of travel.
     pub direction: Direction,
-    /// Maximum allowed speed (0.0 to 1.0).
-    pub max_speed: f32,
+    /// Current speed signed by direction (negative is reverse).
+    pub velocity: Velocity,
+    /// Maximum allowed speed.
+    pub max_speed: Speed,
     /// Current fault condition, if any.
     pub fault: Option<FaultKind>,
     /// Transition lock status, if a locked transition is active.
     pub lock_status: Option<LockStatus>,
     /// Progress of current transition, if any.
     pub transition_progress: Option<TransitionProgress>,
+    /// Speed commands waiting behind the active transition, next first.
+    pub queue: heapless::Vec<QueuedCommand, MAX_QUEUE_DEPTH>,
+    /// Heartbeat lease held by a remote source, if any.
+    pub heartbeat: Option<HeartbeatStatus>,
+    /// Throttle lease holder, filled in by services that manage leases.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lease: Option<LeaseStatus>,
+    /// Physical control lockout, filled in by services that enforce one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lockout: Option<LockoutStatus>,
+    /// Cab controls, present in cab driving mode.
+    pub cab: Option<CabStatus>,
+    /// Active service brake application, if any.
+    pub service_brake: Option<ServiceBrakeStatus>,
+    /// Counts of dropped commands.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metrics: CommandMetrics,
 }
 
 impl Default for ThrottleState {
     fn default() -> Self {
         Self {
-            speed: 0.0,
+            speed: Speed::ZERO,
             target_speed: None,
             direction: Direction::Stopped,
-            max_speed: 1.0,
+            velocity: Velocity::ZERO,
+            max_speed: Speed::FULL,
             fault: None,
             lock_status: None,
             transition_progress: None,
+            queue: heapless::Vec::new(),
+            heartbeat: None,
+            lease: None,
+            lockout: None,
+            cab: None,
+            service_brake: None,
+            metrics: CommandMetrics::default(),
         }
     }
 }
+
+/// An active service brake application.
+///
+/// Reported in [`ThrottleState`] from the [`Brake`] command until the
+/// train is at rest, or until [`ReleaseBrake`] when holding.
+///
+/// [`Brake`]: ThrottleCommandDyn::Brake
+/// [`ReleaseBrake`]: ThrottleCommandDyn::ReleaseBrake
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServiceBrakeStatus {
+    /// Source that applied the brake.
+    pub source: CommandSource,
+    /// Whether the train is held at rest until released.
+    pub hold: bool,
+}
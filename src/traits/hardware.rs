@@ -35,6 +35,8 @@
 //! }
 //! ```
 
+use crate::fixed::Q16;
+
 /// Direction of train travel.
 ///
 /// Controls the polarity of the motor output. For DC motors, this typically
@@ -111,11 +113,16 @@ impl Direction {
     /// assert_eq!(Direction::from_text("  FWD  "), Some(Direction::Forward));
     /// ```
     pub fn from_text(s: &str) -> Option<Self> {
-        match s.trim().to_ascii_lowercase().as_str() {
-            "forward" | "fwd" | "1" => Some(Direction::Forward),
-            "reverse" | "rev" | "-1" => Some(Direction::Reverse),
-            "stopped" | "stop" | "0" => Some(Direction::Stopped),
-            _ => None,
+        let s = s.trim();
+        let is_any = |names: &[&str]| names.iter().any(|n| s.eq_ignore_ascii_case(n));
+        if is_any(&["forward", "fwd", "1"]) {
+            Some(Direction::Forward)
+        } else if is_any(&["reverse", "rev", "-1"]) {
+            Some(Direction::Reverse)
+        } else if is_any(&["stopped", "stop", "0"]) {
+            Some(Direction::Stopped)
+        } else {
+            None
         }
     }
 }
@@ -167,6 +174,15 @@ pub trait MotorController {
     /// Values outside this range should be clamped.
     fn set_speed(&mut self, speed: f32) -> Result<(), Self::Error>;
 
+    /// Set speed from a fixed-point value (0.0 to 1.0).
+    ///
+    /// Called instead of [`set_speed`](Self::set_speed) with the
+    /// `fixed-point` feature. Override to compute the PWM duty with
+    /// [`Q16::duty`]; the default converts to `f32`.
+    fn set_speed_q16(&mut self, speed: Q16) -> Result<(), Self::Error> {
+        self.set_speed(speed.to_f32())
+    }
+
     /// Set direction of travel.
     ///
     /// This controls the H-bridge polarity. For safety, consider
@@ -278,6 +294,16 @@ pub enum FaultKind {
     Overcurrent,
 }
 
+impl FaultKind {
+    /// Returns the fault as a snake_case string, as used in JSON.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::ShortCircuit => "short_circuit",
+            Self::Overcurrent => "overcurrent",
+        }
+    }
+}
+
 /// Time source trait for `no_std` compatibility.
 ///
 /// Provides monotonic time in milliseconds for transition timing.
@@ -327,6 +353,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn direction_clone() {
         let dir = Direction::Forward;
         let cloned = dir.clone();
@@ -408,6 +435,7 @@ mod tests {
     // =========================================================================
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn fault_kind_clone() {
         let fault = FaultKind::ShortCircuit;
         let cloned = fault.clone();
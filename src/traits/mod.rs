@@ -8,7 +8,7 @@
 //! # Submodules
 //!
 //! - `hardware`: Motor control, encoder input, fault detection, clock
-//! - `network`: MQTT client and HTTP server traits
+//! - `network`: MQTT client and HTTP server traits (requires `alloc` feature)
 //! - `strategy`: Execution strategies for speed transitions
 //! - `display`: Display rendering trait
 //!
@@ -29,13 +29,17 @@
 //! - [`Linear`]: Constant rate over duration
 //! - [`EaseInOut`]: Smoothstep curve (good for stations)
 //! - [`Momentum`]: Physics-based acceleration
+//! - [`SCurve`]: Jerk-limited acceleration and deceleration
+//! - [`Keyframes`]: Custom profile from (time, fraction) points
 
 pub mod display;
 pub mod hardware;
+#[cfg(feature = "alloc")]
 pub mod network;
 pub mod strategy;
 
 pub use display::*;
 pub use hardware::*;
+#[cfg(feature = "alloc")]
 pub use network::*;
 pub use strategy::*;
@@ -33,7 +33,6 @@
 //! POST /api/estop     - Trigger emergency stop
 //! ```
 
-extern crate alloc;
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -272,6 +271,7 @@ mod tests {
     // =========================================================================
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn http_method_clone() {
         let method = HttpMethod::Get;
         let cloned = method.clone();
@@ -1,6 +1,6 @@
 //! Execution strategy traits for controlling how speed transitions happen.
 //!
-//! This module defines the [`ExecutionStrategy`] trait and provides four
+//! This module defines the [`ExecutionStrategy`] trait and provides six
 //! built-in implementations for different transition behaviors.
 //!
 //! # Built-in Strategies
@@ -11,6 +11,15 @@
 //! | [`Linear`] | Simple speed ramps | Yes |
 //! | [`EaseInOut`] | Station arrivals/departures | Yes |
 //! | [`Momentum`] | Realistic physics feel | Yes |
+//! | [`SCurve`] | Jerk-limited prototypical motion | Yes |
+//! | [`Keyframes`] | Operator-designed profiles | Yes |
+//!
+//! [`StrategySpec`] wraps any of these in a single serializable enum, for
+//! strategies that arrive over the network. It can also name a preset
+//! (e.g. `"departure"`) that the controller resolves from its registry.
+//!
+//! To chain several moves into one transition, see the combinators in
+//! [`sequence`](crate::sequence). They aren't part of [`StrategySpec`].
 //!
 //! # Transition Locks
 //!
@@ -56,6 +65,22 @@
 //! // Feels like a real throttle
 //! let momentum = Momentum::responsive();
 //! ```
+//!
+//! # Interruptions
+//!
+//! Strategies report their instantaneous [`rate`](ExecutionStrategy::rate)
+//! of change. When a transition is replaced mid-ramp, the transition manager
+//! carries that rate into the new one and fades it out over the new
+//! strategy's [`blend_ms`](ExecutionStrategy::blend_ms), so speed never
+//! changes direction or slope abruptly.
+//!
+//! # Fixed Point
+//!
+//! Every strategy also has [`ExecutionStrategy::interpolate_q16`], which the
+//! built-ins implement with integer math for FPU-less targets (see
+//! [`crate::fixed`]).
+
+use crate::fixed::Q16;
 
 /// How a transition is protected from interruption.
 ///
@@ -102,6 +127,17 @@ pub enum TransitionLock {
     Hard,
 }
 
+impl TransitionLock {
+    /// Returns the lock as a lowercase string, as used in JSON.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Source => "source",
+            Self::Hard => "hard",
+        }
+    }
+}
+
 /// What happens when something tries to interrupt a locked transition.
 ///
 /// Used by [`ExecutionStrategy::on_interrupt`] to specify behavior when
@@ -156,6 +192,10 @@ pub enum InterruptBehavior {
 ///
 /// # Optional Methods
 ///
+/// - [`duration_for`](Self::duration_for): Duration for a specific move (default: `duration_ms`)
+/// - [`rate`](Self::rate): Rate of change at a point in time (default: numeric derivative)
+/// - [`blend_ms`](Self::blend_ms): How long an inherited rate fades out (default: duration)
+/// - [`segment`](Self::segment): Current segment of a multi-segment strategy (default: `None`)
 /// - [`lock`](Self::lock): Return [`TransitionLock`] level (default: `None`)
 /// - [`on_interrupt`](Self::on_interrupt): Return [`InterruptBehavior`] (default: `Replace`)
 ///
@@ -198,9 +238,61 @@ pub trait ExecutionStrategy: Clone + Send {
     /// Tuple of (current_value, is_complete)
     fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool);
 
+    /// Fixed-point version of [`interpolate`](Self::interpolate)
+    ///
+    /// The built-in strategies use integer math only. The default
+    /// implementation converts to `f32` and calls `interpolate`.
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        let (value, complete) = self.interpolate(from.to_f32(), to.to_f32(), elapsed_ms);
+        (Q16::from_f32(value), complete)
+    }
+
     /// Estimated total duration in milliseconds (if known)
     fn duration_ms(&self) -> Option<u64>;
 
+    /// Duration of the move from `from` to `to` in milliseconds (if known)
+    ///
+    /// Strategies whose duration depends on the distance travelled override
+    /// this. The default returns [`duration_ms`](Self::duration_ms).
+    fn duration_for(&self, _from: f32, _to: f32) -> Option<u64> {
+        self.duration_ms()
+    }
+
+    /// Rate of change in units per second at `elapsed_ms`
+    ///
+    /// Zero once the transition is complete. The default differentiates
+    /// [`interpolate`](Self::interpolate) numerically over one millisecond.
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        let (now, complete) = self.interpolate(from, to, elapsed_ms);
+        if complete {
+            return 0.0;
+        }
+        let (next, _) = self.interpolate(from, to, elapsed_ms + 1);
+        (next - now) * 1000.0
+    }
+
+    /// Fixed-point version of [`rate`](Self::rate)
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        Q16::from_f32(self.rate(from.to_f32(), to.to_f32(), elapsed_ms))
+    }
+
+    /// How long a rate inherited from an interrupted transition takes to
+    /// fade out, in milliseconds
+    ///
+    /// Zero jumps straight onto this strategy's own curve. The default is
+    /// the duration of the move, or [`RATE_BLEND_MS`] if that is unknown.
+    fn blend_ms(&self, from: f32, to: f32) -> u64 {
+        self.duration_for(from, to).unwrap_or(RATE_BLEND_MS)
+    }
+
+    /// The segment in progress at `elapsed_ms`, for strategies made of
+    /// several segments (see [`Sequence`](crate::sequence::Sequence))
+    ///
+    /// `None` for single-segment strategies and once the transition is complete.
+    fn segment(&self, _from: f32, _to: f32, _elapsed_ms: u64) -> Option<SegmentProgress> {
+        None
+    }
+
     /// What lock level does this transition require?
     fn lock(&self) -> TransitionLock {
         TransitionLock::None
@@ -212,6 +304,27 @@ pub trait ExecutionStrategy: Clone + Send {
     }
 }
 
+/// Rate blend window for strategies without a known duration
+pub const RATE_BLEND_MS: u64 = 500;
+
+/// Progress through one segment of a multi-segment strategy.
+///
+/// Returned by [`ExecutionStrategy::segment`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentProgress {
+    /// Index of the segment in progress (0-based).
+    pub index: usize,
+    /// Total number of segments.
+    pub count: usize,
+    /// Speed this segment ends at.
+    pub target: f32,
+    /// Time elapsed within this segment (milliseconds).
+    pub elapsed_ms: u64,
+    /// Duration of this segment (milliseconds).
+    pub duration_ms: u64,
+}
+
 // ============================================================================
 // Concrete Strategies
 // ============================================================================
@@ -232,7 +345,8 @@ pub trait ExecutionStrategy: Clone + Send {
 /// assert_eq!(value, 1.0);
 /// assert!(complete);
 /// ```
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Immediate;
 
 impl ExecutionStrategy for Immediate {
@@ -240,9 +354,21 @@ impl ExecutionStrategy for Immediate {
         (to, true)
     }
 
+    fn interpolate_q16(&self, _from: Q16, to: Q16, _elapsed_ms: u64) -> (Q16, bool) {
+        (to, true)
+    }
+
     fn duration_ms(&self) -> Option<u64> {
         Some(0)
     }
+
+    fn rate(&self, _from: f32, _to: f32, _elapsed_ms: u64) -> f32 {
+        0.0
+    }
+
+    fn rate_q16(&self, _from: Q16, _to: Q16, _elapsed_ms: u64) -> Q16 {
+        Q16::ZERO
+    }
 }
 
 /// Linear interpolation over a fixed duration.
@@ -274,13 +400,16 @@ impl ExecutionStrategy for Immediate {
 /// assert_eq!(value, 1.0);
 /// assert!(complete);
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Linear {
     /// Total duration of the transition in milliseconds.
     pub duration_ms: u64,
     /// Lock level for this transition.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub lock: TransitionLock,
     /// Behavior when interrupted.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub interrupt: InterruptBehavior,
 }
 
@@ -324,10 +453,33 @@ impl ExecutionStrategy for Linear {
         (value, false)
     }
 
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        if self.duration_ms == 0 || elapsed_ms >= self.duration_ms {
+            return (to, true);
+        }
+
+        let t = Q16::from_ratio(elapsed_ms, self.duration_ms);
+        (Q16::lerp(from, to, t), false)
+    }
+
     fn duration_ms(&self) -> Option<u64> {
         Some(self.duration_ms)
     }
 
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        if self.duration_ms == 0 || elapsed_ms >= self.duration_ms {
+            return 0.0;
+        }
+        (to - from) * 1000.0 / self.duration_ms as f32
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        if self.duration_ms == 0 || elapsed_ms >= self.duration_ms {
+            return Q16::ZERO;
+        }
+        (to - from) / Q16::from_ratio(self.duration_ms, 1000)
+    }
+
     fn lock(&self) -> TransitionLock {
         self.lock
     }
@@ -370,13 +522,16 @@ impl ExecutionStrategy for Linear {
 /// let (value, _) = strategy.interpolate(0.0, 1.0, 900);
 /// assert!(value > 0.9); // More than 90% at 90% time
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EaseInOut {
     /// Total duration of the transition in milliseconds.
     pub duration_ms: u64,
     /// Lock level for this transition.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub lock: TransitionLock,
     /// Behavior when interrupted.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub interrupt: InterruptBehavior,
 }
 
@@ -425,10 +580,39 @@ impl ExecutionStrategy for EaseInOut {
         (value, false)
     }
 
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        if self.duration_ms == 0 || elapsed_ms >= self.duration_ms {
+            return (to, true);
+        }
+
+        let t = Q16::from_ratio(elapsed_ms, self.duration_ms);
+        (Q16::lerp(from, to, Q16::smoothstep(t)), false)
+    }
+
     fn duration_ms(&self) -> Option<u64> {
         Some(self.duration_ms)
     }
 
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        if self.duration_ms == 0 || elapsed_ms >= self.duration_ms {
+            return 0.0;
+        }
+
+        // Derivative of smoothstep: 6t(1 - t)
+        let t = elapsed_ms as f32 / self.duration_ms as f32;
+        6.0 * t * (1.0 - t) * (to - from) * 1000.0 / self.duration_ms as f32
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        if self.duration_ms == 0 || elapsed_ms >= self.duration_ms {
+            return Q16::ZERO;
+        }
+
+        let t = Q16::from_ratio(elapsed_ms, self.duration_ms);
+        let six = Q16::from_raw(6 << Q16::FRAC_BITS);
+        six * t * (Q16::ONE - t) * (to - from) / Q16::from_ratio(self.duration_ms, 1000)
+    }
+
     fn lock(&self) -> TransitionLock {
         self.lock
     }
@@ -475,13 +659,15 @@ impl ExecutionStrategy for EaseInOut {
 /// let (v2, _) = strategy.interpolate(0.0, 1.0, 500);
 /// assert!(v2 > v1); // Accelerating
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Momentum {
     /// Acceleration rate (units per second per second).
     pub acceleration: f32,
     /// Maximum velocity (units per second).
     pub max_rate: f32,
     /// Lock level for this transition.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub lock: TransitionLock,
 }
 
@@ -535,132 +721,1181 @@ impl ExecutionStrategy for Momentum {
         }
     }
 
+    /// Converts the parameters on every call; an
+    /// [`AnyStrategy`](crate::AnyStrategy) converts them once up front.
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        MomentumQ16::new(self).interpolate(from, to, elapsed_ms)
+    }
+
     fn duration_ms(&self) -> Option<u64> {
         None // Depends on distance
     }
 
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        if self.interpolate(from, to, elapsed_ms).1 {
+            return 0.0;
+        }
+
+        // d/dt of rate(t) * t, with rate(t) = min(acceleration * t, max_rate)
+        let elapsed_s = elapsed_ms as f32 / 1000.0;
+        let ramp = self.acceleration * elapsed_s;
+        let speed = if ramp < self.max_rate {
+            2.0 * ramp
+        } else {
+            self.max_rate
+        };
+        if to >= from {
+            speed
+        } else {
+            -speed
+        }
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        MomentumQ16::new(self).rate(from, to, elapsed_ms)
+    }
+
     fn lock(&self) -> TransitionLock {
         self.lock
     }
 }
 
-// ============================================================================
-// Tests
-// ============================================================================
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// [`Momentum`] parameters converted to fixed point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct MomentumQ16 {
+    acceleration: Q16,
+    max_rate: Q16,
+}
 
-    // === Immediate Strategy ===
-    #[test]
-    fn immediate_returns_target_instantly() {
-        let s = Immediate;
-        let (val, done) = s.interpolate(0.0, 1.0, 0);
-        assert_eq!(val, 1.0);
-        assert!(done);
+impl MomentumQ16 {
+    pub(crate) fn new(momentum: &Momentum) -> Self {
+        Self {
+            acceleration: Q16::from_f32(momentum.acceleration),
+            max_rate: Q16::from_f32(momentum.max_rate),
+        }
     }
 
-    #[test]
-    fn immediate_ignores_elapsed_time() {
-        let s = Immediate;
-        let (val, done) = s.interpolate(0.0, 1.0, 999999);
-        assert_eq!(val, 1.0);
-        assert!(done);
+    pub(crate) fn interpolate(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        let elapsed_s = Q16::from_ratio(elapsed_ms, 1000);
+        let distance = to - from;
+
+        let rate = (self.acceleration * elapsed_s).min(self.max_rate);
+        let moved = rate * elapsed_s;
+
+        if moved >= distance.abs() {
+            (to, true)
+        } else if distance >= Q16::ZERO {
+            (from + moved, false)
+        } else {
+            (from - moved, false)
+        }
     }
 
-    #[test]
-    fn immediate_duration_is_zero() {
-        assert_eq!(Immediate.duration_ms(), Some(0));
+    pub(crate) fn rate(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        if self.interpolate(from, to, elapsed_ms).1 {
+            return Q16::ZERO;
+        }
+
+        let elapsed_s = Q16::from_ratio(elapsed_ms, 1000);
+        let ramp = self.acceleration * elapsed_s;
+        let speed = if ramp < self.max_rate {
+            ramp + ramp
+        } else {
+            self.max_rate
+        };
+        if to >= from {
+            speed
+        } else {
+            -speed
+        }
     }
+}
 
-    #[test]
-    fn immediate_default_lock_is_none() {
-        assert_eq!(Immediate.lock(), TransitionLock::None);
+/// Jerk-limited S-curve with independent acceleration and deceleration.
+///
+/// The rate of speed change ramps up at `jerk`, cruises at the acceleration
+/// (or deceleration) limit, then ramps back down to arrive smoothly, like a
+/// real locomotive notching up. Short moves never reach the limit and use a
+/// triangular rate profile instead.
+///
+/// Unlike [`EaseInOut`], the duration is not fixed: it is computed from the
+/// speed delta and reported by [`duration_for`](ExecutionStrategy::duration_for).
+/// Moves towards a higher speed use `acceleration`, moves towards a lower
+/// speed use `deceleration`.
+///
+/// # Constructors
+///
+/// - [`new`](Self::new): No lock, interruptible
+/// - [`departure`](Self::departure): Hard lock for station departures
+/// - [`arrival`](Self::arrival): Source lock with queueing for arrivals
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::traits::SCurve;
+/// use rs_trainz::ExecutionStrategy;
+///
+/// // 0.5/s acceleration, 0.25/s deceleration, 1.0/s² jerk
+/// let strategy = SCurve::new(0.5, 0.25, 1.0);
+///
+/// // Braking takes longer than accelerating over the same delta
+/// let up = strategy.duration_for(0.0, 1.0).unwrap();
+/// let down = strategy.duration_for(1.0, 0.0).unwrap();
+/// assert_eq!(up, 2500);
+/// assert_eq!(down, 4250);
+///
+/// // Starts gently, is halfway at the midpoint, arrives gently
+/// let (start, _) = strategy.interpolate(0.0, 1.0, 250);
+/// let (mid, _) = strategy.interpolate(0.0, 1.0, 1250);
+/// assert!(start < 0.05);
+/// assert!((mid - 0.5).abs() < 0.01);
+/// assert_eq!(strategy.interpolate(0.0, 1.0, up), (1.0, true));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SCurve {
+    /// Maximum acceleration (units per second).
+    pub acceleration: f32,
+    /// Maximum deceleration (units per second).
+    pub deceleration: f32,
+    /// Maximum jerk (units per second per second); zero means unlimited.
+    pub jerk: f32,
+    /// Lock level for this transition.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock: TransitionLock,
+    /// Behavior when interrupted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interrupt: InterruptBehavior,
+}
+
+/// Timings of one S-curve move
+struct CurveProfile<T> {
+    /// Distance to travel (always positive)
+    distance: T,
+    /// Jerk limit (zero when unlimited)
+    jerk: T,
+    /// Highest rate reached
+    peak_rate: T,
+    /// Time to ramp the rate up (and back down), in seconds
+    ramp_s: T,
+    /// Total time, in seconds
+    total_s: T,
+}
+
+impl SCurve {
+    /// Creates a new S-curve transition with no lock.
+    pub fn new(acceleration: f32, deceleration: f32, jerk: f32) -> Self {
+        Self {
+            acceleration,
+            deceleration,
+            jerk,
+            lock: TransitionLock::None,
+            interrupt: InterruptBehavior::Replace,
+        }
     }
 
-    // === Linear Strategy ===
-    #[test]
-    fn linear_at_zero_elapsed() {
-        let s = Linear::new(1000);
-        let (val, done) = s.interpolate(0.0, 1.0, 0);
-        assert_eq!(val, 0.0);
-        assert!(!done);
+    /// Station departure - locked, can't be interrupted
+    pub fn departure(acceleration: f32, deceleration: f32, jerk: f32) -> Self {
+        Self {
+            lock: TransitionLock::Hard,
+            interrupt: InterruptBehavior::Reject,
+            ..Self::new(acceleration, deceleration, jerk)
+        }
     }
 
-    #[test]
-    fn linear_at_half_elapsed() {
-        let s = Linear::new(1000);
-        let (val, done) = s.interpolate(0.0, 1.0, 500);
-        assert!((val - 0.5).abs() < 0.001);
-        assert!(!done);
+    /// Station arrival - queues follow-up commands
+    pub fn arrival(acceleration: f32, deceleration: f32, jerk: f32) -> Self {
+        Self {
+            lock: TransitionLock::Source,
+            interrupt: InterruptBehavior::Queue,
+            ..Self::new(acceleration, deceleration, jerk)
+        }
     }
 
-    #[test]
-    fn linear_at_full_elapsed() {
-        let s = Linear::new(1000);
-        let (val, done) = s.interpolate(0.0, 1.0, 1000);
-        assert_eq!(val, 1.0);
-        assert!(done);
+    /// Rate limit for a move from `from` to `to`
+    fn rate_limit(&self, from: f32, to: f32) -> f32 {
+        if to >= from {
+            self.acceleration
+        } else {
+            self.deceleration
+        }
     }
 
-    #[test]
-    fn linear_beyond_duration() {
-        let s = Linear::new(1000);
-        let (val, done) = s.interpolate(0.0, 1.0, 2000);
-        assert_eq!(val, 1.0);
-        assert!(done);
+    /// Profile for a move, or `None` if it completes instantly
+    fn profile(&self, from: f32, to: f32) -> Option<CurveProfile<f32>> {
+        let distance = if to >= from { to - from } else { from - to };
+        let rate = self.rate_limit(from, to);
+        let jerk = if self.jerk > 0.0 { self.jerk } else { 0.0 };
+        if distance <= 0.0 || rate <= 0.0 {
+            return None;
+        }
+
+        // Triangular rate profile if the limit is never reached
+        let (peak_rate, ramp_s) = if jerk > 0.0 && distance * jerk < rate * rate {
+            let peak = sqrt(distance * jerk);
+            (peak, peak / jerk)
+        } else if jerk > 0.0 {
+            (rate, rate / jerk)
+        } else {
+            (rate, 0.0)
+        };
+        let cruise_s = distance / peak_rate - ramp_s;
+
+        Some(CurveProfile {
+            distance,
+            jerk,
+            peak_rate,
+            ramp_s,
+            total_s: ramp_s + ramp_s + cruise_s,
+        })
     }
+}
 
-    #[test]
-    fn linear_zero_duration_completes_immediately() {
-        let s = Linear::new(0);
-        let (val, done) = s.interpolate(0.0, 1.0, 0);
-        assert_eq!(val, 1.0);
-        assert!(done);
+impl CurveProfile<f32> {
+    /// Total duration rounded up to whole milliseconds
+    fn duration_ms(&self) -> u64 {
+        let ms = self.total_s * 1000.0;
+        let whole = ms as u64;
+        if (whole as f32) < ms {
+            whole + 1
+        } else {
+            whole
+        }
     }
 
-    #[test]
-    fn linear_reverse_direction() {
-        let s = Linear::new(1000);
-        let (val, _) = s.interpolate(1.0, 0.0, 500);
-        assert!((val - 0.5).abs() < 0.001);
+    /// Rate of travel after `t` seconds
+    fn rate(&self, t: f32) -> f32 {
+        if t < self.ramp_s {
+            self.jerk * t
+        } else if t < self.total_s - self.ramp_s {
+            self.peak_rate
+        } else {
+            self.jerk * (self.total_s - t)
+        }
     }
 
-    #[test]
-    fn linear_locked_has_hard_lock() {
-        let s = Linear::locked(1000);
-        assert_eq!(s.lock(), TransitionLock::Hard);
-        assert_eq!(s.on_interrupt(), InterruptBehavior::Reject);
+    /// Distance travelled after `t` seconds
+    fn travelled(&self, t: f32) -> f32 {
+        let half_jerk = self.jerk * 0.5;
+        if t < self.ramp_s {
+            half_jerk * t * t
+        } else if t < self.total_s - self.ramp_s {
+            self.peak_rate * (t - self.ramp_s * 0.5)
+        } else {
+            let remaining = self.total_s - t;
+            self.distance - half_jerk * remaining * remaining
+        }
     }
+}
 
-    #[test]
-    fn linear_source_locked_has_source_lock() {
-        let s = Linear::source_locked(1000);
-        assert_eq!(s.lock(), TransitionLock::Source);
-        assert_eq!(s.on_interrupt(), InterruptBehavior::Replace);
+impl CurveProfile<Q16> {
+    /// Total duration rounded up to whole milliseconds
+    fn duration_ms(&self) -> u64 {
+        let raw = self.total_s.max(Q16::ZERO).raw() as u64;
+        (raw * 1000).div_ceil(1 << Q16::FRAC_BITS)
     }
 
-    #[test]
-    fn linear_new_has_no_lock() {
-        let s = Linear::new(1000);
-        assert_eq!(s.lock(), TransitionLock::None);
-        assert_eq!(s.on_interrupt(), InterruptBehavior::Replace);
+    /// Rate of travel after `t` seconds
+    fn rate(&self, t: Q16) -> Q16 {
+        if t < self.ramp_s {
+            self.jerk * t
+        } else if t < self.total_s - self.ramp_s {
+            self.peak_rate
+        } else {
+            self.jerk * (self.total_s - t)
+        }
     }
 
-    #[test]
-    fn linear_duration_ms_returns_value() {
-        let s = Linear::new(1234);
-        assert_eq!(s.duration_ms(), Some(1234));
+    /// Distance travelled after `t` seconds
+    fn travelled(&self, t: Q16) -> Q16 {
+        let half_jerk = Q16::from_raw(self.jerk.raw() / 2);
+        if t < self.ramp_s {
+            half_jerk * t * t
+        } else if t < self.total_s - self.ramp_s {
+            let half_ramp = Q16::from_raw(self.ramp_s.raw() / 2);
+            self.peak_rate * (t - half_ramp)
+        } else {
+            let remaining = self.total_s - t;
+            self.distance - half_jerk * remaining * remaining
+        }
     }
+}
 
-    // === EaseInOut Strategy ===
-    #[test]
-    fn ease_in_out_starts_slow() {
-        let s = EaseInOut::new(1000);
-        let (val, _) = s.interpolate(0.0, 1.0, 100); // 10%
-                                                     // Smoothstep at 0.1 = 0.1^2 * (3 - 2*0.1) = 0.01 * 2.8 = 0.028
-        assert!(val < 0.05); // Should be slower than linear (which would be 0.1)
+/// no_std-compatible square root (Newton's method from a bit-level guess)
+fn sqrt(x: f32) -> f32 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let mut y = f32::from_bits((x.to_bits() >> 1) + 0x1fbd_1df5);
+    for _ in 0..3 {
+        y = 0.5 * (y + x / y);
+    }
+    y
+}
+
+impl ExecutionStrategy for SCurve {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        let Some(profile) = self.profile(from, to) else {
+            return (to, true);
+        };
+        if elapsed_ms >= profile.duration_ms() {
+            return (to, true);
+        }
+
+        let moved = profile
+            .travelled(elapsed_ms as f32 / 1000.0)
+            .clamp(0.0, profile.distance);
+        if to >= from {
+            (from + moved, false)
+        } else {
+            (from - moved, false)
+        }
+    }
+
+    /// Converts the parameters on every call; an
+    /// [`AnyStrategy`](crate::AnyStrategy) converts them once up front.
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        SCurveQ16::new(self).interpolate(from, to, elapsed_ms)
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        None // Depends on distance, see duration_for
+    }
+
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        Some(self.profile(from, to).map_or(0, |p| p.duration_ms()))
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        let Some(profile) = self.profile(from, to) else {
+            return 0.0;
+        };
+        if elapsed_ms >= profile.duration_ms() {
+            return 0.0;
+        }
+
+        let rate = profile.rate(elapsed_ms as f32 / 1000.0).max(0.0);
+        if to >= from {
+            rate
+        } else {
+            -rate
+        }
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        SCurveQ16::new(self).rate(from, to, elapsed_ms)
+    }
+
+    fn lock(&self) -> TransitionLock {
+        self.lock
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        self.interrupt
+    }
+}
+
+/// [`SCurve`] parameters converted to fixed point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SCurveQ16 {
+    acceleration: Q16,
+    deceleration: Q16,
+    /// Jerk limit (zero when unlimited)
+    jerk: Q16,
+}
+
+impl SCurveQ16 {
+    pub(crate) fn new(curve: &SCurve) -> Self {
+        Self {
+            acceleration: Q16::from_f32(curve.acceleration),
+            deceleration: Q16::from_f32(curve.deceleration),
+            jerk: Q16::from_f32(curve.jerk).max(Q16::ZERO),
+        }
+    }
+
+    /// Profile for a move, or `None` if it completes instantly
+    fn profile(&self, from: Q16, to: Q16) -> Option<CurveProfile<Q16>> {
+        let distance = (to - from).abs();
+        let rate = if to >= from {
+            self.acceleration
+        } else {
+            self.deceleration
+        };
+        let jerk = self.jerk;
+        if distance <= Q16::ZERO || rate <= Q16::ZERO {
+            return None;
+        }
+
+        let (peak_rate, ramp_s) = if jerk > Q16::ZERO && distance * jerk < rate * rate {
+            let peak = (distance * jerk).sqrt();
+            (peak, peak / jerk)
+        } else if jerk > Q16::ZERO {
+            (rate, rate / jerk)
+        } else {
+            (rate, Q16::ZERO)
+        };
+        let cruise_s = distance / peak_rate - ramp_s;
+
+        Some(CurveProfile {
+            distance,
+            jerk,
+            peak_rate,
+            ramp_s,
+            total_s: ramp_s + ramp_s + cruise_s,
+        })
+    }
+
+    pub(crate) fn interpolate(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        let Some(profile) = self.profile(from, to) else {
+            return (to, true);
+        };
+        if elapsed_ms >= profile.duration_ms() {
+            return (to, true);
+        }
+
+        let moved = profile
+            .travelled(Q16::from_ratio(elapsed_ms, 1000))
+            .clamp(Q16::ZERO, profile.distance);
+        if to >= from {
+            (from + moved, false)
+        } else {
+            (from - moved, false)
+        }
+    }
+
+    pub(crate) fn rate(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        let Some(profile) = self.profile(from, to) else {
+            return Q16::ZERO;
+        };
+        if elapsed_ms >= profile.duration_ms() {
+            return Q16::ZERO;
+        }
+
+        let rate = profile
+            .rate(Q16::from_ratio(elapsed_ms, 1000))
+            .max(Q16::ZERO);
+        if to >= from {
+            rate
+        } else {
+            -rate
+        }
+    }
+}
+
+/// Maximum number of points in a [`Keyframes`] profile
+pub const MAX_KEYFRAMES: usize = 8;
+
+/// Curve of one [`Keyframes`] segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Easing {
+    /// Constant rate across the segment.
+    #[default]
+    Linear,
+    /// Smoothstep `t² × (3 - 2t)`, as used by [`EaseInOut`].
+    Smoothstep,
+    /// Cubic ease-in-out: gentler start and finish than smoothstep.
+    Cubic,
+}
+
+impl Easing {
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Self::Linear => t,
+            Self::Smoothstep => t * t * (3.0 - 2.0 * t),
+            Self::Cubic if t < 0.5 => 4.0 * t * t * t,
+            Self::Cubic => {
+                let r = 1.0 - t;
+                1.0 - 4.0 * r * r * r
+            }
+        }
+    }
+
+    /// Derivative of [`apply`](Self::apply)
+    fn slope(self, t: f32) -> f32 {
+        match self {
+            Self::Linear => 1.0,
+            Self::Smoothstep => 6.0 * t * (1.0 - t),
+            Self::Cubic if t < 0.5 => 12.0 * t * t,
+            Self::Cubic => 12.0 * (1.0 - t) * (1.0 - t),
+        }
+    }
+
+    fn apply_q16(self, t: Q16) -> Q16 {
+        let four = Q16::from_raw(4 << Q16::FRAC_BITS);
+        match self {
+            Self::Linear => t,
+            Self::Smoothstep => Q16::smoothstep(t),
+            Self::Cubic if t < Q16::from_ratio(1, 2) => four * t * t * t,
+            Self::Cubic => {
+                let r = Q16::ONE - t;
+                Q16::ONE - four * r * r * r
+            }
+        }
+    }
+
+    fn slope_q16(self, t: Q16) -> Q16 {
+        let six = Q16::from_raw(6 << Q16::FRAC_BITS);
+        let twelve = Q16::from_raw(12 << Q16::FRAC_BITS);
+        match self {
+            Self::Linear => Q16::ONE,
+            Self::Smoothstep => six * t * (Q16::ONE - t),
+            Self::Cubic if t < Q16::from_ratio(1, 2) => twelve * t * t,
+            Self::Cubic => twelve * (Q16::ONE - t) * (Q16::ONE - t),
+        }
+    }
+}
+
+/// One point of a [`Keyframes`] profile.
+///
+/// Deserializing rejects fractions outside 0.0 to 1.0, like [`Speed`].
+///
+/// [`Speed`]: crate::Speed
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframe {
+    /// Time since the transition started (milliseconds).
+    pub time_ms: u64,
+    /// Progress towards the target at that time (0.0 = start, 1.0 = target).
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_fraction"))]
+    pub fraction: f32,
+    /// Curve of the segment leading up to this point.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    /// Create a keyframe, checking the fraction is within 0.0 to 1.0
+    pub fn new(time_ms: u64, fraction: f32, easing: Easing) -> Result<Self, KeyframeError> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(KeyframeError::InvalidFraction);
+        }
+        Ok(Self {
+            time_ms,
+            fraction,
+            easing,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_fraction<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<f32, D::Error> {
+    let fraction = <f32 as serde::Deserialize>::deserialize(deserializer)?;
+    Keyframe::new(0, fraction, Easing::Linear)
+        .map(|frame| frame.fraction)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Why a keyframe can't be added to a [`Keyframes`] profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyframeError {
+    /// Fraction is NaN or outside 0.0 to 1.0.
+    InvalidFraction,
+    /// The profile already has [`MAX_KEYFRAMES`] points.
+    TooManyFrames,
+}
+
+impl core::fmt::Display for KeyframeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidFraction => write!(f, "keyframe fraction must be between 0.0 and 1.0"),
+            Self::TooManyFrames => write!(f, "at most {} keyframes are allowed", MAX_KEYFRAMES),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyframeError {}
+
+/// Custom profile defined by a list of keyframes.
+///
+/// Each [`Keyframe`] pins the progress of the transition (as a fraction of
+/// the way from the start speed to the target) at a point in time; the
+/// segment leading up to it follows its [`Easing`]. The profile implicitly
+/// starts at `(0 ms, 0.0)` and always finishes at the target once the last
+/// keyframe's time has passed, so the last fraction should be `1.0`.
+///
+/// Keyframes must be in time order. A keyframe that doesn't come after the
+/// previous one makes the profile jump straight to its fraction. Up to
+/// [`MAX_KEYFRAMES`] points are stored inline, without allocation; longer
+/// lists are rejected, both by [`with_keyframe`](Self::with_keyframe) and
+/// when deserializing.
+///
+/// # Constructors
+///
+/// - [`new`](Self::new): No lock, interruptible
+/// - [`departure`](Self::departure): Hard lock for station departures
+/// - [`arrival`](Self::arrival): Source lock with queueing for arrivals
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::traits::{Easing, Keyframes};
+/// use rs_trainz::ExecutionStrategy;
+///
+/// // Creep at 10% for 2 seconds, then ramp up to the target
+/// let creep = Keyframes::departure()
+///     .with_keyframe(500, 0.1, Easing::Smoothstep)?
+///     .with_keyframe(2500, 0.1, Easing::Linear)?
+///     .with_keyframe(4500, 1.0, Easing::Cubic)?;
+///
+/// assert_eq!(creep.duration_ms(), Some(4500));
+/// let (value, _) = creep.interpolate(0.0, 0.8, 1500);
+/// assert!((value - 0.08).abs() < 0.001);
+/// assert_eq!(creep.interpolate(0.0, 0.8, 4500), (0.8, true));
+/// # Ok::<(), rs_trainz::traits::KeyframeError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframes {
+    /// Points of the profile, in time order.
+    pub frames: heapless::Vec<Keyframe, MAX_KEYFRAMES>,
+    /// Lock level for this transition.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock: TransitionLock,
+    /// Behavior when interrupted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interrupt: InterruptBehavior,
+}
+
+/// The keyframe segment active at some point in time
+struct Segment {
+    from: Keyframe,
+    to: Keyframe,
+    /// Index of `from` in the frames (`None` for the implicit start)
+    from_index: Option<usize>,
+    /// Index of `to` in the frames
+    to_index: usize,
+    elapsed_ms: u64,
+}
+
+impl Keyframes {
+    /// Creates an empty profile with no lock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Station departure - locked, can't be interrupted
+    pub fn departure() -> Self {
+        Self {
+            lock: TransitionLock::Hard,
+            interrupt: InterruptBehavior::Reject,
+            ..Self::default()
+        }
+    }
+
+    /// Station arrival - queues follow-up commands
+    pub fn arrival() -> Self {
+        Self {
+            lock: TransitionLock::Source,
+            interrupt: InterruptBehavior::Queue,
+            ..Self::default()
+        }
+    }
+
+    /// Append a keyframe
+    ///
+    /// Fails if the fraction is outside 0.0 to 1.0, or [`MAX_KEYFRAMES`]
+    /// are already set.
+    pub fn with_keyframe(
+        mut self,
+        time_ms: u64,
+        fraction: f32,
+        easing: Easing,
+    ) -> Result<Self, KeyframeError> {
+        let frame = Keyframe::new(time_ms, fraction, easing)?;
+        self.frames
+            .push(frame)
+            .map_err(|_| KeyframeError::TooManyFrames)?;
+        Ok(self)
+    }
+
+    /// Segment in progress at `elapsed_ms`, or `None` once past the last keyframe
+    fn segment(&self, elapsed_ms: u64) -> Option<Segment> {
+        let mut previous = Keyframe {
+            time_ms: 0,
+            fraction: 0.0,
+            easing: Easing::Linear,
+        };
+        let mut from_index = None;
+        for (to_index, frame) in self.frames.iter().enumerate() {
+            if elapsed_ms < frame.time_ms {
+                return Some(Segment {
+                    from: previous,
+                    to: *frame,
+                    from_index,
+                    to_index,
+                    elapsed_ms: elapsed_ms - previous.time_ms,
+                });
+            }
+            // An out-of-order keyframe jumps without going back in time
+            previous = Keyframe {
+                time_ms: previous.time_ms.max(frame.time_ms),
+                ..*frame
+            };
+            from_index = Some(to_index);
+        }
+        None
+    }
+}
+
+impl Segment {
+    fn length_ms(&self) -> u64 {
+        self.to.time_ms - self.from.time_ms
+    }
+
+    fn fraction(&self) -> f32 {
+        let t = self.elapsed_ms as f32 / self.length_ms() as f32;
+        let eased = self.to.easing.apply(t);
+        self.from.fraction + (self.to.fraction - self.from.fraction) * eased
+    }
+
+    /// Fractions at both ends, looked up in the converted `fractions`
+    fn fractions_q16(&self, fractions: &KeyframesQ16) -> (Q16, Q16) {
+        let from = self.from_index.map_or(Q16::ZERO, |i| fractions.0[i]);
+        (from, fractions.0[self.to_index])
+    }
+
+    fn fraction_q16(&self, fractions: &KeyframesQ16) -> Q16 {
+        let t = Q16::from_ratio(self.elapsed_ms, self.length_ms());
+        let (from, to) = self.fractions_q16(fractions);
+        Q16::lerp(from, to, self.to.easing.apply_q16(t))
+    }
+
+    /// Rate of change of the fraction, per second
+    fn rate(&self) -> f32 {
+        let t = self.elapsed_ms as f32 / self.length_ms() as f32;
+        let change = self.to.fraction - self.from.fraction;
+        self.to.easing.slope(t) * change * 1000.0 / self.length_ms() as f32
+    }
+
+    fn rate_q16(&self, fractions: &KeyframesQ16) -> Q16 {
+        let t = Q16::from_ratio(self.elapsed_ms, self.length_ms());
+        let (from, to) = self.fractions_q16(fractions);
+        self.to.easing.slope_q16(t) * (to - from) / Q16::from_ratio(self.length_ms(), 1000)
+    }
+}
+
+impl ExecutionStrategy for Keyframes {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        match self.segment(elapsed_ms) {
+            Some(segment) => (from + (to - from) * segment.fraction(), false),
+            None => (to, true),
+        }
+    }
+
+    /// Converts the fractions on every call; an
+    /// [`AnyStrategy`](crate::AnyStrategy) converts them once up front.
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        KeyframesQ16::new(self).interpolate(self, from, to, elapsed_ms)
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        Some(self.frames.iter().map(|f| f.time_ms).max().unwrap_or(0))
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        self.segment(elapsed_ms)
+            .map_or(0.0, |segment| segment.rate() * (to - from))
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        KeyframesQ16::new(self).rate(self, from, to, elapsed_ms)
+    }
+
+    fn lock(&self) -> TransitionLock {
+        self.lock
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        self.interrupt
+    }
+}
+
+/// [`Keyframes`] fractions converted to fixed point, in frame order
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct KeyframesQ16(heapless::Vec<Q16, MAX_KEYFRAMES>);
+
+impl KeyframesQ16 {
+    pub(crate) fn new(keyframes: &Keyframes) -> Self {
+        Self(
+            keyframes
+                .frames
+                .iter()
+                .map(|frame| Q16::from_f32(frame.fraction))
+                .collect(),
+        )
+    }
+
+    /// Interpolate `keyframes`, which these fractions were converted from
+    pub(crate) fn interpolate(
+        &self,
+        keyframes: &Keyframes,
+        from: Q16,
+        to: Q16,
+        elapsed_ms: u64,
+    ) -> (Q16, bool) {
+        match keyframes.segment(elapsed_ms) {
+            Some(segment) => (Q16::lerp(from, to, segment.fraction_q16(self)), false),
+            None => (to, true),
+        }
+    }
+
+    /// Rate of `keyframes`, which these fractions were converted from
+    pub(crate) fn rate(&self, keyframes: &Keyframes, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        keyframes
+            .segment(elapsed_ms)
+            .map_or(Q16::ZERO, |segment| segment.rate_q16(self) * (to - from))
+    }
+}
+
+// ============================================================================
+// Strategy Spec
+// ============================================================================
+
+/// Maximum length of a strategy preset name
+pub const MAX_PRESET_NAME: usize = 16;
+
+/// Name of a strategy preset (e.g. `"departure"`)
+pub type PresetName = heapless::String<MAX_PRESET_NAME>;
+
+/// Create a [`PresetName`] from a &str, rejecting names over [`MAX_PRESET_NAME`] bytes
+///
+/// Deserializing a [`PresetName`] rejects them too.
+pub fn preset_name(name: &str) -> Result<PresetName, PresetNameError> {
+    PresetName::try_from(name).map_err(|()| PresetNameError)
+}
+
+/// A preset name longer than [`MAX_PRESET_NAME`] bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PresetNameError;
+
+impl core::fmt::Display for PresetNameError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "preset names are at most {} bytes", MAX_PRESET_NAME)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PresetNameError {}
+
+/// Serializable description of one of the built-in strategies.
+///
+/// This is the wire format for strategies in command messages: remote
+/// clients pick a strategy and its lock/interrupt settings without any
+/// code on the device. It implements [`ExecutionStrategy`] itself by
+/// dispatching to the wrapped strategy.
+///
+/// # JSON Examples
+///
+/// ```json
+/// "immediate"
+/// {"linear": {"duration_ms": 1000}}
+/// {"ease_in_out": {"duration_ms": 3000, "lock": "hard", "interrupt": "reject"}}
+/// {"momentum": {"acceleration": 0.5, "max_rate": 0.3}}
+/// {"s_curve": {"acceleration": 0.2, "deceleration": 0.1, "jerk": 0.4}}
+/// {"keyframes": {"frames": [{"time_ms": 2000, "fraction": 0.1}, {"time_ms": 4000, "fraction": 1.0, "easing": "cubic"}]}}
+/// {"preset": "departure"}
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rs_trainz::traits::{EaseInOut, StrategySpec, TransitionLock};
+/// use rs_trainz::ExecutionStrategy;
+///
+/// let spec = StrategySpec::from(EaseInOut::departure(2000));
+/// assert_eq!(spec.lock(), TransitionLock::Hard);
+/// assert_eq!(spec.duration_ms(), Some(2000));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum StrategySpec {
+    /// See [`Immediate`].
+    #[default]
+    Immediate,
+    /// See [`Linear`].
+    Linear(Linear),
+    /// See [`EaseInOut`].
+    EaseInOut(EaseInOut),
+    /// See [`Momentum`].
+    Momentum(Momentum),
+    /// See [`SCurve`].
+    SCurve(SCurve),
+    /// See [`Keyframes`].
+    Keyframes(Keyframes),
+    /// A named preset, resolved by the controller before execution.
+    ///
+    /// Until resolved it holds the current speed, so an unknown name
+    /// never moves the train.
+    Preset(PresetName),
+}
+
+impl StrategySpec {
+    /// Refer to a named preset, rejecting names over [`MAX_PRESET_NAME`] bytes.
+    pub fn preset(name: &str) -> Result<Self, PresetNameError> {
+        preset_name(name).map(Self::Preset)
+    }
+
+    /// The preset name, if this spec refers to one.
+    pub fn preset_name(&self) -> Option<&str> {
+        match self {
+            Self::Preset(name) => Some(name.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl ExecutionStrategy for StrategySpec {
+    fn interpolate(&self, from: f32, to: f32, elapsed_ms: u64) -> (f32, bool) {
+        match self {
+            Self::Immediate => Immediate.interpolate(from, to, elapsed_ms),
+            Self::Linear(s) => s.interpolate(from, to, elapsed_ms),
+            Self::EaseInOut(s) => s.interpolate(from, to, elapsed_ms),
+            Self::Momentum(s) => s.interpolate(from, to, elapsed_ms),
+            Self::SCurve(s) => s.interpolate(from, to, elapsed_ms),
+            Self::Keyframes(s) => s.interpolate(from, to, elapsed_ms),
+            Self::Preset(_) => (from, true),
+        }
+    }
+
+    fn interpolate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> (Q16, bool) {
+        match self {
+            Self::Immediate => Immediate.interpolate_q16(from, to, elapsed_ms),
+            Self::Linear(s) => s.interpolate_q16(from, to, elapsed_ms),
+            Self::EaseInOut(s) => s.interpolate_q16(from, to, elapsed_ms),
+            Self::Momentum(s) => s.interpolate_q16(from, to, elapsed_ms),
+            Self::SCurve(s) => s.interpolate_q16(from, to, elapsed_ms),
+            Self::Keyframes(s) => s.interpolate_q16(from, to, elapsed_ms),
+            Self::Preset(_) => (from, true),
+        }
+    }
+
+    fn duration_ms(&self) -> Option<u64> {
+        match self {
+            Self::Immediate => Immediate.duration_ms(),
+            Self::Linear(s) => s.duration_ms(),
+            Self::EaseInOut(s) => s.duration_ms(),
+            Self::Momentum(s) => s.duration_ms(),
+            Self::SCurve(s) => s.duration_ms(),
+            Self::Keyframes(s) => s.duration_ms(),
+            Self::Preset(_) => Some(0),
+        }
+    }
+
+    fn duration_for(&self, from: f32, to: f32) -> Option<u64> {
+        match self {
+            Self::SCurve(s) => s.duration_for(from, to),
+            _ => self.duration_ms(),
+        }
+    }
+
+    fn rate(&self, from: f32, to: f32, elapsed_ms: u64) -> f32 {
+        match self {
+            Self::Immediate => Immediate.rate(from, to, elapsed_ms),
+            Self::Linear(s) => s.rate(from, to, elapsed_ms),
+            Self::EaseInOut(s) => s.rate(from, to, elapsed_ms),
+            Self::Momentum(s) => s.rate(from, to, elapsed_ms),
+            Self::SCurve(s) => s.rate(from, to, elapsed_ms),
+            Self::Keyframes(s) => s.rate(from, to, elapsed_ms),
+            Self::Preset(_) => 0.0,
+        }
+    }
+
+    fn rate_q16(&self, from: Q16, to: Q16, elapsed_ms: u64) -> Q16 {
+        match self {
+            Self::Immediate => Immediate.rate_q16(from, to, elapsed_ms),
+            Self::Linear(s) => s.rate_q16(from, to, elapsed_ms),
+            Self::EaseInOut(s) => s.rate_q16(from, to, elapsed_ms),
+            Self::Momentum(s) => s.rate_q16(from, to, elapsed_ms),
+            Self::SCurve(s) => s.rate_q16(from, to, elapsed_ms),
+            Self::Keyframes(s) => s.rate_q16(from, to, elapsed_ms),
+            Self::Preset(_) => Q16::ZERO,
+        }
+    }
+
+    fn lock(&self) -> TransitionLock {
+        match self {
+            Self::Immediate => Immediate.lock(),
+            Self::Linear(s) => s.lock(),
+            Self::EaseInOut(s) => s.lock(),
+            Self::Momentum(s) => s.lock(),
+            Self::SCurve(s) => s.lock(),
+            Self::Keyframes(s) => s.lock(),
+            Self::Preset(_) => TransitionLock::None,
+        }
+    }
+
+    fn on_interrupt(&self) -> InterruptBehavior {
+        match self {
+            Self::Immediate => Immediate.on_interrupt(),
+            Self::Linear(s) => s.on_interrupt(),
+            Self::EaseInOut(s) => s.on_interrupt(),
+            Self::Momentum(s) => s.on_interrupt(),
+            Self::SCurve(s) => s.on_interrupt(),
+            Self::Keyframes(s) => s.on_interrupt(),
+            Self::Preset(_) => InterruptBehavior::Replace,
+        }
+    }
+}
+
+impl From<Immediate> for StrategySpec {
+    fn from(_: Immediate) -> Self {
+        Self::Immediate
+    }
+}
+
+impl From<Linear> for StrategySpec {
+    fn from(s: Linear) -> Self {
+        Self::Linear(s)
+    }
+}
+
+impl From<EaseInOut> for StrategySpec {
+    fn from(s: EaseInOut) -> Self {
+        Self::EaseInOut(s)
+    }
+}
+
+impl From<Momentum> for StrategySpec {
+    fn from(s: Momentum) -> Self {
+        Self::Momentum(s)
+    }
+}
+
+impl From<SCurve> for StrategySpec {
+    fn from(s: SCurve) -> Self {
+        Self::SCurve(s)
+    }
+}
+
+impl From<Keyframes> for StrategySpec {
+    fn from(s: Keyframes) -> Self {
+        Self::Keyframes(s)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // === Immediate Strategy ===
+    #[test]
+    fn immediate_returns_target_instantly() {
+        let s = Immediate;
+        let (val, done) = s.interpolate(0.0, 1.0, 0);
+        assert_eq!(val, 1.0);
+        assert!(done);
+    }
+
+    #[test]
+    fn immediate_ignores_elapsed_time() {
+        let s = Immediate;
+        let (val, done) = s.interpolate(0.0, 1.0, 999999);
+        assert_eq!(val, 1.0);
+        assert!(done);
+    }
+
+    #[test]
+    fn immediate_duration_is_zero() {
+        assert_eq!(Immediate.duration_ms(), Some(0));
+    }
+
+    #[test]
+    fn immediate_default_lock_is_none() {
+        assert_eq!(Immediate.lock(), TransitionLock::None);
+    }
+
+    // === Linear Strategy ===
+    #[test]
+    fn linear_at_zero_elapsed() {
+        let s = Linear::new(1000);
+        let (val, done) = s.interpolate(0.0, 1.0, 0);
+        assert_eq!(val, 0.0);
+        assert!(!done);
+    }
+
+    #[test]
+    fn linear_at_half_elapsed() {
+        let s = Linear::new(1000);
+        let (val, done) = s.interpolate(0.0, 1.0, 500);
+        assert!((val - 0.5).abs() < 0.001);
+        assert!(!done);
+    }
+
+    #[test]
+    fn linear_at_full_elapsed() {
+        let s = Linear::new(1000);
+        let (val, done) = s.interpolate(0.0, 1.0, 1000);
+        assert_eq!(val, 1.0);
+        assert!(done);
+    }
+
+    #[test]
+    fn linear_beyond_duration() {
+        let s = Linear::new(1000);
+        let (val, done) = s.interpolate(0.0, 1.0, 2000);
+        assert_eq!(val, 1.0);
+        assert!(done);
+    }
+
+    #[test]
+    fn linear_zero_duration_completes_immediately() {
+        let s = Linear::new(0);
+        let (val, done) = s.interpolate(0.0, 1.0, 0);
+        assert_eq!(val, 1.0);
+        assert!(done);
+    }
+
+    #[test]
+    fn linear_reverse_direction() {
+        let s = Linear::new(1000);
+        let (val, _) = s.interpolate(1.0, 0.0, 500);
+        assert!((val - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn linear_locked_has_hard_lock() {
+        let s = Linear::locked(1000);
+        assert_eq!(s.lock(), TransitionLock::Hard);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Reject);
+    }
+
+    #[test]
+    fn linear_source_locked_has_source_lock() {
+        let s = Linear::source_locked(1000);
+        assert_eq!(s.lock(), TransitionLock::Source);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Replace);
+    }
+
+    #[test]
+    fn linear_new_has_no_lock() {
+        let s = Linear::new(1000);
+        assert_eq!(s.lock(), TransitionLock::None);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Replace);
+    }
+
+    #[test]
+    fn linear_duration_ms_returns_value() {
+        let s = Linear::new(1234);
+        assert_eq!(s.duration_ms(), Some(1234));
+    }
+
+    // === EaseInOut Strategy ===
+    #[test]
+    fn ease_in_out_starts_slow() {
+        let s = EaseInOut::new(1000);
+        let (val, _) = s.interpolate(0.0, 1.0, 100); // 10%
+                                                     // Smoothstep at 0.1 = 0.1^2 * (3 - 2*0.1) = 0.01 * 2.8 = 0.028
+        assert!(val < 0.05); // Should be slower than linear (which would be 0.1)
     }
 
     #[test]
@@ -780,6 +2015,247 @@ mod tests {
         assert!(done);
     }
 
+    // === SCurve Strategy ===
+    #[test]
+    fn s_curve_duration_from_delta() {
+        let s = SCurve::new(0.5, 0.25, 1.0);
+        // 0.5s jerk ramps either side of a 1.5s cruise at 0.5/s
+        assert_eq!(s.duration_for(0.0, 1.0), Some(2500));
+        assert_eq!(s.duration_for(0.2, 0.7), Some(1500));
+        assert_eq!(s.duration_for(0.5, 0.5), Some(0));
+        assert_eq!(s.duration_ms(), None);
+    }
+
+    #[test]
+    fn s_curve_uses_deceleration_when_slowing() {
+        let s = SCurve::new(0.5, 0.25, 1.0);
+        assert!(s.duration_for(1.0, 0.0) > s.duration_for(0.0, 1.0));
+        let (val, done) = s.interpolate(1.0, 0.0, 2000);
+        assert!(val < 1.0 && val > 0.0);
+        assert!(!done);
+    }
+
+    #[test]
+    fn s_curve_short_move_never_reaches_limit() {
+        let s = SCurve::new(0.5, 0.5, 1.0);
+        // Limit needs 0.25 of travel; 0.04 peaks at sqrt(0.04) = 0.2/s
+        assert_eq!(s.duration_for(0.0, 0.04), Some(400));
+        let (val, _) = s.interpolate(0.0, 0.04, 200);
+        assert!((val - 0.02).abs() < 0.001);
+    }
+
+    #[test]
+    fn s_curve_rate_is_jerk_limited() {
+        let s = SCurve::new(0.5, 0.5, 1.0);
+        let mut last = 0.0;
+        let mut last_rate = 0.0;
+        for elapsed in (10..=2500).step_by(10) {
+            let (val, _) = s.interpolate(0.0, 1.0, elapsed);
+            let rate = (val - last) / 0.01;
+            assert!(rate <= 0.5 + 0.01, "rate {rate} at {elapsed}ms");
+            assert!((rate - last_rate).abs() <= 1.0 * 0.01 + 0.001);
+            last = val;
+            last_rate = rate;
+        }
+        assert_eq!(last, 1.0);
+    }
+
+    #[test]
+    fn s_curve_without_jerk_limit_is_trapezoid() {
+        let s = SCurve::new(0.5, 0.5, 0.0);
+        assert_eq!(s.duration_for(0.0, 1.0), Some(2000));
+        let (val, _) = s.interpolate(0.0, 1.0, 1000);
+        assert!((val - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn s_curve_zero_rate_completes_immediately() {
+        let s = SCurve::new(0.0, 0.5, 1.0);
+        assert_eq!(s.interpolate(0.0, 1.0, 0), (1.0, true));
+        assert_eq!(s.duration_for(0.0, 1.0), Some(0));
+    }
+
+    #[test]
+    fn s_curve_completes_at_duration() {
+        let s = SCurve::new(0.5, 0.25, 1.0);
+        assert!(!s.interpolate(1.0, 0.0, 4249).1);
+        assert_eq!(s.interpolate(1.0, 0.0, 4250), (0.0, true));
+    }
+
+    #[test]
+    fn s_curve_presets() {
+        let s = SCurve::new(0.5, 0.25, 1.0);
+        assert_eq!(s.lock(), TransitionLock::None);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Replace);
+
+        let s = SCurve::departure(0.5, 0.25, 1.0);
+        assert_eq!(s.lock(), TransitionLock::Hard);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Reject);
+
+        let s = SCurve::arrival(0.5, 0.25, 1.0);
+        assert_eq!(s.lock(), TransitionLock::Source);
+        assert_eq!(s.on_interrupt(), InterruptBehavior::Queue);
+        assert_eq!(s.acceleration, 0.5);
+        assert_eq!(s.deceleration, 0.25);
+    }
+
+    // === Keyframes Strategy ===
+    fn creep() -> Keyframes {
+        Keyframes::new()
+            .with_keyframe(500, 0.1, Easing::Smoothstep)
+            .unwrap()
+            .with_keyframe(2500, 0.1, Easing::Linear)
+            .unwrap()
+            .with_keyframe(4500, 1.0, Easing::Cubic)
+            .unwrap()
+    }
+
+    #[test]
+    fn keyframes_follow_profile() {
+        let s = creep();
+        assert_eq!(s.interpolate(0.0, 1.0, 0), (0.0, false));
+        let (val, _) = s.interpolate(0.0, 1.0, 250);
+        assert!((val - 0.05).abs() < 0.001);
+        let (val, _) = s.interpolate(0.0, 1.0, 2000);
+        assert!((val - 0.1).abs() < 0.001);
+        let (val, _) = s.interpolate(0.0, 1.0, 3500);
+        assert!((val - 0.55).abs() < 0.001);
+        assert_eq!(s.interpolate(0.0, 1.0, 4500), (1.0, true));
+    }
+
+    #[test]
+    fn keyframes_scale_to_any_move() {
+        let s = creep();
+        let (val, _) = s.interpolate(0.8, 0.4, 1500);
+        assert!((val - 0.76).abs() < 0.001);
+        assert_eq!(s.duration_ms(), Some(4500));
+    }
+
+    #[test]
+    fn keyframes_easing_shapes() {
+        let at_quarter = |easing| {
+            let s = Keyframes::new().with_keyframe(1000, 1.0, easing).unwrap();
+            s.interpolate(0.0, 1.0, 250).0
+        };
+        assert!((at_quarter(Easing::Linear) - 0.25).abs() < 0.001);
+        assert!((at_quarter(Easing::Smoothstep) - 0.15625).abs() < 0.001);
+        assert!((at_quarter(Easing::Cubic) - 0.0625).abs() < 0.001);
+
+        let s = Keyframes::new()
+            .with_keyframe(1000, 1.0, Easing::Cubic)
+            .unwrap();
+        let (val, _) = s.interpolate(0.0, 1.0, 750);
+        assert!((val - 0.9375).abs() < 0.001);
+    }
+
+    #[test]
+    fn keyframes_empty_completes_immediately() {
+        let s = Keyframes::new();
+        assert_eq!(s.interpolate(0.2, 0.6, 0), (0.6, true));
+        assert_eq!(s.duration_ms(), Some(0));
+    }
+
+    #[test]
+    fn keyframes_out_of_order_jumps() {
+        let s = Keyframes::new()
+            .with_keyframe(1000, 0.5, Easing::Linear)
+            .unwrap()
+            .with_keyframe(500, 0.8, Easing::Linear)
+            .unwrap()
+            .with_keyframe(2000, 1.0, Easing::Linear)
+            .unwrap();
+        let (val, _) = s.interpolate(0.0, 1.0, 1000);
+        assert!((val - 0.8).abs() < 0.001);
+        let (val, _) = s.interpolate(0.0, 1.0, 1500);
+        assert!((val - 0.9).abs() < 0.001);
+    }
+
+    #[test]
+    fn keyframes_capacity_is_bounded() {
+        let mut s = Keyframes::new();
+        for i in 0..MAX_KEYFRAMES as u64 {
+            s = s.with_keyframe(100 * (i + 1), 1.0, Easing::Linear).unwrap();
+        }
+        assert_eq!(s.duration_ms(), Some(100 * MAX_KEYFRAMES as u64));
+        assert_eq!(
+            s.with_keyframe(5000, 1.0, Easing::Linear),
+            Err(KeyframeError::TooManyFrames)
+        );
+    }
+
+    #[test]
+    fn keyframes_reject_invalid_fractions() {
+        for fraction in [-0.1, 1.01, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                Keyframes::new().with_keyframe(1000, fraction, Easing::Linear),
+                Err(KeyframeError::InvalidFraction)
+            );
+        }
+        assert!(Keyframe::new(1000, 0.0, Easing::Linear).is_ok());
+        assert!(Keyframe::new(1000, 1.0, Easing::Linear).is_ok());
+    }
+
+    #[test]
+    fn keyframes_presets() {
+        assert_eq!(Keyframes::new().lock(), TransitionLock::None);
+        assert_eq!(Keyframes::departure().lock(), TransitionLock::Hard);
+        assert_eq!(
+            Keyframes::departure().on_interrupt(),
+            InterruptBehavior::Reject
+        );
+        assert_eq!(Keyframes::arrival().lock(), TransitionLock::Source);
+        assert_eq!(
+            Keyframes::arrival().on_interrupt(),
+            InterruptBehavior::Queue
+        );
+    }
+
+    // === Rate of Change ===
+    fn assert_rate_is_derivative<S: ExecutionStrategy>(strategy: &S) {
+        for (from, to) in [(0.0, 1.0), (0.8, 0.2), (0.3, 0.35)] {
+            for elapsed in (3..4000).step_by(7) {
+                let (before, _) = strategy.interpolate(from, to, elapsed - 1);
+                let (after, done) = strategy.interpolate(from, to, elapsed + 1);
+                if done {
+                    break;
+                }
+                let numeric = (after - before) * 500.0;
+                let rate = strategy.rate(from, to, elapsed);
+                assert!(
+                    (rate - numeric).abs() < 0.01,
+                    "{from} -> {to} at {elapsed}ms: {rate} vs {numeric}"
+                );
+                let fixed = strategy.rate_q16(Q16::from_f32(from), Q16::from_f32(to), elapsed);
+                assert!((rate - fixed.to_f32()).abs() < 0.01);
+            }
+            assert_eq!(strategy.rate(from, to, 100_000), 0.0);
+        }
+    }
+
+    #[test]
+    fn rate_matches_derivative() {
+        assert_rate_is_derivative(&Linear::new(1500));
+        assert_rate_is_derivative(&EaseInOut::new(2000));
+        assert_rate_is_derivative(&Momentum::gentle());
+        assert_rate_is_derivative(&Momentum::responsive());
+        assert_rate_is_derivative(&SCurve::new(0.5, 0.25, 1.0));
+        assert_rate_is_derivative(&creep());
+        assert_rate_is_derivative(&StrategySpec::from(EaseInOut::arrival(800)));
+    }
+
+    #[test]
+    fn immediate_has_no_rate_or_blend() {
+        assert_eq!(Immediate.rate(0.0, 1.0, 0), 0.0);
+        assert_eq!(Immediate.blend_ms(0.0, 1.0), 0);
+    }
+
+    #[test]
+    fn blend_ms_follows_duration() {
+        assert_eq!(Linear::new(1200).blend_ms(0.0, 1.0), 1200);
+        assert_eq!(SCurve::new(0.5, 0.25, 1.0).blend_ms(0.0, 1.0), 2500);
+        assert_eq!(Momentum::gentle().blend_ms(0.0, 1.0), RATE_BLEND_MS);
+    }
+
     // === TransitionLock and InterruptBehavior ===
     #[test]
     fn transition_lock_default_is_none() {
@@ -790,4 +2266,135 @@ mod tests {
     fn interrupt_behavior_default_is_replace() {
         assert_eq!(InterruptBehavior::default(), InterruptBehavior::Replace);
     }
+
+    // === StrategySpec ===
+    #[test]
+    fn strategy_spec_default_is_immediate() {
+        assert_eq!(StrategySpec::default(), StrategySpec::Immediate);
+        assert_eq!(StrategySpec::default().duration_ms(), Some(0));
+    }
+
+    #[test]
+    fn strategy_spec_dispatches_to_inner() {
+        let spec = StrategySpec::from(Linear::new(1000));
+        let (val, done) = spec.interpolate(0.0, 1.0, 500);
+        assert!((val - 0.5).abs() < 0.01);
+        assert!(!done);
+        assert_eq!(spec.duration_ms(), Some(1000));
+    }
+
+    #[test]
+    fn strategy_spec_preserves_lock_and_interrupt() {
+        let spec = StrategySpec::from(EaseInOut::arrival(2000));
+        assert_eq!(spec.lock(), TransitionLock::Source);
+        assert_eq!(spec.on_interrupt(), InterruptBehavior::Queue);
+
+        let spec = StrategySpec::from(Momentum::gentle());
+        assert_eq!(spec.duration_ms(), None);
+        assert_eq!(spec.on_interrupt(), InterruptBehavior::Replace);
+    }
+
+    #[test]
+    fn strategy_spec_duration_for() {
+        let spec = StrategySpec::from(SCurve::departure(0.5, 0.25, 1.0));
+        assert_eq!(spec.duration_for(0.0, 1.0), Some(2500));
+        assert_eq!(spec.lock(), TransitionLock::Hard);
+
+        let spec = StrategySpec::from(Linear::new(1000));
+        assert_eq!(spec.duration_for(0.0, 0.2), Some(1000));
+    }
+
+    // === Fixed Point ===
+    fn assert_q16_matches<S: ExecutionStrategy>(strategy: &S) {
+        let points = [0.0, 0.1, 0.35, 0.5, 0.8, 1.0];
+        for &from in &points {
+            for &to in &points {
+                for elapsed in (0..=4000).step_by(20) {
+                    let (float, float_done) = strategy.interpolate(from, to, elapsed);
+                    let (fixed, fixed_done) = strategy.interpolate_q16(
+                        Q16::from_f32(from),
+                        Q16::from_f32(to),
+                        elapsed,
+                    );
+                    assert!(
+                        (float - fixed.to_f32()).abs() <= Q16::TOLERANCE,
+                        "{from} -> {to} at {elapsed}ms: {float} vs {}",
+                        fixed.to_f32()
+                    );
+                    // Completion may differ only right at the boundary
+                    if float_done != fixed_done {
+                        assert!((float - to).abs() <= Q16::TOLERANCE);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_point_matches_float_immediate() {
+        assert_q16_matches(&Immediate);
+    }
+
+    #[test]
+    fn fixed_point_matches_float_linear() {
+        assert_q16_matches(&Linear::new(1000));
+        assert_q16_matches(&Linear::new(3));
+        assert_q16_matches(&Linear::new(0));
+    }
+
+    #[test]
+    fn fixed_point_matches_float_ease_in_out() {
+        assert_q16_matches(&EaseInOut::new(2000));
+        assert_q16_matches(&EaseInOut::departure(750));
+    }
+
+    #[test]
+    fn fixed_point_matches_float_momentum() {
+        assert_q16_matches(&Momentum::gentle());
+        assert_q16_matches(&Momentum::responsive());
+    }
+
+    #[test]
+    fn fixed_point_matches_float_s_curve() {
+        assert_q16_matches(&SCurve::new(0.5, 0.25, 1.0));
+        assert_q16_matches(&SCurve::new(0.8, 0.8, 0.3));
+        assert_q16_matches(&SCurve::new(0.4, 0.6, 0.0));
+    }
+
+    #[test]
+    fn fixed_point_matches_float_keyframes() {
+        assert_q16_matches(&creep());
+        assert_q16_matches(
+            &Keyframes::arrival()
+                .with_keyframe(800, 0.7, Easing::Cubic)
+                .unwrap()
+                .with_keyframe(3000, 1.0, Easing::Smoothstep)
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn fixed_point_matches_float_spec() {
+        assert_q16_matches(&StrategySpec::from(Linear::new(1500)));
+        assert_q16_matches(&StrategySpec::from(Momentum::new(1.2, 0.4)));
+    }
+
+    #[test]
+    fn strategy_spec_unresolved_preset_holds_speed() {
+        let spec = StrategySpec::preset("departure").unwrap();
+        assert_eq!(spec.preset_name(), Some("departure"));
+        assert_eq!(spec.interpolate(0.3, 1.0, 0), (0.3, true));
+        assert_eq!(StrategySpec::Immediate.preset_name(), None);
+    }
+
+    #[test]
+    fn strategy_spec_preset_name_too_long() {
+        let name = "sixteen-byte-nam";
+        assert_eq!(name.len(), MAX_PRESET_NAME);
+        assert!(StrategySpec::preset(name).is_ok());
+        assert_eq!(
+            StrategySpec::preset("a-very-long-preset-name"),
+            Err(PresetNameError)
+        );
+    }
 }
@@ -12,18 +12,31 @@
 //! - [`TransitionLock::Source`]: Only same or higher priority source can interrupt
 //! - [`TransitionLock::Hard`]: Only e-stop can interrupt
 //!
+//! Source priority is judged by the manager's [`SourcePriorities`] (see
+//! [`TransitionManager::with_priorities`]), here and for pause, resume and
+//! queue cancellation.
+//!
+//! Transitions remember the [`ClientId`] that started them, if any. A source
+//! lock held by a named client also holds off other clients of the same
+//! source; only the owner or a higher priority source can interrupt it.
+//!
+//! A lock can be capped with [`TransitionManager::with_max_lock_duration`],
+//! after which it degrades to [`TransitionLock::None`], or dropped early with
+//! [`TransitionManager::release_lock`]. The transition itself keeps running;
+//! [`LockStatus::released`] reports why the lock no longer holds.
+//!
 //! # Example
 //!
 //! ```rust
 //! use rs_trainz::transition::TransitionManager;
-//! use rs_trainz::{AnyStrategy, CommandSource};
+//! use rs_trainz::{AnyStrategy, CommandSource, Speed};
 //! use rs_trainz::traits::Linear;
 //!
-//! let mut manager = TransitionManager::new(0.0);
+//! let mut manager = TransitionManager::new(Speed::ZERO);
 //!
 //! // Start a 1-second linear transition
 //! let result = manager.try_start(
-//!     1.0,                            // target
+//!     Speed::FULL,                    // target
 //!     AnyStrategy::new(Linear::new(1000)),
 //!     CommandSource::Physical,
 //!     false,                          // not e-stop
@@ -32,13 +45,40 @@
 //!
 //! // Update in your main loop
 //! let (current_speed, is_complete) = manager.update(500); // at 500ms
-//! assert!((current_speed - 0.5).abs() < 0.1); // ~50% complete
+//! assert!((current_speed.get() - 0.5).abs() < 0.1); // ~50% complete
 //! ```
 //!
+//! # Interruptions
+//!
+//! Replacing a transition mid-ramp keeps the speed's rate of change
+//! continuous: the rate of the old transition is blended into the new one
+//! and fades out over the new strategy's
+//! [`blend_ms`](crate::traits::ExecutionStrategy::blend_ms). E-stop and
+//! [`Immediate`](crate::traits::Immediate) still jump.
+//!
+//! # Pausing
+//!
+//! [`pause`](TransitionManager::pause) freezes the active transition at its
+//! current value and elapsed time; [`resume`](TransitionManager::resume)
+//! continues the profile where it left off. Queued commands wait while a
+//! transition is paused.
+//!
+//! # Preview
+//!
+//! [`preview`] samples the curve a strategy would produce without starting
+//! it, for drawing or validating a profile before it's sent.
+//!
 //! # Queueing
 //!
 //! When a locked transition with [`InterruptBehavior::Queue`] is interrupted,
 //! the new command is queued and executes after the current transition completes.
+//! The queue holds [`DEFAULT_QUEUE_DEPTH`] commands unless configured with
+//! [`TransitionManager::with_queue_depth`]; each entry gets an id that can be
+//! used to [cancel](TransitionManager::cancel_queued) it.
+//!
+//! Each transition keeps its id when it starts; whether it started,
+//! completed or was interrupted is reported as a [`TransitionEvent`] by
+//! [`TransitionManager::pop_event`].
 //!
 //! [`TransitionLock`]: crate::traits::TransitionLock
 //! [`TransitionLock::None`]: crate::traits::TransitionLock::None
@@ -46,9 +86,132 @@
 //! [`TransitionLock::Hard`]: crate::traits::TransitionLock::Hard
 //! [`InterruptBehavior::Queue`]: crate::traits::InterruptBehavior::Queue
 
-use crate::commands::{CommandSource, RejectReason, TransitionResult};
+use crate::commands::{ClientId, CommandSource, RejectReason, SourcePriorities, TransitionResult};
+use crate::fixed::Q16;
+use crate::speed::Speed;
 use crate::strategy_dyn::AnyStrategy;
-use crate::traits::{InterruptBehavior, TransitionLock};
+use crate::traits::{InterruptBehavior, SegmentProgress, TransitionLock};
+use heapless::Deque;
+
+// ============================================================================
+// Numeric Representation
+// ============================================================================
+
+// Speeds are held as Q16 with the `fixed-point` feature so that `update()`
+// never touches the FPU; the public API converts at the edges.
+
+#[cfg(feature = "fixed-point")]
+type Value = Q16;
+
+#[cfg(not(feature = "fixed-point"))]
+type Value = f32;
+
+#[cfg(feature = "fixed-point")]
+const ZERO: Value = Q16::ZERO;
+
+#[cfg(not(feature = "fixed-point"))]
+const ZERO: Value = 0.0;
+
+#[cfg(feature = "fixed-point")]
+const ONE: Value = Q16::ONE;
+
+#[cfg(not(feature = "fixed-point"))]
+const ONE: Value = 1.0;
+
+#[cfg(feature = "fixed-point")]
+fn to_value(speed: Speed) -> Value {
+    speed.to_q16()
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn to_value(speed: Speed) -> Value {
+    speed.get()
+}
+
+#[cfg(feature = "fixed-point")]
+fn to_speed(v: Value) -> Speed {
+    Speed::from_q16(v)
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn to_speed(v: Value) -> Speed {
+    Speed::clamped(v)
+}
+
+#[cfg(feature = "fixed-point")]
+fn to_f32(v: Value) -> f32 {
+    v.to_f32()
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn to_f32(v: Value) -> f32 {
+    v
+}
+
+#[cfg(feature = "fixed-point")]
+fn ratio(num: u64, den: u64) -> Value {
+    Q16::from_ratio(num, den)
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn ratio(num: u64, den: u64) -> Value {
+    num as f32 / den as f32
+}
+
+/// Whether `by` may override what `owner` set: a higher priority source,
+/// or the same source unless `owner` is a different client of it
+fn may_override(
+    priorities: &SourcePriorities,
+    by: (CommandSource, Option<ClientId>),
+    owner: (CommandSource, Option<ClientId>),
+) -> bool {
+    let rank = priorities.compare(by.0, owner.0);
+    let other_client = owner.1.is_some() && owner.1 != by.1;
+    rank.is_gt() || (rank.is_eq() && !other_client)
+}
+
+/// `v` kept in range and short of `to`, heading there from `from`
+fn clamp_short_of(v: Value, from: Value, to: Value) -> Value {
+    if from <= to {
+        v.clamp(ZERO, to)
+    } else {
+        v.clamp(to, ONE)
+    }
+}
+
+#[cfg(feature = "fixed-point")]
+fn to_q16(v: Value) -> Q16 {
+    v
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn to_q16(v: Value) -> Q16 {
+    Q16::from_f32(v)
+}
+
+#[cfg(feature = "fixed-point")]
+fn interpolate(strategy: &AnyStrategy, from: Value, to: Value, elapsed_ms: u64) -> (Value, bool) {
+    strategy.interpolate_q16(from, to, elapsed_ms)
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn interpolate(strategy: &AnyStrategy, from: Value, to: Value, elapsed_ms: u64) -> (Value, bool) {
+    strategy.interpolate(from, to, elapsed_ms)
+}
+
+#[cfg(feature = "fixed-point")]
+fn rate(strategy: &AnyStrategy, from: Value, to: Value, elapsed_ms: u64) -> Value {
+    strategy.rate_q16(from, to, elapsed_ms)
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn rate(strategy: &AnyStrategy, from: Value, to: Value, elapsed_ms: u64) -> Value {
+    strategy.rate(from, to, elapsed_ms)
+}
+
+// ============================================================================
+// Transition Manager
+// ============================================================================
 
 /// An active speed transition.
 ///
@@ -69,13 +232,167 @@ pub struct ActiveTransition {
     pub lock: TransitionLock,
     /// Behavior when something tries to interrupt.
     pub interrupt_behavior: InterruptBehavior,
+    id: u32,
+    /// `from` and `to` in the manager's number format
+    start: Value,
+    end: Value,
+    /// Commanded target, reported as given
+    target: Speed,
+    client: Option<ClientId>,
+    blend: RateBlend,
+    paused: Option<Pause>,
+    /// When the lock was taken; pauses don't extend it
+    locked_at_ms: u64,
+    released: Option<LockRelease>,
+}
+
+impl ActiveTransition {
+    /// Why the lock no longer holds at `now_ms`, if it was ever taken
+    fn release_at(&self, now_ms: u64, max_lock_ms: Option<u64>) -> Option<LockRelease> {
+        if self.lock == TransitionLock::None {
+            return None;
+        }
+        self.released.or_else(|| {
+            let max = max_lock_ms?;
+            (now_ms.saturating_sub(self.locked_at_ms) >= max).then_some(LockRelease::Expired)
+        })
+    }
+
+    /// Lock in effect at `now_ms`
+    fn lock_at(&self, now_ms: u64, max_lock_ms: Option<u64>) -> TransitionLock {
+        match self.release_at(now_ms, max_lock_ms) {
+            Some(_) => TransitionLock::None,
+            None => self.lock,
+        }
+    }
+
+    /// Time spent running at `now_ms`, not counting a pause in progress
+    fn elapsed_at(&self, now_ms: u64) -> u64 {
+        let until = self.paused.map_or(now_ms, |p| p.since_ms);
+        until.saturating_sub(self.started_ms)
+    }
+
+    /// Rate of change per second at `now_ms`, including any blend
+    fn rate_at(&self, now_ms: u64) -> Value {
+        if self.paused.is_some() {
+            return ZERO;
+        }
+        let elapsed = self.elapsed_at(now_ms);
+        rate(&self.strategy, self.start, self.end, elapsed) + self.blend.rate(elapsed)
+    }
+}
+
+/// A pause in progress
+#[derive(Clone, Copy)]
+struct Pause {
+    since_ms: u64,
+    source: CommandSource,
+    client: Option<ClientId>,
+}
+
+/// Rate inherited from an interrupted transition.
+///
+/// Adds `excess * t * (1 - t/window)²` to the new curve: zero offset at
+/// both ends, slope `excess` at the start and zero slope at the end.
+#[derive(Clone, Copy)]
+struct RateBlend {
+    /// Inherited rate minus the new strategy's starting rate, per second
+    excess: Value,
+    window_ms: u64,
+}
+
+impl RateBlend {
+    const NONE: Self = Self {
+        excess: ZERO,
+        window_ms: 0,
+    };
+
+    fn offset(&self, elapsed_ms: u64) -> Value {
+        if elapsed_ms >= self.window_ms {
+            return ZERO;
+        }
+        let remaining = ONE - ratio(elapsed_ms, self.window_ms);
+        self.excess * ratio(elapsed_ms, 1000) * remaining * remaining
+    }
+
+    fn rate(&self, elapsed_ms: u64) -> Value {
+        if elapsed_ms >= self.window_ms {
+            return ZERO;
+        }
+        let t = ratio(elapsed_ms, self.window_ms);
+        let remaining = ONE - t;
+        self.excess * remaining * (remaining - t - t)
+    }
+}
+
+/// Number of queued transitions a [`TransitionManager`] holds by default
+pub const DEFAULT_QUEUE_DEPTH: usize = 4;
+
+/// Largest supported queue depth, and the most entries [`ThrottleState`] reports
+///
+/// [`ThrottleState`]: crate::ThrottleState
+pub const MAX_QUEUE_DEPTH: usize = 16;
+
+/// Most [`TransitionEvent`]s a [`TransitionManager`] keeps until they are taken
+///
+/// Enough for an e-stop that interrupts the active transition and a full
+/// queue, twice over.
+pub const MAX_TRANSITION_EVENTS: usize = 2 * (MAX_QUEUE_DEPTH + 1);
+
+/// A change in a transition's lifecycle.
+///
+/// Every transition gets an id when it starts or is queued (the queue id
+/// carries over when it starts). Taken in order with
+/// [`TransitionManager::pop_event`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionEvent {
+    /// The transition started running.
+    Started {
+        /// Transition id.
+        id: u32,
+    },
+    /// The transition reached its target.
+    Completed {
+        /// Transition id.
+        id: u32,
+    },
+    /// The transition was replaced, cancelled or dropped from the queue
+    /// before it finished.
+    Interrupted {
+        /// Transition id.
+        id: u32,
+    },
+}
+
+impl TransitionEvent {
+    /// Id of the transition the event is about
+    pub const fn id(&self) -> u32 {
+        match self {
+            Self::Started { id } | Self::Completed { id } | Self::Interrupted { id } => *id,
+        }
+    }
 }
 
 /// A queued transition waiting to execute
 struct QueuedTransition {
-    to: f32,
+    id: u32,
+    target: Speed,
     strategy: AnyStrategy,
     source: CommandSource,
+    client: Option<ClientId>,
+}
+
+impl QueuedTransition {
+    fn entry(&self) -> QueuedCommand {
+        QueuedCommand {
+            id: self.id,
+            target: self.target,
+            source: self.source,
+            client: self.client,
+            lock: self.strategy.lock(),
+            duration_ms: self.strategy.duration_ms(),
+        }
+    }
 }
 
 /// Manages speed transitions with locking and queuing.
@@ -92,14 +409,14 @@ struct QueuedTransition {
 ///
 /// ```rust
 /// use rs_trainz::transition::TransitionManager;
-/// use rs_trainz::{AnyStrategy, CommandSource};
+/// use rs_trainz::{AnyStrategy, CommandSource, Speed};
 /// use rs_trainz::traits::EaseInOut;
 ///
-/// let mut manager = TransitionManager::new(0.0);
+/// let mut manager = TransitionManager::new(Speed::ZERO);
 ///
 /// // Start a departure transition (locked)
 /// manager.try_start(
-///     0.8,
+///     Speed::new(0.8).unwrap(),
 ///     AnyStrategy::new(EaseInOut::departure(2000)),
 ///     CommandSource::Physical,
 ///     false,
@@ -117,22 +434,112 @@ struct QueuedTransition {
 ///     # break; // for doctest
 /// }
 /// ```
-pub struct TransitionManager {
+///
+/// # Queue Depth
+///
+/// Up to `QUEUE` commands wait behind a locked transition (see
+/// [`with_queue_depth`](Self::with_queue_depth)). `QUEUE` must be between 1
+/// and [`MAX_QUEUE_DEPTH`].
+pub struct TransitionManager<const QUEUE: usize = DEFAULT_QUEUE_DEPTH> {
     active: Option<ActiveTransition>,
-    queued: Option<QueuedTransition>,
-    current_value: f32,
+    queue: Deque<QueuedTransition, QUEUE>,
+    events: Deque<TransitionEvent, MAX_TRANSITION_EVENTS>,
+    next_id: u32,
+    current_value: Value,
+    max_value: Value,
+    max_lock_ms: Option<u64>,
+    priorities: SourcePriorities,
+    force_release_source: CommandSource,
 }
 
 impl TransitionManager {
     /// Create a new transition manager with an initial value
-    pub fn new(initial: f32) -> Self {
+    pub fn new(initial: Speed) -> Self {
         Self {
             active: None,
-            queued: None,
-            current_value: initial,
+            queue: Deque::new(),
+            events: Deque::new(),
+            next_id: 1,
+            current_value: to_value(initial),
+            max_value: ONE,
+            max_lock_ms: None,
+            priorities: SourcePriorities::default(),
+            force_release_source: CommandSource::Physical,
+        }
+    }
+}
+
+impl<const QUEUE: usize> TransitionManager<QUEUE> {
+    /// Hold up to `DEPTH` queued transitions
+    ///
+    /// Keeps the active transition; queued commands beyond the new depth
+    /// are dropped, newest first.
+    pub fn with_queue_depth<const DEPTH: usize>(mut self) -> TransitionManager<DEPTH> {
+        const {
+            assert!(
+                DEPTH > 0 && DEPTH <= MAX_QUEUE_DEPTH,
+                "unsupported queue depth"
+            );
+        }
+        let mut queue = Deque::new();
+        while let Some(queued) = self.queue.pop_front() {
+            if queue.is_full() {
+                self.emit(TransitionEvent::Interrupted { id: queued.id });
+            } else {
+                let _ = queue.push_back(queued);
+            }
+        }
+        TransitionManager {
+            active: self.active,
+            queue,
+            events: self.events,
+            next_id: self.next_id,
+            current_value: self.current_value,
+            max_value: self.max_value,
+            max_lock_ms: self.max_lock_ms,
+            priorities: self.priorities,
+            force_release_source: self.force_release_source,
         }
     }
 
+    /// Rank sources with `priorities` instead of the default table
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.priorities = priorities;
+        self
+    }
+
+    /// The table used to rank command sources
+    pub fn priorities(&self) -> &SourcePriorities {
+        &self.priorities
+    }
+
+    /// Never drive the value above `max`
+    ///
+    /// Transitions keep running; their value is capped until it falls
+    /// below `max` again.
+    pub fn set_max_speed(&mut self, max: Speed) {
+        self.max_value = to_value(max);
+    }
+
+    /// Lowest source allowed to pause another source's hard locked transition
+    ///
+    /// Defaults to [`CommandSource::Physical`], matching the controller's
+    /// force release threshold.
+    pub fn with_force_release_source(mut self, source: CommandSource) -> Self {
+        self.force_release_source = source;
+        self
+    }
+
+    /// Degrade locks to [`TransitionLock::None`] after `max_ms`
+    ///
+    /// Bounds how long a long locked transition can hold off other
+    /// commands; e-stop and [`release_lock`](Self::release_lock) still
+    /// apply sooner.
+    pub fn with_max_lock_duration(mut self, max_ms: u64) -> Self {
+        self.max_lock_ms = Some(max_ms);
+        self
+    }
+
     /// Attempt to start a new transition
     ///
     /// Returns the result indicating whether the transition was started,
@@ -140,18 +547,35 @@ impl TransitionManager {
     #[must_use]
     pub fn try_start(
         &mut self,
-        to: f32,
+        to: Speed,
+        strategy: AnyStrategy,
+        source: CommandSource,
+        is_estop: bool,
+        now_ms: u64,
+    ) -> TransitionResult {
+        self.try_start_as(to, strategy, source, None, is_estop, now_ms)
+    }
+
+    /// Attempt to start a new transition on behalf of `client`
+    ///
+    /// Like [`try_start`](Self::try_start), but the transition records the
+    /// client and a source lock it takes only yields to that client or a
+    /// higher priority source.
+    #[must_use]
+    pub fn try_start_as(
+        &mut self,
+        to: Speed,
         strategy: AnyStrategy,
         source: CommandSource,
+        client: Option<ClientId>,
         is_estop: bool,
         now_ms: u64,
     ) -> TransitionResult {
         // E-stop always wins immediately
         if is_estop {
-            let previous = self.active.as_ref().map(|t| t.to);
-            self.active = None;
-            self.queued = None;
-            self.current_value = to;
+            let previous = self.active.as_ref().map(|t| t.target);
+            self.cancel_all();
+            self.current_value = to_value(to);
             return match previous {
                 Some(prev) => TransitionResult::Interrupted {
                     previous_target: prev,
@@ -162,24 +586,31 @@ impl TransitionManager {
 
         // Check if we can interrupt the current transition
         if let Some(ref active) = self.active {
-            match active.lock {
+            match active.lock_at(now_ms, self.max_lock_ms) {
                 TransitionLock::Hard => {
                     // Only e-stop can interrupt (handled above)
                     return self.handle_blocked_command(
                         to,
                         strategy,
                         source,
+                        client,
                         active.interrupt_behavior,
                     );
                 }
 
                 TransitionLock::Source => {
-                    // Same or higher priority source can interrupt
-                    if source < active.source {
+                    // Same or higher priority source can interrupt, but not
+                    // another client of the owning source
+                    if !may_override(
+                        &self.priorities,
+                        (source, client),
+                        (active.source, active.client),
+                    ) {
                         return self.handle_blocked_command(
                             to,
                             strategy,
                             source,
+                            client,
                             active.interrupt_behavior,
                         );
                     }
@@ -192,20 +623,43 @@ impl TransitionManager {
             }
         }
 
-        // Start the new transition
-        let previous = self.active.as_ref().map(|t| t.to);
+        // Start the new transition, carrying over the current rate
+        let previous = self.active.as_ref().map(|t| t.target);
+        let from = self.current_value;
+        let to_v = to_value(to);
+        let blend = match &self.active {
+            Some(active) => RateBlend {
+                excess: active.rate_at(now_ms) - rate(&strategy, from, to_v, 0),
+                window_ms: strategy.blend_ms(to_f32(from), to.get()),
+            },
+            None => RateBlend::NONE,
+        };
 
         let lock = strategy.lock();
         let interrupt_behavior = strategy.on_interrupt();
 
+        if let Some(active) = &self.active {
+            self.emit(TransitionEvent::Interrupted { id: active.id });
+        }
+        let id = self.take_id();
+        self.emit(TransitionEvent::Started { id });
         self.active = Some(ActiveTransition {
-            from: self.current_value,
-            to,
+            from: to_f32(from),
+            to: to_f32(to_v),
+            id,
+            start: from,
+            end: to_v,
+            target: to,
             strategy,
             started_ms: now_ms,
             source,
+            client,
             lock,
             interrupt_behavior,
+            blend,
+            paused: None,
+            locked_at_ms: now_ms,
+            released: None,
         });
 
         match previous {
@@ -219,25 +673,28 @@ impl TransitionManager {
     /// Handle a command that can't interrupt the current transition
     fn handle_blocked_command(
         &mut self,
-        to: f32,
+        to: Speed,
         strategy: AnyStrategy,
         source: CommandSource,
+        client: Option<ClientId>,
         interrupt_behavior: InterruptBehavior,
     ) -> TransitionResult {
         match interrupt_behavior {
             InterruptBehavior::Queue => {
-                if self.queued.is_some() {
-                    TransitionResult::Rejected {
+                if self.queue.is_full() {
+                    return TransitionResult::Rejected {
                         reason: RejectReason::QueueFull,
-                    }
-                } else {
-                    self.queued = Some(QueuedTransition {
-                        to,
-                        strategy,
-                        source,
-                    });
-                    TransitionResult::Queued
+                    };
                 }
+                let id = self.take_id();
+                let _ = self.queue.push_back(QueuedTransition {
+                    id,
+                    target: to,
+                    strategy,
+                    source,
+                    client,
+                });
+                TransitionResult::Queued { id }
             }
             InterruptBehavior::Reject => TransitionResult::Rejected {
                 reason: RejectReason::TransitionLocked,
@@ -255,39 +712,78 @@ impl TransitionManager {
     /// Update the transition state - call every tick
     ///
     /// Returns (current_value, is_complete)
-    pub fn update(&mut self, now_ms: u64) -> (f32, bool) {
+    pub fn update(&mut self, now_ms: u64) -> (Speed, bool) {
+        let (value, complete) = self.step(now_ms);
+        (to_speed(value), complete)
+    }
+
+    /// Fixed-point version of [`update`](Self::update)
+    ///
+    /// With the `fixed-point` feature this uses no floating-point math.
+    pub fn update_q16(&mut self, now_ms: u64) -> (Q16, bool) {
+        let (value, complete) = self.step(now_ms);
+        (to_q16(value), complete)
+    }
+
+    fn step(&mut self, now_ms: u64) -> (Value, bool) {
         match &self.active {
             None => {
                 // Check for queued transition
-                if let Some(queued) = self.queued.take() {
+                if let Some(queued) = self.queue.pop_front() {
                     let lock = queued.strategy.lock();
                     let interrupt_behavior = queued.strategy.on_interrupt();
 
+                    self.emit(TransitionEvent::Started { id: queued.id });
+                    let end = to_value(queued.target);
                     self.active = Some(ActiveTransition {
-                        from: self.current_value,
-                        to: queued.to,
+                        from: to_f32(self.current_value),
+                        to: to_f32(end),
+                        id: queued.id,
+                        start: self.current_value,
+                        end,
+                        target: queued.target,
                         strategy: queued.strategy,
                         started_ms: now_ms,
                         source: queued.source,
+                        client: queued.client,
                         lock,
                         interrupt_behavior,
+                        blend: RateBlend::NONE,
+                        paused: None,
+                        locked_at_ms: now_ms,
+                        released: None,
                     });
                     // Recurse to process the new transition
-                    return self.update(now_ms);
+                    return self.step(now_ms);
                 }
                 (self.current_value, true)
             }
+            Some(transition) if transition.paused.is_some() => (self.current_value, false),
             Some(transition) => {
-                let elapsed = now_ms.saturating_sub(transition.started_ms);
-                let (value, complete) =
-                    transition
-                        .strategy
-                        .interpolate(transition.from, transition.to, elapsed);
+                let elapsed = transition.elapsed_at(now_ms);
+                let (value, complete) = interpolate(
+                    &transition.strategy,
+                    transition.start,
+                    transition.end,
+                    elapsed,
+                );
+                let (value, complete) = if elapsed < transition.blend.window_ms {
+                    // The inherited rate may turn the train around, but must
+                    // not carry it past the target
+                    let offset = transition.blend.offset(elapsed);
+                    let value = clamp_short_of(value + offset, transition.start, transition.end);
+                    (value, false)
+                } else {
+                    (value, complete)
+                };
+                let value = value.min(self.max_value);
 
                 self.current_value = value;
 
                 if complete {
+                    let id = transition.id;
                     self.active = None;
+                    self.emit(TransitionEvent::Completed { id });
                     // Queued transition will be picked up next update
                 }
 
@@ -296,22 +792,238 @@ impl TransitionManager {
         }
     }
 
+    /// Freeze the active transition on behalf of `source` and `client`
+    ///
+    /// The value and elapsed time hold until [`resume`](Self::resume).
+    /// Source locked transitions can only be paused by the lock owner or a
+    /// higher priority source. Hard locked transitions can only be paused by
+    /// the lock owner or a source allowed to force release the lock
+    /// (see [`with_force_release_source`](Self::with_force_release_source)).
+    /// Either way, another client of the owning source is not the owner.
+    /// Pausing again is a no-op that records the higher source.
+    pub fn pause(
+        &mut self,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
+        let (max_lock_ms, priorities) = (self.max_lock_ms, self.priorities);
+        let force_release_source = self.force_release_source;
+        let active = self.active.as_mut().ok_or(RejectReason::NotTransitioning)?;
+        let owner = (active.source, active.client);
+        match active.lock_at(now_ms, max_lock_ms) {
+            TransitionLock::None => {}
+            TransitionLock::Hard => {
+                let is_owner =
+                    source == active.source && may_override(&priorities, (source, client), owner);
+                if !is_owner && priorities.is_below(source, force_release_source) {
+                    return Err(RejectReason::TransitionLocked);
+                }
+            }
+            TransitionLock::Source => {
+                if !may_override(&priorities, (source, client), owner) {
+                    return Err(RejectReason::LowerPriority);
+                }
+            }
+        }
+        match &mut active.paused {
+            Some(pause) if priorities.outranks(source, pause.source) => {
+                pause.source = source;
+                pause.client = client;
+            }
+            Some(_) => {}
+            None => {
+                active.paused = Some(Pause {
+                    since_ms: now_ms,
+                    source,
+                    client,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Continue a paused transition on behalf of `source` and `client`
+    ///
+    /// Only the source that paused the transition, or a higher priority
+    /// one, may resume it. Other clients of the pausing source may not.
+    pub fn resume(
+        &mut self,
+        source: CommandSource,
+        client: Option<ClientId>,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
+        let active = self.active.as_mut().ok_or(RejectReason::NotPaused)?;
+        let pause = active.paused.ok_or(RejectReason::NotPaused)?;
+        if !may_override(
+            &self.priorities,
+            (source, client),
+            (pause.source, pause.client),
+        ) {
+            return Err(RejectReason::LowerPriority);
+        }
+        active.started_ms += now_ms.saturating_sub(pause.since_ms);
+        active.paused = None;
+        Ok(())
+    }
+
+    /// Drop the lock on the active transition on behalf of `by`
+    ///
+    /// The transition keeps running but any command may now interrupt it.
+    /// Whether `by` is allowed to do this is up to the caller.
+    pub fn release_lock(&mut self, by: CommandSource, now_ms: u64) -> Result<(), RejectReason> {
+        let max_lock_ms = self.max_lock_ms;
+        let active = self
+            .active
+            .as_mut()
+            .filter(|t| t.lock_at(now_ms, max_lock_ms) != TransitionLock::None)
+            .ok_or(RejectReason::NotLocked)?;
+        active.released = Some(LockRelease::Forced { by });
+        Ok(())
+    }
+
+    /// Check if the active transition is paused
+    pub fn is_paused(&self) -> bool {
+        self.active.as_ref().is_some_and(|t| t.paused.is_some())
+    }
+
     /// Cancel all transitions and set a specific value
-    pub fn cancel_and_set(&mut self, value: f32) {
-        self.active = None;
-        self.queued = None;
-        self.current_value = value;
+    pub fn cancel_and_set(&mut self, value: Speed) {
+        self.cancel_all();
+        self.current_value = to_value(value);
     }
 
     /// Cancel all pending transitions
     pub fn cancel_all(&mut self) {
-        self.active = None;
-        self.queued = None;
+        if let Some(active) = self.active.take() {
+            self.emit(TransitionEvent::Interrupted { id: active.id });
+        }
+        self.retain_queued(|_| false);
+    }
+
+    /// Id of the active transition
+    pub fn active_id(&self) -> Option<u32> {
+        self.active.as_ref().map(|t| t.id)
+    }
+
+    /// Take the oldest lifecycle event not yet taken
+    ///
+    /// Once [`MAX_TRANSITION_EVENTS`] are waiting, the oldest are dropped.
+    pub fn pop_event(&mut self) -> Option<TransitionEvent> {
+        self.events.pop_front()
+    }
+
+    /// Let the active transition carry on as transition `id`
+    ///
+    /// For commands that run as more than one transition, like a reversal
+    /// through zero: `id` is neither reported completed nor started again,
+    /// it just keeps running.
+    pub fn continue_as(&mut self, id: u32) {
+        let Some(active) = self.active.as_mut() else {
+            return;
+        };
+        let replaced = core::mem::replace(&mut active.id, id);
+        for _ in 0..self.events.len() {
+            if let Some(event) = self.events.pop_front() {
+                let hidden = event == TransitionEvent::Completed { id }
+                    || event == TransitionEvent::Started { id: replaced };
+                if !hidden {
+                    let _ = self.events.push_back(event);
+                }
+            }
+        }
+    }
+
+    fn emit(&mut self, event: TransitionEvent) {
+        if self.events.is_full() {
+            self.events.pop_front();
+        }
+        let _ = self.events.push_back(event);
+    }
+
+    fn take_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        id
+    }
+
+    /// Queued transitions, next to run first
+    pub fn queued(&self) -> impl Iterator<Item = QueuedCommand> + '_ {
+        self.queue.iter().map(QueuedTransition::entry)
+    }
+
+    /// Number of queued transitions
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Remove one queued transition on behalf of `source` and `client`
+    ///
+    /// Only the same or a higher priority source than the one that queued
+    /// the command may cancel it, and not another client of that source.
+    pub fn cancel_queued(
+        &mut self,
+        id: u32,
+        source: CommandSource,
+        client: Option<ClientId>,
+    ) -> Result<QueuedCommand, RejectReason> {
+        let entry = self
+            .queue
+            .iter()
+            .find(|q| q.id == id)
+            .map(QueuedTransition::entry)
+            .ok_or(RejectReason::NotQueued)?;
+        if !may_override(
+            &self.priorities,
+            (source, client),
+            (entry.source, entry.client),
+        ) {
+            return Err(RejectReason::LowerPriority);
+        }
+        self.retain_queued(|q| q.id != id);
+        Ok(entry)
+    }
+
+    /// Remove the queued transitions `source` and `client` may cancel
+    ///
+    /// Returns how many were removed; commands queued by higher priority
+    /// sources, or by other clients of the same source, stay.
+    pub fn clear_queue(&mut self, source: CommandSource, client: Option<ClientId>) -> usize {
+        let before = self.queue.len();
+        let priorities = self.priorities;
+        self.retain_queued(|q| !may_override(&priorities, (source, client), (q.source, q.client)));
+        before - self.queue.len()
+    }
+
+    fn retain_queued(&mut self, mut keep: impl FnMut(&QueuedTransition) -> bool) {
+        for _ in 0..self.queue.len() {
+            if let Some(queued) = self.queue.pop_front() {
+                if keep(&queued) {
+                    let _ = self.queue.push_back(queued);
+                } else {
+                    self.emit(TransitionEvent::Interrupted { id: queued.id });
+                }
+            }
+        }
     }
 
     /// Get the current value
-    pub fn current(&self) -> f32 {
-        self.current_value
+    pub fn current(&self) -> Speed {
+        to_speed(self.current_value)
+    }
+
+    /// Get the current value in fixed point
+    pub fn current_q16(&self) -> Q16 {
+        to_q16(self.current_value)
+    }
+
+    /// Current rate of change in speed units per second
+    ///
+    /// Zero when no transition is active.
+    pub fn rate(&self, now_ms: u64) -> f32 {
+        self.active
+            .as_ref()
+            .map_or(0.0, |t| to_f32(t.rate_at(now_ms)))
     }
 
     /// Check if a transition is in progress
@@ -320,30 +1032,45 @@ impl TransitionManager {
     }
 
     /// Get the target value if a transition is active
-    pub fn target(&self) -> Option<f32> {
-        self.active.as_ref().map(|t| t.to)
+    pub fn target(&self) -> Option<Speed> {
+        self.active.as_ref().map(|t| t.target)
     }
 
-    /// Get the current lock status
-    pub fn lock_status(&self) -> Option<LockStatus> {
-        self.active.as_ref().map(|t| LockStatus {
-            lock: t.lock,
-            source: t.source,
-            target: t.to,
-            has_queued: self.queued.is_some(),
+    /// Get the lock status at `now_ms`
+    pub fn lock_status(&self, now_ms: u64) -> Option<LockStatus> {
+        self.active.as_ref().map(|t| {
+            let released = t.release_at(now_ms, self.max_lock_ms);
+            let remaining_ms = match (released, t.lock, self.max_lock_ms) {
+                (None, TransitionLock::Source | TransitionLock::Hard, Some(max)) => {
+                    Some((t.locked_at_ms + max).saturating_sub(now_ms))
+                }
+                _ => None,
+            };
+            LockStatus {
+                lock: t.lock_at(now_ms, self.max_lock_ms),
+                source: t.source,
+                client: t.client,
+                target: t.target,
+                has_queued: !self.queue.is_empty(),
+                remaining_ms,
+                released,
+            }
         })
     }
 
     /// Get progress information for UI feedback
     pub fn progress(&self, now_ms: u64) -> Option<TransitionProgress> {
         self.active.as_ref().map(|t| {
-            let elapsed = now_ms.saturating_sub(t.started_ms);
+            let elapsed = t.elapsed_at(now_ms);
+            let (from, to) = (to_speed(t.start).get(), to_speed(t.end).get());
             TransitionProgress {
-                from: t.from,
-                to: t.to,
-                current: self.current_value,
+                from: to_speed(t.start),
+                to: t.target,
+                current: to_speed(self.current_value),
                 elapsed_ms: elapsed,
-                estimated_total_ms: t.strategy.duration_ms(),
+                estimated_total_ms: t.strategy.duration_for(from, to),
+                segment: t.strategy.segment(from, to, elapsed),
+                paused: t.paused.is_some(),
             }
         })
     }
@@ -360,10 +1087,64 @@ pub struct LockStatus {
     pub lock: TransitionLock,
     /// Source that owns the lock.
     pub source: CommandSource,
-    /// Target speed of the locked transition (0.0 to 1.0).
-    pub target: f32,
+    /// Client within the source that owns the lock, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub client: Option<ClientId>,
+    /// Target speed of the locked transition.
+    pub target: Speed,
     /// Whether there is a queued command waiting to execute.
     pub has_queued: bool,
+    /// Time left before the lock degrades, if a limit applies.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub remaining_ms: Option<u64>,
+    /// Why the transition's lock no longer holds, if it was released.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub released: Option<LockRelease>,
+}
+
+/// Why a transition lock stopped holding before the transition finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LockRelease {
+    /// The lock outlived the maximum lock duration.
+    Expired,
+    /// A sufficiently privileged source forced the release.
+    Forced {
+        /// Source that released the lock.
+        by: CommandSource,
+    },
+}
+
+impl LockRelease {
+    /// Returns the release reason as a snake_case string, as used in JSON.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Expired => "expired",
+            Self::Forced { .. } => "forced",
+        }
+    }
+}
+
+/// A command waiting in the transition queue.
+///
+/// Listed by [`TransitionManager::queued`], next to run first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueuedCommand {
+    /// Id for [`TransitionManager::cancel_queued`].
+    pub id: u32,
+    /// Target speed.
+    pub target: Speed,
+    /// Source that sent the command.
+    pub source: CommandSource,
+    /// Client within the source that sent the command, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub client: Option<ClientId>,
+    /// Lock the transition will take when it starts.
+    pub lock: TransitionLock,
+    /// Duration of the strategy, if known.
+    pub duration_ms: Option<u64>,
 }
 
 /// Progress information for a transition.
@@ -375,13 +1156,16 @@ pub struct LockStatus {
 ///
 /// ```rust
 /// use rs_trainz::transition::TransitionProgress;
+/// use rs_trainz::Speed;
 ///
 /// let progress = TransitionProgress {
-///     from: 0.0,
-///     to: 1.0,
-///     current: 0.5,
+///     from: Speed::ZERO,
+///     to: Speed::FULL,
+///     current: Speed::new(0.5).unwrap(),
 ///     elapsed_ms: 500,
 ///     estimated_total_ms: Some(1000),
+///     segment: None,
+///     paused: false,
 /// };
 ///
 /// // Get percentage (0.0 to 1.0)
@@ -390,12 +1174,12 @@ pub struct LockStatus {
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransitionProgress {
-    /// Starting speed value (0.0 to 1.0).
-    pub from: f32,
-    /// Target speed value (0.0 to 1.0).
-    pub to: f32,
-    /// Current speed value (0.0 to 1.0).
-    pub current: f32,
+    /// Starting speed.
+    pub from: Speed,
+    /// Target speed.
+    pub to: Speed,
+    /// Current speed.
+    pub current: Speed,
     /// Time elapsed since transition started (milliseconds).
     pub elapsed_ms: u64,
     /// Estimated total duration (milliseconds), if known.
@@ -405,6 +1189,17 @@ pub struct TransitionProgress {
     ///
     /// [`Momentum`]: crate::traits::Momentum
     pub estimated_total_ms: Option<u64>,
+    /// Segment in progress, for multi-segment strategies like [`Sequence`].
+    ///
+    /// [`Sequence`]: crate::sequence::Sequence
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub segment: Option<SegmentProgress>,
+    /// Whether the transition is paused.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub paused: bool,
 }
 
 impl TransitionProgress {
@@ -421,25 +1216,150 @@ impl TransitionProgress {
 }
 
 // ============================================================================
-// Tests
+// Preview
 // ============================================================================
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::traits::{EaseInOut, Immediate, Linear};
+/// Most points a [`TransitionPreview`] holds
+pub const MAX_PREVIEW_POINTS: usize = 128;
 
-    fn immediate() -> AnyStrategy {
-        AnyStrategy::new(Immediate)
-    }
+/// How long [`preview`] follows a strategy whose duration isn't known up front
+pub const PREVIEW_HORIZON_MS: u64 = 600_000;
 
-    fn linear(ms: u64) -> AnyStrategy {
-        AnyStrategy::new(Linear::new(ms))
-    }
+/// One sample of a previewed transition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreviewPoint {
+    /// Time since the transition started (milliseconds).
+    pub elapsed_ms: u64,
+    /// Speed at that time.
+    pub speed: Speed,
+}
 
-    fn linear_locked(ms: u64) -> AnyStrategy {
-        AnyStrategy::new(Linear::locked(ms))
-    }
+/// The curve a strategy would produce, as returned by [`preview`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitionPreview {
+    /// Starting speed.
+    pub from: Speed,
+    /// Target speed.
+    pub to: Speed,
+    /// Total duration (milliseconds).
+    ///
+    /// Found by searching for distance-dependent strategies like
+    /// [`Momentum`]; `None` if the transition doesn't finish within
+    /// [`PREVIEW_HORIZON_MS`].
+    ///
+    /// [`Momentum`]: crate::traits::Momentum
+    pub duration_ms: Option<u64>,
+    /// Lock the transition would take.
+    pub lock: TransitionLock,
+    /// What happens to commands that can't interrupt it.
+    pub on_interrupt: InterruptBehavior,
+    /// Samples from start to finish, first and last included.
+    pub points: heapless::Vec<PreviewPoint, MAX_PREVIEW_POINTS>,
+}
+
+/// Sample `strategy` moving from `from` to `to` every `resolution_ms`
+///
+/// Pure: nothing is started. The spacing widens when needed to fit
+/// [`MAX_PREVIEW_POINTS`], and a `resolution_ms` of zero picks the finest
+/// spacing that fits. Named presets must be resolved first; an unresolved
+/// preset previews as an immediate change.
+///
+/// ```rust
+/// use rs_trainz::transition::preview;
+/// use rs_trainz::{AnyStrategy, Speed};
+/// use rs_trainz::traits::Linear;
+///
+/// let strategy = AnyStrategy::new(Linear::new(1000));
+/// let curve = preview(&strategy, Speed::ZERO, Speed::FULL, 250);
+///
+/// assert_eq!(curve.duration_ms, Some(1000));
+/// assert_eq!(curve.points.len(), 5);
+/// assert!((curve.points[2].speed.get() - 0.5).abs() < 0.01);
+/// ```
+pub fn preview(
+    strategy: &AnyStrategy,
+    from: Speed,
+    to: Speed,
+    resolution_ms: u64,
+) -> TransitionPreview {
+    let (from_v, to_v) = (to_value(from), to_value(to));
+    let duration_ms = strategy
+        .duration_for(from.get(), to.get())
+        .or_else(|| completion_ms(strategy, from_v, to_v));
+
+    let span = duration_ms.unwrap_or(PREVIEW_HORIZON_MS);
+    let fit = (span + MAX_PREVIEW_POINTS as u64 - 2) / (MAX_PREVIEW_POINTS as u64 - 1);
+    let step = resolution_ms.max(fit).max(1);
+
+    let mut points = heapless::Vec::new();
+    let mut sample = |elapsed_ms| {
+        let (value, _) = interpolate(strategy, from_v, to_v, elapsed_ms);
+        let _ = points.push(PreviewPoint {
+            elapsed_ms,
+            speed: to_speed(value),
+        });
+    };
+    let mut elapsed = 0;
+    while elapsed < span {
+        sample(elapsed);
+        elapsed += step;
+    }
+    sample(span);
+
+    TransitionPreview {
+        from,
+        to,
+        duration_ms,
+        lock: strategy.lock(),
+        on_interrupt: strategy.on_interrupt(),
+        points,
+    }
+}
+
+/// First millisecond at which `strategy` reports completion, if within the horizon
+fn completion_ms(strategy: &AnyStrategy, from: Value, to: Value) -> Option<u64> {
+    let complete = |elapsed_ms| interpolate(strategy, from, to, elapsed_ms).1;
+    if complete(0) {
+        return Some(0);
+    }
+    if !complete(PREVIEW_HORIZON_MS) {
+        return None;
+    }
+    let (mut running, mut done) = (0, PREVIEW_HORIZON_MS);
+    while done - running > 1 {
+        let mid = running + (done - running) / 2;
+        if complete(mid) {
+            done = mid;
+        } else {
+            running = mid;
+        }
+    }
+    Some(done)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::Sequence;
+    use crate::traits::{EaseInOut, Immediate, Linear};
+
+    fn immediate() -> AnyStrategy {
+        AnyStrategy::new(Immediate)
+    }
+
+    fn linear(ms: u64) -> AnyStrategy {
+        AnyStrategy::new(Linear::new(ms))
+    }
+
+    fn linear_locked(ms: u64) -> AnyStrategy {
+        AnyStrategy::new(Linear::locked(ms))
+    }
 
     fn linear_source_locked(ms: u64) -> AnyStrategy {
         AnyStrategy::new(Linear::source_locked(ms))
@@ -452,55 +1372,73 @@ mod tests {
     // === Basic Operations ===
     #[test]
     fn new_starts_at_initial_value() {
-        let tm = TransitionManager::new(0.5);
-        assert!((tm.current() - 0.5).abs() < 0.001);
+        let tm = TransitionManager::new(Speed::literal(0.5));
+        assert!((tm.current().get() - 0.5).abs() < 0.001);
         assert!(!tm.is_transitioning());
         assert!(tm.target().is_none());
     }
 
     #[test]
     fn start_immediate_transition() {
-        let mut tm = TransitionManager::new(0.0);
-        let result = tm.try_start(1.0, immediate(), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let result = tm.try_start(
+            Speed::literal(1.0),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         assert!(matches!(result, TransitionResult::Started));
         assert!(tm.is_transitioning());
 
         // Update should complete immediately
         let (val, complete) = tm.update(0);
-        assert!((val - 1.0).abs() < 0.001);
+        assert!((val.get() - 1.0).abs() < 0.001);
         assert!(complete);
         assert!(!tm.is_transitioning());
     }
 
     #[test]
     fn start_linear_transition() {
-        let mut tm = TransitionManager::new(0.0);
-        let result = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let result = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         assert!(matches!(result, TransitionResult::Started));
         assert!(tm.is_transitioning());
-        assert!((tm.target().unwrap() - 1.0).abs() < 0.001);
+        assert!((tm.target().unwrap().get() - 1.0).abs() < 0.001);
     }
 
     #[test]
     fn linear_transition_interpolates_correctly() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         // At t=0
         let (val, complete) = tm.update(0);
-        assert!((val - 0.0).abs() < 0.01);
+        assert!((val.get() - 0.0).abs() < 0.01);
         assert!(!complete);
 
         // At t=500
         let (val, complete) = tm.update(500);
-        assert!((val - 0.5).abs() < 0.01);
+        assert!((val.get() - 0.5).abs() < 0.01);
         assert!(!complete);
 
         // At t=1000
         let (val, complete) = tm.update(1000);
-        assert!((val - 1.0).abs() < 0.01);
+        assert!((val.get() - 1.0).abs() < 0.01);
         assert!(complete);
         assert!(!tm.is_transitioning());
     }
@@ -508,64 +1446,112 @@ mod tests {
     // === E-stop Handling ===
     #[test]
     fn estop_interrupts_any_transition() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear_locked(5000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(5000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         // E-stop should interrupt even hard-locked transitions
-        let result = tm.try_start(0.0, immediate(), CommandSource::Mqtt, true, 100);
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Mqtt,
+            true,
+            100,
+        );
 
         assert!(
-            matches!(result, TransitionResult::Interrupted { previous_target } if (previous_target - 1.0).abs() < 0.001)
+            matches!(result, TransitionResult::Interrupted { previous_target } if (previous_target.get() - 1.0).abs() < 0.001)
         );
         assert!(!tm.is_transitioning());
-        assert!((tm.current() - 0.0).abs() < 0.001);
+        assert!((tm.current().get() - 0.0).abs() < 0.001);
     }
 
     #[test]
     fn estop_clears_queued() {
-        let mut tm = TransitionManager::new(0.5);
-        let _ = tm.try_start(0.0, arrival(1000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         // Queue a command
-        let result = tm.try_start(0.8, linear(500), CommandSource::Mqtt, false, 100);
-        assert!(matches!(result, TransitionResult::Queued));
+        let result = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        assert!(matches!(result, TransitionResult::Queued { .. }));
 
         // E-stop should clear both active and queued
-        let _ = tm.try_start(0.0, immediate(), CommandSource::Mqtt, true, 200);
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Mqtt,
+            true,
+            200,
+        );
 
         // Complete and verify no queued transition executes
         let _ = tm.update(200);
         let _ = tm.update(201);
         assert!(!tm.is_transitioning());
-        assert!((tm.current() - 0.0).abs() < 0.001);
+        assert!((tm.current().get() - 0.0).abs() < 0.001);
     }
 
     #[test]
     fn estop_when_no_active_transition() {
-        let mut tm = TransitionManager::new(0.5);
-        let result = tm.try_start(0.0, immediate(), CommandSource::Mqtt, true, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Mqtt,
+            true,
+            0,
+        );
 
         // Should just start (not interrupted)
         assert!(matches!(result, TransitionResult::Started));
-        assert!((tm.current() - 0.0).abs() < 0.001);
+        assert!((tm.current().get() - 0.0).abs() < 0.001);
     }
 
     // === Lock Types ===
     #[test]
     fn no_lock_allows_interruption() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         // Lower priority can interrupt no-lock transition
-        let result = tm.try_start(0.5, immediate(), CommandSource::Mqtt, false, 100);
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
         assert!(matches!(result, TransitionResult::Interrupted { .. }));
     }
 
     #[test]
     fn source_lock_allows_same_or_higher_priority() {
-        let mut tm = TransitionManager::new(0.0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
         let _ = tm.try_start(
-            1.0,
+            Speed::literal(1.0),
             linear_source_locked(1000),
             CommandSource::WebApi,
             false,
@@ -573,15 +1559,21 @@ mod tests {
         );
 
         // Higher priority can interrupt
-        let result = tm.try_start(0.5, immediate(), CommandSource::Physical, false, 100);
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            100,
+        );
         assert!(matches!(result, TransitionResult::Interrupted { .. }));
     }
 
     #[test]
     fn source_lock_blocks_lower_priority() {
-        let mut tm = TransitionManager::new(0.0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
         let _ = tm.try_start(
-            1.0,
+            Speed::literal(1.0),
             linear_source_locked(1000),
             CommandSource::Physical,
             false,
@@ -589,22 +1581,120 @@ mod tests {
         );
 
         // Lower priority is blocked (Replace becomes LowerPriority rejection)
-        let result = tm.try_start(0.5, immediate(), CommandSource::Mqtt, false, 100);
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        assert!(matches!(
+            result,
+            TransitionResult::Rejected {
+                reason: RejectReason::LowerPriority
+            }
+        ));
+    }
+
+    #[test]
+    fn source_lock_holds_off_other_clients_of_same_source() {
+        let alice = ClientId::new("alice").unwrap();
+        let bob = ClientId::new("bob").unwrap();
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start_as(
+            Speed::literal(1.0),
+            linear_source_locked(1000),
+            CommandSource::WebApi,
+            Some(alice),
+            false,
+            0,
+        );
+        assert_eq!(tm.lock_status(0).unwrap().client, Some(alice));
+
+        // Another client of the same source is blocked
+        let result = tm.try_start_as(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::WebApi,
+            Some(bob),
+            false,
+            100,
+        );
+        assert!(matches!(result, TransitionResult::Rejected { .. }));
+
+        // The owner, or a higher priority source, can still interrupt
+        let result = tm.try_start_as(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::WebApi,
+            Some(alice),
+            false,
+            200,
+        );
+        assert!(matches!(result, TransitionResult::Interrupted { .. }));
+    }
+
+    #[test]
+    fn source_lock_follows_configured_priorities() {
+        let priorities = SourcePriorities::default()
+            .with_rank(CommandSource::Mqtt, 2)
+            .with_rank(CommandSource::WebLocal, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0)).with_priorities(priorities);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_source_locked(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::WebLocal,
+            false,
+            100,
+        );
         assert!(matches!(
             result,
             TransitionResult::Rejected {
                 reason: RejectReason::LowerPriority
             }
         ));
+        assert_eq!(
+            tm.pause(CommandSource::WebLocal, None, 100),
+            Err(RejectReason::LowerPriority)
+        );
+
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            200,
+        );
+        assert!(matches!(result, TransitionResult::Interrupted { .. }));
     }
 
     #[test]
     fn hard_lock_blocks_all_except_estop() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear_locked(1000), CommandSource::Mqtt, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
 
         // Even higher priority is blocked
-        let result = tm.try_start(0.5, immediate(), CommandSource::Emergency, false, 100);
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Emergency,
+            false,
+            100,
+        );
         assert!(matches!(
             result,
             TransitionResult::Rejected {
@@ -615,37 +1705,81 @@ mod tests {
 
     #[test]
     fn hard_lock_allows_estop() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear_locked(1000), CommandSource::Mqtt, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
 
         // E-stop still works
-        let result = tm.try_start(0.0, immediate(), CommandSource::Mqtt, true, 100);
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Mqtt,
+            true,
+            100,
+        );
         assert!(matches!(result, TransitionResult::Interrupted { .. }));
     }
 
     // === Queuing Behavior ===
     #[test]
     fn queue_behavior_queues_command() {
-        let mut tm = TransitionManager::new(0.5);
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
         // arrival() has Source lock + Queue interrupt behavior
-        let _ = tm.try_start(0.0, arrival(1000), CommandSource::Physical, false, 0);
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         // Lower priority command should be queued
-        let result = tm.try_start(0.8, linear(500), CommandSource::Mqtt, false, 100);
-        assert!(matches!(result, TransitionResult::Queued));
+        let result = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        assert!(matches!(result, TransitionResult::Queued { .. }));
     }
 
     #[test]
     fn queue_full_rejects() {
-        let mut tm = TransitionManager::new(0.5);
-        let _ = tm.try_start(0.0, arrival(1000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
-        // First queue succeeds
-        let result = tm.try_start(0.8, linear(500), CommandSource::Mqtt, false, 100);
-        assert!(matches!(result, TransitionResult::Queued));
+        // Queueing succeeds up to the depth
+        for i in 0..DEFAULT_QUEUE_DEPTH as u64 {
+            let result = tm.try_start(
+                Speed::literal(0.8),
+                linear(500),
+                CommandSource::Mqtt,
+                false,
+                i,
+            );
+            assert!(matches!(result, TransitionResult::Queued { .. }));
+        }
 
-        // Second queue fails
-        let result = tm.try_start(0.9, linear(500), CommandSource::Mqtt, false, 200);
+        // One more fails
+        let result = tm.try_start(
+            Speed::literal(0.9),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            200,
+        );
         assert!(matches!(
             result,
             TransitionResult::Rejected {
@@ -655,103 +1789,961 @@ mod tests {
     }
 
     #[test]
-    fn queued_starts_after_active_completes() {
-        let mut tm = TransitionManager::new(0.5);
-        let _ = tm.try_start(0.0, arrival(1000), CommandSource::Physical, false, 0);
-        let _ = tm.try_start(0.8, linear(500), CommandSource::Mqtt, false, 100);
-
-        // Complete the first transition
-        let _ = tm.update(1000);
-        assert!(!tm.is_transitioning()); // First complete
+    fn queue_depth_is_configurable() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5)).with_queue_depth::<1>();
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
-        // Next update should start queued
-        let _ = tm.update(1001);
-        assert!(tm.is_transitioning());
-        assert!((tm.target().unwrap() - 0.8).abs() < 0.001);
+        let result = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        assert!(matches!(result, TransitionResult::Queued { .. }));
+        let result = tm.try_start(
+            Speed::literal(0.9),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            200,
+        );
+        assert!(matches!(
+            result,
+            TransitionResult::Rejected {
+                reason: RejectReason::QueueFull
+            }
+        ));
     }
 
-    // === Cancel Operations ===
     #[test]
-    fn cancel_and_set() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
-        let _ = tm.try_start(0.8, linear(500), CommandSource::Physical, false, 100);
-
-        tm.cancel_and_set(0.25);
+    fn queue_runs_in_order() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            immediate(),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.4),
+            immediate(),
+            CommandSource::WebApi,
+            false,
+            200,
+        );
 
-        assert!(!tm.is_transitioning());
-        assert!((tm.current() - 0.25).abs() < 0.001);
+        let targets: Vec<f32> = tm.queued().map(|q| q.target.get()).collect();
+        assert_eq!(targets, [0.2, 0.4]);
 
-        // Queued should also be cleared
-        let _ = tm.update(0);
-        assert!(!tm.is_transitioning());
+        let _ = tm.update(1000);
+        let _ = tm.update(1001);
+        assert!((tm.current().get() - 0.2).abs() < Q16::TOLERANCE);
+        let _ = tm.update(1002);
+        assert!((tm.current().get() - 0.4).abs() < Q16::TOLERANCE);
+        assert_eq!(tm.queue_len(), 0);
     }
 
     #[test]
-    fn cancel_all() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
-
-        // Update partway
-        let _ = tm.update(500);
-        let mid_value = tm.current();
-
-        tm.cancel_all();
+    fn queued_entries_describe_commands() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let TransitionResult::Queued { id } = tm.try_start(
+            Speed::literal(0.8),
+            linear_locked(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        ) else {
+            panic!("expected queued");
+        };
 
-        assert!(!tm.is_transitioning());
-        // Value should stay where it was
-        assert!((tm.current() - mid_value).abs() < 0.001);
+        let entry = tm.queued().next().unwrap();
+        assert_eq!(entry.id, id);
+        assert_eq!(entry.target, Speed::literal(0.8));
+        assert_eq!(entry.source, CommandSource::Mqtt);
+        assert_eq!(entry.lock, TransitionLock::Hard);
+        assert_eq!(entry.duration_ms, Some(500));
     }
 
-    // === Status and Progress ===
     #[test]
-    fn lock_status_when_active() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear_locked(1000), CommandSource::Physical, false, 0);
+    fn queued_ids_are_unique() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.4),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            200,
+        );
 
-        let status = tm.lock_status().unwrap();
-        assert_eq!(status.lock, TransitionLock::Hard);
-        assert_eq!(status.source, CommandSource::Physical);
-        assert!((status.target - 1.0).abs() < 0.001);
-        assert!(!status.has_queued);
+        let ids: Vec<u32> = tm.queued().map(|q| q.id).collect();
+        assert_ne!(ids[0], ids[1]);
     }
 
     #[test]
-    fn lock_status_shows_queued() {
-        let mut tm = TransitionManager::new(0.5);
-        let _ = tm.try_start(0.0, arrival(1000), CommandSource::Physical, false, 0);
-        let _ = tm.try_start(0.8, linear(500), CommandSource::Mqtt, false, 100);
+    fn events_follow_transition_lifecycle() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let first = tm.active_id().unwrap();
+        let TransitionResult::Queued { id: second } = tm.try_start(
+            Speed::literal(0.2),
+            immediate(),
+            CommandSource::Mqtt,
+            false,
+            100,
+        ) else {
+            panic!("expected queued");
+        };
+        let TransitionResult::Queued { id: third } = tm.try_start(
+            Speed::literal(0.4),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            200,
+        ) else {
+            panic!("expected queued");
+        };
 
-        let status = tm.lock_status().unwrap();
-        assert!(status.has_queued);
+        let _ = tm.update(1000);
+        let _ = tm.update(1001);
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Physical,
+            true,
+            1002,
+        );
+
+        let events: Vec<TransitionEvent> = core::iter::from_fn(|| tm.pop_event()).collect();
+        assert_eq!(
+            events,
+            [
+                TransitionEvent::Started { id: first },
+                TransitionEvent::Completed { id: first },
+                TransitionEvent::Started { id: second },
+                TransitionEvent::Completed { id: second },
+                TransitionEvent::Interrupted { id: third },
+            ]
+        );
     }
 
     #[test]
-    fn lock_status_none_when_no_transition() {
-        let tm = TransitionManager::new(0.0);
-        assert!(tm.lock_status().is_none());
+    fn continue_as_keeps_the_first_id() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        let first = tm.active_id().unwrap();
+        let _ = tm.update(1);
+        let _ = tm.try_start(
+            Speed::literal(0.3),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            1,
+        );
+        tm.continue_as(first);
+
+        assert_eq!(tm.active_id(), Some(first));
+        let _ = tm.update(501);
+        let events: Vec<TransitionEvent> = core::iter::from_fn(|| tm.pop_event()).collect();
+        assert_eq!(
+            events,
+            [
+                TransitionEvent::Started { id: first },
+                TransitionEvent::Completed { id: first },
+            ]
+        );
     }
 
     #[test]
-    fn progress_info() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
+    fn cancel_queued_removes_entry() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.4),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            200,
+        );
+        let first = tm.queued().next().unwrap().id;
+
+        let removed = tm.cancel_queued(first, CommandSource::Mqtt, None).unwrap();
+        assert_eq!(removed.target, Speed::literal(0.2));
+        let targets: Vec<f32> = tm.queued().map(|q| q.target.get()).collect();
+        assert_eq!(targets, [0.4]);
+
+        assert_eq!(
+            tm.cancel_queued(first, CommandSource::Mqtt, None),
+            Err(RejectReason::NotQueued)
+        );
+    }
+
+    #[test]
+    fn cancel_queued_needs_priority() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            linear(500),
+            CommandSource::WebLocal,
+            false,
+            100,
+        );
+        let id = tm.queued().next().unwrap().id;
+
+        assert_eq!(
+            tm.cancel_queued(id, CommandSource::Mqtt, None),
+            Err(RejectReason::LowerPriority)
+        );
+        assert!(tm.cancel_queued(id, CommandSource::Physical, None).is_ok());
+    }
+
+    #[test]
+    fn clear_queue_keeps_higher_priority_entries() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.4),
+            linear(500),
+            CommandSource::WebLocal,
+            false,
+            200,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.6),
+            linear(500),
+            CommandSource::WebApi,
+            false,
+            300,
+        );
+
+        assert_eq!(tm.clear_queue(CommandSource::WebApi, None), 2);
+        let sources: Vec<CommandSource> = tm.queued().map(|q| q.source).collect();
+        assert_eq!(sources, [CommandSource::WebLocal]);
+
+        assert_eq!(tm.clear_queue(CommandSource::Physical, None), 1);
+        assert_eq!(tm.queue_len(), 0);
+    }
+
+    #[test]
+    fn queue_edits_hold_off_other_clients_of_same_source() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let queue = |tm: &mut TransitionManager, client, target| match tm.try_start_as(
+            Speed::literal(target),
+            linear(500),
+            CommandSource::WebApi,
+            client,
+            false,
+            100,
+        ) {
+            TransitionResult::Queued { id } => id,
+            result => panic!("not queued: {result:?}"),
+        };
+        let alices = queue(&mut tm, alice, 0.2);
+        let bobs = queue(&mut tm, bob, 0.4);
+        let _ = queue(&mut tm, alice, 0.6);
+
+        assert_eq!(
+            tm.cancel_queued(bobs, CommandSource::WebApi, alice),
+            Err(RejectReason::LowerPriority)
+        );
+        assert!(tm
+            .cancel_queued(alices, CommandSource::WebApi, alice)
+            .is_ok());
+
+        // Clearing leaves the other client's entries
+        assert_eq!(tm.clear_queue(CommandSource::WebApi, alice), 1);
+        let clients: Vec<_> = tm.queued().map(|q| q.client).collect();
+        assert_eq!(clients, [bob]);
+
+        // A higher priority source clears them all
+        assert_eq!(tm.clear_queue(CommandSource::WebLocal, alice), 1);
+        assert_eq!(tm.queue_len(), 0);
+    }
+
+    #[test]
+    fn with_queue_depth_keeps_active_transition() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.update(500);
+
+        let mut tm = tm.with_queue_depth::<8>();
+        assert!(tm.is_transitioning());
+        let (value, _) = tm.update(1000);
+        assert!((value.get() - 1.0).abs() < Q16::TOLERANCE);
+    }
+
+    #[test]
+    fn queued_starts_after_active_completes() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+
+        // Complete the first transition
+        let _ = tm.update(1000);
+        assert!(!tm.is_transitioning()); // First complete
+
+        // Next update should start queued
+        let _ = tm.update(1001);
+        assert!(tm.is_transitioning());
+        assert!((tm.target().unwrap().get() - 0.8).abs() < 0.001);
+    }
+
+    // === Pause and Resume ===
+    #[test]
+    fn pause_freezes_value_and_elapsed() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::WebApi,
+            false,
+            0,
+        );
+        let _ = tm.update(400);
+
+        assert!(tm.pause(CommandSource::WebApi, None, 400).is_ok());
+        assert!(tm.is_paused());
+        let (value, complete) = tm.update(5000);
+        assert!((value.get() - 0.4).abs() < 0.01);
+        assert!(!complete);
+        assert!(tm.is_transitioning());
+        assert_eq!(tm.rate(5000), 0.0);
+
+        let progress = tm.progress(5000).unwrap();
+        assert!(progress.paused);
+        assert_eq!(progress.elapsed_ms, 400);
+    }
+
+    #[test]
+    fn resume_continues_where_it_left_off() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::WebApi,
+            false,
+            0,
+        );
+        let _ = tm.update(400);
+        let _ = tm.pause(CommandSource::WebApi, None, 400);
+
+        assert!(tm.resume(CommandSource::WebApi, None, 2400).is_ok());
+        assert!(!tm.is_paused());
+        let (value, _) = tm.update(2700);
+        assert!((value.get() - 0.7).abs() < 0.01);
+        assert!(!tm.progress(2700).unwrap().paused);
+
+        let (value, complete) = tm.update(3000);
+        assert!((value.get() - 1.0).abs() < 0.01);
+        assert!(complete);
+    }
+
+    #[test]
+    fn pause_needs_a_transition() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        assert_eq!(
+            tm.pause(CommandSource::WebApi, None, 0),
+            Err(RejectReason::NotTransitioning)
+        );
+        assert_eq!(
+            tm.resume(CommandSource::WebApi, None, 0),
+            Err(RejectReason::NotPaused)
+        );
+
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::WebApi,
+            false,
+            0,
+        );
+        assert_eq!(
+            tm.resume(CommandSource::WebApi, None, 0),
+            Err(RejectReason::NotPaused)
+        );
+    }
+
+    #[test]
+    fn pause_respects_lock_owner() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_source_locked(1000),
+            CommandSource::WebLocal,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            tm.pause(CommandSource::Mqtt, None, 100),
+            Err(RejectReason::LowerPriority)
+        );
+        assert!(tm.pause(CommandSource::WebLocal, None, 100).is_ok());
+
+        // Unlocked transitions can be paused by anyone
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        assert!(tm.pause(CommandSource::Mqtt, None, 100).is_ok());
+    }
+
+    #[test]
+    fn pause_respects_hard_lock() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+
+        // Outranking the owner isn't enough to pause a hard lock
+        assert_eq!(
+            tm.pause(CommandSource::WebLocal, None, 100),
+            Err(RejectReason::TransitionLocked)
+        );
+        assert!(!tm.is_paused());
+        assert!(tm.pause(CommandSource::Mqtt, None, 100).is_ok());
+
+        // The force release source may pause it
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        assert!(tm.pause(CommandSource::Physical, None, 100).is_ok());
+
+        let mut tm = TransitionManager::new(Speed::literal(0.0))
+            .with_force_release_source(CommandSource::WebLocal);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        assert!(tm.pause(CommandSource::WebLocal, None, 100).is_ok());
+    }
+
+    #[test]
+    fn resume_needs_pausing_source_or_higher() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        let _ = tm.pause(CommandSource::Physical, None, 100);
+
+        assert_eq!(
+            tm.resume(CommandSource::Mqtt, None, 200),
+            Err(RejectReason::LowerPriority)
+        );
+        assert!(tm.is_paused());
+        assert!(tm.resume(CommandSource::Physical, None, 200).is_ok());
+    }
+
+    #[test]
+    fn pause_and_resume_hold_off_other_clients_of_same_source() {
+        let alice = Some(ClientId::new("alice").unwrap());
+        let bob = Some(ClientId::new("bob").unwrap());
+        for strategy in [linear_source_locked(1000), linear_locked(1000)] {
+            let mut tm = TransitionManager::new(Speed::literal(0.0));
+            let _ = tm.try_start_as(
+                Speed::literal(1.0),
+                strategy,
+                CommandSource::WebApi,
+                alice,
+                false,
+                0,
+            );
+
+            assert!(tm.pause(CommandSource::WebApi, bob, 100).is_err());
+            assert!(!tm.is_paused());
+            assert!(tm.pause(CommandSource::WebApi, alice, 100).is_ok());
+
+            assert_eq!(
+                tm.resume(CommandSource::WebApi, bob, 200),
+                Err(RejectReason::LowerPriority)
+            );
+            assert!(tm.is_paused());
+            assert!(tm.resume(CommandSource::WebApi, alice, 200).is_ok());
+        }
+
+        // Unlocked transitions only check who paused them
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start_as(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::WebApi,
+            alice,
+            false,
+            0,
+        );
+        assert!(tm.pause(CommandSource::WebApi, bob, 100).is_ok());
+        assert!(tm.resume(CommandSource::WebApi, alice, 200).is_err());
+        assert!(tm.resume(CommandSource::WebApi, bob, 200).is_ok());
+    }
+
+    #[test]
+    fn queue_waits_while_paused() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+        let _ = tm.pause(CommandSource::Physical, None, 200);
+
+        let _ = tm.update(5000);
+        let _ = tm.update(5001);
+        assert_eq!(tm.queue_len(), 1);
+        assert_eq!(tm.target(), Some(Speed::literal(0.0)));
+    }
+
+    #[test]
+    fn new_command_replaces_paused_transition() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::WebApi,
+            false,
+            0,
+        );
+        let _ = tm.update(500);
+        let _ = tm.pause(CommandSource::WebApi, None, 500);
+
+        let result = tm.try_start(
+            Speed::literal(0.2),
+            immediate(),
+            CommandSource::WebApi,
+            false,
+            800,
+        );
+        assert!(matches!(result, TransitionResult::Interrupted { .. }));
+        assert!(!tm.is_paused());
+        let (value, _) = tm.update(800);
+        assert!((value.get() - 0.2).abs() < 0.01);
+    }
+
+    // === Cancel Operations ===
+    #[test]
+    fn cancel_and_set() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Physical,
+            false,
+            100,
+        );
+
+        tm.cancel_and_set(Speed::literal(0.25));
+
+        assert!(!tm.is_transitioning());
+        assert!((tm.current().get() - 0.25).abs() < 0.001);
+
+        // Queued should also be cleared
+        let _ = tm.update(0);
+        assert!(!tm.is_transitioning());
+    }
+
+    #[test]
+    fn cancel_all() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+
+        // Update partway
+        let _ = tm.update(500);
+        let mid_value = tm.current();
+
+        tm.cancel_all();
+
+        assert!(!tm.is_transitioning());
+        // Value should stay where it was
+        assert_eq!(tm.current(), mid_value);
+    }
+
+    // === Status and Progress ===
+    #[test]
+    fn lock_status_when_active() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+
+        let status = tm.lock_status(0).unwrap();
+        assert_eq!(status.lock, TransitionLock::Hard);
+        assert_eq!(status.source, CommandSource::Physical);
+        assert!((status.target.get() - 1.0).abs() < 0.001);
+        assert!(!status.has_queued);
+        assert_eq!(status.remaining_ms, None);
+        assert_eq!(status.released, None);
+    }
+
+    #[test]
+    fn lock_status_shows_queued() {
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.try_start(
+            Speed::literal(0.8),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            100,
+        );
+
+        let status = tm.lock_status(100).unwrap();
+        assert!(status.has_queued);
+    }
+
+    #[test]
+    fn lock_status_none_when_no_transition() {
+        let tm = TransitionManager::new(Speed::literal(0.0));
+        assert!(tm.lock_status(0).is_none());
+    }
+
+    // === Lock Timeouts and Release ===
+    #[test]
+    fn lock_expires_after_max_duration() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0)).with_max_lock_duration(1000);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(60_000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+
+        let status = tm.lock_status(400).unwrap();
+        assert_eq!(status.lock, TransitionLock::Hard);
+        assert_eq!(status.remaining_ms, Some(600));
+
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            linear(500),
+            CommandSource::WebApi,
+            false,
+            900,
+        );
+        assert!(matches!(result, TransitionResult::Rejected { .. }));
+
+        let status = tm.lock_status(1000).unwrap();
+        assert_eq!(status.lock, TransitionLock::None);
+        assert_eq!(status.remaining_ms, None);
+        assert_eq!(status.released, Some(LockRelease::Expired));
+
+        // Even a lower priority source can now interrupt
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            1000,
+        );
+        assert!(matches!(result, TransitionResult::Interrupted { .. }));
+    }
+
+    #[test]
+    fn lock_expiry_counts_paused_time() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0)).with_max_lock_duration(1000);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(5000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.pause(CommandSource::Physical, None, 200);
+        let _ = tm.resume(CommandSource::Physical, None, 2000);
+
+        let status = tm.lock_status(2000).unwrap();
+        assert_eq!(status.released, Some(LockRelease::Expired));
+    }
+
+    #[test]
+    fn unlocked_transition_never_expires() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0)).with_max_lock_duration(100);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+
+        let status = tm.lock_status(500).unwrap();
+        assert_eq!(status.remaining_ms, None);
+        assert_eq!(status.released, None);
+    }
+
+    #[test]
+    fn release_lock_unlocks_running_transition() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(60_000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+
+        assert!(tm.release_lock(CommandSource::Physical, 100).is_ok());
+        let status = tm.lock_status(100).unwrap();
+        assert_eq!(status.lock, TransitionLock::None);
+        assert_eq!(
+            status.released,
+            Some(LockRelease::Forced {
+                by: CommandSource::Physical
+            })
+        );
+        assert!(tm.is_transitioning());
+
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            linear(500),
+            CommandSource::Mqtt,
+            false,
+            200,
+        );
+        assert!(matches!(result, TransitionResult::Interrupted { .. }));
+    }
+
+    #[test]
+    fn release_lock_needs_a_lock() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        assert_eq!(
+            tm.release_lock(CommandSource::Physical, 0),
+            Err(RejectReason::NotLocked)
+        );
+
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        assert_eq!(
+            tm.release_lock(CommandSource::Physical, 100),
+            Err(RejectReason::NotLocked)
+        );
+    }
+
+    #[test]
+    fn max_lock_duration_survives_queue_depth_change() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0))
+            .with_max_lock_duration(1000)
+            .with_queue_depth::<2>();
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear_locked(5000),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        assert_eq!(tm.lock_status(0).unwrap().remaining_ms, Some(1000));
+    }
+
+    #[test]
+    fn progress_info() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
 
         // Update to 500ms
         let _ = tm.update(500);
 
         let progress = tm.progress(500).unwrap();
-        assert!((progress.from - 0.0).abs() < 0.001);
-        assert!((progress.to - 1.0).abs() < 0.001);
-        assert!((progress.current - 0.5).abs() < 0.01);
+        assert!((progress.from.get() - 0.0).abs() < 0.001);
+        assert!((progress.to.get() - 1.0).abs() < 0.001);
+        assert!((progress.current.get() - 0.5).abs() < 0.01);
         assert_eq!(progress.elapsed_ms, 500);
         assert_eq!(progress.estimated_total_ms, Some(1000));
     }
 
+    #[test]
+    fn progress_reports_s_curve_duration() {
+        let mut tm = TransitionManager::new(Speed::literal(0.2));
+        let strategy = crate::SCurve::new(0.5, 0.25, 1.0).into();
+        let _ = tm.try_start(
+            Speed::literal(0.7),
+            strategy,
+            CommandSource::WebApi,
+            false,
+            0,
+        );
+
+        let progress = tm.progress(0).unwrap();
+        assert_eq!(progress.estimated_total_ms, Some(1500));
+    }
+
     #[test]
     fn progress_percent() {
-        let mut tm = TransitionManager::new(0.0);
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
         let _ = tm.update(250);
 
         let progress = tm.progress(250).unwrap();
@@ -762,11 +2754,13 @@ mod tests {
     #[test]
     fn progress_percent_zero_duration() {
         let progress = TransitionProgress {
-            from: 0.0,
-            to: 1.0,
-            current: 1.0,
+            from: Speed::ZERO,
+            to: Speed::FULL,
+            current: Speed::FULL,
             elapsed_ms: 0,
             estimated_total_ms: Some(0),
+            segment: None,
+            paused: false,
         };
         assert!((progress.percent().unwrap() - 1.0).abs() < 0.001);
     }
@@ -774,66 +2768,160 @@ mod tests {
     #[test]
     fn progress_percent_none_for_unknown_duration() {
         let progress = TransitionProgress {
-            from: 0.0,
-            to: 1.0,
-            current: 0.5,
+            from: Speed::ZERO,
+            to: Speed::FULL,
+            current: Speed::literal(0.5),
             elapsed_ms: 100,
             estimated_total_ms: None,
+            segment: None,
+            paused: false,
         };
         assert!(progress.percent().is_none());
     }
 
     #[test]
     fn progress_none_when_no_transition() {
-        let tm = TransitionManager::new(0.0);
+        let tm = TransitionManager::new(Speed::literal(0.0));
         assert!(tm.progress(0).is_none());
     }
 
+    #[test]
+    fn progress_reports_sequence_segment() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let sequence: Sequence = Sequence::new()
+            .then(Speed::literal(0.3), Linear::new(2000))
+            .hold(5000)
+            .finish(Linear::new(4000));
+        let _ = tm.try_start(
+            Speed::literal(0.7),
+            AnyStrategy::new(sequence),
+            CommandSource::WebApi,
+            false,
+            0,
+        );
+        let _ = tm.update(3000);
+
+        let progress = tm.progress(3000).unwrap();
+        assert_eq!(progress.estimated_total_ms, Some(11_000));
+        let segment = progress.segment.unwrap();
+        assert_eq!((segment.index, segment.count), (1, 3));
+        assert_eq!((segment.elapsed_ms, segment.duration_ms), (1000, 5000));
+        assert!((progress.current.get() - 0.3).abs() < Q16::TOLERANCE);
+
+        assert!(tm.progress(0).is_some());
+        let _ = tm.update(11_000);
+        assert!(tm.progress(11_000).is_none());
+    }
+
+    #[test]
+    fn progress_has_no_segment_for_single_strategy() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        assert!(tm.progress(500).unwrap().segment.is_none());
+    }
+
+    #[test]
+    fn locked_sequence_rejects_interrupt_while_holding() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let sequence: Sequence = Sequence::locked()
+            .then(Speed::literal(0.3), Linear::new(1000))
+            .hold(5000)
+            .finish(Linear::new(1000));
+        let _ = tm.try_start(
+            Speed::literal(0.7),
+            AnyStrategy::new(sequence),
+            CommandSource::Mqtt,
+            false,
+            0,
+        );
+        let _ = tm.update(3000);
+
+        let result = tm.try_start(
+            Speed::literal(0.0),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            3000,
+        );
+        assert!(matches!(result, TransitionResult::Rejected { .. }));
+        assert!(tm.lock_status(3000).is_some());
+    }
+
     // === Edge Cases ===
     #[test]
     fn update_with_no_active_transition() {
-        let mut tm = TransitionManager::new(0.5);
+        let mut tm = TransitionManager::new(Speed::literal(0.5));
         let (val, complete) = tm.update(100);
-        assert!((val - 0.5).abs() < 0.001);
+        assert!((val.get() - 0.5).abs() < 0.001);
         assert!(complete);
     }
 
     #[test]
     fn transition_continues_from_current_value() {
-        let mut tm = TransitionManager::new(0.0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
 
         // First transition
-        let _ = tm.try_start(0.5, immediate(), CommandSource::Physical, false, 0);
+        let _ = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            0,
+        );
         let _ = tm.update(0);
 
         // Second transition should start from 0.5
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 100);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            100,
+        );
 
         let progress = tm.progress(100).unwrap();
-        assert!((progress.from - 0.5).abs() < 0.001);
+        assert!((progress.from.get() - 0.5).abs() < 0.001);
     }
 
     #[test]
     fn interrupt_updates_from_value() {
-        let mut tm = TransitionManager::new(0.0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
 
         // Start long transition
-        let _ = tm.try_start(1.0, linear(1000), CommandSource::Physical, false, 0);
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
         let _ = tm.update(500); // At 0.5
 
         // Interrupt with new transition
-        let _ = tm.try_start(0.0, linear(500), CommandSource::Physical, false, 500);
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            linear(500),
+            CommandSource::Physical,
+            false,
+            500,
+        );
 
         let progress = tm.progress(500).unwrap();
-        assert!((progress.from - 0.5).abs() < 0.01);
-        assert!((progress.to - 0.0).abs() < 0.001);
+        assert!((progress.from.get() - 0.5).abs() < 0.01);
+        assert!((progress.to.get() - 0.0).abs() < 0.001);
     }
 
     #[test]
     fn same_source_can_interrupt_source_locked() {
-        let mut tm = TransitionManager::new(0.0);
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
         let _ = tm.try_start(
-            1.0,
+            Speed::literal(1.0),
             linear_source_locked(1000),
             CommandSource::Physical,
             false,
@@ -841,7 +2929,328 @@ mod tests {
         );
 
         // Same source can interrupt source-locked transition
-        let result = tm.try_start(0.5, immediate(), CommandSource::Physical, false, 100);
+        let result = tm.try_start(
+            Speed::literal(0.5),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            100,
+        );
         assert!(matches!(result, TransitionResult::Interrupted { .. }));
     }
+
+    // === Rate Continuity ===
+    /// Largest change in slope between consecutive 20ms ticks
+    fn max_slope_change(tm: &mut TransitionManager, from_ms: u64, to_ms: u64) -> f32 {
+        let mut last = tm.update(from_ms).0.get();
+        let mut last_slope = tm.rate(from_ms);
+        let mut worst: f32 = 0.0;
+        for now in (from_ms + 20..=to_ms).step_by(20) {
+            let value = tm.update(now).0.get();
+            let slope = (value - last) / 0.02;
+            worst = worst.max((slope - last_slope).abs());
+            last = value;
+            last_slope = slope;
+        }
+        worst
+    }
+
+    #[test]
+    fn interrupting_ease_in_out_keeps_rate_continuous() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let ease = AnyStrategy::new(EaseInOut::new(2000));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            ease.clone(),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let before = max_slope_change(&mut tm, 0, 1000);
+
+        let rate = tm.rate(1000);
+        assert!((rate - 0.75).abs() < 0.01);
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            ease,
+            CommandSource::Physical,
+            false,
+            1000,
+        );
+        assert!((tm.rate(1000) - rate).abs() < 0.001);
+
+        // Slope changes no faster across the interruption than within a ramp
+        let after = max_slope_change(&mut tm, 1000, 3200);
+        assert!(before < 0.1);
+        assert!(after < 0.1, "slope jumped by {after}");
+        assert!((tm.current().get() - 0.2).abs() <= Q16::TOLERANCE);
+        assert!(!tm.is_transitioning());
+    }
+
+    #[test]
+    fn interrupting_linear_keeps_rate_continuous() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.update(500);
+
+        // Reverse towards zero: the train keeps climbing briefly, then turns
+        let _ = tm.try_start(
+            Speed::literal(0.0),
+            arrival(2000),
+            CommandSource::Physical,
+            false,
+            500,
+        );
+        assert!((tm.rate(500) - 1.0).abs() < 0.001);
+        let (next, _) = tm.update(520);
+        assert!(next.get() > 0.5);
+        assert!(max_slope_change(&mut tm, 520, 2500) < 0.1);
+        assert_eq!(tm.current(), Speed::ZERO);
+    }
+
+    #[test]
+    fn interrupting_never_passes_target() {
+        // Re-sent mid-ramp with a slower strategy
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(0.5),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.update(900);
+        let _ = tm.try_start(
+            Speed::literal(0.5),
+            linear(3000),
+            CommandSource::Physical,
+            false,
+            900,
+        );
+        for now in (900..=4000).step_by(20) {
+            let value = tm.update(now).0.get();
+            assert!(value <= 0.5 + Q16::TOLERANCE, "{value} at {now}ms");
+        }
+
+        // The current value commanded while ramping
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.update(500);
+        let _ = tm.try_start(
+            Speed::literal(0.5),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            500,
+        );
+        for now in (500..=2000).step_by(20) {
+            let value = tm.update(now).0.get();
+            assert!(value <= 0.5 + Q16::TOLERANCE, "{value} at {now}ms");
+        }
+        assert_eq!(tm.current(), Speed::literal(0.5));
+    }
+
+    #[test]
+    fn interrupting_with_immediate_still_jumps() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.update(500);
+
+        let _ = tm.try_start(
+            Speed::literal(0.2),
+            immediate(),
+            CommandSource::Physical,
+            false,
+            500,
+        );
+        let (value, done) = tm.update(500);
+        assert!((value.get() - 0.2).abs() <= Q16::TOLERANCE);
+        assert!(done);
+    }
+
+    #[test]
+    fn blend_outlasts_unknown_duration_strategy() {
+        let mut tm = TransitionManager::new(Speed::literal(0.0));
+        let _ = tm.try_start(
+            Speed::literal(1.0),
+            linear(1000),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = tm.update(500);
+
+        let momentum = AnyStrategy::new(crate::Momentum::responsive());
+        let _ = tm.try_start(
+            Speed::literal(0.6),
+            momentum,
+            CommandSource::Physical,
+            false,
+            500,
+        );
+        let (_, done) = tm.update(800);
+        assert!(!done);
+        let (value, done) = tm.update(500 + crate::traits::RATE_BLEND_MS);
+        assert!(done);
+        assert!((value.get() - 0.6).abs() <= Q16::TOLERANCE);
+    }
+
+    #[test]
+    fn rate_is_zero_when_idle() {
+        let mut tm = TransitionManager::new(Speed::literal(0.4));
+        assert_eq!(tm.rate(0), 0.0);
+
+        let _ = tm.try_start(
+            Speed::literal(0.8),
+            linear(400),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        assert!((tm.rate(100) - 1.0).abs() < 0.001);
+        let _ = tm.update(400);
+        assert_eq!(tm.rate(400), 0.0);
+    }
+
+    // === Preview ===
+    #[test]
+    fn preview_samples_at_resolution() {
+        let curve = preview(&linear(1000), Speed::literal(0.2), Speed::literal(0.6), 100);
+        assert_eq!(curve.from, Speed::literal(0.2));
+        assert_eq!(curve.to, Speed::literal(0.6));
+        assert_eq!(curve.duration_ms, Some(1000));
+        assert_eq!(curve.points.len(), 11);
+        assert_eq!(curve.points[0].elapsed_ms, 0);
+        assert!((curve.points[0].speed.get() - 0.2).abs() < 0.01);
+        assert!((curve.points[5].speed.get() - 0.4).abs() < 0.01);
+        assert_eq!(curve.points[10].elapsed_ms, 1000);
+        assert!((curve.points[10].speed.get() - 0.6).abs() < 0.01);
+    }
+
+    #[test]
+    fn preview_includes_last_point_off_grid() {
+        let curve = preview(&linear(1000), Speed::literal(0.0), Speed::literal(1.0), 300);
+        let times: Vec<u64> = curve.points.iter().map(|p| p.elapsed_ms).collect();
+        assert_eq!(times, [0, 300, 600, 900, 1000]);
+    }
+
+    #[test]
+    fn preview_reports_lock_metadata() {
+        let curve = preview(
+            &arrival(2000),
+            Speed::literal(0.8),
+            Speed::literal(0.0),
+            500,
+        );
+        assert_eq!(curve.lock, TransitionLock::Source);
+        assert_eq!(curve.on_interrupt, InterruptBehavior::Queue);
+
+        let curve = preview(&linear(2000), Speed::literal(0.0), Speed::literal(0.8), 500);
+        assert_eq!(curve.lock, TransitionLock::None);
+        assert_eq!(curve.on_interrupt, InterruptBehavior::Replace);
+    }
+
+    #[test]
+    fn preview_finds_momentum_duration() {
+        let momentum = AnyStrategy::new(crate::Momentum::new(0.5, 0.25));
+        assert_eq!(momentum.duration_for(0.0, 1.0), None);
+
+        let curve = preview(&momentum, Speed::literal(0.0), Speed::literal(1.0), 0);
+        let duration = curve.duration_ms.unwrap();
+        assert!(momentum.interpolate(0.0, 1.0, duration).1);
+        assert!(!momentum.interpolate(0.0, 1.0, duration - 1).1);
+        let last = curve.points.last().unwrap();
+        assert_eq!(last.elapsed_ms, duration);
+        assert!((last.speed.get() - 1.0).abs() < 0.01);
+
+        // Further to go takes longer
+        let short = preview(&momentum, Speed::literal(0.0), Speed::literal(0.5), 0);
+        assert!(short.duration_ms.unwrap() < duration);
+    }
+
+    #[test]
+    fn preview_unfinished_strategy_has_no_duration() {
+        let stalled = AnyStrategy::new(crate::Momentum::new(0.0, 0.0));
+        let curve = preview(&stalled, Speed::literal(0.0), Speed::literal(1.0), 0);
+        assert_eq!(curve.duration_ms, None);
+        assert_eq!(curve.points.last().unwrap().elapsed_ms, PREVIEW_HORIZON_MS);
+    }
+
+    #[test]
+    fn preview_caps_point_count() {
+        let curve = preview(&linear(60_000), Speed::literal(0.0), Speed::literal(1.0), 1);
+        assert!(curve.points.len() <= MAX_PREVIEW_POINTS);
+        assert_eq!(curve.points.last().unwrap().elapsed_ms, 60_000);
+
+        let spacing = curve.points[1].elapsed_ms;
+        assert!(curve
+            .points
+            .windows(2)
+            .all(|w| w[1].elapsed_ms - w[0].elapsed_ms <= spacing));
+    }
+
+    #[test]
+    fn preview_immediate_is_single_point() {
+        let curve = preview(&immediate(), Speed::literal(0.0), Speed::literal(0.7), 100);
+        assert_eq!(curve.duration_ms, Some(0));
+        assert_eq!(curve.points.len(), 1);
+        assert!((curve.points[0].speed.get() - 0.7).abs() < 0.01);
+    }
+
+    #[test]
+    fn preview_does_not_start_anything() {
+        let tm = TransitionManager::new(Speed::literal(0.3));
+        let _ = preview(&linear(1000), tm.current(), Speed::literal(1.0), 100);
+        assert!(!tm.is_transitioning());
+        assert!((tm.current().get() - 0.3).abs() < 0.001);
+    }
+
+    // === Fixed Point ===
+    #[test]
+    fn update_q16_tracks_update() {
+        let mut float_tm = TransitionManager::new(Speed::literal(0.2));
+        let mut fixed_tm = TransitionManager::new(Speed::literal(0.2));
+        let strategy = AnyStrategy::new(EaseInOut::new(1000));
+        let _ = float_tm.try_start(
+            Speed::literal(0.9),
+            strategy.clone(),
+            CommandSource::Physical,
+            false,
+            0,
+        );
+        let _ = fixed_tm.try_start(
+            Speed::literal(0.9),
+            strategy,
+            CommandSource::Physical,
+            false,
+            0,
+        );
+
+        for now in (0..=1200).step_by(20) {
+            let (float, float_done) = float_tm.update(now);
+            let (fixed, fixed_done) = fixed_tm.update_q16(now);
+            assert!((float.get() - fixed.to_f32()).abs() <= Q16::TOLERANCE);
+            assert_eq!(float_done, fixed_done);
+            assert_eq!(fixed_tm.current_q16(), fixed);
+        }
+        assert_eq!(fixed_tm.current_q16(), Q16::from_f32(0.9));
+    }
 }
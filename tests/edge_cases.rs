@@ -1,8 +1,8 @@
 //! Edge case and boundary condition tests for the throttle controller
 
 use rs_trainz::{
-    hal::MockMotor, CommandOutcome, CommandSource, Direction, EaseInOut, Linear, Momentum,
-    ThrottleCommand, ThrottleCommandDyn, ThrottleController, TransitionResult,
+    hal::MockMotor, CommandOutcome, CommandSource, Direction, EaseInOut, Linear, Momentum, Speed,
+    SpeedError, ThrottleCommand, ThrottleCommandDyn, ThrottleController, TransitionResult,
 };
 
 // ============================================================================
@@ -15,13 +15,13 @@ fn speed_at_zero_boundary() {
     let mut controller = ThrottleController::new(motor);
 
     // Set speed to exactly 0
-    let cmd = ThrottleCommand::speed_immediate(0.0);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.0));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
 
-    assert!((controller.current_speed() - 0.0).abs() < 0.001);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.001);
 }
 
 #[test]
@@ -30,45 +30,26 @@ fn speed_at_one_boundary() {
     let mut controller = ThrottleController::new(motor);
 
     // Set speed to exactly 1.0
-    let cmd = ThrottleCommand::speed_immediate(1.0);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(1.0));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
 
-    assert!((controller.current_speed() - 1.0).abs() < 0.001);
+    assert!((controller.current_speed().get() - 1.0).abs() < 0.001);
 }
 
 #[test]
-fn speed_clamped_above_one() {
-    let motor = MockMotor::new();
-    let mut controller = ThrottleController::new(motor);
-
-    // Try to set speed above 1.0
-    let cmd = ThrottleCommand::speed_immediate(1.5);
-    controller
-        .apply_command(cmd.into(), CommandSource::Physical, 0)
-        .unwrap();
-    controller.update(0).unwrap();
-
-    // Should be clamped to 1.0
-    assert!(controller.current_speed() <= 1.0);
+fn speed_above_one_rejected() {
+    // Out-of-range speeds can't be turned into a command at all
+    assert_eq!(Speed::new(1.5), Err(SpeedError::OutOfRange));
+    assert_eq!("1.5".parse::<Speed>(), Err(SpeedError::OutOfRange));
 }
 
 #[test]
-fn speed_clamped_below_zero() {
-    let motor = MockMotor::new();
-    let mut controller = ThrottleController::new(motor);
-
-    // Try to set speed below 0.0
-    let cmd = ThrottleCommand::speed_immediate(-0.5);
-    controller
-        .apply_command(cmd.into(), CommandSource::Physical, 0)
-        .unwrap();
-    controller.update(0).unwrap();
-
-    // Should be clamped to 0.0
-    assert!(controller.current_speed() >= 0.0);
+fn speed_below_zero_rejected() {
+    assert_eq!(Speed::new(-0.5), Err(SpeedError::OutOfRange));
+    assert_eq!(Speed::new(f32::NAN), Err(SpeedError::NotFinite));
 }
 
 #[test]
@@ -77,20 +58,20 @@ fn max_speed_at_zero_stops_all_motion() {
     let mut controller = ThrottleController::new(motor);
 
     // Set max speed to 0
-    let cmd = ThrottleCommandDyn::SetMaxSpeed(0.0);
+    let cmd = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.0));
     controller
         .apply_command(cmd, CommandSource::Physical, 0)
         .unwrap();
 
     // Try to set speed to 50%
-    let cmd = ThrottleCommand::speed_immediate(0.5);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
 
     // Should be clamped to 0
-    assert!((controller.current_speed() - 0.0).abs() < 0.001);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.001);
 }
 
 #[test]
@@ -99,19 +80,19 @@ fn max_speed_at_one_allows_full_range() {
     let mut controller = ThrottleController::new(motor);
 
     // Max speed defaults to 1.0, but let's explicitly set it
-    let cmd = ThrottleCommandDyn::SetMaxSpeed(1.0);
+    let cmd = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(1.0));
     controller
         .apply_command(cmd, CommandSource::Physical, 0)
         .unwrap();
 
     // Set speed to 100%
-    let cmd = ThrottleCommand::speed_immediate(1.0);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(1.0));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
 
-    assert!((controller.current_speed() - 1.0).abs() < 0.001);
+    assert!((controller.current_speed().get() - 1.0).abs() < 0.001);
 }
 
 // ============================================================================
@@ -125,7 +106,7 @@ fn zero_duration_transition() {
 
     // Linear transition with 0 duration should complete immediately
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.8,
+        target: Speed::literal(0.8),
         strategy: Linear::new(0),
     };
     controller
@@ -133,7 +114,7 @@ fn zero_duration_transition() {
         .unwrap();
     controller.update(0).unwrap();
 
-    assert!((controller.current_speed() - 0.8).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.8).abs() < 0.01);
     assert!(!controller.is_transitioning());
 }
 
@@ -144,7 +125,7 @@ fn very_long_transition() {
 
     // Very long transition (24 hours in ms)
     let cmd = ThrottleCommand::SetSpeed {
-        target: 1.0,
+        target: Speed::literal(1.0),
         strategy: Linear::new(86_400_000),
     };
     controller
@@ -154,7 +135,7 @@ fn very_long_transition() {
     // Update at various times
     controller.update(1000).unwrap(); // 1 second
     assert!(controller.is_transitioning());
-    assert!(controller.current_speed() < 0.001); // Barely moved
+    assert!(controller.current_speed().get() < 0.001); // Barely moved
 
     controller.update(3_600_000).unwrap(); // 1 hour
     assert!(controller.is_transitioning());
@@ -168,7 +149,7 @@ fn timestamp_overflow_handling() {
     // Start at a very high timestamp
     let start_time = u64::MAX - 1000;
     let cmd = ThrottleCommand::SetSpeed {
-        target: 1.0,
+        target: Speed::literal(1.0),
         strategy: Linear::new(500),
     };
     controller
@@ -214,8 +195,7 @@ fn rapid_speed_commands() {
 
     // Rapidly send speed commands
     for i in 0..10 {
-        let speed = (i as f32) * 0.1;
-        let cmd = ThrottleCommand::speed_immediate(speed);
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(i as f32 * 0.1));
         controller
             .apply_command(cmd.into(), CommandSource::Physical, i as u64)
             .unwrap();
@@ -223,7 +203,7 @@ fn rapid_speed_commands() {
     }
 
     // Final speed should be 0.9
-    assert!((controller.current_speed() - 0.9).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.9).abs() < 0.01);
 }
 
 #[test]
@@ -233,7 +213,7 @@ fn interrupt_in_progress_transition() {
 
     // Start transition to 1.0
     let cmd = ThrottleCommand::SetSpeed {
-        target: 1.0,
+        target: Speed::literal(1.0),
         strategy: Linear::new(1000),
     };
     controller
@@ -242,17 +222,17 @@ fn interrupt_in_progress_transition() {
 
     // Update partway
     controller.update(500).unwrap();
-    assert!((controller.current_speed() - 0.5).abs() < 0.1);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.1);
 
     // Interrupt with new target
-    let cmd = ThrottleCommand::speed_immediate(0.0);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.0));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 500)
         .unwrap();
     controller.update(500).unwrap();
 
     // Should be at 0 now
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
 }
 
 // ============================================================================
@@ -266,7 +246,7 @@ fn estop_from_lowest_priority_still_works() {
 
     // Set speed with an ongoing transition
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.8,
+        target: Speed::literal(0.8),
         strategy: Linear::new(5000),
     };
     controller
@@ -284,7 +264,7 @@ fn estop_from_lowest_priority_still_works() {
         result,
         CommandOutcome::SpeedTransition(TransitionResult::Interrupted { .. })
     ));
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
 }
 
 #[test]
@@ -293,7 +273,7 @@ fn multiple_estops_in_sequence() {
     let mut controller = ThrottleController::new(motor);
 
     // Set speed
-    let cmd = ThrottleCommand::speed_immediate(0.8);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.8));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
@@ -307,7 +287,7 @@ fn multiple_estops_in_sequence() {
             .unwrap();
     }
 
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
     assert_eq!(controller.current_direction(), Direction::Stopped);
 }
 
@@ -318,7 +298,7 @@ fn estop_during_locked_departure() {
 
     // Start a locked departure transition
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.8,
+        target: Speed::literal(0.8),
         strategy: EaseInOut::departure(5000),
     };
     controller
@@ -339,7 +319,7 @@ fn estop_during_locked_departure() {
         result,
         CommandOutcome::SpeedTransition(TransitionResult::Interrupted { .. })
     ));
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
 }
 
 // ============================================================================
@@ -358,14 +338,14 @@ fn direction_independent_of_speed() {
         .unwrap();
 
     // Set speed
-    let cmd = ThrottleCommand::speed_immediate(0.5);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
 
     assert_eq!(controller.current_direction(), Direction::Forward);
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
 
     // Change direction without affecting speed
     let cmd = ThrottleCommandDyn::SetDirection(Direction::Reverse);
@@ -374,7 +354,7 @@ fn direction_independent_of_speed() {
         .unwrap();
 
     assert_eq!(controller.current_direction(), Direction::Reverse);
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
 }
 
 #[test]
@@ -383,20 +363,20 @@ fn max_speed_applies_to_new_commands() {
     let mut controller = ThrottleController::new(motor);
 
     // Set max speed first
-    let cmd = ThrottleCommandDyn::SetMaxSpeed(0.3);
+    let cmd = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.3));
     controller
         .apply_command(cmd, CommandSource::Physical, 0)
         .unwrap();
 
     // Now try to set speed above max
-    let cmd = ThrottleCommand::speed_immediate(1.0);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(1.0));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 100)
         .unwrap();
     controller.update(100).unwrap();
 
     // Speed should be clamped by max_speed
-    assert!(controller.current_speed() <= 0.31);
+    assert!(controller.current_speed().get() <= 0.31);
 }
 
 #[test]
@@ -410,13 +390,13 @@ fn state_snapshot_consistent() {
         .apply_command(cmd, CommandSource::Physical, 0)
         .unwrap();
 
-    let cmd = ThrottleCommandDyn::SetMaxSpeed(0.75);
+    let cmd = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.75));
     controller
         .apply_command(cmd, CommandSource::Physical, 0)
         .unwrap();
 
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.5,
+        target: Speed::literal(0.5),
         strategy: Linear::new(1000),
     };
     controller
@@ -429,9 +409,9 @@ fn state_snapshot_consistent() {
 
     // Verify all state fields are consistent
     assert_eq!(state.direction, Direction::Forward);
-    assert!((state.max_speed - 0.75).abs() < 0.01);
-    assert!((state.speed - 0.25).abs() < 0.05); // Halfway through transition
-    assert_eq!(state.target_speed, Some(0.5));
+    assert!((state.max_speed.get() - 0.75).abs() < 0.01);
+    assert!((state.speed.get() - 0.25).abs() < 0.05); // Halfway through transition
+    assert_eq!(state.target_speed, Some(Speed::literal(0.5)));
     assert!(state.fault.is_none());
     assert!(state.transition_progress.is_some());
 }
@@ -446,7 +426,7 @@ fn momentum_with_same_from_and_to() {
     let mut controller = ThrottleController::new(motor);
 
     // Set initial speed
-    let cmd = ThrottleCommand::speed_immediate(0.5);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
@@ -454,7 +434,7 @@ fn momentum_with_same_from_and_to() {
 
     // Momentum transition to same speed
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.5,
+        target: Speed::literal(0.5),
         strategy: Momentum::gentle(),
     };
     controller
@@ -463,7 +443,7 @@ fn momentum_with_same_from_and_to() {
 
     // Should complete immediately (no distance to travel)
     controller.update(100).unwrap();
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
 }
 
 #[test]
@@ -472,7 +452,7 @@ fn ease_in_out_very_small_change() {
     let mut controller = ThrottleController::new(motor);
 
     // Set initial speed
-    let cmd = ThrottleCommand::speed_immediate(0.5);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
@@ -480,7 +460,7 @@ fn ease_in_out_very_small_change() {
 
     // Very small change with ease-in-out
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.501,
+        target: Speed::literal(0.501),
         strategy: EaseInOut::new(1000),
     };
     controller
@@ -488,7 +468,7 @@ fn ease_in_out_very_small_change() {
         .unwrap();
 
     controller.update(1100).unwrap();
-    assert!((controller.current_speed() - 0.501).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.501).abs() < 0.01);
 }
 
 // ============================================================================
@@ -509,12 +489,12 @@ fn all_source_priorities_respected() {
     ];
 
     for (i, source) in sources.iter().enumerate() {
-        let speed = (i as f32 + 1.0) * 0.1;
-        let cmd = ThrottleCommand::speed_immediate(speed);
+        let target = (i as f32 + 1.0) * 0.1;
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(target));
         controller
             .apply_command(cmd.into(), *source, (i * 100) as u64)
             .unwrap();
         controller.update((i * 100) as u64).unwrap();
-        assert!((controller.current_speed() - speed).abs() < 0.01);
+        assert!((controller.current_speed().get() - target).abs() < 0.01);
     }
 }
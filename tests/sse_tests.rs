@@ -0,0 +1,182 @@
+//! Integration tests for the `/api/events` Server-Sent Events stream.
+
+#![cfg(feature = "web")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{Body, BodyDataStream};
+use axum::http::Request;
+use futures_util::StreamExt;
+use tower::ServiceExt;
+
+use rs_trainz::hal::MockMotor;
+use rs_trainz::services::{build_router, AppState, WebServerConfig};
+use rs_trainz::traits::Linear;
+use rs_trainz::{
+    CommandSource, Direction, FaultKind, Speed, ThrottleCommand, ThrottleCommandDyn,
+    ThrottleController,
+};
+
+/// One parsed `id`/`event`/`data` block.
+#[derive(Debug)]
+struct SseEvent {
+    id: u64,
+    event: String,
+    data: serde_json::Value,
+}
+
+struct Events {
+    body: BodyDataStream,
+    buffer: String,
+}
+
+impl Events {
+    /// Next event, skipping keep-alive comments.
+    async fn next(&mut self) -> SseEvent {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let block: String = self.buffer.drain(..end + 2).collect();
+                let (mut id, mut event, mut data) = (None, None, None);
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("id: ") {
+                        id = Some(value.parse().unwrap());
+                    } else if let Some(value) = line.strip_prefix("event: ") {
+                        event = Some(value.to_string());
+                    } else if let Some(value) = line.strip_prefix("data: ") {
+                        data = Some(serde_json::from_str(value).unwrap());
+                    }
+                }
+                if let (Some(id), Some(event), Some(data)) = (id, event, data) {
+                    return SseEvent { id, event, data };
+                }
+                continue;
+            }
+            let chunk = tokio::time::timeout(Duration::from_secs(2), self.body.next())
+                .await
+                .expect("timed out waiting for an event")
+                .unwrap()
+                .unwrap();
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+
+    /// Next event of the given type, skipping others.
+    async fn next_of_type(&mut self, event: &str) -> SseEvent {
+        loop {
+            let next = self.next().await;
+            if next.event == event {
+                return next;
+            }
+        }
+    }
+}
+
+fn new_state() -> Arc<AppState<MockMotor>> {
+    Arc::new(AppState::new(ThrottleController::new(MockMotor::new())))
+}
+
+async fn subscribe(state: &Arc<AppState<MockMotor>>, last_event_id: Option<u64>) -> Events {
+    let router = build_router(Arc::clone(state), &WebServerConfig::default());
+    let mut request = Request::builder().uri("/api/events");
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id.to_string());
+    }
+    let response = router
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+    Events {
+        body: response.into_body().into_data_stream(),
+        buffer: String::new(),
+    }
+}
+
+fn apply(state: &AppState<MockMotor>, cmd: impl Into<ThrottleCommandDyn>) {
+    let now = state.now_ms();
+    state.with_controller(|c| {
+        c.apply_command(cmd.into(), CommandSource::Physical, now)
+            .unwrap();
+        c.update(now).unwrap();
+    });
+}
+
+#[tokio::test]
+async fn test_sends_state_then_typed_events() {
+    let state = new_state();
+    let mut events = subscribe(&state, None).await;
+
+    let hello = events.next().await;
+    assert_eq!(hello.event, "state");
+    assert_eq!(hello.data["direction"], "stopped");
+    assert_eq!(hello.data["max_speed"], 1.0);
+
+    apply(&state, ThrottleCommandDyn::SetDirection(Direction::Forward));
+    let event = events.next_of_type("direction").await;
+    assert_eq!(event.data["direction"], "forward");
+    assert!(event.id > hello.id);
+
+    apply(
+        &state,
+        ThrottleCommand::speed_immediate(Speed::new(0.4).unwrap()),
+    );
+    let event = events.next_of_type("speed").await;
+    assert_eq!(event.data["speed"], 0.4);
+    assert_eq!(event.data["velocity"], 0.4);
+}
+
+#[tokio::test]
+async fn test_state_includes_fault_and_progress() {
+    let state = new_state();
+    let now = state.now_ms();
+    state.with_controller(|c| {
+        let cmd = ThrottleCommand::SetSpeed {
+            target: Speed::new(0.8).unwrap(),
+            strategy: Linear::new(10_000),
+        };
+        c.apply_command(cmd.into(), CommandSource::Physical, now)
+            .unwrap();
+        c.update(now).unwrap();
+    });
+    let hello = subscribe(&state, None).await.next().await;
+    assert_eq!(hello.event, "state");
+    assert_eq!(hello.data["progress"]["to"], 0.8);
+    assert!(hello.data["progress"]["percent"].is_number());
+    assert!(hello.data.get("fault").is_none());
+
+    state.with_controller(|c| c.handle_fault(FaultKind::Overcurrent).unwrap());
+    let hello = subscribe(&state, None).await.next().await;
+    assert_eq!(hello.event, "state");
+    assert_eq!(hello.data["fault"], "overcurrent");
+}
+
+#[tokio::test]
+async fn test_resumes_from_last_event_id() {
+    let state = new_state();
+    let mut events = subscribe(&state, None).await;
+    events.next_of_type("state").await;
+    apply(&state, ThrottleCommandDyn::SetDirection(Direction::Forward));
+    let seen = events.next_of_type("direction").await;
+    drop(events);
+
+    // Missed while disconnected
+    apply(&state, ThrottleCommandDyn::SetDirection(Direction::Reverse));
+
+    let mut events = subscribe(&state, Some(seen.id)).await;
+    let missed = events.next().await;
+    assert_eq!(missed.event, "direction");
+    assert_eq!(missed.data["direction"], "reverse");
+    assert!(missed.id > seen.id);
+
+    // An id this server never issued starts over from the full state
+    let mut events = subscribe(&state, Some(missed.id + 1000)).await;
+    let hello = events.next().await;
+    assert_eq!(hello.event, "state");
+    assert_eq!(hello.data["direction"], "reverse");
+    assert_eq!(hello.id, missed.id);
+}
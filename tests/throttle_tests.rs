@@ -1,8 +1,12 @@
 //! Integration tests for the throttle controller
 
 use rs_trainz::{
-    hal::MockMotor, CommandOutcome, CommandSource, Direction, EaseInOut, Linear, ThrottleCommand,
-    ThrottleCommandDyn, ThrottleController, TransitionResult,
+    hal::{MockClock, MockMotor},
+    traits::Clock,
+    BrakeSetting, ClientId, CommandOutcome, CommandSource, Direction, DrivingMode, EaseInOut,
+    HeartbeatLease, Linear, LockRelease, LocoProfile, Notch, RateLimit, RateLimits, RejectReason,
+    SafeStop, Sequence, SourcePriorities, Speed, StrategySpec, ThrottleCommand, ThrottleCommandDyn,
+    ThrottleConfig, ThrottleController, TransitionLock, TransitionResult, Velocity, Q16,
 };
 
 #[test]
@@ -10,7 +14,7 @@ fn immediate_speed_change() {
     let motor = MockMotor::new();
     let mut controller = ThrottleController::new(motor);
 
-    let cmd = ThrottleCommand::speed_immediate(0.5);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
     let result = controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
@@ -22,7 +26,7 @@ fn immediate_speed_change() {
 
     // After update, speed should be at target
     controller.update(0).unwrap();
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
 }
 
 #[test]
@@ -32,7 +36,7 @@ fn linear_transition() {
 
     // Start a 1000ms linear transition from 0 to 1.0
     let cmd = ThrottleCommand::SetSpeed {
-        target: 1.0,
+        target: Speed::literal(1.0),
         strategy: Linear::new(1000),
     };
     controller
@@ -41,15 +45,15 @@ fn linear_transition() {
 
     // At t=0, should still be at 0
     controller.update(0).unwrap();
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
 
     // At t=500ms, should be at 0.5
     controller.update(500).unwrap();
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
 
     // At t=1000ms, should be at 1.0
     controller.update(1000).unwrap();
-    assert!((controller.current_speed() - 1.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 1.0).abs() < 0.01);
 
     // Transition should be complete
     assert!(!controller.is_transitioning());
@@ -62,7 +66,7 @@ fn estop_interrupts_locked_transition() {
 
     // Start a locked departure transition
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.8,
+        target: Speed::literal(0.8),
         strategy: EaseInOut::departure(3000), // locked
     };
     controller
@@ -74,7 +78,7 @@ fn estop_interrupts_locked_transition() {
     assert!(controller.is_transitioning());
 
     // Try to interrupt with a regular command - should be rejected
-    let cmd = ThrottleCommand::speed_immediate(0.2);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.2));
     let result = controller
         .apply_command(cmd.into(), CommandSource::WebApi, 500)
         .unwrap();
@@ -94,10 +98,50 @@ fn estop_interrupts_locked_transition() {
     ));
 
     // Speed should be 0
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
     assert_eq!(controller.current_direction(), Direction::Stopped);
 }
 
+#[test]
+fn locked_sequence_runs_as_one_move() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    // Ramp to 0.3 over 2s, hold 5s, ramp to 0.7 over 4s
+    let sequence: Sequence = Sequence::locked()
+        .then(Speed::literal(0.3), Linear::new(2000))
+        .hold(5000)
+        .finish(Linear::new(4000));
+    let cmd = ThrottleCommand::SetSpeed {
+        target: Speed::literal(0.7),
+        strategy: sequence,
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    controller.update(4000).unwrap();
+    assert!((controller.current_speed().get() - 0.3).abs() < 0.01);
+    let progress = controller.state(4000).transition_progress.unwrap();
+    assert_eq!(progress.estimated_total_ms, Some(11_000));
+    assert_eq!(progress.segment.unwrap().index, 1);
+
+    // Still locked while holding between ramps
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.0));
+    let result = controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 4000)
+        .unwrap();
+    assert!(matches!(
+        result,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })
+    ));
+
+    controller.update(9000).unwrap();
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
+    controller.update(11_000).unwrap();
+    assert!((controller.current_speed().get() - 0.7).abs() < 0.01);
+    assert!(!controller.is_transitioning());
+}
+
 #[test]
 fn direction_change() {
     let motor = MockMotor::new();
@@ -126,17 +170,17 @@ fn queued_transition() {
     let mut controller = ThrottleController::new(motor);
 
     // Set initial speed so arrival transition has work to do
-    let cmd = ThrottleCommand::speed_immediate(0.5);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
 
     // Start an arrival transition from Physical (high priority)
     // EaseInOut::arrival uses Source lock + Queue interrupt behavior
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.0,
+        target: Speed::literal(0.0),
         strategy: EaseInOut::arrival(1000),
     };
     controller
@@ -147,7 +191,7 @@ fn queued_transition() {
     // Because the active transition is Source-locked from Physical,
     // this lower-priority command should be queued (not rejected, not replacing)
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.8,
+        target: Speed::literal(0.8),
         strategy: EaseInOut::departure(1000),
     };
     let result = controller
@@ -156,12 +200,12 @@ fn queued_transition() {
 
     assert!(matches!(
         result,
-        CommandOutcome::SpeedTransition(TransitionResult::Queued)
+        CommandOutcome::SpeedTransition(TransitionResult::Queued { .. })
     ));
 
     // Complete the arrival (started at t=100, duration=1000, so complete at t=1100)
     controller.update(1100).unwrap();
-    assert!((controller.current_speed() - 0.0).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.0).abs() < 0.01);
 
     // On next update, queued transition should start
     controller.update(1101).unwrap();
@@ -169,7 +213,7 @@ fn queued_transition() {
 
     // Complete the departure (started at t=1101, duration=1000, so complete at t=2101)
     controller.update(2101).unwrap();
-    assert!((controller.current_speed() - 0.8).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.8).abs() < 0.01);
 }
 
 #[test]
@@ -178,20 +222,74 @@ fn max_speed_limit() {
     let mut controller = ThrottleController::new(motor);
 
     // Set max speed to 50%
-    let cmd = ThrottleCommandDyn::SetMaxSpeed(0.5);
+    let cmd = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.5));
     controller
         .apply_command(cmd, CommandSource::Physical, 0)
         .unwrap();
 
     // Try to set speed to 80%
-    let cmd = ThrottleCommand::speed_immediate(0.8);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.8));
     controller
         .apply_command(cmd.into(), CommandSource::Physical, 0)
         .unwrap();
     controller.update(0).unwrap();
 
     // Should be clamped to 50%
-    assert!((controller.current_speed() - 0.5).abs() < 0.01);
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn interrupting_never_exceeds_target_or_max_speed() {
+    let motor = MockMotor::new();
+    let mut controller = ThrottleController::new(motor);
+    let set_speed = |target: f32, ms: u64| {
+        ThrottleCommandDyn::from(ThrottleCommand::SetSpeed {
+            target: Speed::literal(target),
+            strategy: Linear::new(ms),
+        })
+    };
+
+    let max = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.5));
+    controller
+        .apply_command(max, CommandSource::Physical, 0)
+        .unwrap();
+    controller
+        .apply_command(set_speed(0.5, 1000), CommandSource::Physical, 0)
+        .unwrap();
+    controller.update(900).unwrap();
+
+    // Re-sent more slowly: the inherited rate must not carry it past 0.5
+    controller
+        .apply_command(set_speed(0.5, 3000), CommandSource::Physical, 900)
+        .unwrap();
+    for now in (900..=4000).step_by(20) {
+        controller.update(now).unwrap();
+        let value = controller.current_speed().get();
+        assert!(value <= 0.5 + Q16::TOLERANCE, "{value} at {now}ms");
+    }
+
+    // Lowering the max caps a ramp already running
+    controller
+        .apply_command(
+            ThrottleCommandDyn::SetMaxSpeed(Speed::FULL),
+            CommandSource::Physical,
+            4000,
+        )
+        .unwrap();
+    controller
+        .apply_command(set_speed(1.0, 1000), CommandSource::Physical, 4000)
+        .unwrap();
+    controller.update(4200).unwrap();
+    let max = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.6));
+    controller
+        .apply_command(max, CommandSource::Physical, 4200)
+        .unwrap();
+    for now in (4200..=5500).step_by(20) {
+        controller.update(now).unwrap();
+        let value = controller.current_speed().get();
+        assert!(value <= 0.6 + Q16::TOLERANCE, "{value} at {now}ms");
+    }
+    assert!((controller.current_speed().get() - 0.6).abs() < 0.01);
 }
 
 #[test]
@@ -201,7 +299,7 @@ fn state_snapshot() {
 
     // Set up some state
     let cmd = ThrottleCommand::SetSpeed {
-        target: 0.6,
+        target: Speed::literal(0.6),
         strategy: Linear::new(1000),
     };
     controller
@@ -216,10 +314,1265 @@ fn state_snapshot() {
     controller.update(500).unwrap();
 
     let state = controller.state(500);
-    assert!((state.speed - 0.3).abs() < 0.05); // halfway through transition
-    assert_eq!(state.target_speed, Some(0.6));
+    assert!((state.speed.get() - 0.3).abs() < 0.05); // halfway through transition
+    assert_eq!(state.target_speed, Some(Speed::literal(0.6)));
     assert_eq!(state.direction, Direction::Forward);
     assert_eq!(state.max_speed, 1.0);
     assert!(state.fault.is_none());
     assert!(state.transition_progress.is_some());
 }
+
+fn remote_controller(safe_stop: SafeStop) -> ThrottleController<MockMotor> {
+    ThrottleController::new(MockMotor::new()).with_heartbeat(HeartbeatLease::new(3000, safe_stop))
+}
+
+#[test]
+fn heartbeat_lapse_performs_safe_stop() {
+    let mut controller = remote_controller(SafeStop::Ramp { duration_ms: 1000 });
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.6));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebLocal, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+    assert!(controller.state(0).heartbeat.is_some());
+
+    // No heartbeat for 3s: lease lapses and a 1s ramp to zero starts
+    controller.update(3000).unwrap();
+    assert!(controller.is_transitioning());
+    assert!(controller.state(3000).heartbeat.is_none());
+
+    controller.update(3500).unwrap();
+    assert!((controller.current_speed().get() - 0.3).abs() < 0.01);
+
+    controller.update(4000).unwrap();
+    assert_eq!(controller.current_speed().get(), 0.0);
+}
+
+#[test]
+fn heartbeat_keeps_remote_control_alive() {
+    let mut controller = remote_controller(SafeStop::EmergencyStop);
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.6));
+    controller
+        .apply_command(cmd.into(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    for t in (1000..=10_000).step_by(1000) {
+        controller
+            .apply_command(ThrottleCommandDyn::Heartbeat, CommandSource::Mqtt, t)
+            .unwrap();
+        controller.update(t).unwrap();
+    }
+
+    assert!((controller.current_speed().get() - 0.6).abs() < 0.01);
+}
+
+#[test]
+fn heartbeat_lapse_emergency_stop() {
+    let mut controller = remote_controller(SafeStop::EmergencyStop);
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.6));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller
+        .apply_command(
+            ThrottleCommandDyn::SetDirection(Direction::Forward),
+            CommandSource::WebApi,
+            0,
+        )
+        .unwrap();
+    controller.update(0).unwrap();
+
+    controller.update(3000).unwrap();
+    assert_eq!(controller.current_speed().get(), 0.0);
+    assert_eq!(controller.current_direction(), Direction::Stopped);
+}
+
+#[test]
+fn heartbeat_ramp_falls_back_to_estop_when_hard_locked() {
+    let mut controller = remote_controller(SafeStop::Ramp { duration_ms: 1000 });
+
+    let cmd = ThrottleCommand::SetSpeed {
+        target: Speed::literal(1.0),
+        strategy: EaseInOut::departure(10_000),
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::WebLocal, 0)
+        .unwrap();
+
+    controller.update(3000).unwrap();
+    assert_eq!(controller.current_speed().get(), 0.0);
+    assert!(!controller.is_transitioning());
+}
+
+#[test]
+fn heartbeat_physical_control_exempt() {
+    let mut controller = remote_controller(SafeStop::EmergencyStop);
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.4));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebLocal, 0)
+        .unwrap();
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
+    controller
+        .apply_command(cmd.into(), CommandSource::Physical, 1000)
+        .unwrap();
+
+    controller.update(60_000).unwrap();
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn preset_strategy_resolved_by_controller() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommandDyn::SetSpeed {
+        target: Speed::literal(0.8),
+        strategy: StrategySpec::preset("departure").unwrap().into(),
+    };
+    let outcome = controller
+        .apply_command(cmd, CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Started)
+    ));
+
+    // Departure is a hard-locked 3s ease-in-out
+    controller.update(1500).unwrap();
+    assert!((controller.current_speed().get() - 0.4).abs() < 0.05);
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.0));
+    let outcome = controller
+        .apply_command(cmd.into(), CommandSource::Physical, 1500)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })
+    ));
+}
+
+#[test]
+fn preset_from_config_overrides_builtin() {
+    let config = ThrottleConfig::default()
+        .with_preset("departure", Linear::new(1000))
+        .unwrap();
+    let mut controller =
+        ThrottleController::new(MockMotor::new()).with_presets(config.presets.clone());
+
+    let cmd = ThrottleCommandDyn::SetSpeed {
+        target: Speed::literal(1.0),
+        strategy: StrategySpec::preset("departure").unwrap().into(),
+    };
+    controller
+        .apply_command(cmd, CommandSource::Mqtt, 0)
+        .unwrap();
+    controller.update(500).unwrap();
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn unknown_preset_rejected() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommandDyn::SetSpeed {
+        target: Speed::literal(0.8),
+        strategy: StrategySpec::preset("warp").unwrap().into(),
+    };
+    let outcome = controller
+        .apply_command(cmd, CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::UnknownPreset
+        })
+    ));
+    assert!(!controller.is_transitioning());
+}
+
+fn velocity(value: f32) -> Velocity {
+    Velocity::new(value).unwrap()
+}
+
+#[test]
+fn velocity_from_rest_sets_direction() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommand::velocity_immediate(velocity(-0.4));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+
+    let state = controller.state(0);
+    assert_eq!(state.direction, Direction::Reverse);
+    assert!((state.speed.get() - 0.4).abs() < 0.01);
+    assert!((state.velocity.get() + 0.4).abs() < 0.01);
+}
+
+#[test]
+fn velocity_reverses_through_zero() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommand::velocity_immediate(velocity(0.6));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+
+    // Each leg of the reversal takes 1000ms
+    let cmd = ThrottleCommand::SetVelocity {
+        target: velocity(-0.4),
+        strategy: Linear::new(1000),
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+
+    // Decelerating while still moving forward
+    controller.update(500).unwrap();
+    assert_eq!(controller.current_direction(), Direction::Forward);
+    assert!((controller.current_speed().get() - 0.3).abs() < 0.01);
+
+    // Direction flips once the train is at rest
+    controller.update(1000).unwrap();
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+    assert_eq!(controller.current_direction(), Direction::Reverse);
+    assert_eq!(
+        controller.state(1000).target_speed,
+        Some(Speed::literal(0.4))
+    );
+
+    controller.update(1500).unwrap();
+    assert!((controller.current_velocity().get() + 0.2).abs() < 0.01);
+
+    controller.update(2000).unwrap();
+    assert!((controller.current_velocity().get() + 0.4).abs() < 0.01);
+    assert!(!controller.is_transitioning());
+}
+
+#[test]
+fn velocity_same_direction_is_single_transition() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommand::velocity_immediate(velocity(-0.2));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+
+    let cmd = ThrottleCommand::SetVelocity {
+        target: velocity(-0.6),
+        strategy: Linear::new(1000),
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+
+    controller.update(500).unwrap();
+    assert_eq!(controller.current_direction(), Direction::Reverse);
+    assert!((controller.current_velocity().get() + 0.4).abs() < 0.01);
+}
+
+#[test]
+fn velocity_limited_by_max_speed() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+    controller
+        .apply_command(
+            ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.5)),
+            CommandSource::Physical,
+            0,
+        )
+        .unwrap();
+
+    let cmd = ThrottleCommand::velocity_immediate(velocity(-0.9));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+
+    assert!((controller.current_velocity().get() + 0.5).abs() < 0.01);
+}
+
+#[test]
+fn set_speed_cancels_pending_reversal() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommand::velocity_immediate(velocity(0.5));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+
+    let cmd = ThrottleCommand::SetVelocity {
+        target: velocity(-0.5),
+        strategy: Linear::new(1000),
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(500).unwrap();
+
+    // A plain speed command replaces the reversal; direction stays forward
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.0));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 500)
+        .unwrap();
+    controller.update(600).unwrap();
+    controller.update(2000).unwrap();
+
+    assert_eq!(controller.current_direction(), Direction::Forward);
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+}
+
+// === Cab Driving Mode Tests ===
+
+fn cab_controller() -> ThrottleController<MockMotor> {
+    ThrottleController::new(MockMotor::new()).with_driving_mode(DrivingMode::Cab)
+}
+
+fn notch(value: u8) -> ThrottleCommandDyn {
+    ThrottleCommandDyn::SetNotch(Notch::new(value).unwrap())
+}
+
+/// Run the controller for `ms` milliseconds in 20ms ticks.
+fn drive(controller: &mut ThrottleController<MockMotor>, clock: &mut MockClock, ms: u64) {
+    for _ in 0..ms / 20 {
+        clock.advance(20);
+        controller.update(clock.now_ms()).unwrap();
+    }
+}
+
+#[test]
+fn cab_notch_accelerates_train() {
+    let mut clock = MockClock::new();
+    let mut controller = cab_controller();
+    controller.update(clock.now_ms()).unwrap();
+
+    controller
+        .apply_command(notch(8), CommandSource::Physical, clock.now_ms())
+        .unwrap();
+    drive(&mut controller, &mut clock, 1000);
+    let after_1s = controller.current_speed();
+    assert!(after_1s > Speed::ZERO);
+
+    drive(&mut controller, &mut clock, 4000);
+    assert!(controller.current_speed() > after_1s);
+
+    let cab = controller.state(clock.now_ms()).cab.unwrap();
+    assert_eq!(cab.notch, Notch::MAX);
+    assert_eq!(cab.brake, BrakeSetting::Release);
+}
+
+#[test]
+fn cab_heavier_loco_accelerates_slower() {
+    let mut light = cab_controller().with_loco(LocoProfile::switcher());
+    let mut heavy = cab_controller().with_loco(LocoProfile::freight());
+
+    for controller in [&mut light, &mut heavy] {
+        let mut clock = MockClock::new();
+        controller.update(0).unwrap();
+        controller
+            .apply_command(notch(8), CommandSource::Physical, 0)
+            .unwrap();
+        drive(controller, &mut clock, 2000);
+    }
+    assert!(heavy.current_speed() < light.current_speed());
+}
+
+#[test]
+fn cab_brake_stops_train() {
+    let mut clock = MockClock::new();
+    let mut controller = cab_controller();
+    controller.update(clock.now_ms()).unwrap();
+
+    controller
+        .apply_command(notch(8), CommandSource::Physical, clock.now_ms())
+        .unwrap();
+    drive(&mut controller, &mut clock, 3000);
+    let cruising = controller.current_speed();
+
+    // Idle alone only coasts down slowly
+    controller
+        .apply_command(notch(0), CommandSource::Physical, clock.now_ms())
+        .unwrap();
+    drive(&mut controller, &mut clock, 500);
+    let coasting = controller.current_speed();
+    assert!(coasting < cruising);
+    assert!(coasting > Speed::ZERO);
+
+    let brake = ThrottleCommandDyn::SetBrake(BrakeSetting::Apply);
+    controller
+        .apply_command(brake, CommandSource::Physical, clock.now_ms())
+        .unwrap();
+    drive(&mut controller, &mut clock, 10_000);
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+}
+
+#[test]
+fn cab_speed_limited_by_max_speed() {
+    let mut clock = MockClock::new();
+    let mut controller = cab_controller();
+    controller.update(clock.now_ms()).unwrap();
+
+    let max = ThrottleCommandDyn::SetMaxSpeed(Speed::literal(0.2));
+    controller
+        .apply_command(max, CommandSource::Physical, 0)
+        .unwrap();
+    controller
+        .apply_command(notch(8), CommandSource::Physical, 0)
+        .unwrap();
+    drive(&mut controller, &mut clock, 10_000);
+    assert!(controller.current_speed() <= Speed::literal(0.2));
+}
+
+#[test]
+fn cab_mode_rejects_speed_commands() {
+    let mut controller = cab_controller();
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
+    let outcome = controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::WrongDrivingMode,
+        })
+    ));
+
+    let cmd = ThrottleCommandDyn::SetVelocity {
+        target: velocity(-0.5),
+        strategy: StrategySpec::Immediate.into(),
+    };
+    let outcome = controller
+        .apply_command(cmd, CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::WrongDrivingMode,
+        })
+    ));
+}
+
+#[test]
+fn direct_mode_rejects_cab_controls() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+    assert_eq!(controller.driving_mode(), DrivingMode::Direct);
+
+    for cmd in [notch(3), ThrottleCommandDyn::SetBrake(BrakeSetting::Apply)] {
+        let outcome = controller
+            .apply_command(cmd, CommandSource::WebApi, 0)
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::WrongDrivingMode,
+            })
+        ));
+    }
+    assert!(controller.state(0).cab.is_none());
+}
+
+#[test]
+fn switching_driving_mode_keeps_speed() {
+    let mut clock = MockClock::new();
+    let mut controller = ThrottleController::new(MockMotor::new());
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+
+    let cab = ThrottleCommandDyn::SetDrivingMode(DrivingMode::Cab);
+    controller
+        .apply_command(cab, CommandSource::WebApi, 0)
+        .unwrap();
+    assert_eq!(controller.driving_mode(), DrivingMode::Cab);
+    controller.update(clock.now_ms()).unwrap();
+    assert!((controller.current_speed().get() - 0.5).abs() < 0.01);
+
+    // Idle throttle with released brake coasts; speed doesn't jump
+    drive(&mut controller, &mut clock, 100);
+    assert!(controller.current_speed() > Speed::literal(0.45));
+
+    let direct = ThrottleCommandDyn::SetDrivingMode(DrivingMode::Direct);
+    controller
+        .apply_command(direct, CommandSource::WebApi, clock.now_ms())
+        .unwrap();
+    let coasted = controller.current_speed();
+    drive(&mut controller, &mut clock, 100);
+    assert_eq!(controller.current_speed(), coasted);
+}
+
+#[test]
+fn cab_estop_stops_dead() {
+    let mut clock = MockClock::new();
+    let mut controller = cab_controller();
+    controller.update(clock.now_ms()).unwrap();
+    controller
+        .apply_command(notch(8), CommandSource::Physical, 0)
+        .unwrap();
+    drive(&mut controller, &mut clock, 2000);
+
+    let cmd = ThrottleCommand::estop();
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, clock.now_ms())
+        .unwrap();
+    drive(&mut controller, &mut clock, 100);
+
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+    let cab = controller.cab_status().unwrap();
+    assert_eq!(cab.notch, Notch::IDLE);
+    assert_eq!(cab.brake, BrakeSetting::Emergency);
+}
+
+#[test]
+fn cab_heartbeat_lapse_applies_brake() {
+    let mut clock = MockClock::new();
+    let mut controller =
+        remote_controller(SafeStop::Ramp { duration_ms: 1000 }).with_driving_mode(DrivingMode::Cab);
+    controller.update(clock.now_ms()).unwrap();
+    controller
+        .apply_command(notch(8), CommandSource::WebLocal, 0)
+        .unwrap();
+    drive(&mut controller, &mut clock, 2000);
+    assert!(controller.current_speed() > Speed::ZERO);
+
+    // Lease lapses at 3s: throttle closes and the brake goes on
+    drive(&mut controller, &mut clock, 1000);
+    let cab = controller.cab_status().unwrap();
+    assert_eq!(cab.notch, Notch::IDLE);
+    assert_eq!(cab.brake, BrakeSetting::Apply);
+
+    drive(&mut controller, &mut clock, 10_000);
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+}
+
+#[test]
+fn cab_mode_from_config() {
+    let config = ThrottleConfig::default()
+        .with_driving_mode(DrivingMode::Cab)
+        .with_loco("freight");
+    let controller = ThrottleController::new(MockMotor::new())
+        .with_loco(config.loco_profile())
+        .with_driving_mode(config.driving_mode);
+
+    assert_eq!(controller.driving_mode(), DrivingMode::Cab);
+    assert!(controller.cab_status().is_some());
+}
+
+// === Service Brake Tests ===
+
+fn moving_controller(at: f32) -> ThrottleController<MockMotor> {
+    let mut controller = ThrottleController::new(MockMotor::new());
+    let forward = ThrottleCommandDyn::SetDirection(Direction::Forward);
+    controller
+        .apply_command(forward, CommandSource::WebApi, 0)
+        .unwrap();
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(at));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(0).unwrap();
+    controller
+}
+
+fn brake(hold: bool) -> ThrottleCommandDyn {
+    ThrottleCommand::brake(hold).into()
+}
+
+#[test]
+fn service_brake_follows_configured_profile() {
+    let mut controller = moving_controller(0.8).with_service_brake(Linear::new(1000));
+
+    controller
+        .apply_command(brake(false), CommandSource::WebApi, 0)
+        .unwrap();
+    assert_eq!(
+        controller.state(0).service_brake.map(|b| b.hold),
+        Some(false)
+    );
+
+    // Halfway through the profile, unlike an instant e-stop
+    controller.update(500).unwrap();
+    assert!((controller.current_speed().get() - 0.4).abs() < 0.01);
+
+    // Stopped: a non-holding application ends by itself, direction kept
+    controller.update(1000).unwrap();
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+    assert!(controller.state(1000).service_brake.is_none());
+    assert_eq!(controller.current_direction(), Direction::Forward);
+}
+
+#[test]
+fn service_brake_hold_until_released() {
+    let mut controller = moving_controller(0.5).with_service_brake(Linear::new(500));
+
+    controller
+        .apply_command(brake(true), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(500).unwrap();
+    controller.update(600).unwrap();
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+    assert!(controller.state(600).service_brake.is_some());
+
+    // Held: even physical controls can't move the train
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.3));
+    let outcome = controller
+        .apply_command(cmd.into(), CommandSource::Physical, 700)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::BrakeHeld,
+        })
+    ));
+
+    controller
+        .apply_command(ThrottleCommandDyn::ReleaseBrake, CommandSource::WebApi, 800)
+        .unwrap();
+    assert!(controller.state(800).service_brake.is_none());
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.3));
+    let outcome = controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 900)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Started)
+    ));
+}
+
+#[test]
+fn service_brake_lock_protects_application() {
+    let mut controller = moving_controller(0.6);
+
+    let cmd = ThrottleCommandDyn::Brake {
+        strategy: Some(Linear::locked(1000).into()),
+        hold: false,
+    };
+    controller
+        .apply_command(cmd, CommandSource::WebApi, 0)
+        .unwrap();
+
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.9));
+    let outcome = controller
+        .apply_command(cmd.into(), CommandSource::Physical, 100)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::TransitionLocked,
+        })
+    ));
+
+    // E-stop still wins
+    let cmd = ThrottleCommand::estop();
+    controller
+        .apply_command(cmd.into(), CommandSource::Mqtt, 200)
+        .unwrap();
+    controller.update(200).unwrap();
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+    assert!(controller.state(200).service_brake.is_none());
+}
+
+#[test]
+fn service_brake_superseded_by_speed_command() {
+    let mut controller = moving_controller(0.6).with_service_brake(Linear::new(1000));
+
+    controller
+        .apply_command(brake(false), CommandSource::WebApi, 0)
+        .unwrap();
+    let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.4));
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 100)
+        .unwrap();
+    controller.update(100).unwrap();
+
+    assert!(controller.state(100).service_brake.is_none());
+    assert!((controller.current_speed().get() - 0.4).abs() < 0.01);
+}
+
+#[test]
+fn service_brake_preset_profile_from_config() {
+    let config =
+        ThrottleConfig::default().with_service_brake(StrategySpec::preset("arrival").unwrap());
+    let mut controller = moving_controller(0.5)
+        .with_presets(config.presets.clone())
+        .with_service_brake(config.service_brake.clone());
+
+    let outcome = controller
+        .apply_command(brake(false), CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Started)
+    ));
+    assert!(controller.is_transitioning());
+
+    let mut controller =
+        moving_controller(0.5).with_service_brake(StrategySpec::preset("nope").unwrap());
+    let outcome = controller
+        .apply_command(brake(false), CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::UnknownPreset,
+        })
+    ));
+    assert!(controller.state(0).service_brake.is_none());
+}
+
+#[test]
+fn service_brake_in_cab_mode_applies_brake_handle() {
+    let mut clock = MockClock::new();
+    let mut controller = cab_controller();
+    controller.update(clock.now_ms()).unwrap();
+    controller
+        .apply_command(notch(8), CommandSource::Physical, 0)
+        .unwrap();
+    drive(&mut controller, &mut clock, 2000);
+
+    controller
+        .apply_command(brake(true), CommandSource::WebApi, clock.now_ms())
+        .unwrap();
+    let cab = controller.cab_status().unwrap();
+    assert_eq!(cab.notch, Notch::IDLE);
+    assert_eq!(cab.brake, BrakeSetting::Apply);
+
+    // Held: notching up is refused
+    let outcome = controller
+        .apply_command(notch(4), CommandSource::Physical, clock.now_ms())
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::BrakeHeld,
+        })
+    ));
+
+    drive(&mut controller, &mut clock, 10_000);
+    assert_eq!(controller.current_speed(), Speed::ZERO);
+    assert!(controller.state(clock.now_ms()).service_brake.is_some());
+
+    controller
+        .apply_command(
+            ThrottleCommandDyn::ReleaseBrake,
+            CommandSource::WebApi,
+            clock.now_ms(),
+        )
+        .unwrap();
+    assert_eq!(
+        controller.cab_status().unwrap().brake,
+        BrakeSetting::Release
+    );
+}
+
+// === Transition Queue Tests ===
+
+fn arriving_controller<const QUEUE: usize>(
+    controller: ThrottleController<MockMotor, QUEUE>,
+) -> ThrottleController<MockMotor, QUEUE> {
+    let mut controller = controller;
+    let cmd = ThrottleCommand::SetSpeed {
+        target: Speed::literal(0.0),
+        strategy: EaseInOut::arrival(1000),
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::Physical, 0)
+        .unwrap();
+    controller
+}
+
+fn departure(target: f32) -> ThrottleCommandDyn {
+    ThrottleCommand::SetSpeed {
+        target: Speed::literal(target),
+        strategy: EaseInOut::departure(1000),
+    }
+    .into()
+}
+
+#[test]
+fn queued_commands_listed_and_cancelled() {
+    let mut controller = arriving_controller(ThrottleController::new(MockMotor::new()));
+
+    let first = controller
+        .apply_command(departure(0.5), CommandSource::Mqtt, 100)
+        .unwrap();
+    let CommandOutcome::SpeedTransition(TransitionResult::Queued { id }) = first else {
+        panic!("expected queued");
+    };
+    controller
+        .apply_command(departure(0.8), CommandSource::Mqtt, 200)
+        .unwrap();
+    assert_eq!(controller.queued().count(), 2);
+    assert_eq!(controller.state(200).queue[0].id, id);
+
+    let outcome = controller
+        .apply_command(
+            ThrottleCommandDyn::CancelQueued(id),
+            CommandSource::Mqtt,
+            300,
+        )
+        .unwrap();
+    assert!(matches!(outcome, CommandOutcome::Applied));
+    let queue: Vec<_> = controller.queued().collect();
+    assert_eq!(queue.len(), 1);
+    assert!((queue[0].target.get() - 0.8).abs() < 0.01);
+
+    // Cancelling again reports the id is gone
+    let outcome = controller
+        .apply_command(
+            ThrottleCommandDyn::CancelQueued(id),
+            CommandSource::Mqtt,
+            400,
+        )
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::NotQueued,
+        })
+    ));
+
+    controller
+        .apply_command(ThrottleCommandDyn::ClearQueue, CommandSource::Mqtt, 500)
+        .unwrap();
+    assert_eq!(controller.queued().count(), 0);
+
+    // The active arrival is untouched
+    assert!(controller.is_transitioning());
+}
+
+#[test]
+fn queue_depth_configurable() {
+    let controller = ThrottleController::new(MockMotor::new()).with_queue_depth::<1>();
+    let mut controller = arriving_controller(controller);
+
+    let outcome = controller
+        .apply_command(departure(0.5), CommandSource::Mqtt, 100)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Queued { .. })
+    ));
+
+    let outcome = controller
+        .apply_command(departure(0.8), CommandSource::Mqtt, 200)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::QueueFull,
+        })
+    ));
+    assert_eq!(controller.metrics().queue_full, 1);
+}
+
+// === Pause and Resume Tests ===
+
+#[test]
+fn pause_holds_speed_until_resumed() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+    let cmd = ThrottleCommand::SetSpeed {
+        target: Speed::literal(1.0),
+        strategy: Linear::new(1000),
+    };
+    controller
+        .apply_command(cmd.into(), CommandSource::WebApi, 0)
+        .unwrap();
+    controller.update(300).unwrap();
+
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::Pause, CommandSource::Physical, 300)
+        .unwrap();
+    assert!(matches!(outcome, CommandOutcome::Applied));
+
+    // A grade-crossing hold: the speed stays put however long it takes
+    controller.update(10_000).unwrap();
+    assert!((controller.current_speed().get() - 0.3).abs() < 0.01);
+    let progress = controller.state(10_000).transition_progress.unwrap();
+    assert!(progress.paused);
+    assert_eq!(progress.elapsed_ms, 300);
+
+    // The lower-priority sender can't lift the marshal's hold
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::Resume, CommandSource::WebApi, 10_000)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::LowerPriority,
+        })
+    ));
+
+    controller
+        .apply_command(ThrottleCommandDyn::Resume, CommandSource::Physical, 10_000)
+        .unwrap();
+    controller.update(10_350).unwrap();
+    assert!((controller.current_speed().get() - 0.65).abs() < 0.01);
+    controller.update(10_700).unwrap();
+    assert!((controller.current_speed().get() - 1.0).abs() < 0.01);
+    assert!(!controller.is_transitioning());
+}
+
+#[test]
+fn pause_without_transition_rejected() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::Pause, CommandSource::WebApi, 0)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::NotTransitioning,
+        })
+    ));
+}
+
+#[test]
+fn pause_and_queue_edits_hold_off_other_clients() {
+    let alice = Some(ClientId::new("alice").unwrap());
+    let bob = Some(ClientId::new("bob").unwrap());
+    let mut controller = ThrottleController::new(MockMotor::new());
+    let cmd = ThrottleCommand::SetSpeed {
+        target: Speed::literal(1.0),
+        strategy: Linear::source_locked(1000),
+    };
+    controller
+        .apply_command_as(cmd.into(), CommandSource::WebApi, alice, 0)
+        .unwrap();
+
+    let rejected = |outcome| {
+        matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::LowerPriority,
+            })
+        )
+    };
+    let outcome = controller
+        .apply_command_as(ThrottleCommandDyn::Pause, CommandSource::WebApi, bob, 100)
+        .unwrap();
+    assert!(rejected(outcome));
+    controller
+        .apply_command_as(ThrottleCommandDyn::Pause, CommandSource::WebApi, alice, 100)
+        .unwrap();
+    let outcome = controller
+        .apply_command_as(ThrottleCommandDyn::Resume, CommandSource::WebApi, bob, 200)
+        .unwrap();
+    assert!(rejected(outcome));
+    assert!(controller.state(200).transition_progress.unwrap().paused);
+
+    // Alice queues behind a station arrival; Bob can't drop her command
+    let mut controller = ThrottleController::new(MockMotor::new());
+    let arrival = ThrottleCommand::SetSpeed {
+        target: Speed::ZERO,
+        strategy: EaseInOut::arrival(1000),
+    };
+    controller
+        .apply_command(arrival.into(), CommandSource::Physical, 0)
+        .unwrap();
+    let cmd = ThrottleCommand::SetSpeed {
+        target: Speed::literal(0.5),
+        strategy: Linear::new(500),
+    };
+    controller
+        .apply_command_as(cmd.into(), CommandSource::WebApi, alice, 300)
+        .unwrap();
+    let id = controller.queued().next().unwrap().id;
+    let outcome = controller
+        .apply_command_as(
+            ThrottleCommandDyn::CancelQueued(id),
+            CommandSource::WebApi,
+            bob,
+            400,
+        )
+        .unwrap();
+    assert!(rejected(outcome));
+    controller
+        .apply_command_as(
+            ThrottleCommandDyn::ClearQueue,
+            CommandSource::WebApi,
+            bob,
+            400,
+        )
+        .unwrap();
+    assert_eq!(controller.queued().count(), 1);
+
+    controller
+        .apply_command_as(
+            ThrottleCommandDyn::ClearQueue,
+            CommandSource::WebApi,
+            alice,
+            500,
+        )
+        .unwrap();
+    assert_eq!(controller.queued().count(), 0);
+}
+
+// === Lock Timeout and Release Tests ===
+
+fn runaway_departure() -> ThrottleCommandDyn {
+    ThrottleCommand::SetSpeed {
+        target: Speed::literal(1.0),
+        strategy: EaseInOut::departure(60_000),
+    }
+    .into()
+}
+
+fn stop_over(ms: u64) -> ThrottleCommandDyn {
+    ThrottleCommand::SetSpeed {
+        target: Speed::ZERO,
+        strategy: Linear::new(ms),
+    }
+    .into()
+}
+
+#[test]
+fn lock_degrades_after_max_duration() {
+    let mut controller = ThrottleController::new(MockMotor::new()).with_max_lock_duration(5000);
+    controller
+        .apply_command(runaway_departure(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    let outcome = controller
+        .apply_command(stop_over(1000), CommandSource::WebApi, 1000)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::TransitionLocked,
+        })
+    ));
+    let lock = controller.state(1000).lock_status.unwrap();
+    assert_eq!(lock.source, CommandSource::Mqtt);
+    assert_eq!(lock.remaining_ms, Some(4000));
+
+    let lock = controller.state(5000).lock_status.unwrap();
+    assert_eq!(lock.lock, TransitionLock::None);
+    assert_eq!(lock.released, Some(LockRelease::Expired));
+
+    let outcome = controller
+        .apply_command(stop_over(1000), CommandSource::WebApi, 5000)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Interrupted { .. })
+    ));
+}
+
+#[test]
+fn force_release_needs_configured_source() {
+    let mut controller = ThrottleController::new(MockMotor::new());
+    controller
+        .apply_command(runaway_departure(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::ForceRelease, CommandSource::WebApi, 100)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::LowerPriority,
+        })
+    ));
+
+    let outcome = controller
+        .apply_command(
+            ThrottleCommandDyn::ForceRelease,
+            CommandSource::Physical,
+            100,
+        )
+        .unwrap();
+    assert!(matches!(outcome, CommandOutcome::Applied));
+    let lock = controller.state(100).lock_status.unwrap();
+    assert_eq!(
+        lock.released,
+        Some(LockRelease::Forced {
+            by: CommandSource::Physical
+        })
+    );
+
+    // The departure keeps running until something replaces it
+    assert!(controller.is_transitioning());
+    let outcome = controller
+        .apply_command(stop_over(1000), CommandSource::Mqtt, 200)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Interrupted { .. })
+    ));
+}
+
+#[test]
+fn force_release_source_configurable() {
+    let mut controller =
+        ThrottleController::new(MockMotor::new()).with_force_release_source(CommandSource::WebApi);
+    controller
+        .apply_command(runaway_departure(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::ForceRelease, CommandSource::WebApi, 100)
+        .unwrap();
+    assert!(matches!(outcome, CommandOutcome::Applied));
+
+    // Nothing left to release
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::ForceRelease, CommandSource::WebApi, 200)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::NotLocked,
+        })
+    ));
+}
+
+// === Source Priority Tests ===
+
+#[test]
+fn controller_ranks_sources_from_config() {
+    let config = ThrottleConfig::default()
+        .with_source_rank(CommandSource::Mqtt, 2)
+        .with_source_rank(CommandSource::WebLocal, 0);
+    let mut controller =
+        ThrottleController::new(MockMotor::new()).with_priorities(config.priorities);
+
+    let arrival = ThrottleCommand::SetSpeed {
+        target: Speed::literal(0.2),
+        strategy: Linear::source_locked(1000),
+    };
+    controller
+        .apply_command(arrival.into(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    // The web UI no longer outranks the automation
+    let outcome = controller
+        .apply_command(stop_over(500), CommandSource::WebLocal, 100)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::LowerPriority,
+        })
+    ));
+
+    let outcome = controller
+        .apply_command(stop_over(500), CommandSource::WebApi, 100)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })
+    ));
+
+    let outcome = controller
+        .apply_command(stop_over(500), CommandSource::Mqtt, 200)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Interrupted { .. })
+    ));
+}
+
+#[test]
+fn force_release_threshold_follows_priorities() {
+    let priorities = SourcePriorities::default().with_rank(CommandSource::WebApi, 3);
+    let mut controller = ThrottleController::new(MockMotor::new()).with_priorities(priorities);
+    controller
+        .apply_command(runaway_departure(), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::ForceRelease, CommandSource::WebApi, 100)
+        .unwrap();
+    assert!(matches!(outcome, CommandOutcome::Applied));
+}
+
+// === Rate Limit Tests ===
+
+#[test]
+fn speed_flood_is_rate_limited() {
+    let limits = RateLimits::default().with_limit(CommandSource::Mqtt, RateLimit::new(10, 5));
+    let mut controller = ThrottleController::new(MockMotor::new()).with_rate_limits(limits);
+
+    // A runaway automation sending speed/set at 100Hz for one second
+    let mut accepted = 0;
+    for tick in 0..100u64 {
+        let cmd = ThrottleCommand::speed_immediate(Speed::literal(0.5)).into();
+        let outcome = controller
+            .apply_command(cmd, CommandSource::Mqtt, tick * 10)
+            .unwrap();
+        if !matches!(
+            outcome,
+            CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+                reason: RejectReason::RateLimited,
+            })
+        ) {
+            accepted += 1;
+        }
+    }
+
+    // Burst of 5, then one every 100ms
+    assert_eq!(accepted, 14);
+    let metrics = controller.state(1000).metrics;
+    assert_eq!(metrics.rate_limited.mqtt, 86);
+    assert_eq!(metrics.rate_limited.total(), 86);
+}
+
+#[test]
+fn rate_limit_spares_estop_and_other_sources() {
+    let limits = RateLimits::default().with_limit(CommandSource::Mqtt, RateLimit::new(1, 1));
+    let mut controller = ThrottleController::new(MockMotor::new()).with_rate_limits(limits);
+    controller
+        .apply_command(departure(0.5), CommandSource::Mqtt, 0)
+        .unwrap();
+
+    let outcome = controller
+        .apply_command(ThrottleCommandDyn::EmergencyStop, CommandSource::Mqtt, 10)
+        .unwrap();
+    assert!(!matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected { .. })
+    ));
+    assert_eq!(controller.current_speed(), 0.0);
+
+    let outcome = controller
+        .apply_command(departure(0.3), CommandSource::WebApi, 20)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Started)
+    ));
+    assert_eq!(controller.metrics().rate_limited.total(), 0);
+}
+
+#[test]
+fn rate_limits_from_config() {
+    let config =
+        ThrottleConfig::default().with_rate_limit(CommandSource::WebApi, RateLimit::new(1, 1));
+    let mut controller =
+        ThrottleController::new(MockMotor::new()).with_rate_limits(config.rate_limits);
+
+    controller
+        .apply_command(departure(0.5), CommandSource::WebApi, 0)
+        .unwrap();
+    let outcome = controller
+        .apply_command(departure(0.6), CommandSource::WebApi, 500)
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        CommandOutcome::SpeedTransition(TransitionResult::Rejected {
+            reason: RejectReason::RateLimited,
+        })
+    ));
+    assert_eq!(controller.metrics().rate_limited.web_api, 1);
+}
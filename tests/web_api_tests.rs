@@ -78,7 +78,7 @@ async fn test_set_speed_immediate() {
     });
 
     // Check state
-    let current_speed = state.with_controller(|controller| controller.current_speed());
+    let current_speed = state.with_controller(|controller| controller.current_speed().get());
     assert!((current_speed - 0.5).abs() < 0.01);
 }
 
@@ -159,7 +159,8 @@ async fn test_emergency_stop() {
     // First set some speed
     let now_ms = state.now_ms();
     state.with_controller(|controller| {
-        let cmd = rs_trainz::ThrottleCommand::speed_immediate(0.8).into();
+        let cmd =
+            rs_trainz::ThrottleCommand::speed_immediate(rs_trainz::Speed::new(0.8).unwrap()).into();
         controller
             .apply_command(cmd, rs_trainz::CommandSource::WebApi, now_ms)
             .unwrap();
@@ -205,7 +206,7 @@ async fn test_set_max_speed() {
     assert_eq!(response.status(), StatusCode::OK);
 
     let throttle_state = state.state();
-    assert!((throttle_state.max_speed - 0.7).abs() < 0.01);
+    assert!((throttle_state.max_speed.get() - 0.7).abs() < 0.01);
 }
 
 #[tokio::test]
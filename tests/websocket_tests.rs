@@ -0,0 +1,197 @@
+//! Integration tests for the `/api/ws` WebSocket.
+//!
+//! These tests run the web server on a local port and talk to it with a
+//! real WebSocket client.
+
+#![cfg(feature = "web")]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use rs_trainz::hal::MockMotor;
+use rs_trainz::messages::LeaseAction;
+use rs_trainz::services::{build_router, AppState, WebServerConfig};
+use rs_trainz::{CommandSource, Direction, ThrottleController};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn start_server() -> (SocketAddr, Arc<AppState<MockMotor>>) {
+    let motor = MockMotor::new();
+    let controller = ThrottleController::new(motor);
+    let state = Arc::new(AppState::new(controller));
+    let router = build_router(Arc::clone(&state), &WebServerConfig::default());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (addr, state)
+}
+
+async fn connect(addr: SocketAddr) -> Socket {
+    let (socket, _) = connect_async(format!("ws://{}/api/ws", addr))
+        .await
+        .unwrap();
+    socket
+}
+
+/// Next JSON frame from the server.
+async fn next_frame(socket: &mut Socket) -> serde_json::Value {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(2), socket.next())
+            .await
+            .expect("timed out waiting for a frame")
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+/// Next frame of the given type, skipping others.
+async fn next_of_type(socket: &mut Socket, kind: &str) -> serde_json::Value {
+    loop {
+        let frame = next_frame(socket).await;
+        if frame["type"] == kind {
+            return frame;
+        }
+    }
+}
+
+/// New value of `key` from the next delta that changes it.
+async fn next_change(socket: &mut Socket, key: &str) -> serde_json::Value {
+    loop {
+        let frame = next_of_type(socket, "delta").await;
+        if let Some(value) = frame["changes"].get(key) {
+            return value.clone();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_sends_full_state_on_connect() {
+    let (addr, _state) = start_server().await;
+    let mut socket = connect(addr).await;
+
+    let frame = next_frame(&mut socket).await;
+    assert_eq!(frame["type"], "state");
+    assert_eq!(frame["state"]["speed"], 0.0);
+    assert_eq!(frame["state"]["direction"], "stopped");
+    assert_eq!(frame["state"]["max_speed"], 1.0);
+}
+
+#[tokio::test]
+async fn test_command_gets_response_and_acks() {
+    let (addr, state) = start_server().await;
+    let mut socket = connect(addr).await;
+    next_of_type(&mut socket, "state").await;
+
+    let command =
+        r#"{"correlation_id": "ws-1", "command": {"set_direction": {"direction": "forward"}}}"#;
+    socket.send(Message::Text(command.into())).await.unwrap();
+
+    let response = next_of_type(&mut socket, "response").await;
+    assert_eq!(response["status"], 200);
+    assert_eq!(response["body"]["correlation_id"], "ws-1");
+    let id = response["body"]["command_id"].clone();
+
+    let delta = next_of_type(&mut socket, "delta").await;
+    assert_eq!(delta["changes"]["direction"], "forward");
+    assert!(delta["changes"].get("max_speed").is_none());
+    assert_eq!(state.state().direction, Direction::Forward);
+
+    let ack = next_of_type(&mut socket, "ack").await;
+    assert_eq!(ack["ack"]["command_id"], id);
+    assert_eq!(ack["ack"]["status"], "accepted");
+    let ack = next_of_type(&mut socket, "ack").await;
+    assert_eq!(ack["ack"]["status"], "completed");
+    assert_eq!(ack["ack"]["correlation_id"], "ws-1");
+}
+
+#[tokio::test]
+async fn test_bad_command_gets_error_response() {
+    let (addr, _state) = start_server().await;
+    let mut socket = connect(addr).await;
+    next_of_type(&mut socket, "state").await;
+
+    socket
+        .send(Message::Text(r#"{"command": "fly"}"#.into()))
+        .await
+        .unwrap();
+
+    let response = next_of_type(&mut socket, "response").await;
+    assert_eq!(response["status"], 400);
+    assert!(response["body"]["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_clients_share_state() {
+    let (addr, state) = start_server().await;
+    let mut alice = connect(addr).await;
+    let mut bob = connect(addr).await;
+    next_of_type(&mut alice, "state").await;
+    next_of_type(&mut bob, "state").await;
+
+    alice
+        .send(Message::Text(
+            r#"{"command": {"set_max_speed": {"max_speed": 0.6}}}"#.into(),
+        ))
+        .await
+        .unwrap();
+    next_of_type(&mut alice, "response").await;
+
+    // Bob sees Alice's change and her command's acks
+    assert_eq!(next_change(&mut bob, "max_speed").await, 0.6);
+    let ack = next_of_type(&mut bob, "ack").await;
+    assert_eq!(ack["ack"]["command"], "set_max_speed");
+
+    // Changes made outside the socket are pushed too
+    let now = state.now_ms();
+    state.with_controller(|c| {
+        let _ = c.apply_command(
+            rs_trainz::ThrottleCommand::speed_immediate(rs_trainz::Speed::new(0.4).unwrap()).into(),
+            rs_trainz::CommandSource::Physical,
+            now,
+        );
+        c.update(now).unwrap();
+    });
+    assert_eq!(next_change(&mut alice, "speed").await, 0.4);
+    assert_eq!(next_change(&mut bob, "speed").await, 0.4);
+
+    // One client leaving doesn't affect the other
+    bob.close(None).await.unwrap();
+    alice
+        .send(Message::Text(r#"{"command": "heartbeat"}"#.into()))
+        .await
+        .unwrap();
+    let response = next_of_type(&mut alice, "response").await;
+    assert_eq!(response["body"]["result"], "applied");
+}
+
+#[tokio::test]
+async fn test_countdowns_alone_send_no_delta() {
+    let (addr, state) = start_server().await;
+    let mut socket = connect(addr).await;
+    next_of_type(&mut socket, "state").await;
+
+    state
+        .lease(LeaseAction::Acquire, CommandSource::WebApi, None)
+        .unwrap();
+    let lease = next_change(&mut socket, "lease").await;
+    assert_eq!(lease["owner"], "web_api");
+
+    // The lease counting down isn't a change
+    let quiet = tokio::time::timeout(Duration::from_millis(300), socket.next()).await;
+    assert!(quiet.is_err(), "unexpected frame: {:?}", quiet);
+
+    state
+        .lease(LeaseAction::Release, CommandSource::WebApi, None)
+        .unwrap();
+    assert!(next_change(&mut socket, "lease").await.is_null());
+}